@@ -27,9 +27,11 @@ pub use ockam_macros::{node, test};
 
 // Export node implementation
 pub use ockam_node::{debugger, Context, DelayedEvent, Executor, NodeBuilder, WorkerBuilder};
+
+#[cfg(feature = "simulation")]
+pub use ockam_node::simulation;
 // ---
 
-mod delay;
 mod error;
 mod forwarder;
 mod metadata;
@@ -56,8 +58,8 @@ pub use ockam_identity as identity;
 
 pub use ockam_core::{
     allow, deny, errcode, route, Address, Any, AsyncTryClone, Encoded, Error, LocalMessage,
-    Mailbox, Mailboxes, Message, Processor, ProtocolId, Result, Route, Routed, TransportMessage,
-    Worker,
+    Mailbox, Mailboxes, Message, Priority, Processor, ProtocolId, Result, Route, Routed,
+    TransportMessage, Worker,
 };
 
 /// Access Control
@@ -126,5 +128,5 @@ pub use ockam_transport_tcp::{TcpTransport, TCP};
 #[cfg(feature = "ockam_transport_tcp")]
 /// Tcp
 pub mod tcp {
-    pub use ockam_transport_tcp::{InletOptions, OutletOptions};
+    pub use ockam_transport_tcp::{InletOptions, OutletOptions, PortalCounters, TcpLocalInfo};
 }
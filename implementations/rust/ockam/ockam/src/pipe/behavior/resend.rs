@@ -1,5 +1,4 @@
 use crate::{
-    delay::DelayedEvent,
     pipe::behavior::{BehaviorHook, PipeModifier},
     protocols::pipe::{
         internal::{Ack, InternalCmd, Resend},
@@ -7,8 +6,10 @@ use crate::{
     },
     Context,
 };
+use core::time::Duration;
 use ockam_core::compat::boxed::Box;
 use ockam_core::{async_trait, compat::collections::BTreeMap, Address, Result, Route};
+use ockam_node::Schedule;
 
 #[derive(Default, Clone)]
 pub struct SenderConfirm {
@@ -35,16 +36,14 @@ impl BehaviorHook for SenderConfirm {
     ) -> Result<PipeModifier> {
         self.on_route.insert(msg.index.u64(), msg.clone());
 
-        DelayedEvent::new(
-            ctx,
-            this.into(),
+        ctx.schedule(
+            this,
             InternalCmd::Resend(Resend {
                 idx: msg.index.u64(),
             }),
+            Schedule::once(Duration::from_secs(5)),
         )
-        .await?
-        .with_seconds(5)
-        .spawn();
+        .await?;
 
         Ok(PipeModifier::None)
     }
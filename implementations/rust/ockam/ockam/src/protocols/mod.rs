@@ -12,7 +12,7 @@ pub mod pipe;
 pub mod stream;
 
 /// A protocol payload wrapper for pre-parsing.
-#[derive(Debug, Serialize, Deserialize, Message)]
+#[derive(Debug, Clone, Serialize, Deserialize, Message)]
 pub struct ProtocolPayload {
     /// The type of protocol, which specifies how `data` is encoded.
     pub protocol: ProtocolId,
@@ -5,28 +5,28 @@ use ockam_core::{Decodable, Result, Route, TransportMessage};
 use serde::{Deserialize, Serialize};
 
 /// Make the sender re-send a payload
-#[derive(Debug, Serialize, Deserialize, Message)]
+#[derive(Debug, Clone, Serialize, Deserialize, Message)]
 pub struct Resend {
     /// The index needing to be re-sent
     pub idx: u64,
 }
 
 /// Acknowledge successful delivery
-#[derive(Debug, Serialize, Deserialize, Message)]
+#[derive(Debug, Clone, Serialize, Deserialize, Message)]
 pub struct Ack {
     /// The acknowledged index.
     pub idx: u64,
 }
 
 /// Payload sent from handshake listener to newly spawned receiver
-#[derive(Debug, Serialize, Deserialize, Message)]
+#[derive(Debug, Clone, Serialize, Deserialize, Message)]
 pub struct Handshake {
     /// The route to the sender
     pub route_to_sender: Route,
 }
 
 /// An enum containing all internal commands
-#[derive(Debug, Serialize, Deserialize, Message)]
+#[derive(Debug, Clone, Serialize, Deserialize, Message)]
 pub enum InternalCmd {
     /// Issue the pipe sender to re-send
     Resend(Resend),
@@ -1,5 +1,4 @@
 use crate::{
-    delay::DelayedEvent,
     monotonic::Monotonic,
     protocols::{
         stream::{requests::*, responses::*},
@@ -12,6 +11,7 @@ use crate::{Address, Any, Context, Result, Route, Routed, Worker};
 use core::time::Duration;
 use ockam_core::compat::{boxed::Box, string::String, vec::Vec};
 use ockam_core::{Decodable, LocalMessage};
+use ockam_node::Schedule;
 
 /// A stream worker
 pub struct StreamConsumer {
@@ -174,10 +174,12 @@ async fn handle_cmd(
 /// This function must be re-called whenever a fetch event is handled
 /// in the `parse_cmd` function.
 async fn fetch_interval(ctx: &Context, interval: Duration) -> Result<()> {
-    DelayedEvent::new(ctx, ctx.address().into(), StreamWorkerCmd::fetch())
-        .await?
-        .with_duration(interval)
-        .spawn();
+    ctx.schedule(
+        ctx.address(),
+        StreamWorkerCmd::fetch(),
+        Schedule::once(interval),
+    )
+    .await?;
     Ok(())
 }
 
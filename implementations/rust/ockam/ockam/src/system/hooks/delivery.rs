@@ -1,19 +1,11 @@
-use crate::{
-    // Warning: delay::DelayedEvent is not the ockam_node one and will
-    // be deprecated soon!
-    delay::DelayedEvent,
-    Context,
-    OckamError,
-    OckamMessage,
-    Result,
-    Routed,
-    SystemHandler,
-};
+use crate::{Context, OckamError, OckamMessage, Result, Routed, SystemHandler};
+use core::time::Duration;
 use ockam_core::{
     async_trait,
     compat::{boxed::Box, collections::BTreeMap, string::String, vec::Vec},
     Address, Any, Decodable, Encodable,
 };
+use ockam_node::Schedule;
 
 #[derive(Clone, Default)]
 pub struct SenderConfirm {
@@ -67,18 +59,17 @@ impl SystemHandler<Context, OckamMessage> for SenderConfirm {
                     .scope_data(self_addr.encode()?);
                 self.journal.insert(ack_id, outer_msg.clone());
 
-                // Register a delayed event to check whether we
-                // received an ACK for this message.  TODO: replace
-                // this with ockam_core::Heartbeat?
-                DelayedEvent::new(ctx, self_addr.clone().into(), {
+                // Schedule a check whether we received an ACK for
+                // this message
+                ctx.schedule(
+                    self_addr.clone(),
                     OckamMessage::new(Any)?.generic_data(
                         "ockam.pipe.type",
                         "ockam.pipe.resend_notify".as_bytes().to_vec(),
-                    )
-                })
-                .await?
-                .with_seconds(5)
-                .spawn();
+                    ),
+                    Schedule::once(Duration::from_secs(5)),
+                )
+                .await?;
 
                 // Forward the new message to the next address
                 ctx.send(self.next.as_ref().unwrap().clone(), outer_msg)
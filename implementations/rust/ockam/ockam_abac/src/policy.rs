@@ -1,5 +1,6 @@
 use core::{fmt, str};
 use ockam_core::compat::boxed::Box;
+use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::format;
 use ockam_core::compat::string::ToString;
 use ockam_core::{async_trait, RelayMessage};
@@ -26,6 +27,7 @@ pub struct PolicyAccessControl<P, S> {
     attributes: S,
     environment: Env,
     overwrite: bool,
+    require_attributes: bool,
 }
 
 impl<P, S> PolicyAccessControl<P, S> {
@@ -42,12 +44,21 @@ impl<P, S> PolicyAccessControl<P, S> {
             attributes: store,
             environment: env,
             overwrite: false,
+            require_attributes: true,
         }
     }
 
     pub fn overwrite(&mut self) {
         self.overwrite = true
     }
+
+    /// Evaluate the policy even if the subject has no attributes.
+    ///
+    /// By default access is denied to subjects without attributes. With
+    /// this option the policy is evaluated against the environment alone.
+    pub fn allow_missing_attributes(&mut self) {
+        self.require_attributes = false
+    }
 }
 
 #[async_trait]
@@ -96,6 +107,8 @@ where
         let attrs =
             if let Some(a) = AttributesStorageUtils::get_attributes(&id, &self.attributes).await? {
                 a
+            } else if !self.require_attributes {
+                BTreeMap::new()
             } else {
                 log::debug! {
                     resource = %self.resource,
@@ -120,7 +133,8 @@ where
             }
             match str::from_utf8(v) {
                 Ok(s) => {
-                    if !self.overwrite && e.contains(k) {
+                    let key = format!("subject.{k}");
+                    if !self.overwrite && e.contains(&key) {
                         log::debug! {
                             resource = %self.resource,
                             action   = %self.action,
@@ -130,7 +144,7 @@ where
                         }
                         continue;
                     }
-                    e.put(format!("subject.{k}"), str(s.to_string()));
+                    e.put(key, str(s.to_string()));
                 }
                 Err(e) => {
                     log::warn! {
@@ -8,7 +8,9 @@ use ockam_core::api::{Error, Method, Request, RequestBuilder, Response, Response
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{self, Address, Result, Route, Routed, Worker};
 use ockam_identity::authenticated_storage::AuthenticatedStorage;
-use ockam_identity::credential::{Credential, SchemaId};
+use ockam_identity::credential::{
+    AttributeType, Credential, Schema, SchemaId, MAX_CREDENTIAL_VALIDITY,
+};
 use ockam_identity::{Identity, IdentityIdentifier, IdentitySecureChannelLocalInfo, IdentityVault};
 use ockam_node::Context;
 use serde_json as json;
@@ -36,6 +38,20 @@ pub const PROJECT_MEMBER_SCHEMA: SchemaId = SchemaId(1);
 pub const PROJECT_ID: &str = "project_id";
 pub const ROLE: &str = "role";
 
+/// Definition of [`PROJECT_MEMBER_SCHEMA`].
+///
+/// Attributes given at enrollment are kept as additional UTF-8 attributes.
+pub fn project_member_schema() -> Schema {
+    Schema::new(
+        PROJECT_MEMBER_SCHEMA,
+        "project member",
+        MAX_CREDENTIAL_VALIDITY,
+    )
+    .with_attribute(PROJECT_ID, AttributeType::Bytes, true)
+    .with_attribute(ROLE, AttributeType::Utf8, false)
+    .with_additional_attributes()
+}
+
 pub struct Server<S, V: IdentityVault> {
     project: Vec<u8>,
     store: S,
@@ -81,6 +97,7 @@ where
     where
         P: AsRef<Path>,
     {
+        identity.schemas().register(project_member_schema());
         Server {
             project,
             store,
@@ -155,8 +172,10 @@ where
                                 })
                                 .with_schema(PROJECT_MEMBER_SCHEMA)
                                 .with_attribute(PROJECT_ID, &self.project);
-                            let crd = self.ident.issue_credential(crd).await?;
-                            Response::ok(req.id()).body(crd).to_vec()?
+                            match self.ident.issue_credential(crd).await {
+                                Ok(crd) => Response::ok(req.id()).body(crd).to_vec()?,
+                                Err(e) => api::bad_request(&req, &e.to_string()).to_vec()?,
+                            }
                         }
                     } else {
                         api::forbidden(&req, "unknown token").to_vec()?
@@ -173,8 +192,10 @@ where
                                 |crd, (a, v)| crd.with_attribute(a, v.as_bytes()),
                             )
                             .with_attribute(PROJECT_ID, &self.project);
-                        let crd = self.ident.issue_credential(crd).await?;
-                        Response::ok(req.id()).body(crd).to_vec()?
+                        match self.ident.issue_credential(crd).await {
+                            Ok(crd) => Response::ok(req.id()).body(crd).to_vec()?,
+                            Err(e) => api::bad_request(&req, &e.to_string()).to_vec()?,
+                        }
                     }
                     Ok(None) => api::forbidden(&req, "unauthorized member").to_vec()?,
                     Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
@@ -43,6 +43,10 @@ pub struct OckamConfig {
     pub default_vault_path: Option<PathBuf>,
     /// Default node
     pub default: Option<String>,
+
+    /// Portals created with `ockam portal create`, by name
+    #[serde(default)]
+    pub portals: BTreeMap<String, PortalConfig>,
 }
 
 fn default_nodes() -> BTreeMap<String, NodeConfigOld> {
@@ -62,6 +66,7 @@ impl ConfigValues for OckamConfig {
             default_identity: None,
             default_vault_path: None,
             default: None,
+            portals: BTreeMap::new(),
         }
     }
 }
@@ -105,6 +110,29 @@ impl OckamConfig {
     }
 }
 
+/// A portal created with `ockam portal create`
+///
+/// A portal is an inlet and an outlet, on possibly different nodes,
+/// together with the forwarder and secure channel between them.
+/// Recording them lets `ockam portal delete` remove all of them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PortalConfig {
+    /// Transport of the inlet and the outlet: `tcp`, `udp` or `unix`
+    pub transport: String,
+    pub from: MultiAddr,
+    pub to: MultiAddr,
+    /// Route to the node the forwarder to the outlet node was created at
+    pub via: Option<MultiAddr>,
+    pub inlet_node: String,
+    pub outlet_node: String,
+    /// Address of the outlet worker on the outlet node
+    pub outlet_address: String,
+    /// Remote address of the forwarder to the outlet node
+    pub forwarder: Option<String>,
+    /// Secure channel created on the inlet node for this portal only
+    pub secure_channel: Option<String>,
+}
+
 /// Per-node runtime configuration
 ///
 /// ## Updates
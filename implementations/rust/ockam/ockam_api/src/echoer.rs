@@ -1,4 +1,4 @@
-use ockam::{Any, Context, Result, Routed, Worker};
+use ockam::{Any, Context, Priority, Result, Routed, Worker};
 use ockam_core::NeutralMessage;
 use tracing as log;
 
@@ -9,6 +9,12 @@ impl Worker for Echoer {
     type Context = Context;
     type Message = Any;
 
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        // Echoes answer health checks, which must get through under load
+        ctx.set_priority(Priority::Control);
+        Ok(())
+    }
+
     async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
         log::debug!(to = %msg.sender(), "echoing back");
         ctx.send(msg.return_route(), NeutralMessage::from(msg.take_payload()))
@@ -4,11 +4,12 @@ use crate::identity::models::*;
 use minicbor::encode::Write;
 use minicbor::{Decoder, Encode};
 use ockam_core::api::{Error, Id, Method, Request, Response, Status};
+use ockam_core::compat::sync::Arc;
 use ockam_core::vault::Signature;
-use ockam_core::{Address, Result, Routed, Worker};
+use ockam_core::{AccessControl, Address, AllowAll, Result, Routed, Worker};
 use ockam_identity::change_history::IdentityHistoryComparison;
 use ockam_identity::{Identity, IdentityVault, PublicIdentity};
-use ockam_node::Context;
+use ockam_node::{Context, WorkerBuilder};
 use tracing::trace;
 
 /// Vault Service Worker
@@ -25,6 +26,23 @@ impl<V: IdentityVault> IdentityService<V> {
         };
         ctx.start_worker(address.into(), s).await
     }
+
+    /// Start the service, only accepting the messages allowed by `access_control`.
+    pub async fn create_with_access_control(
+        ctx: &Context,
+        address: impl Into<Address>,
+        vault: V,
+        access_control: Arc<dyn AccessControl>,
+    ) -> Result<()> {
+        let s = Self {
+            ctx: ctx.new_detached(Address::random_local()).await?,
+            vault,
+        };
+        WorkerBuilder::with_access_control(access_control, Arc::new(AllowAll), address, s)
+            .start(ctx)
+            .await?;
+        Ok(())
+    }
 }
 
 impl<V: IdentityVault> IdentityService<V> {
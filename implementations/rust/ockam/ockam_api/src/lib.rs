@@ -6,10 +6,12 @@ pub mod echoer;
 pub mod error;
 pub mod identity;
 pub mod nodes;
+pub mod oidc;
 pub mod okta;
 pub mod uppercase;
 pub mod vault;
 pub mod verifier;
+pub mod x3dh;
 
 mod session;
 mod util;
@@ -34,17 +36,23 @@ impl DefaultAddress {
     pub const AUTHENTICATOR: &'static str = "authenticator";
     pub const VERIFIER: &'static str = "verifier";
     pub const OKTA_IDENTITY_PROVIDER: &'static str = "okta";
+    pub const OIDC_IDENTITY_PROVIDER: &'static str = "oidc";
+    pub const PREKEY_SERVICE: &'static str = "prekeys";
 }
 
 pub mod actions {
     use ockam_abac::Action;
     pub const HANDLE_MESSAGE: Action = Action::assert_inline("handle_message");
+    pub const READ: Action = Action::assert_inline("read");
+    pub const WRITE: Action = Action::assert_inline("write");
+    pub const EXPORT: Action = Action::assert_inline("export");
 }
 
 pub mod resources {
     use ockam_abac::Resource;
     pub const INLET: Resource = Resource::assert_inline("tcp-inlet");
     pub const OUTLET: Resource = Resource::assert_inline("tcp-outlet");
+    pub const NODE: Resource = Resource::assert_inline("node");
 }
 
 use core::fmt;
@@ -0,0 +1,284 @@
+//! Export and import of the whole state of a node
+//!
+//! A [`NodeBackup`] holds the files of a node state directory (its
+//! configuration, identity, authenticated and policy storage), and its
+//! vault.  It is sealed with a passphrase into a single archive, which
+//! can be restored on another machine.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use aes_gcm::aead::{Aead, NewAead};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, Context};
+use argon2::Argon2;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::rand::{thread_rng, RngCore};
+
+use crate::nodes::config::{NodeConfig, NodeConfigVersion};
+use crate::nodes::NodeManager;
+
+/// Version of the archive format
+const ARCHIVE_VERSION: u8 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// The state of a node, as bundled by `ockam node export`
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeBackup {
+    #[n(1)] pub node_name: String,
+    /// The [`NodeConfigVersion`] of the node state
+    #[n(2)] config_version: String,
+    /// Files of the node state directory, by path relative to it
+    #[n(3)] files: BTreeMap<String, Bytes>,
+    /// The vault of the node, wherever it was stored
+    #[n(4)] vault: Option<Bytes>,
+    /// The TCP listener address of the node
+    #[n(5)] pub tcp_listener_address: String,
+    #[n(6)] pub verbose: u8,
+    /// The default identity of the CLI and its vault, if the node uses it
+    #[n(7)] pub default_identity: Option<Bytes>,
+    #[n(8)] pub default_vault: Option<Bytes>,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[cbor(transparent)]
+pub struct Bytes(#[cbor(n(0), with = "minicbor::bytes")] pub Vec<u8>);
+
+/// A [`NodeBackup`] encrypted with a key derived from a passphrase
+#[derive(Debug, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+struct SealedBackup {
+    #[n(1)] version: u8,
+    #[cbor(n(2), with = "minicbor::bytes")] salt: Vec<u8>,
+    #[cbor(n(3), with = "minicbor::bytes")] nonce: Vec<u8>,
+    #[cbor(n(4), with = "minicbor::bytes")] ciphertext: Vec<u8>,
+}
+
+impl NodeBackup {
+    /// Collect the state of a stopped node
+    ///
+    /// Logs and LMDB lock files are left out.
+    pub fn collect(
+        node_name: &str,
+        node_dir: &Path,
+        tcp_listener_address: &str,
+        verbose: u8,
+    ) -> anyhow::Result<Self> {
+        // The node is left as it is, its configuration is upgraded on import
+        let config_version = NodeConfigVersion::read(node_dir)?;
+        let vault_path = NodeConfig::read_state(node_dir)?.vault_path;
+        let vault = match &vault_path {
+            Some(p) => {
+                Some(Bytes(std::fs::read(p).with_context(|| {
+                    format!("failed to read vault {}", p.display())
+                })?))
+            }
+            None => None,
+        };
+
+        let mut files = BTreeMap::new();
+        let mut dirs = vec![node_dir.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                if Some(&path) == vault_path.as_ref() || !is_state_file(&path) {
+                    continue;
+                }
+                let name = path
+                    .strip_prefix(node_dir)?
+                    .to_str()
+                    .ok_or_else(|| anyhow!("unsupported path {}", path.display()))?
+                    .to_string();
+                files.insert(name, Bytes(std::fs::read(&path)?));
+            }
+        }
+
+        Ok(Self {
+            node_name: node_name.to_string(),
+            config_version: config_version.to_string(),
+            files,
+            vault,
+            tcp_listener_address: tcp_listener_address.to_string(),
+            verbose,
+            default_identity: None,
+            default_vault: None,
+        })
+    }
+
+    /// Write the state into the directory of a new node
+    ///
+    /// The vault is stored in the node directory, so that the node
+    /// keeps its identity wherever its vault was stored before.
+    pub fn restore(&self, node_dir: &Path) -> anyhow::Result<()> {
+        NodeConfigVersion::from_str(&self.config_version).map_err(|_| {
+            anyhow!(
+                "The node was exported by a newer version of ockam (config version {})",
+                self.config_version
+            )
+        })?;
+
+        for (name, content) in &self.files {
+            let path = node_dir.join(name);
+            if !path.starts_with(node_dir) || Path::new(name).is_absolute() || name.contains("..") {
+                return Err(anyhow!("invalid file {name} in the node archive"));
+            }
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, &content.0)?;
+        }
+
+        // Loading the configuration upgrades it from older versions
+        let config = NodeConfig::new(node_dir)?;
+        let state = config.state();
+        let vault_path: Option<PathBuf> = match &self.vault {
+            Some(vault) => {
+                let path = NodeManager::default_vault_path(node_dir);
+                std::fs::write(&path, &vault.0)?;
+                Some(path)
+            }
+            None => None,
+        };
+        state.write().vault_path = vault_path;
+        state.persist_config_updates()?;
+        Ok(())
+    }
+
+    /// Encrypt the backup with a key derived from `passphrase`
+    pub fn seal(&self, passphrase: &str) -> anyhow::Result<Vec<u8>> {
+        let mut salt = vec![0; SALT_LEN];
+        let mut nonce = vec![0; NONCE_LEN];
+        thread_rng().fill_bytes(&mut salt);
+        thread_rng().fill_bytes(&mut nonce);
+        let cipher = cipher(passphrase, &salt)?;
+        let plaintext = minicbor::to_vec(self)?;
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|_| anyhow!("failed to encrypt the node archive"))?;
+        let sealed = SealedBackup {
+            version: ARCHIVE_VERSION,
+            salt,
+            nonce,
+            ciphertext,
+        };
+        Ok(minicbor::to_vec(&sealed)?)
+    }
+
+    /// Decrypt a backup sealed with [`NodeBackup::seal`]
+    pub fn open(archive: &[u8], passphrase: &str) -> anyhow::Result<Self> {
+        let sealed: SealedBackup =
+            minicbor::decode(archive).context("the file is not a node archive")?;
+        if sealed.version != ARCHIVE_VERSION {
+            return Err(anyhow!(
+                "Unsupported node archive version {}",
+                sealed.version
+            ));
+        }
+        if sealed.nonce.len() != NONCE_LEN {
+            return Err(anyhow!("the file is not a node archive"));
+        }
+        let cipher = cipher(passphrase, &sealed.salt)?;
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(&sealed.nonce),
+                sealed.ciphertext.as_slice(),
+            )
+            .map_err(|_| anyhow!("wrong passphrase, or the node archive is corrupted"))?;
+        Ok(minicbor::decode(&plaintext)?)
+    }
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> anyhow::Result<Aes256Gcm> {
+    let mut key = [0; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("failed to derive a key from the passphrase: {e}"))?;
+    Ok(Aes256Gcm::new(Key::from_slice(&key)))
+}
+
+/// Whether a file of a node directory is part of its state
+fn is_state_file(path: &Path) -> bool {
+    let name = match path.file_name().and_then(|n| n.to_str()) {
+        Some(n) => n,
+        None => return false,
+    };
+    let is_log = name.ends_with(".log") || name.contains(".log.");
+    !(is_log || name.ends_with("-lock") || name == "supervisor.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::config::NodeStateConfig;
+    use tempfile::tempdir;
+
+    #[test]
+    fn export_and_import_keep_the_node_state() {
+        let from = tempdir().unwrap();
+        NodeConfig::init_for_new_node(from.path()).unwrap();
+        let vault_path = NodeManager::default_vault_path(from.path());
+        std::fs::write(&vault_path, b"vault").unwrap();
+        {
+            let config = NodeConfig::new(from.path()).unwrap();
+            let state = config.state();
+            state.write().vault_path = Some(vault_path);
+            state.write().identity = Some(b"identity".to_vec());
+            state.persist_config_updates().unwrap();
+        }
+        std::fs::write(from.path().join("stdout.log"), b"log").unwrap();
+        std::fs::write(from.path().join("authorities.json"), b"{}").unwrap();
+
+        let backup = NodeBackup::collect("n1", from.path(), "127.0.0.1:4000", 1).unwrap();
+        let archive = backup.seal("secret").unwrap();
+        assert!(NodeBackup::open(&archive, "wrong").is_err());
+        let backup = NodeBackup::open(&archive, "secret").unwrap();
+        assert_eq!(backup.node_name, "n1");
+        assert_eq!(backup.tcp_listener_address, "127.0.0.1:4000");
+
+        let to = tempdir().unwrap();
+        backup.restore(to.path()).unwrap();
+        assert!(!to.path().join("stdout.log").exists());
+        assert!(to.path().join("authorities.json").exists());
+        let config = NodeConfig::new(to.path()).unwrap();
+        let state = config.state().read().clone();
+        assert_eq!(state.identity, Some(b"identity".to_vec()));
+        let vault_path = state.vault_path.unwrap();
+        assert!(vault_path.starts_with(to.path()));
+        assert_eq!(std::fs::read(vault_path).unwrap(), b"vault");
+    }
+
+    #[test]
+    fn export_leaves_older_configs_untouched() {
+        let from = tempdir().unwrap();
+        let state = NodeStateConfig {
+            identity: Some(b"identity".to_vec()),
+            ..Default::default()
+        };
+        let config_path = from.path().join("config.json");
+        std::fs::write(&config_path, serde_json::to_vec(&state).unwrap()).unwrap();
+
+        let backup = NodeBackup::collect("n1", from.path(), "127.0.0.1:4000", 1).unwrap();
+        assert_eq!(backup.config_version, NodeConfigVersion::V0.to_string());
+        let mut files = std::fs::read_dir(from.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, vec!["config.json"]);
+
+        let to = tempdir().unwrap();
+        backup.restore(to.path()).unwrap();
+        let config = NodeConfig::new(to.path()).unwrap();
+        assert_eq!(config.state().read().identity, Some(b"identity".to_vec()));
+    }
+}
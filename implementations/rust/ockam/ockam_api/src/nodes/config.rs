@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fmt::Formatter;
 use std::io::Write;
 use std::{
@@ -8,12 +9,14 @@ use std::{
     str::FromStr,
 };
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
+use ockam_identity::IdentityIdentifier;
 use serde::{Deserialize, Serialize};
 
 pub use commands::*;
 
 use crate::config::{build_config_path, Config, ConfigValues};
+use crate::nodes::models::access::ApiRole;
 
 #[derive(Debug)]
 pub struct NodeConfig {
@@ -47,6 +50,20 @@ impl NodeConfig {
         Ok(())
     }
 
+    /// Read the node state without upgrading or creating any configuration file
+    pub(crate) fn read_state(config_dir: &Path) -> anyhow::Result<NodeStateConfig> {
+        let version = NodeConfigVersion::read(config_dir)?;
+        let path = match version.state_config_name() {
+            None => return Ok(NodeStateConfig::default()),
+            Some(name) => build_config_path(config_dir, name),
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(json) if !json.is_empty() => serde_json::from_str(&json)
+                .with_context(|| format!("failed to parse config {}", path.display())),
+            _ => Ok(NodeStateConfig::default()),
+        }
+    }
+
     pub fn state(&self) -> &Config<NodeStateConfig> {
         &self.state
     }
@@ -57,7 +74,7 @@ impl NodeConfig {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum NodeConfigVersion {
+pub(crate) enum NodeConfigVersion {
     V0,
     V1,
 }
@@ -69,7 +86,12 @@ impl NodeConfigVersion {
         Self::V1
     }
 
-    fn load(config_dir: &Path) -> anyhow::Result<Self> {
+    pub(crate) fn load(config_dir: &Path) -> anyhow::Result<Self> {
+        Self::read(config_dir)?.upgrade(config_dir)
+    }
+
+    /// Read the version of the configuration, without upgrading it
+    pub(crate) fn read(config_dir: &Path) -> anyhow::Result<Self> {
         let version_path = config_dir.join(Self::FILE_NAME);
         let version = if version_path.exists() {
             let mut version_file = File::open(version_path)?;
@@ -80,7 +102,7 @@ impl NodeConfigVersion {
             Self::V0
         };
         debug!(%version, "Loaded config");
-        version.upgrade(config_dir)
+        Ok(version)
     }
 
     fn upgrade(&self, config_dir: &Path) -> anyhow::Result<Self> {
@@ -174,6 +196,9 @@ pub struct NodeStateConfig {
     pub identity: Option<Vec<u8>>,
     /// Identity was overridden
     pub identity_was_overridden: bool,
+    /// Identities allowed to use the node API over secure channels
+    #[serde(default)]
+    pub api_users: BTreeMap<IdentityIdentifier, ApiRole>,
 }
 
 impl ConfigValues for NodeStateConfig {
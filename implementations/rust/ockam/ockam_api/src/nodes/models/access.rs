@@ -0,0 +1,85 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use minicbor::{Decode, Encode};
+use ockam_identity::IdentityIdentifier;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tag")]
+use ockam_core::TypeTag;
+
+/// What an identity may do with the node API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Encode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiRole {
+    /// Read and change the node
+    #[n(0)] Admin,
+    /// Only read the node state
+    #[n(1)] Reader,
+}
+
+impl Display for ApiRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Admin => "admin",
+            Self::Reader => "reader",
+        })
+    }
+}
+
+impl FromStr for ApiRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "admin" => Ok(Self::Admin),
+            "reader" => Ok(Self::Reader),
+            _ => Err(format!("unknown role: {s} (expected admin or reader)")),
+        }
+    }
+}
+
+/// An identity allowed to use the node API
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ApiUser {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<5081346>,
+    #[n(1)] pub identity: IdentityIdentifier,
+    #[n(2)] pub role: ApiRole,
+}
+
+impl ApiUser {
+    pub fn new(identity: IdentityIdentifier, role: ApiRole) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity,
+            role,
+        }
+    }
+}
+
+/// Response body when listing the identities allowed to use the node API
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ApiUserList {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7730425>,
+    #[n(1)] pub list: Vec<ApiUser>,
+}
+
+impl ApiUserList {
+    pub fn new(list: Vec<ApiUser>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            list,
+        }
+    }
+}
@@ -10,6 +10,27 @@ use ockam_core::TypeTag;
 
 ///////////////////-!  REQUEST BODIES
 
+/// Request body to drain and then stop a node
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DrainNode {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2954306>,
+    /// Seconds to wait for the drain phase and for the shutdown after it
+    #[n(1)] pub timeout: u8,
+}
+
+impl DrainNode {
+    pub fn new(timeout: u8) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            timeout,
+        }
+    }
+}
+
 ///////////////////-!  RESPONSE BODIES
 
 /// Response body for a node status
@@ -1,5 +1,6 @@
 use minicbor::{Decode, Encode};
 
+use crate::nodes::models::portal::ConnectionStatus;
 use ockam::remote::RemoteForwarderInfo;
 use ockam_core::CowStr;
 use ockam_identity::IdentityIdentifier;
@@ -83,6 +84,9 @@ pub struct ForwarderInfo<'a> {
     #[b(1)] forwarding_route: CowStr<'a>,
     #[b(2)] remote_address: CowStr<'a>,
     #[b(3)] worker_address: CowStr<'a>,
+    #[n(4)] at: Option<MultiAddr>,
+    #[b(5)] alias: Option<CowStr<'a>>,
+    #[n(6)] status: Option<ConnectionStatus>,
 }
 
 impl<'a> ForwarderInfo<'a> {
@@ -93,6 +97,34 @@ impl<'a> ForwarderInfo<'a> {
     pub fn remote_address(&'a self) -> &'a str {
         &self.remote_address
     }
+
+    pub fn worker_address(&'a self) -> &'a str {
+        &self.worker_address
+    }
+
+    /// The node the forwarder was created at, if known
+    pub fn at(&self) -> Option<&MultiAddr> {
+        self.at.as_ref()
+    }
+
+    pub fn alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+
+    pub fn status(&self) -> Option<ConnectionStatus> {
+        self.status
+    }
+
+    pub(crate) fn with_status(mut self, status: ConnectionStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    pub(crate) fn with_origin(mut self, at: &MultiAddr, alias: Option<&str>) -> Self {
+        self.at = Some(at.clone());
+        self.alias = alias.map(|a| a.to_string().into());
+        self
+    }
 }
 
 impl<'a> From<RemoteForwarderInfo> for ForwarderInfo<'a> {
@@ -103,6 +135,29 @@ impl<'a> From<RemoteForwarderInfo> for ForwarderInfo<'a> {
             forwarding_route: inner.forwarding_route().to_string().into(),
             remote_address: inner.remote_address().to_string().into(),
             worker_address: inner.worker_address().to_string().into(),
+            at: None,
+            alias: None,
+            status: None,
+        }
+    }
+}
+
+/// Response body when listing forwarders
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ForwarderList<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6172391>,
+    #[b(1)] pub list: Vec<ForwarderInfo<'a>>
+}
+
+impl<'a> ForwarderList<'a> {
+    pub fn new(list: Vec<ForwarderInfo<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            list,
         }
     }
 }
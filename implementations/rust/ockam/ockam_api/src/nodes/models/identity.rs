@@ -8,11 +8,12 @@ use ockam_core::CowBytes;
 use ockam_core::TypeTag;
 
 /// Response body when instructing a node to create a Secure Channel
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct CreateIdentityResponse<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<2187575>,
     #[b(1)] pub identity_id: Cow<'a, str>,
 }
@@ -2,6 +2,7 @@
 ///
 /// This module is only a type facade and should not have any logic of
 /// its own
+pub mod access;
 pub mod base;
 pub mod credentials;
 pub mod forwarder;
@@ -10,5 +11,6 @@ pub mod policy;
 pub mod portal;
 pub mod secure_channel;
 pub mod services;
+pub mod topology;
 pub mod transport;
 pub mod vault;
@@ -1,8 +1,10 @@
 //! Inlets and outlet request/response types
 
+use std::fmt::{self, Display};
 use std::net::SocketAddr;
 
 use minicbor::{Decode, Encode};
+use ockam::tcp::PortalCounters;
 use ockam_core::compat::borrow::Cow;
 
 use ockam_core::CowStr;
@@ -32,7 +34,11 @@ pub struct CreateInlet<'a> {
     /// An authorised identity for secure channels.
     /// Only set for non-project addresses as for projects the project's
     /// authorised identity will be used.
-    #[n(5)] authorized: Option<IdentityIdentifier>
+    #[n(5)] authorized: Option<IdentityIdentifier>,
+    /// The kind of connections the portal accepts, TCP if not set.
+    #[n(6)] transport: Option<PortalTransport>,
+    /// The socket file a Unix socket portal listens at, instead of `listen_addr`.
+    #[b(7)] listen_path: Option<CowStr<'a>>,
 }
 
 impl<'a> CreateInlet<'a> {
@@ -45,6 +51,8 @@ impl<'a> CreateInlet<'a> {
             alias: None,
             check_credential,
             authorized: None,
+            transport: None,
+            listen_path: None,
         }
     }
 
@@ -62,6 +70,8 @@ impl<'a> CreateInlet<'a> {
             alias: None,
             check_credential,
             authorized: auth,
+            transport: None,
+            listen_path: None,
         }
     }
 
@@ -69,10 +79,30 @@ impl<'a> CreateInlet<'a> {
         self.alias = Some(CowStr(a.into()))
     }
 
+    /// Accept UDP datagrams at the listen address instead of TCP connections
+    pub fn set_udp(&mut self) {
+        self.transport = Some(PortalTransport::Udp)
+    }
+
+    /// Listen at the Unix socket file `path` instead of the listen address
+    pub fn set_unix_path(&mut self, path: impl Into<Cow<'a, str>>) {
+        self.transport = Some(PortalTransport::Unix);
+        self.listen_path = Some(CowStr(path.into()))
+    }
+
     pub fn listen_addr(&self) -> SocketAddr {
         self.listen_addr
     }
 
+    /// The socket file a Unix socket portal listens at
+    pub fn listen_path(&self) -> Option<&str> {
+        self.listen_path.as_deref()
+    }
+
+    pub fn transport(&self) -> PortalTransport {
+        self.transport.unwrap_or(PortalTransport::Tcp)
+    }
+
     pub fn outlet_addr(&self) -> &MultiAddr {
         &self.outlet_addr
     }
@@ -106,6 +136,9 @@ pub struct CreateOutlet<'a> {
     /// Enable credentials authorization.
     /// Defaults to the Node's `enable-credential-checks` value passed upon creation.
     #[n(4)] pub check_credential: Option<bool>,
+    /// How the portal connects to `tcp_addr`, TCP if not set.
+    /// For Unix sockets `tcp_addr` is the path of the socket file.
+    #[n(5)] pub transport: Option<PortalTransport>,
 }
 
 impl<'a> CreateOutlet<'a> {
@@ -122,16 +155,23 @@ impl<'a> CreateOutlet<'a> {
             worker_addr: worker_addr.into(),
             alias: alias.into(),
             check_credential,
+            transport: None,
         }
     }
+
+    pub fn with_transport(mut self, transport: PortalTransport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
 }
 
 /// Response body when interacting with a portal endpoint
-#[derive(Clone, Debug, Decode, Encode)]
+#[derive(Clone, Debug, Decode, Encode, serde::Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct InletStatus<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<9302588>,
     #[b(1)] pub bind_addr: CowStr<'a>,
     #[b(2)] pub worker_addr: CowStr<'a>,
@@ -139,6 +179,15 @@ pub struct InletStatus<'a> {
     /// An optional status payload
     #[b(4)] pub payload: Option<CowStr<'a>>,
     #[b(5)] pub outlet_route: CowStr<'a>,
+    /// The state of the connection to the outlet
+    #[n(6)] pub status: Option<ConnectionStatus>,
+    #[n(7)] pub traffic: Option<Traffic>,
+    /// The address the inlet was asked to connect to
+    #[b(8)] pub outlet_addr: Option<CowStr<'a>>,
+    /// The identity the inlet was asked to authorise, if any
+    #[n(9)] pub authorized: Option<IdentityIdentifier>,
+    /// Whether credentials are checked
+    #[n(10)] pub check_credential: Option<bool>,
 }
 
 impl<'a> InletStatus<'a> {
@@ -151,6 +200,11 @@ impl<'a> InletStatus<'a> {
             alias: "".into(),
             payload: Some(reason.into()),
             outlet_route: "".into(),
+            status: None,
+            traffic: None,
+            outlet_addr: None,
+            authorized: None,
+            check_credential: None,
         }
     }
 
@@ -169,22 +223,50 @@ impl<'a> InletStatus<'a> {
             alias: alias.into(),
             payload: payload.into(),
             outlet_route: outlet_route.into(),
+            status: None,
+            traffic: None,
+            outlet_addr: None,
+            authorized: None,
+            check_credential: None,
         }
     }
+
+    pub fn with_status(mut self, status: ConnectionStatus, traffic: Traffic) -> Self {
+        self.status = Some(status);
+        self.traffic = Some(traffic);
+        self
+    }
+
+    pub fn with_config(
+        mut self,
+        outlet_addr: impl Into<CowStr<'a>>,
+        authorized: Option<IdentityIdentifier>,
+        check_credential: bool,
+    ) -> Self {
+        self.outlet_addr = Some(outlet_addr.into());
+        self.authorized = authorized;
+        self.check_credential = Some(check_credential);
+        self
+    }
 }
 
 /// Response body when interacting with a portal endpoint
-#[derive(Clone, Debug, Decode, Encode)]
+#[derive(Clone, Debug, Decode, Encode, serde::Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct OutletStatus<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<4012569>,
     #[b(1)] pub tcp_addr: CowStr<'a>,
     #[b(2)] pub worker_addr: CowStr<'a>,
     #[b(3)] pub alias: CowStr<'a>,
     /// An optional status payload
     #[b(4)] pub payload: Option<CowStr<'a>>,
+    #[n(5)] pub status: Option<ConnectionStatus>,
+    #[n(6)] pub traffic: Option<Traffic>,
+    /// Whether credentials are checked
+    #[n(7)] pub check_credential: Option<bool>,
 }
 
 impl<'a> OutletStatus<'a> {
@@ -196,6 +278,9 @@ impl<'a> OutletStatus<'a> {
             worker_addr: "".into(),
             alias: "".into(),
             payload: Some(reason.into()),
+            status: None,
+            traffic: None,
+            check_credential: None,
         }
     }
 
@@ -212,6 +297,93 @@ impl<'a> OutletStatus<'a> {
             worker_addr: worker_addr.into(),
             alias: alias.into(),
             payload: payload.into(),
+            status: None,
+            traffic: None,
+            check_credential: None,
+        }
+    }
+
+    pub fn with_status(mut self, status: ConnectionStatus, traffic: Traffic) -> Self {
+        self.status = Some(status);
+        self.traffic = Some(traffic);
+        self
+    }
+
+    pub fn with_check_credential(mut self, check_credential: bool) -> Self {
+        self.check_credential = Some(check_credential);
+        self
+    }
+}
+
+/// The kind of connections a portal carries
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum PortalTransport {
+    #[n(0)] Tcp,
+    #[n(1)] Udp,
+    #[n(2)] Unix,
+}
+
+impl Display for PortalTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Tcp => "tcp",
+            Self::Udp => "udp",
+            Self::Unix => "unix",
+        })
+    }
+}
+
+/// The state of the connection of an inlet or forwarder
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum ConnectionStatus {
+    #[n(0)] Connected,
+    /// The connection was lost and is being re-established
+    #[n(1)] Reconnecting,
+    /// The connection could not be established
+    #[n(2)] Failed,
+    /// The portal or forwarder was deleted
+    #[n(3)] Deleted,
+}
+
+impl Display for ConnectionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Connected => "connected",
+            Self::Reconnecting => "reconnecting",
+            Self::Failed => "failed",
+            Self::Deleted => "deleted",
+        })
+    }
+}
+
+/// Traffic of the TCP connections of an inlet or outlet
+#[derive(Copy, Clone, Debug, Default, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Traffic {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<3918624>,
+    /// Currently open connections
+    #[n(1)] pub connections: u64,
+    /// Bytes read from the connections
+    #[n(2)] pub bytes_received: u64,
+    /// Bytes written to the connections
+    #[n(3)] pub bytes_sent: u64,
+}
+
+impl From<&PortalCounters> for Traffic {
+    fn from(c: &PortalCounters) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            connections: c.connections(),
+            bytes_received: c.bytes_received(),
+            bytes_sent: c.bytes_sent(),
         }
     }
 }
@@ -255,3 +427,83 @@ impl<'a> OutletList<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use minicbor::{Decoder, Encode};
+
+    use ockam::Context;
+    use ockam_core::api::{Request, RequestBuilder, Response, Status};
+    use ockam_core::{Result, Route};
+
+    use crate::nodes::NodeManager;
+
+    use super::*;
+
+    async fn call<T: Encode<()>>(
+        ctx: &Context,
+        node: &Route,
+        req: RequestBuilder<'_, T>,
+    ) -> Result<Vec<u8>> {
+        let mut buf = vec![];
+        req.encode(&mut buf)?;
+        ctx.send_and_receive(node.clone(), buf).await
+    }
+
+    fn status(response: &[u8]) -> Result<Option<Status>> {
+        let header: Response = Decoder::new(response).decode()?;
+        Ok(header.status())
+    }
+
+    #[ockam_macros::test]
+    async fn delete_portals(ctx: &mut Context) -> Result<()> {
+        let node = NodeManager::test_create(ctx).await?;
+
+        let outlet = CreateOutlet::new("127.0.0.1:5000", "outlet", Some("o1".into()), Some(false));
+        let res = call(ctx, &node, Request::post("/node/outlet").body(outlet)).await?;
+        assert_eq!(status(&res)?, Some(Status::Ok));
+
+        let mut inlet = CreateInlet::to_node(
+            "127.0.0.1:0".parse().unwrap(),
+            "/service/outlet".parse().unwrap(),
+            Some(false),
+            None,
+        );
+        inlet.set_alias("i1");
+        let res = call(ctx, &node, Request::post("/node/inlet").body(inlet)).await?;
+        assert_eq!(status(&res)?, Some(Status::Ok));
+
+        // The inlet reports its status and traffic:
+        let res = call(ctx, &node, Request::get("/node/inlet/i1")).await?;
+        let mut dec = Decoder::new(&res);
+        let header: Response = dec.decode()?;
+        assert_eq!(header.status(), Some(Status::Ok));
+        let body: InletStatus = dec.decode()?;
+        assert_eq!(body.status, Some(ConnectionStatus::Connected));
+        assert_eq!(body.traffic.map(|t| t.connections), Some(0));
+
+        // Once deleted, the inlet is unknown:
+        let res = call(ctx, &node, Request::delete("/node/inlet/i1")).await?;
+        let mut dec = Decoder::new(&res);
+        let header: Response = dec.decode()?;
+        assert_eq!(header.status(), Some(Status::Ok));
+        let body: InletStatus = dec.decode()?;
+        assert_eq!(body.status, Some(ConnectionStatus::Deleted));
+        let res = call(ctx, &node, Request::get("/node/inlet/i1")).await?;
+        assert_eq!(status(&res)?, Some(Status::NotFound));
+        let res = call(ctx, &node, Request::delete("/node/inlet/i1")).await?;
+        assert_eq!(status(&res)?, Some(Status::NotFound));
+
+        let res = call(ctx, &node, Request::delete("/node/outlet/o1")).await?;
+        assert_eq!(status(&res)?, Some(Status::Ok));
+        let res = call(ctx, &node, Request::get("/node/outlet")).await?;
+        let mut dec = Decoder::new(&res);
+        let _: Response = dec.decode()?;
+        assert!(dec.decode::<OutletList>()?.list.is_empty());
+
+        let res = call(ctx, &node, Request::delete("/node/portal")).await?;
+        assert_eq!(status(&res)?, Some(Status::NotImplemented));
+
+        ctx.stop().await
+    }
+}
@@ -193,3 +193,86 @@ impl<'a> ShowSecureChannelResponse<'a> {
         }
     }
 }
+
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ShowSecureChannelListenerRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5130843>,
+    #[b(1)] pub addr: Cow<'a, str>,
+}
+
+impl<'a> ShowSecureChannelListenerRequest<'a> {
+    pub fn new(addr: &Address) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            addr: addr.to_string().into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ShowSecureChannelListenerResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<2748195>,
+    #[b(1)] pub addr: Option<Cow<'a, str>>,
+    #[b(2)] pub authorized_identifiers: Option<Vec<CowStr<'a>>>,
+}
+
+impl<'a> ShowSecureChannelListenerResponse<'a> {
+    pub fn new(
+        addr: Option<&Address>,
+        authorized_identifiers: Option<&[IdentityIdentifier]>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            addr: addr.map(|addr| addr.to_string().into()),
+            authorized_identifiers: authorized_identifiers
+                .map(|ids| ids.iter().map(|iid| iid.to_string().into()).collect()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DeleteSecureChannelListenerRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<9137261>,
+    #[b(1)] pub addr: Cow<'a, str>,
+}
+
+impl<'a> DeleteSecureChannelListenerRequest<'a> {
+    pub fn new(addr: &Address) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            addr: addr.to_string().into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DeleteSecureChannelListenerResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6419807>,
+    #[b(1)] pub addr: Option<Cow<'a, str>>,
+}
+
+impl<'a> DeleteSecureChannelListenerResponse<'a> {
+    pub fn new(addr: Option<Address>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            addr: addr.map(|addr| addr.to_string().into()),
+        }
+    }
+}
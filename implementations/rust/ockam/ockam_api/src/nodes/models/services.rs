@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use minicbor::{Decode, Encode};
@@ -14,14 +15,16 @@ pub struct StartVaultServiceRequest<'a> {
     #[cfg(feature = "tag")]
     #[n(0)] tag: TypeTag<9798850>,
     #[b(1)] pub addr: CowStr<'a>,
+    #[n(2)] pub deny_export: bool,
 }
 
 impl<'a> StartVaultServiceRequest<'a> {
-    pub fn new(addr: impl Into<CowStr<'a>>) -> Self {
+    pub fn new(addr: impl Into<CowStr<'a>>, deny_export: bool) -> Self {
         Self {
             #[cfg(feature = "tag")]
             tag: TypeTag,
             addr: addr.into(),
+            deny_export,
         }
     }
 }
@@ -164,6 +167,29 @@ impl<'a> StartVerifierService<'a> {
     }
 }
 
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartPreKeyService<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3046811>,
+    #[b(1)] addr: CowStr<'a>,
+}
+
+impl<'a> StartPreKeyService<'a> {
+    pub fn new(addr: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            addr: addr.into(),
+        }
+    }
+
+    pub fn address(&'a self) -> &'a str {
+        &self.addr
+    }
+}
+
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -243,11 +269,66 @@ impl<'a> StartOktaIdentityProviderRequest<'a> {
     }
 }
 
+/// Request body when instructing a node to start an OpenID Connect Identity Provider service
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
+pub struct StartOidcIdentityProviderRequest<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4760173>,
+    #[b(1)] addr: CowStr<'a>,
+    #[b(2)] issuer: CowStr<'a>,
+    #[b(3)] audience: CowStr<'a>,
+    #[b(4)] claims: BTreeMap<CowStr<'a>, CowStr<'a>>,
+    #[b(5)] certificate: Option<CowStr<'a>>,
+}
+
+impl<'a> StartOidcIdentityProviderRequest<'a> {
+    pub fn new(
+        addr: impl Into<CowStr<'a>>,
+        issuer: impl Into<CowStr<'a>>,
+        audience: impl Into<CowStr<'a>>,
+        claims: BTreeMap<CowStr<'a>, CowStr<'a>>,
+        certificate: Option<CowStr<'a>>,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            addr: addr.into(),
+            issuer: issuer.into(),
+            audience: audience.into(),
+            claims,
+            certificate,
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.addr
+    }
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+    pub fn audience(&self) -> &str {
+        &self.audience
+    }
+    /// ID token claim names mapped to the attribute names they are stored as
+    pub fn claims(&self) -> BTreeMap<String, String> {
+        self.claims
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+    pub fn certificate(&self) -> Option<&str> {
+        self.certificate.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
 pub struct ServiceStatus<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<8542064>,
     #[b(2)] pub addr: CowStr<'a>,
     #[b(3)] pub service_type: CowStr<'a>,
@@ -0,0 +1,139 @@
+use minicbor::{Decode, Encode};
+use ockam_core::CowStr;
+use ockam_node::debugger::{MailboxEntry, MessageEdge, Topology, WorkerEntry};
+use serde::Serialize;
+
+#[cfg(feature = "tag")]
+use ockam_core::TypeTag;
+
+///////////////////-!  RESPONSE BODIES
+
+/// Response body for the worker graph of a node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeTopology<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<7341552>,
+    #[b(1)] pub workers: Vec<WorkerInfo<'a>>,
+    #[b(2)] pub mailboxes: Vec<MailboxInfo<'a>>,
+    #[b(3)] pub inheritance: Vec<InheritanceInfo<'a>>,
+    #[b(4)] pub edges: Vec<MessageEdgeInfo<'a>>,
+    /// Whether the node recorded mailboxes and message flows
+    #[n(5)] pub recorded: bool,
+}
+
+/// A worker or processor running on a node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct WorkerInfo<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<1928452>,
+    #[b(1)] pub address: CowStr<'a>,
+    #[b(2)] pub aliases: Vec<CowStr<'a>>,
+    #[n(3)] pub processor: bool,
+    #[n(4)] pub detached: bool,
+    #[b(5)] pub cluster: Option<CowStr<'a>>,
+}
+
+/// A mailbox and its access controls
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct MailboxInfo<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<4402861>,
+    #[b(1)] pub address: CowStr<'a>,
+    #[b(2)] pub incoming_access_control: CowStr<'a>,
+    #[b(3)] pub outgoing_access_control: CowStr<'a>,
+}
+
+/// A context that was created by another context
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InheritanceInfo<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<6091285>,
+    #[b(1)] pub parent: CowStr<'a>,
+    #[b(2)] pub child: CowStr<'a>,
+}
+
+/// Messages sent from one address to another
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct MessageEdgeInfo<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<2215570>,
+    #[b(1)] pub source: CowStr<'a>,
+    #[b(2)] pub destination: CowStr<'a>,
+    #[n(3)] pub count: u64,
+}
+
+impl<'a> From<Topology> for NodeTopology<'a> {
+    fn from(t: Topology) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            workers: t.workers.into_iter().map(WorkerInfo::from).collect(),
+            mailboxes: t.mailboxes.into_iter().map(MailboxInfo::from).collect(),
+            inheritance: t
+                .inheritance
+                .into_iter()
+                .map(|(parent, child)| InheritanceInfo {
+                    #[cfg(feature = "tag")]
+                    tag: TypeTag,
+                    parent: parent.to_string().into(),
+                    child: child.to_string().into(),
+                })
+                .collect(),
+            edges: t.edges.into_iter().map(MessageEdgeInfo::from).collect(),
+            recorded: t.recorded,
+        }
+    }
+}
+
+impl<'a> From<WorkerEntry> for WorkerInfo<'a> {
+    fn from(w: WorkerEntry) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            address: w.address.to_string().into(),
+            aliases: w.aliases.iter().map(|a| a.to_string().into()).collect(),
+            processor: w.processor,
+            detached: w.detached,
+            cluster: w.cluster.map(CowStr::from),
+        }
+    }
+}
+
+impl<'a> From<MailboxEntry> for MailboxInfo<'a> {
+    fn from(m: MailboxEntry) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            address: m.address.to_string().into(),
+            incoming_access_control: m.incoming_access_control.into(),
+            outgoing_access_control: m.outgoing_access_control.into(),
+        }
+    }
+}
+
+impl<'a> From<MessageEdge> for MessageEdgeInfo<'a> {
+    fn from(e: MessageEdge) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            source: e.source.to_string().into(),
+            destination: e.destination.to_string().into(),
+            count: e.count as u64,
+        }
+    }
+}
@@ -61,7 +61,7 @@ impl<'a> DeleteTransport<'a> {
 /// Encode which type of transport is being requested
 // TODO: we have a TransportType in ockam_core.  Do we really want to
 // mirror this kind of type here?
-#[derive(Copy, Clone, Debug, Decode, Encode)]
+#[derive(Copy, Clone, Debug, Decode, Encode, serde::Serialize)]
 #[rustfmt::skip]
 #[cbor(index_only)]
 pub enum TransportType {
@@ -84,7 +84,7 @@ impl Display for TransportType {
 }
 
 /// Encode which type of transport is being requested
-#[derive(Copy, Clone, Debug, Decode, Encode, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Decode, Encode, PartialEq, Eq, serde::Serialize)]
 #[rustfmt::skip]
 pub enum TransportMode {
     /// Listen on a set address
@@ -105,11 +105,12 @@ impl Display for TransportMode {
 ///////////////////-!  RESPONSE BODIES
 
 /// Response body when interacting with a transport
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, serde::Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct TransportStatus<'a> {
     #[cfg(feature = "tag")]
+    #[serde(skip)]
     #[n(0)] tag: TypeTag<1581592>,
     /// The type of transport to create
     #[n(2)] pub tt: TransportType,
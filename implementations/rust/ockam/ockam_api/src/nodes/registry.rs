@@ -1,7 +1,13 @@
+use crate::nodes::models::forwarder::ForwarderInfo;
+use crate::nodes::models::secure_channel::CredentialExchangeMode;
 use crate::nodes::service::Alias;
+use crate::session::Key;
+use ockam::tcp::PortalCounters;
 use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::Arc;
 use ockam_core::{Address, Route};
 use ockam_identity::IdentityIdentifier;
+use ockam_multiaddr::MultiAddr;
 
 #[derive(Default)]
 pub(crate) struct SecureChannelRegistry {
@@ -27,6 +33,12 @@ impl SecureChannelRegistry {
             .push(SecureChannelInfo::new(route, addr, authorized_identifiers))
     }
 
+    pub fn set_credential_exchange_mode(&mut self, addr: &Address, mode: CredentialExchangeMode) {
+        if let Some(channel) = self.channels.iter_mut().find(|x| x.addr() == addr) {
+            channel.credential_exchange_mode = mode
+        }
+    }
+
     pub fn remove_by_addr(&mut self, addr: &Address) {
         self.channels.retain(|x| x.addr() != addr)
     }
@@ -43,6 +55,8 @@ pub struct SecureChannelInfo {
     // Local address of the created channel
     addr: Address,
     authorized_identifiers: Option<Vec<IdentityIdentifier>>,
+    // How our credential was presented over the channel
+    credential_exchange_mode: CredentialExchangeMode,
 }
 
 impl SecureChannelInfo {
@@ -55,6 +69,7 @@ impl SecureChannelInfo {
             addr,
             route,
             authorized_identifiers,
+            credential_exchange_mode: CredentialExchangeMode::None,
         }
     }
 
@@ -69,10 +84,24 @@ impl SecureChannelInfo {
     pub fn authorized_identifiers(&self) -> Option<&Vec<IdentityIdentifier>> {
         self.authorized_identifiers.as_ref()
     }
+
+    pub fn credential_exchange_mode(&self) -> CredentialExchangeMode {
+        self.credential_exchange_mode
+    }
 }
 
 #[derive(Default)]
-pub(crate) struct SecureChannelListenerInfo {}
+pub(crate) struct SecureChannelListenerInfo {
+    pub(crate) authorized_identifiers: Option<Vec<IdentityIdentifier>>,
+}
+
+impl SecureChannelListenerInfo {
+    pub(crate) fn new(authorized_identifiers: Option<Vec<IdentityIdentifier>>) -> Self {
+        Self {
+            authorized_identifiers,
+        }
+    }
+}
 
 #[derive(Default)]
 pub(crate) struct VaultServiceInfo {}
@@ -86,6 +115,9 @@ pub(crate) struct AuthenticatedServiceInfo {}
 #[derive(Default)]
 pub(crate) struct OktaIdentityProviderServiceInfo {}
 
+#[derive(Default)]
+pub(crate) struct OidcIdentityProviderServiceInfo {}
+
 #[derive(Default)]
 pub(crate) struct UppercaseServiceInfo {}
 
@@ -98,6 +130,9 @@ pub(crate) struct VerifierServiceInfo {}
 #[derive(Default)]
 pub(crate) struct CredentialsServiceInfo {}
 
+#[derive(Default)]
+pub(crate) struct PreKeyServiceInfo {}
+
 #[derive(Default)]
 pub(crate) struct AuthenticatorServiceInfo {}
 
@@ -105,6 +140,13 @@ pub(crate) struct InletInfo {
     pub(crate) bind_addr: String,
     pub(crate) worker_addr: Address,
     pub(crate) outlet_route: Route,
+    // Session which keeps the inlet connected, if any
+    pub(crate) session: Option<Key>,
+    pub(crate) counters: Arc<PortalCounters>,
+    // What the inlet was created with
+    pub(crate) outlet_addr: MultiAddr,
+    pub(crate) authorized: Option<IdentityIdentifier>,
+    pub(crate) check_credential: bool,
 }
 
 impl InletInfo {
@@ -112,6 +154,7 @@ impl InletInfo {
         bind_addr: &str,
         worker_addr: Option<&Address>,
         outlet_route: &Route,
+        counters: Arc<PortalCounters>,
     ) -> Self {
         let worker_addr = match worker_addr {
             Some(addr) => addr.clone(),
@@ -121,17 +164,46 @@ impl InletInfo {
             bind_addr: bind_addr.to_owned(),
             worker_addr,
             outlet_route: outlet_route.to_owned(),
+            session: None,
+            counters,
+            outlet_addr: MultiAddr::default(),
+            authorized: None,
+            check_credential: false,
         }
     }
+
+    pub(crate) fn with_session(mut self, session: Option<Key>) -> Self {
+        self.session = session;
+        self
+    }
+
+    pub(crate) fn with_config(
+        mut self,
+        outlet_addr: &MultiAddr,
+        authorized: Option<IdentityIdentifier>,
+        check_credential: bool,
+    ) -> Self {
+        self.outlet_addr = outlet_addr.clone();
+        self.authorized = authorized;
+        self.check_credential = check_credential;
+        self
+    }
 }
 
 pub(crate) struct OutletInfo {
     pub(crate) tcp_addr: String,
     pub(crate) worker_addr: Address,
+    pub(crate) counters: Arc<PortalCounters>,
+    pub(crate) check_credential: bool,
 }
 
 impl OutletInfo {
-    pub(crate) fn new(tcp_addr: &str, worker_addr: Option<&Address>) -> Self {
+    pub(crate) fn new(
+        tcp_addr: &str,
+        worker_addr: Option<&Address>,
+        counters: Arc<PortalCounters>,
+        check_credential: bool,
+    ) -> Self {
         let worker_addr = match worker_addr {
             Some(addr) => addr.clone(),
             None => Address::from_string(""),
@@ -139,10 +211,18 @@ impl OutletInfo {
         Self {
             tcp_addr: tcp_addr.to_owned(),
             worker_addr,
+            counters,
+            check_credential,
         }
     }
 }
 
+pub(crate) struct ForwarderRegistryInfo {
+    pub(crate) info: ForwarderInfo<'static>,
+    // Session which keeps the forwarder registered, if any
+    pub(crate) session: Option<Key>,
+}
+
 #[derive(Default)]
 pub(crate) struct Registry {
     pub(crate) secure_channels: SecureChannelRegistry,
@@ -151,14 +231,17 @@ pub(crate) struct Registry {
     pub(crate) identity_services: BTreeMap<Address, IdentityServiceInfo>,
     pub(crate) authenticated_services: BTreeMap<Address, AuthenticatedServiceInfo>,
     pub(crate) okta_identity_provider_services: BTreeMap<Address, OktaIdentityProviderServiceInfo>,
+    pub(crate) oidc_identity_provider_services: BTreeMap<Address, OidcIdentityProviderServiceInfo>,
     pub(crate) uppercase_services: BTreeMap<Address, UppercaseServiceInfo>,
     pub(crate) echoer_services: BTreeMap<Address, EchoerServiceInfo>,
     pub(crate) verifier_services: BTreeMap<Address, VerifierServiceInfo>,
     pub(crate) credentials_services: BTreeMap<Address, CredentialsServiceInfo>,
+    pub(crate) prekey_services: BTreeMap<Address, PreKeyServiceInfo>,
     #[cfg(feature = "direct-authenticator")]
     pub(crate) authenticator_service: BTreeMap<Address, AuthenticatorServiceInfo>,
 
     // FIXME: wow this is a terrible way to store data
     pub(crate) inlets: BTreeMap<Alias, InletInfo>,
     pub(crate) outlets: BTreeMap<Alias, OutletInfo>,
+    pub(crate) forwarders: BTreeMap<String, ForwarderRegistryInfo>,
 }
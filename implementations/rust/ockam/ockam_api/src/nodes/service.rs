@@ -3,19 +3,20 @@
 use minicbor::Decoder;
 
 use ockam::compat::asynchronous::RwLock;
-use ockam::{Address, Context, ForwardingService, Result, Routed, TcpTransport, Worker};
-use ockam_core::api::{Error, Method, Request, Response, ResponseBuilder, Status};
+use ockam::{Address, Context, ForwardingService, Priority, Result, Routed, TcpTransport, Worker};
+use ockam_core::api::{self, Error, Method, Request, Response, ResponseBuilder, Status};
 use ockam_core::compat::{
     boxed::Box,
     string::String,
     sync::{Arc, Mutex},
 };
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::AsyncTryClone;
+use ockam_core::{AsyncTryClone, RelayMessage};
 use ockam_identity::{Identity, IdentityIdentifier, PublicIdentity};
 use ockam_multiaddr::proto::{Project, Secure};
 use ockam_multiaddr::{MultiAddr, Protocol};
 use ockam_node::tokio;
+use ockam_node::tokio::sync::broadcast;
 use ockam_node::tokio::task::JoinHandle;
 use ockam_vault::storage::FileStorage;
 use ockam_vault::Vault;
@@ -26,13 +27,15 @@ use std::time::Duration;
 
 use super::models::secure_channel::CredentialExchangeMode;
 use super::registry::Registry;
+use crate::authenticator::direct::project_member_schema;
 use crate::authenticator::direct::types::OneTimeCode;
 use crate::config::cli::AuthoritiesConfig;
 use crate::config::lookup::ProjectLookup;
 use crate::error::ApiError;
 use crate::lmdb::LmdbStorage;
 use crate::nodes::config::NodeConfig;
-use crate::nodes::models::base::NodeStatus;
+use crate::nodes::models::base::{DrainNode, NodeStatus};
+use crate::nodes::models::topology::NodeTopology;
 use crate::nodes::models::transport::{TransportMode, TransportType};
 use crate::session::util::starts_with_host_tcp_secure;
 use crate::session::{Medic, Sessions};
@@ -40,6 +43,7 @@ use crate::{multiaddr_to_route, try_address_to_multiaddr, DefaultAddress};
 
 pub mod message;
 
+mod access;
 mod credentials;
 mod forwarder;
 mod identity;
@@ -50,6 +54,11 @@ mod services;
 mod transport;
 mod vault;
 
+pub use access::ApiAccessControl;
+use access::ApiUsers;
+pub use credentials::CredentialEvent;
+use credentials::CredentialRefresher;
+
 const TARGET: &str = "ockam_api::nodemanager::service";
 
 pub(crate) type Alias = String;
@@ -60,6 +69,15 @@ fn random_alias() -> String {
     Address::random_local().without_type().to_owned()
 }
 
+/// Build a "404 Not Found" response with an error body.
+pub(super) fn not_found<'a>(req: &'a Request<'_>, msg: &'static str) -> ResponseBuilder<Error<'a>> {
+    let mut err = Error::new(req.path()).with_message(msg);
+    if let Some(m) = req.method() {
+        err.set_method(m)
+    }
+    Response::not_found(req.id()).body(err)
+}
+
 // TODO: Move to multiaddr implementation
 pub(crate) fn invalid_multiaddr_error() -> ockam_core::Error {
     ockam_core::Error::new(Origin::Core, Kind::Invalid, "Invalid multiaddr")
@@ -118,23 +136,46 @@ pub struct NodeManager {
     sessions: Arc<Mutex<Sessions>>,
     medic: JoinHandle<Result<(), ockam_core::Error>>,
     policies: LmdbStorage,
+    api_users: ApiUsers,
     token: Option<OneTimeCode>,
+    credential_events: broadcast::Sender<CredentialEvent>,
 }
 
 pub struct NodeManagerWorker {
     node_manager: Arc<RwLock<NodeManager>>,
+    access: ApiAccessControl,
+    credential_refresher: Option<JoinHandle<()>>,
 }
 
 impl NodeManagerWorker {
     pub fn new(node_manager: NodeManager) -> Self {
         NodeManagerWorker {
+            access: node_manager.api_access_control(),
             node_manager: Arc::new(RwLock::new(node_manager)),
+            credential_refresher: None,
         }
     }
 
     pub fn get(&mut self) -> &mut Arc<RwLock<NodeManager>> {
         &mut self.node_manager
     }
+
+    /// Drain and stop this node once the response has been sent
+    async fn drain_node(
+        &mut self,
+        ctx: &Context,
+        req: &Request<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<ResponseBuilder> {
+        let DrainNode { timeout, .. } = dec.decode()?;
+        let mut ctx = ctx.async_try_clone().await?;
+        tokio::spawn(async move {
+            if let Err(err) = ctx.drain_and_stop(timeout).await {
+                error!(%err, "failed to drain node");
+            }
+        });
+        Ok(Response::ok(req.id()))
+    }
 }
 
 pub struct IdentityOverride {
@@ -161,6 +202,11 @@ impl NodeManager {
             .ok_or_else(|| ApiError::generic("Authorities don't exist"))
     }
 
+    /// Subscribe to events about the renewal of the node credential
+    pub fn credential_events(&self) -> broadcast::Receiver<CredentialEvent> {
+        self.credential_events.subscribe()
+    }
+
     /// Available only for member nodes
     pub(crate) fn project_id(&self) -> Result<&str> {
         self.project_id
@@ -292,10 +338,17 @@ impl NodeManager {
             },
             None => None,
         };
+        // Credentials of project members are checked wherever they are
+        // received
+        if let Some(identity) = &identity {
+            identity.schemas().register(project_member_schema());
+        }
 
         let medic = Medic::new();
         let sessions = medic.sessions();
 
+        let api_users = state.read().api_users.clone();
+
         let mut s = Self {
             node_name: general_options.node_name,
             node_dir: general_options.node_dir,
@@ -320,7 +373,9 @@ impl NodeManager {
             },
             sessions,
             policies: policies_storage,
+            api_users: Arc::new(std::sync::RwLock::new(api_users)),
             token: projects_options.token,
+            credential_events: broadcast::channel(16).0,
         };
 
         if !general_options.skip_defaults {
@@ -366,7 +421,7 @@ impl NodeManager {
 
     async fn initialize_defaults(&mut self, ctx: &Context) -> Result<()> {
         // Start services
-        self.start_vault_service_impl(ctx, DefaultAddress::VAULT_SERVICE.into())
+        self.start_vault_service_impl(ctx, DefaultAddress::VAULT_SERVICE.into(), false)
             .await?;
         self.start_identity_service_impl(ctx, DefaultAddress::IDENTITY_SERVICE.into())
             .await?;
@@ -503,6 +558,14 @@ impl NodeManagerWorker {
                     .to_vec()?
             }
 
+            (Get, ["node", "topology"]) => Response::ok(req.id())
+                .body(NodeTopology::from(ctx.topology().await?))
+                .to_vec()?,
+
+            (Post, ["node", "actions", "drain"]) => {
+                self.drain_node(ctx, req, dec).await?.to_vec()?
+            }
+
             // ==*== Tcp Connection ==*==
             // TODO: Get all tcp connections
             (Get, ["node", "tcp", "connection"]) => {
@@ -578,6 +641,14 @@ impl NodeManagerWorker {
                 .create_secure_channel_listener(req, dec)
                 .await?
                 .to_vec()?,
+            (Delete, ["node", "secure_channel_listener"]) => self
+                .delete_secure_channel_listener(req, dec)
+                .await?
+                .to_vec()?,
+            (Get, ["node", "show_secure_channel_listener"]) => self
+                .show_secure_channel_listener(req, dec)
+                .await?
+                .to_vec()?,
 
             // ==*== Services ==*==
             (Post, ["node", "services", "vault"]) => {
@@ -604,6 +675,9 @@ impl NodeManagerWorker {
             (Post, ["node", "services", "verifier"]) => {
                 self.start_verifier_service(ctx, req, dec).await?.to_vec()?
             }
+            (Post, ["node", "services", "prekeys"]) => {
+                self.start_prekey_service(ctx, req, dec).await?.to_vec()?
+            }
             (Post, ["node", "services", "credentials"]) => self
                 .start_credentials_service(ctx, req, dec)
                 .await?
@@ -612,26 +686,80 @@ impl NodeManagerWorker {
                 .start_okta_identity_provider_service(ctx, req, dec)
                 .await?
                 .to_vec()?,
+            (Post, ["node", "services", "oidc_identity_provider"]) => self
+                .start_oidc_identity_provider_service(ctx, req, dec)
+                .await?
+                .to_vec()?,
             (Get, ["node", "services"]) => {
                 let node_manager = self.node_manager.read().await;
                 self.list_services(req, &node_manager.registry).to_vec()?
             }
 
+            // ==*== Node API users ==*==
+            (Get, ["node", "access"]) => {
+                let node_manager = self.node_manager.read().await;
+                self.list_api_users(req, &node_manager).to_vec()?
+            }
+            (Post, ["node", "access"]) => {
+                let node_manager = self.node_manager.read().await;
+                self.add_api_user(req, dec, &node_manager)?.to_vec()?
+            }
+            (Delete, ["node", "access", identity]) => {
+                let node_manager = self.node_manager.read().await;
+                self.remove_api_user(req, &node_manager, identity)?
+            }
+
             // ==*== Forwarder commands ==*==
+            (Get, ["node", "forwarder"]) => {
+                let node_manager = self.node_manager.read().await;
+                self.get_forwarders(req, &node_manager).to_vec()?
+            }
+            (Get, ["node", "forwarder", remote_address]) => {
+                let node_manager = self.node_manager.read().await;
+                self.get_forwarder(req, &node_manager, remote_address)
+                    .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?
+            }
             (Post, ["node", "forwarder"]) => self.create_forwarder(ctx, req.id(), dec).await?,
+            (Delete, ["node", "forwarder", remote_address]) => self
+                .delete_forwarder(ctx, req, remote_address)
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
 
             // ==*== Inlets & Outlets ==*==
             (Get, ["node", "inlet"]) => {
                 let node_manager = self.node_manager.read().await;
-                self.get_inlets(req, &node_manager.registry).to_vec()?
+                self.get_inlets(req, &node_manager).to_vec()?
+            }
+            (Get, ["node", "inlet", alias]) => {
+                let node_manager = self.node_manager.read().await;
+                self.get_inlet(req, &node_manager, alias)
+                    .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?
             }
             (Get, ["node", "outlet"]) => {
                 let node_manager = self.node_manager.read().await;
-                self.get_outlets(req, &node_manager.registry).to_vec()?
+                self.get_outlets(req, &node_manager).to_vec()?
+            }
+            (Get, ["node", "outlet", alias]) => {
+                let node_manager = self.node_manager.read().await;
+                self.get_outlet(req, &node_manager, alias)
+                    .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?
             }
             (Post, ["node", "inlet"]) => self.create_inlet(req, dec).await?.to_vec()?,
             (Post, ["node", "outlet"]) => self.create_outlet(req, dec).await?.to_vec()?,
-            (Delete, ["node", "portal"]) => todo!(),
+            (Delete, ["node", "inlet", alias]) => self
+                .delete_inlet(req, alias)
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
+            (Delete, ["node", "outlet", alias]) => self
+                .delete_outlet(req, alias)
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
+            (Delete, ["node", "portal"]) => {
+                let err = Error::new(path)
+                    .with_method(Delete)
+                    .with_message("use DELETE /node/inlet/{alias} or /node/outlet/{alias}");
+                Response::not_implemented(req.id()).body(err).to_vec()?
+            }
 
             (Post, ["policy", resource, action]) => self
                 .node_manager
@@ -737,17 +865,28 @@ impl Worker for NodeManagerWorker {
     type Context = Context;
 
     async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        // API responses must not queue behind application traffic
+        ctx.set_priority(Priority::Control);
+
         let mut node_manger = self.node_manager.write().await;
         if !node_manger.skip_defaults {
             node_manger.initialize_defaults(ctx).await?;
         }
 
+        if node_manger.enable_credential_checks {
+            let refresher = CredentialRefresher::new(self.node_manager.clone());
+            self.credential_refresher = Some(tokio::spawn(refresher.start()));
+        }
+
         Ok(())
     }
 
     async fn shutdown(&mut self, _: &mut Self::Context) -> Result<()> {
         let node_manager = self.node_manager.read().await;
         node_manager.medic.abort();
+        if let Some(refresher) = &self.credential_refresher {
+            refresher.abort();
+        }
         Ok(())
     }
 
@@ -761,23 +900,43 @@ impl Worker for NodeManagerWorker {
             }
         };
 
-        let r = match self.handle_request(ctx, &req, &mut dec).await {
-            Ok(r) => r,
+        let relay = RelayMessage::new(
+            msg.sender(),
+            msg.msg_addr(),
+            msg.local_message().clone(),
+            msg.onward_route(),
+            false,
+        );
+        let allowed = match self.access.is_allowed(&relay, &req).await {
+            Ok(allowed) => allowed,
             Err(err) => {
-                error! {
-                    target: TARGET,
-                    re     = %req.id(),
-                    method = ?req.method(),
-                    path   = %req.path(),
-                    code   = %err.code(),
-                    cause  = ?err.source(),
-                    "failed to handle request"
+                warn!(target: TARGET, path = %req.path(), %err, "failed to authorize request");
+                false
+            }
+        };
+
+        let r = if !allowed {
+            warn!(target: TARGET, re = %req.id(), path = %req.path(), "request not authorized");
+            api::forbidden(&req, "not authorized").to_vec()?
+        } else {
+            match self.handle_request(ctx, &req, &mut dec).await {
+                Ok(r) => r,
+                Err(err) => {
+                    error! {
+                        target: TARGET,
+                        re     = %req.id(),
+                        method = ?req.method(),
+                        path   = %req.path(),
+                        code   = %err.code(),
+                        cause  = ?err.source(),
+                        "failed to handle request"
+                    }
+                    let err = Error::new(req.path())
+                        .with_message(format!("failed to handle request: {err}"));
+                    Response::builder(req.id(), Status::InternalServerError)
+                        .body(err)
+                        .to_vec()?
                 }
-                let err =
-                    Error::new(req.path()).with_message(format!("failed to handle request: {err}"));
-                Response::builder(req.id(), Status::InternalServerError)
-                    .body(err)
-                    .to_vec()?
             }
         };
         debug! {
@@ -800,8 +959,18 @@ pub(crate) mod tests {
 
     impl NodeManager {
         pub(crate) async fn test_create(ctx: &Context) -> Result<Route> {
-            let node_dir = tempfile::tempdir().unwrap();
+            let node_man = Self::test_node_manager(ctx).await?;
             let node_manager = "manager";
+            let node_manager_worker = NodeManagerWorker::new(node_man);
+
+            // Initialize node_man worker and return its route
+            ctx.start_worker(node_manager, node_manager_worker).await?;
+            Ok(route![node_manager])
+        }
+
+        /// A node manager with a vault and an identity, without its worker
+        pub(crate) async fn test_node_manager(ctx: &Context) -> Result<NodeManager> {
+            let node_dir = tempfile::tempdir().unwrap();
             let transport = TcpTransport::create(ctx).await?;
             let node_address = transport.listen("127.0.0.1:0").await?;
             let mut node_man = NodeManager::create(
@@ -827,12 +996,7 @@ pub(crate) mod tests {
             // Initialize identity
             node_man.create_vault_impl(None, false).await?;
             node_man.create_identity_impl(ctx, false).await?;
-
-            let node_manager_worker = NodeManagerWorker::new(node_man);
-
-            // Initialize node_man worker and return its route
-            ctx.start_worker(node_manager, node_manager_worker).await?;
-            Ok(route![node_manager])
+            Ok(node_man)
         }
     }
 }
@@ -0,0 +1,478 @@
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+
+use minicbor::Decoder;
+use ockam::tcp::TcpLocalInfo;
+use ockam_abac::expr::{eq, ident, or, str};
+use ockam_abac::{Action, Env, Expr, PolicyAccessControl, PolicyStorage, Resource};
+use ockam_core::api::{self, Method, Request, Response, ResponseBuilder};
+use ockam_core::{async_trait, AccessControl, Decodable, RelayMessage, Result};
+use ockam_identity::{IdentityIdentifier, IdentitySecureChannelLocalInfo};
+use ockam_node::ExternalLocalInfo;
+
+use crate::lmdb::LmdbStorage;
+use crate::nodes::models::access::{ApiRole, ApiUser, ApiUserList};
+use crate::nodes::service::{map_anyhow_err, not_found};
+use crate::vault::models::{GetSecretRequest, GetSecretRequestOperation};
+use crate::{actions, resources};
+
+use super::{NodeManager, NodeManagerWorker};
+
+/// Roles of the identities allowed to use the node API.
+pub(crate) type ApiUsers = Arc<RwLock<BTreeMap<IdentityIdentifier, ApiRole>>>;
+
+/// Authorizes requests to the node manager and the services it starts.
+///
+/// Requests from the node itself, or from the local CLI through the API
+/// listener of the node, are always allowed. Other requests are checked
+/// against the policy of their resource and action, which defaults to only
+/// allowing admins to make changes or export secrets, and admins or readers
+/// to look. Requests
+/// which did not arrive over a secure channel have no identity, so only
+/// policies allowing everyone let them through.
+#[derive(Debug, Clone)]
+pub struct ApiAccessControl {
+    policies: LmdbStorage,
+    attributes: LmdbStorage,
+    users: ApiUsers,
+    resource: Resource,
+    /// Address of the API listener of the node
+    api_addr: Option<SocketAddr>,
+}
+
+impl ApiAccessControl {
+    pub(crate) fn new(
+        policies: LmdbStorage,
+        attributes: LmdbStorage,
+        users: ApiUsers,
+        api_addr: Option<SocketAddr>,
+    ) -> Self {
+        Self {
+            policies,
+            attributes,
+            users,
+            resource: resources::NODE,
+            api_addr,
+        }
+    }
+
+    /// Use this access control for a service, under the given resource.
+    pub(crate) fn for_service(&self, resource: Resource) -> Arc<dyn AccessControl> {
+        Arc::new(Self {
+            resource,
+            ..self.clone()
+        })
+    }
+
+    /// Check if the sender of `msg` may send the request `req` to the
+    /// node manager.
+    pub(crate) async fn is_allowed(&self, msg: &RelayMessage, req: &Request<'_>) -> Result<bool> {
+        let r = api_resource(req.path_segments::<5>().as_slice());
+        self.authorize(msg, r, api_action(req)).await
+    }
+
+    /// Check if the sender of `msg` may perform the action `a` on `r`.
+    async fn authorize(&self, msg: &RelayMessage, r: Resource, a: Action) -> Result<bool> {
+        let id = match IdentitySecureChannelLocalInfo::find_info(&msg.local_msg) {
+            Ok(info) => Some(info.their_identity_id().clone()),
+            Err(_) if self.is_local(msg) => return Ok(true),
+            Err(_) => None,
+        };
+
+        if self.policies.get_policy(&r, &a).await?.is_none() {
+            self.policies
+                .set_policy(&r, &a, &default_policy(&r, &a))
+                .await?
+        }
+
+        let mut env = Env::new();
+        env.put("resource.id", str(r.as_str()));
+        env.put("action.id", str(a.as_str()));
+        if let Some(id) = &id {
+            env.put("subject.identifier", str(id.to_string()));
+            if let Some(role) = self.users.read().unwrap().get(id) {
+                env.put("subject.api_role", str(role.to_string()));
+            }
+        }
+
+        let mut ac =
+            PolicyAccessControl::new(self.policies.clone(), self.attributes.clone(), r, a, env);
+        ac.allow_missing_attributes();
+        ac.is_authorized(msg).await
+    }
+
+    /// Check if `msg` was sent by the node itself, or by a local process
+    /// connected to the API listener.
+    ///
+    /// Messages received over other TCP connections, e.g. through a
+    /// listener created by the user, may come from anywhere.
+    fn is_local(&self, msg: &RelayMessage) -> bool {
+        if ExternalLocalInfo::find_info(&msg.local_msg).is_err() {
+            return true;
+        }
+        match (TcpLocalInfo::find_info(&msg.local_msg), self.api_addr) {
+            (Ok(info), Some(api)) => {
+                info.local_addr().port() == api.port() && info.peer_addr().ip().is_loopback()
+            }
+            _ => false,
+        }
+    }
+}
+
+#[async_trait]
+impl AccessControl for ApiAccessControl {
+    async fn is_authorized(&self, msg: &RelayMessage) -> Result<bool> {
+        if IdentitySecureChannelLocalInfo::find_info(&msg.local_msg).is_err() && self.is_local(msg)
+        {
+            return Ok(true);
+        }
+        let body = Vec::<u8>::decode(&msg.local_msg.transport().payload)?;
+        let mut dec = Decoder::new(&body);
+        let req: Request = match dec.decode() {
+            Ok(req) => req,
+            Err(_) => return Ok(false),
+        };
+        let a = service_action(&self.resource, &req, &mut dec);
+        self.authorize(msg, self.resource.clone(), a).await
+    }
+}
+
+/// The resource of a node manager route, e.g. `node:tcp:listener`.
+fn api_resource(segments: &[&str]) -> Resource {
+    match segments {
+        ["node"] => resources::NODE,
+        ["node", group @ ("tcp" | "services"), kind, ..] => {
+            Resource::new(&format!("node:{group}:{kind}"))
+        }
+        ["node", kind, ..] => Resource::new(&format!("node:{kind}")),
+        ["policy", ..] => Resource::new("node:policy"),
+        // Requests the node makes to Ockam Orchestrator on behalf of the caller:
+        _ => Resource::new("node:cloud"),
+    }
+}
+
+/// The action of a request: reading if it does not change anything.
+fn api_action(req: &Request) -> Action {
+    match (req.method(), req.path_segments::<5>().as_slice()) {
+        (Some(Method::Get), _) | (_, ["node", _, "actions", "show", ..]) => actions::READ,
+        _ => actions::WRITE,
+    }
+}
+
+/// The action of a request to a service.
+///
+/// Exporting a secret hands key material to the caller, so it is not a
+/// mere read. Other vault requests using secrets, like signing, are posts
+/// and thus writes.
+fn service_action(r: &Resource, req: &Request, dec: &mut Decoder) -> Action {
+    if r.as_str() == "service:vault" && matches!(req.method(), Some(Method::Get)) {
+        if let ["secrets", _] = req.path_segments::<3>().as_slice() {
+            match dec.decode::<GetSecretRequest>() {
+                Ok(args) if args.operation() == GetSecretRequestOperation::GetAttributes => {}
+                _ => return actions::EXPORT,
+            }
+        }
+    }
+    api_action(req)
+}
+
+fn default_policy(r: &Resource, a: &Action) -> Expr {
+    let role = |name| eq([ident("subject.api_role"), str(name)]);
+    match r.as_str() {
+        // These services do their own checks and must be reachable by
+        // project members.
+        "service:authenticator" | "service:verifier" => Expr::Bool(true),
+        _ if *a == actions::READ => or([role("admin"), role("reader")]),
+        _ => role("admin"),
+    }
+}
+
+impl NodeManager {
+    pub fn api_access_control(&self) -> ApiAccessControl {
+        let api_addr = self
+            .transports
+            .get(&self.api_transport_id)
+            .and_then(|(_, _, addr)| addr.parse().ok());
+        ApiAccessControl::new(
+            self.policies.clone(),
+            self.authenticated_storage.clone(),
+            self.api_users.clone(),
+            api_addr,
+        )
+    }
+
+    /// Allow an identity to use the node API, or change its role.
+    pub fn add_api_user(&self, id: IdentityIdentifier, role: ApiRole) -> Result<()> {
+        self.api_users.write().unwrap().insert(id.clone(), role);
+        let state = self.config.state();
+        state.write().api_users.insert(id, role);
+        state.persist_config_updates().map_err(map_anyhow_err)
+    }
+
+    fn remove_api_user(&self, id: &IdentityIdentifier) -> Result<Option<ApiRole>> {
+        let role = self.api_users.write().unwrap().remove(id);
+        let state = self.config.state();
+        state.write().api_users.remove(id);
+        state.persist_config_updates().map_err(map_anyhow_err)?;
+        Ok(role)
+    }
+}
+
+impl NodeManagerWorker {
+    pub(super) fn list_api_users(
+        &self,
+        req: &Request,
+        node_manager: &NodeManager,
+    ) -> ResponseBuilder<ApiUserList> {
+        let list = node_manager
+            .api_users
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(id, role)| ApiUser::new(id.clone(), *role))
+            .collect();
+        Response::ok(req.id()).body(ApiUserList::new(list))
+    }
+
+    pub(super) fn add_api_user(
+        &self,
+        req: &Request,
+        dec: &mut Decoder<'_>,
+        node_manager: &NodeManager,
+    ) -> Result<ResponseBuilder> {
+        let user: ApiUser = dec.decode()?;
+        info!(identity = %user.identity, role = %user.role, "Allowing identity to use the node API");
+        node_manager.add_api_user(user.identity, user.role)?;
+        Ok(Response::ok(req.id()))
+    }
+
+    pub(super) fn remove_api_user(
+        &self,
+        req: &Request,
+        node_manager: &NodeManager,
+        id: &str,
+    ) -> Result<Vec<u8>> {
+        let id = match IdentityIdentifier::try_from(id) {
+            Ok(id) => id,
+            Err(_) => return Ok(api::bad_request(req, "invalid identity identifier").to_vec()?),
+        };
+        match node_manager.remove_api_user(&id)? {
+            Some(_) => {
+                info!(identity = %id, "Removed identity from the node API users");
+                Ok(Response::ok(req.id()).to_vec()?)
+            }
+            None => Ok(not_found(req, "identity is not a node API user").to_vec()?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use minicbor::{Decoder, Encode};
+
+    use ockam::{Context, TCP};
+    use ockam_core::api::{Request, RequestBuilder, Response, Status};
+    use ockam_core::{route, Result, Route};
+
+    use ockam::authenticated_storage::InMemoryStorage;
+    use ockam::identity::{Identity, TrustEveryonePolicy};
+    use ockam_core::vault::{SecretAttributes, SecretPersistence, SecretType};
+    use ockam_core::Address;
+    use ockam_identity::IdentityIdentifier;
+    use ockam_vault::Vault;
+
+    use crate::nodes::models::access::{ApiRole, ApiUser, ApiUserList};
+    use crate::nodes::models::secure_channel::CreateSecureChannelListenerRequest;
+    use crate::nodes::models::services::StartVaultServiceRequest;
+    use crate::nodes::models::transport::{
+        CreateTransport, TransportList, TransportMode, TransportType,
+    };
+    use crate::nodes::NodeManager;
+    use crate::vault::models::{
+        CreateSecretRequest, CreateSecretResponse, GetSecretRequest, GetSecretRequestOperation,
+        SignRequest,
+    };
+
+    async fn call<T: Encode<()>>(
+        ctx: &Context,
+        node: &Route,
+        req: RequestBuilder<'_, T>,
+    ) -> Result<Vec<u8>> {
+        let mut buf = vec![];
+        req.encode(&mut buf)?;
+        ctx.send_and_receive(node.clone(), buf).await
+    }
+
+    fn status(response: &[u8]) -> Result<Option<Status>> {
+        let header: Response = Decoder::new(response).decode()?;
+        Ok(header.status())
+    }
+
+    async fn list(ctx: &Context, node: &Route) -> Result<Vec<(String, ApiRole)>> {
+        let res = call(ctx, node, Request::get("/node/access")).await?;
+        let mut dec = Decoder::new(&res);
+        let header: Response = dec.decode()?;
+        assert_eq!(header.status(), Some(Status::Ok));
+        let body: ApiUserList = dec.decode()?;
+        Ok(body
+            .list
+            .into_iter()
+            .map(|u| (u.identity.to_string(), u.role))
+            .collect())
+    }
+
+    #[ockam_macros::test]
+    async fn manage_api_users(ctx: &mut Context) -> Result<()> {
+        let node = NodeManager::test_create(ctx).await?;
+        let id = "P6c20e814b56579306f55c64e8747e6c1b4a53d9a3f4ca83c252cc2fbfc72fa94";
+        let identity = IdentityIdentifier::try_from(id)?;
+
+        let user = ApiUser::new(identity.clone(), ApiRole::Reader);
+        let res = call(ctx, &node, Request::post("/node/access").body(user)).await?;
+        assert_eq!(status(&res)?, Some(Status::Ok));
+        assert_eq!(
+            list(ctx, &node).await?,
+            vec![(id.to_string(), ApiRole::Reader)]
+        );
+
+        // Adding the identity again changes its role:
+        let user = ApiUser::new(identity, ApiRole::Admin);
+        let res = call(ctx, &node, Request::post("/node/access").body(user)).await?;
+        assert_eq!(status(&res)?, Some(Status::Ok));
+        assert_eq!(
+            list(ctx, &node).await?,
+            vec![(id.to_string(), ApiRole::Admin)]
+        );
+
+        let res = call(ctx, &node, Request::delete(format!("/node/access/{id}"))).await?;
+        assert_eq!(status(&res)?, Some(Status::Ok));
+        assert!(list(ctx, &node).await?.is_empty());
+
+        let res = call(ctx, &node, Request::delete(format!("/node/access/{id}"))).await?;
+        assert_eq!(status(&res)?, Some(Status::NotFound));
+        let res = call(ctx, &node, Request::delete("/node/access/nope")).await?;
+        assert_eq!(status(&res)?, Some(Status::BadRequest));
+
+        ctx.stop().await
+    }
+
+    /// Send a request to the node manager over a TCP connection to `addr`.
+    async fn call_over_tcp(ctx: &Context, node: &Route, addr: &str) -> Result<Vec<u8>> {
+        let body = CreateTransport::new(TransportType::Tcp, TransportMode::Connect, addr);
+        let res = call(ctx, node, Request::post("/node/tcp/connection").body(body)).await?;
+        assert_eq!(status(&res)?, Some(Status::Ok));
+        call(
+            ctx,
+            &route![(TCP, addr), "manager"],
+            Request::get("/node/access"),
+        )
+        .await
+    }
+
+    #[ockam_macros::test]
+    async fn plain_tcp_requests_are_denied(ctx: &mut Context) -> Result<()> {
+        let node = NodeManager::test_create(ctx).await?;
+
+        let res = call(ctx, &node, Request::get("/node/tcp/listener")).await?;
+        let mut dec = Decoder::new(&res);
+        let _: Response = dec.decode()?;
+        let listeners: TransportList = dec.decode()?;
+        let api_addr = listeners.list[0].payload.to_string();
+
+        // A listener created by the user may be reachable by anyone
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let addr = format!("127.0.0.1:{port}");
+        let body = CreateTransport::new(TransportType::Tcp, TransportMode::Listen, addr.as_str());
+        let res = call(ctx, &node, Request::post("/node/tcp/listener").body(body)).await?;
+        assert_eq!(status(&res)?, Some(Status::Ok));
+
+        let res = call_over_tcp(ctx, &node, &addr).await?;
+        assert_eq!(status(&res)?, Some(Status::Forbidden));
+
+        // The API listener is how the CLI talks to the node
+        let res = call_over_tcp(ctx, &node, &api_addr).await?;
+        assert_eq!(status(&res)?, Some(Status::Ok));
+
+        ctx.stop().await
+    }
+
+    async fn set_role(ctx: &Context, node: &Route, id: &IdentityIdentifier, role: ApiRole) {
+        let user = ApiUser::new(id.clone(), role);
+        let res = call(ctx, node, Request::post("/node/access").body(user))
+            .await
+            .unwrap();
+        assert_eq!(status(&res).unwrap(), Some(Status::Ok));
+    }
+
+    /// Send a request to a service, which drops the requests it does not
+    /// authorize, so `None` is returned if no reply arrives in time.
+    async fn call_service<T: Encode<()>>(
+        ctx: &Context,
+        service: &Route,
+        req: RequestBuilder<'_, T>,
+    ) -> Option<Vec<u8>> {
+        let mut buf = vec![];
+        req.encode(&mut buf).unwrap();
+        ctx.send_and_receive_with_timeout(service.clone(), buf, 2)
+            .await
+            .ok()
+    }
+
+    #[ockam_macros::test]
+    async fn vault_secrets_are_only_exported_to_admins(ctx: &mut Context) -> Result<()> {
+        let node = NodeManager::test_create(ctx).await?;
+        let body = StartVaultServiceRequest::new("vault", false);
+        let res = call(ctx, &node, Request::post("/node/services/vault").body(body)).await?;
+        assert_eq!(status(&res)?, Some(Status::Ok));
+        let body = CreateSecureChannelListenerRequest::new(&Address::from("api"), None);
+        let res = call(
+            ctx,
+            &node,
+            Request::post("/node/secure_channel_listener").body(body),
+        )
+        .await?;
+        assert_eq!(status(&res)?, Some(Status::Ok));
+
+        let identity = Identity::create(ctx, &Vault::create()).await?;
+        let storage = InMemoryStorage::new();
+        let channel = identity
+            .create_secure_channel(route!["api"], TrustEveryonePolicy, &storage)
+            .await?;
+        let vault = route![channel, "vault"];
+
+        set_role(ctx, &node, identity.identifier(), ApiRole::Admin).await;
+        let attributes =
+            SecretAttributes::new(SecretType::Ed25519, SecretPersistence::Ephemeral, 32);
+        let req = Request::post("secrets").body(CreateSecretRequest::new_generate(attributes));
+        let res = call_service(ctx, &vault, req).await.unwrap();
+        let mut dec = Decoder::new(&res);
+        let header: Response = dec.decode()?;
+        assert_eq!(header.status(), Some(Status::Ok));
+        let key_id = dec.decode::<CreateSecretResponse>()?.key_id().to_string();
+        let secret = format!("secrets/{key_id}");
+
+        // Readers may look at a secret, but neither export it nor sign with it
+        set_role(ctx, &node, identity.identifier(), ApiRole::Reader).await;
+        let op = GetSecretRequest::new(GetSecretRequestOperation::GetAttributes);
+        let res = call_service(ctx, &vault, Request::get(&secret).body(op)).await;
+        assert_eq!(status(&res.unwrap())?, Some(Status::Ok));
+        let op = GetSecretRequest::new(GetSecretRequestOperation::GetSecretBytes);
+        let res = call_service(ctx, &vault, Request::get(&secret).body(op)).await;
+        assert!(res.is_none());
+        let sign = SignRequest::new(key_id.as_str(), b"data".as_slice());
+        let res = call_service(ctx, &vault, Request::post("sign").body(sign)).await;
+        assert!(res.is_none());
+
+        set_role(ctx, &node, identity.identifier(), ApiRole::Admin).await;
+        let op = GetSecretRequest::new(GetSecretRequestOperation::GetSecretBytes);
+        let res = call_service(ctx, &vault, Request::get(&secret).body(op)).await;
+        assert_eq!(status(&res.unwrap())?, Some(Status::Ok));
+
+        ctx.stop().await
+    }
+}
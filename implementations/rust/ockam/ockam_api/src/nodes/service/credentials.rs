@@ -16,6 +16,11 @@ use std::str::FromStr;
 
 use super::NodeManagerWorker;
 
+mod refresh;
+
+pub use refresh::CredentialEvent;
+pub(crate) use refresh::CredentialRefresher;
+
 impl NodeManager {
     pub(super) async fn get_credential_impl(&mut self, overwrite: bool) -> Result<()> {
         debug!("Credential check: looking for identity");
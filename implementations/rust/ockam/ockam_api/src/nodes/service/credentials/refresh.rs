@@ -0,0 +1,469 @@
+//! Renewal of the node credential before it expires
+
+use crate::authenticator::direct::Client;
+use crate::error::ApiError;
+use crate::lmdb::LmdbStorage;
+use crate::multiaddr_to_route;
+use crate::nodes::models::secure_channel::CredentialExchangeMode;
+use crate::nodes::NodeManager;
+use crate::DefaultAddress;
+use ockam::compat::asynchronous::RwLock;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{route, Address, AsyncTryClone, Result, Route};
+use ockam_identity::credential::{CredentialData, Timestamp, Unverified};
+use ockam_identity::{Identity, IdentityIdentifier, PublicIdentity, TrustMultiIdentifiersPolicy};
+use ockam_node::tokio;
+use ockam_vault::Vault;
+use std::time::Duration;
+
+/// How long to wait before looking at the credential again when there
+/// is nothing to renew yet.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Delay before the first retry of a failed renewal, doubled after each
+/// further failure.
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Maximum delay between two renewal attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// How long to wait for a secure channel to the authority.
+const CHANNEL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Events about the renewal of the node credential.
+#[derive(Debug, Clone)]
+pub enum CredentialEvent {
+    /// A new credential was obtained from the authority.
+    Renewed { expires: Timestamp },
+    /// The credential could not be renewed, another attempt will be made.
+    RenewalFailed {
+        attempts: u32,
+        expired: bool,
+        error: String,
+    },
+    /// The new credential could not be presented over a secure channel.
+    PresentationFailed { channel: Address, error: String },
+}
+
+/// Background task asking the authority for a new credential once most
+/// of the validity of the current one has elapsed.
+pub(crate) struct CredentialRefresher {
+    node_manager: Arc<RwLock<NodeManager>>,
+    channel_timeout: Duration,
+}
+
+impl CredentialRefresher {
+    pub(crate) fn new(node_manager: Arc<RwLock<NodeManager>>) -> Self {
+        Self {
+            node_manager,
+            channel_timeout: CHANNEL_TIMEOUT,
+        }
+    }
+
+    pub(crate) async fn start(self) {
+        let mut attempts = 0;
+        loop {
+            let validity = self.node_manager.read().await.credential_validity().await;
+            let (now, created, expires) = match (Timestamp::now(), validity) {
+                (Some(now), Some((created, expires))) => (now, created, expires),
+                _ => {
+                    tokio::time::sleep(CHECK_INTERVAL).await;
+                    continue;
+                }
+            };
+
+            if attempts == 0 {
+                let delay = renewal_delay(now.into(), created.into(), expires.into());
+                if !delay.is_zero() {
+                    // The credential may be replaced in the meantime, so
+                    // don't wait too long before looking at it again.
+                    tokio::time::sleep(delay.min(CHECK_INTERVAL)).await;
+                    continue;
+                }
+            }
+
+            match self.renew_credential().await {
+                Ok(expires) => {
+                    attempts = 0;
+                    info!(?expires, "Renewed the node credential");
+                    self.send_credential_event(CredentialEvent::Renewed { expires })
+                        .await;
+                }
+                Err(err) => {
+                    attempts += 1;
+                    let expired = expires <= now;
+                    warn!(%err, attempts, expired, "Failed to renew the node credential");
+                    self.send_credential_event(CredentialEvent::RenewalFailed {
+                        attempts,
+                        expired,
+                        error: err.to_string(),
+                    })
+                    .await;
+                    tokio::time::sleep(backoff(attempts)).await;
+                }
+            }
+        }
+    }
+
+    /// Get a new credential and present it again over the secure
+    /// channels the previous one was presented on.
+    ///
+    /// The node manager is only locked to copy what is needed and to
+    /// record the results, not while talking to other nodes.
+    async fn renew_credential(&self) -> Result<Timestamp> {
+        let renewal = self.node_manager.read().await.renewal().await?;
+
+        let channel = match renewal.channel.clone() {
+            Some(channel) => channel,
+            None => {
+                let channel = renewal
+                    .identity
+                    .create_secure_channel_extended(
+                        renewal.authority_route.clone(),
+                        TrustMultiIdentifiersPolicy::new(vec![renewal.authority.clone()]),
+                        &renewal.storage,
+                        self.channel_timeout,
+                    )
+                    .await?;
+                self.node_manager
+                    .write()
+                    .await
+                    .registry
+                    .secure_channels
+                    .insert(
+                        channel.clone(),
+                        renewal.authority_route.clone(),
+                        Some(vec![renewal.authority.clone()]),
+                    );
+                channel
+            }
+        };
+
+        if let Err(err) = renewal.get_credential(&channel).await {
+            // The channel may be dead, the next attempt creates a new one
+            self.node_manager
+                .write()
+                .await
+                .registry
+                .secure_channels
+                .remove_by_addr(&channel);
+            if let Err(err) = renewal.identity.stop_secure_channel(&channel).await {
+                debug!(%err, %channel, "Failed to stop the secure channel to the authority");
+            }
+            return Err(err);
+        }
+
+        let (channels, expires) = {
+            let node_manager = self.node_manager.read().await;
+            let channels: Vec<(Address, CredentialExchangeMode)> = node_manager
+                .registry
+                .secure_channels
+                .list()
+                .iter()
+                .map(|c| (c.addr().clone(), c.credential_exchange_mode()))
+                .collect();
+            let (_, expires) = node_manager
+                .credential_validity()
+                .await
+                .ok_or_else(|| ApiError::generic("credential is invalid"))?;
+            (channels, expires)
+        };
+
+        for (addr, mode) in channels {
+            let route = route![addr.clone(), DefaultAddress::CREDENTIAL_SERVICE];
+            let res = match mode {
+                CredentialExchangeMode::None => continue,
+                CredentialExchangeMode::Oneway => renewal.identity.present_credential(route).await,
+                CredentialExchangeMode::Mutual => {
+                    renewal
+                        .identity
+                        .present_credential_mutual(route, &renewal.authorities, &renewal.storage)
+                        .await
+                }
+            };
+            if let Err(err) = res {
+                warn!(%err, %addr, "Failed to present the renewed credential");
+                self.send_credential_event(CredentialEvent::PresentationFailed {
+                    channel: addr,
+                    error: err.to_string(),
+                })
+                .await;
+            }
+        }
+
+        Ok(expires)
+    }
+
+    async fn send_credential_event(&self, event: CredentialEvent) {
+        self.node_manager.read().await.send_credential_event(event)
+    }
+}
+
+/// What a renewal needs from the node manager.
+struct Renewal {
+    identity: Identity<Vault>,
+    authority: IdentityIdentifier,
+    authority_route: Route,
+    authorities: Vec<PublicIdentity>,
+    storage: LmdbStorage,
+    /// The secure channel to the authority, if there is one already
+    channel: Option<Address>,
+}
+
+impl Renewal {
+    /// Get a new credential from the authority and make it the node
+    /// credential.
+    async fn get_credential(&self, channel: &Address) -> Result<()> {
+        let mut client = Client::new(
+            route![channel.clone(), DefaultAddress::AUTHENTICATOR],
+            self.identity.ctx(),
+        )
+        .await?;
+        let credential = client.credential().await?;
+        self.identity
+            .verify_self_credential(&credential, self.authorities.iter())
+            .await?;
+        self.identity
+            .set_credential(Some(credential.to_owned()))
+            .await;
+        Ok(())
+    }
+}
+
+impl NodeManager {
+    /// Return when the node credential was created and when it expires.
+    async fn credential_validity(&self) -> Option<(Timestamp, Timestamp)> {
+        let credential = self.identity().ok()?.credential().await?;
+        let data = CredentialData::<Unverified>::try_from(&credential).ok()?;
+        Some((data.unverified_created_at(), data.unverified_expires_at()))
+    }
+
+    async fn renewal(&self) -> Result<Renewal> {
+        let authorities = self.authorities()?;
+        let authority = authorities
+            .as_ref()
+            .first()
+            .ok_or_else(|| ApiError::generic("No known Authority"))?;
+        let authority_route = multiaddr_to_route(&authority.addr)
+            .ok_or_else(|| ApiError::generic("invalid authority route"))?;
+        let channel = self
+            .registry
+            .secure_channels
+            .get_by_route(&authority_route)
+            .map(|c| c.addr().clone());
+        Ok(Renewal {
+            identity: self.identity()?.async_try_clone().await?,
+            authority: authority.identity.identifier().clone(),
+            authority_route,
+            authorities: authorities.public_identities(),
+            storage: self.authenticated_storage.clone(),
+            channel,
+        })
+    }
+
+    fn send_credential_event(&self, event: CredentialEvent) {
+        // Nobody may be listening, which is fine
+        let _ = self.credential_events.send(event);
+    }
+}
+
+/// Time to wait before renewing a credential, so that it is renewed once
+/// 80% of its validity has elapsed.
+fn renewal_delay(now: u64, created: u64, expires: u64) -> Duration {
+    let margin = expires.saturating_sub(created) / 5;
+    Duration::from_secs(expires.saturating_sub(margin).saturating_sub(now))
+}
+
+/// Delay before the next attempt after the given number of failures.
+fn backoff(attempts: u32) -> Duration {
+    let factor = 1 << attempts.saturating_sub(1).min(16);
+    MIN_BACKOFF.saturating_mul(factor).min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authenticator::direct::{self, project_member_schema, PROJECT_ID};
+    use crate::authenticator::direct::{PROJECT_MEMBER_SCHEMA, ROLE};
+    use crate::nodes::service::{Authorities, AuthorityInfo};
+    use ockam_identity::authenticated_storage::mem::InMemoryStorage;
+    use ockam_identity::authenticated_storage::AuthenticatedStorage;
+    use ockam_identity::credential::{AttributesStorageUtils, Credential};
+    use ockam_identity::TrustEveryonePolicy;
+    use ockam_multiaddr::MultiAddr;
+    use ockam_node::tokio::sync::broadcast;
+    use ockam_node::tokio::time::{timeout, Instant};
+    use ockam_node::Context;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    #[test]
+    fn renewal_happens_before_expiry() {
+        assert_eq!(renewal_delay(0, 0, 100), Duration::from_secs(80));
+        assert_eq!(renewal_delay(50, 0, 100), Duration::from_secs(30));
+        assert_eq!(renewal_delay(90, 0, 100), Duration::ZERO);
+        assert_eq!(renewal_delay(200, 0, 100), Duration::ZERO);
+    }
+
+    #[test]
+    fn backoff_grows_up_to_a_maximum() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(5), Duration::from_secs(16));
+        assert_eq!(backoff(10), MAX_BACKOFF);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[ockam_macros::test]
+    async fn renewed_credential_is_presented_again(ctx: &mut Context) -> Result<()> {
+        let authority = authority(ctx).await?;
+        authority
+            .create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+            .await?;
+        let node_manager = member_node(ctx, &authority, "/service/api").await?;
+        let member = node_manager.read().await.identity()?.identifier().clone();
+
+        // The authority gives the member a new role, which tells the
+        // renewed credential from the initial one
+        let store = InMemoryStorage::new();
+        let attrs = HashMap::from([(ROLE.to_string(), "renewed".to_string())]);
+        store
+            .set(
+                member.key_id(),
+                "member".to_string(),
+                minicbor::to_vec(attrs)?,
+            )
+            .await?;
+        let server = direct::Server::new(
+            b"project42".to_vec(),
+            store,
+            "enrollers.json",
+            authority.async_try_clone().await?,
+        );
+        ctx.start_worker(DefaultAddress::AUTHENTICATOR, server)
+            .await?;
+
+        // A peer which got the initial credential over a secure channel
+        let peer = Identity::create(ctx, &Vault::create()).await?;
+        peer.schemas().register(project_member_schema());
+        let peer_storage = InMemoryStorage::new();
+        peer.create_secure_channel_listener("peer", TrustEveryonePolicy, &peer_storage)
+            .await?;
+        peer.start_credentials_exchange_worker(
+            vec![authority.to_public().await?],
+            DefaultAddress::CREDENTIAL_SERVICE,
+            false,
+            peer_storage.clone(),
+        )
+        .await?;
+        {
+            let mut node_manager = node_manager.write().await;
+            let channel = node_manager
+                .identity()?
+                .create_secure_channel(
+                    route!["peer"],
+                    TrustEveryonePolicy,
+                    &node_manager.authenticated_storage,
+                )
+                .await?;
+            let channels = &mut node_manager.registry.secure_channels;
+            channels.insert(channel.clone(), route!["peer"], None);
+            channels.set_credential_exchange_mode(&channel, CredentialExchangeMode::Oneway);
+        }
+
+        let mut events = node_manager.read().await.credential_events();
+        let refresher = tokio::spawn(CredentialRefresher::new(node_manager.clone()).start());
+
+        let event = next_event(&mut events).await;
+        assert!(
+            matches!(event, CredentialEvent::Renewed { .. }),
+            "{event:?}"
+        );
+        let attrs = AttributesStorageUtils::get_attributes(&member, &peer_storage)
+            .await?
+            .expect("the peer got the renewed credential");
+        assert_eq!(attrs.get(ROLE), Some(&b"renewed".to_vec()));
+
+        refresher.abort();
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn renewal_is_retried_while_the_authority_is_unreachable(
+        ctx: &mut Context,
+    ) -> Result<()> {
+        let authority = authority(ctx).await?;
+        // Nothing listens at this address
+        let node_manager = member_node(ctx, &authority, "/service/nowhere").await?;
+
+        let mut events = node_manager.read().await.credential_events();
+        let channel_timeout = Duration::from_secs(1);
+        let refresher = CredentialRefresher {
+            node_manager: node_manager.clone(),
+            channel_timeout,
+        };
+        let refresher = tokio::spawn(refresher.start());
+
+        let event = next_event(&mut events).await;
+        assert!(
+            matches!(event, CredentialEvent::RenewalFailed { attempts: 1, .. }),
+            "{event:?}"
+        );
+        let failed_at = Instant::now();
+
+        // The next attempt waits for the backoff, then for the channel
+        let event = next_event(&mut events).await;
+        assert!(
+            matches!(
+                event,
+                CredentialEvent::RenewalFailed {
+                    attempts: 2,
+                    expired: true,
+                    ..
+                }
+            ),
+            "{event:?}"
+        );
+        assert!(failed_at.elapsed() >= backoff(1) + channel_timeout);
+
+        refresher.abort();
+        ctx.stop().await
+    }
+
+    async fn authority(ctx: &Context) -> Result<Identity<Vault>> {
+        let authority = Identity::create(ctx, &Vault::create()).await?;
+        authority.schemas().register(project_member_schema());
+        Ok(authority)
+    }
+
+    /// A member node which trusts `authority`, reachable at
+    /// `authority_addr`, and holds a credential from it expiring in a
+    /// second
+    async fn member_node(
+        ctx: &Context,
+        authority: &Identity<Vault>,
+        authority_addr: &str,
+    ) -> Result<Arc<RwLock<NodeManager>>> {
+        let mut node_manager = NodeManager::test_node_manager(ctx).await?;
+        node_manager.authorities = Some(Authorities::new(vec![AuthorityInfo {
+            identity: authority.to_public().await?,
+            addr: MultiAddr::from_str(authority_addr).unwrap(),
+        }]));
+        let identity = node_manager.identity()?;
+        let credential = Credential::builder(identity.identifier().clone())
+            .with_schema(PROJECT_MEMBER_SCHEMA)
+            .with_attribute(PROJECT_ID, b"project42")
+            .with_attribute(ROLE, b"initial")
+            .valid_for(Duration::from_secs(1));
+        let credential = authority.issue_credential(credential).await?;
+        identity.set_credential(Some(credential)).await;
+        Ok(Arc::new(RwLock::new(node_manager)))
+    }
+
+    async fn next_event(events: &mut broadcast::Receiver<CredentialEvent>) -> CredentialEvent {
+        timeout(Duration::from_secs(10), events.recv())
+            .await
+            .expect("no credential event")
+            .expect("credential events were dropped")
+    }
+}
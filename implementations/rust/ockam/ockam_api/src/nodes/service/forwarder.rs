@@ -1,26 +1,100 @@
 use std::sync::Arc;
 
+use either::Either;
 use minicbor::Decoder;
 
 use ockam::compat::asynchronous::RwLock;
 use ockam::remote::RemoteForwarder;
 use ockam::Result;
-use ockam_core::api::{Id, Response, Status};
-use ockam_core::AsyncTryClone;
+use ockam_core::api::{Error, Id, Request, Response, ResponseBuilder, Status};
+use ockam_core::{Address, AsyncTryClone};
 use ockam_identity::IdentityIdentifier;
 use ockam_multiaddr::MultiAddr;
 use ockam_node::tokio::time::timeout;
 use ockam_node::Context;
 
 use crate::error::ApiError;
-use crate::nodes::models::forwarder::{CreateForwarder, ForwarderInfo};
+use crate::nodes::models::forwarder::{CreateForwarder, ForwarderInfo, ForwarderList};
+use crate::nodes::models::portal::ConnectionStatus;
+use crate::nodes::registry::ForwarderRegistryInfo;
+use crate::nodes::service::not_found;
 use crate::session::util;
-use crate::session::{Replacer, Session};
+use crate::session::{Data, Replacer, Session};
 use crate::{multiaddr_to_route, try_multiaddr_to_addr};
 
 use super::{NodeManager, NodeManagerWorker};
 
+const REMOTE_ADDRESS: &str = "remote-address";
+
+impl NodeManager {
+    fn forwarder_status(&self, entry: &ForwarderRegistryInfo) -> ForwarderInfo<'static> {
+        let worker = entry
+            .info
+            .worker_address()
+            .parse()
+            .unwrap_or_else(|_| Address::from_string(""));
+        let status = self.connection_status(&worker, entry.session.as_ref());
+        entry.info.clone().with_status(status)
+    }
+}
+
 impl NodeManagerWorker {
+    pub(super) fn get_forwarders<'a>(
+        &self,
+        req: &Request<'_>,
+        node_manager: &'a NodeManager,
+    ) -> ResponseBuilder<ForwarderList<'a>> {
+        let list = node_manager
+            .registry
+            .forwarders
+            .values()
+            .map(|entry| node_manager.forwarder_status(entry))
+            .collect();
+        Response::ok(req.id()).body(ForwarderList::new(list))
+    }
+
+    pub(super) fn get_forwarder<'a>(
+        &self,
+        req: &'a Request<'a>,
+        node_manager: &NodeManager,
+        remote_address: &str,
+    ) -> Either<ResponseBuilder<Error<'a>>, ResponseBuilder<ForwarderInfo<'a>>> {
+        match node_manager.registry.forwarders.get(remote_address) {
+            Some(entry) => {
+                Either::Right(Response::ok(req.id()).body(node_manager.forwarder_status(entry)))
+            }
+            None => Either::Left(not_found(req, "forwarder not found")),
+        }
+    }
+
+    pub(super) async fn delete_forwarder<'a>(
+        &self,
+        ctx: &Context,
+        req: &'a Request<'a>,
+        remote_address: &str,
+    ) -> Result<Either<ResponseBuilder<Error<'a>>, ResponseBuilder<ForwarderInfo<'a>>>> {
+        let mut node_manager = self.node_manager.write().await;
+        let entry = match node_manager.registry.forwarders.remove(remote_address) {
+            Some(entry) => entry,
+            None => return Ok(Either::Left(not_found(req, "forwarder not found"))),
+        };
+
+        info!(%remote_address, "Handling request to delete forwarder");
+
+        // The session is removed first, so that the forwarder is not recreated:
+        if let Some(key) = &entry.session {
+            node_manager.sessions.lock().unwrap().remove(key);
+        }
+        if let Ok(worker) = entry.info.worker_address().parse::<Address>() {
+            if let Err(e) = ctx.stop_worker(worker).await {
+                warn!(%remote_address, err = %e, "failed to stop forwarder")
+            }
+        }
+
+        let info = entry.info.with_status(ConnectionStatus::Deleted);
+        Ok(Either::Right(Response::ok(req.id()).body(info)))
+    }
+
     pub(super) async fn create_forwarder(
         &mut self,
         ctx: &mut Context,
@@ -41,6 +115,7 @@ impl NodeManagerWorker {
         let route = multiaddr_to_route(&full)
             .ok_or_else(|| ApiError::message("invalid address: {addr}"))?;
 
+        let mut session = None;
         let forwarder = if req.at_rust_node() {
             if let Some(alias) = req.alias() {
                 RemoteForwarder::create_static_without_heartbeats(ctx, route, alias).await
@@ -53,25 +128,35 @@ impl NodeManagerWorker {
             } else {
                 RemoteForwarder::create(ctx, route).await
             };
-            if f.is_ok() && !sec_chan.is_empty() {
+            if let (Ok(info), false) = (&f, sec_chan.is_empty()) {
                 let ctx = Arc::new(ctx.async_try_clone().await?);
+                let mut s = Session::new(sec_chan);
+                s.data()
+                    .put(REMOTE_ADDRESS, info.remote_address().to_string());
                 let repl = replacer(
                     manager,
                     ctx,
+                    s.data(),
                     req.address().clone(),
                     req.alias().map(|a| a.to_string()),
                     req.authorized(),
                 );
-                let mut s = Session::new(sec_chan);
                 s.set_replacer(repl);
-                node_manager.sessions.lock().unwrap().add(s);
+                session = Some(node_manager.sessions.lock().unwrap().add(s));
             }
             f
         };
 
         match forwarder {
             Ok(info) => {
-                let b = ForwarderInfo::from(info);
+                let b = ForwarderInfo::from(info).with_origin(req.address(), req.alias());
+                node_manager.registry.forwarders.insert(
+                    b.remote_address().to_string(),
+                    ForwarderRegistryInfo {
+                        info: b.clone(),
+                        session,
+                    },
+                );
                 debug!(
                     forwarding_route = %b.forwarding_route(),
                     remote_address = %b.remote_address(),
@@ -97,12 +182,14 @@ impl NodeManagerWorker {
 fn replacer(
     manager: Arc<RwLock<NodeManager>>,
     ctx: Arc<Context>,
+    data: Data,
     addr: MultiAddr,
     alias: Option<String>,
     auth: Option<IdentityIdentifier>,
 ) -> Replacer {
     Box::new(move |prev| {
         let ctx = ctx.clone();
+        let data = data.clone();
         let addr = addr.clone();
         let alias = alias.clone();
         let auth = auth.clone();
@@ -112,17 +199,38 @@ fn replacer(
             let f = async {
                 let prev = try_multiaddr_to_addr(&prev)?;
                 let mut this = manager.write().await;
+
+                // The forwarder may have been deleted in the meantime:
+                let key = data.get::<String>(REMOTE_ADDRESS).unwrap_or_default();
+                let (worker, session) = match this.registry.forwarders.get(&key) {
+                    Some(e) => (e.info.worker_address().to_string(), e.session),
+                    None => return Err(ApiError::generic("forwarder has been deleted")),
+                };
+                if let Ok(worker) = worker.parse::<Address>() {
+                    let _ = ctx.stop_worker(worker).await;
+                }
+
                 let _ = this.delete_secure_channel(&prev).await;
                 let timeout = Some(util::MAX_CONNECT_TIME);
                 let (sec, rest) = this.connect(&addr, auth, timeout).await?;
                 let a = sec.clone().try_with(&rest)?;
                 let r = multiaddr_to_route(&a)
                     .ok_or_else(|| ApiError::message(format!("invalid multiaddr: {a}")))?;
-                if let Some(alias) = &alias {
-                    RemoteForwarder::create_static(&ctx, r, alias).await?;
+                let info = if let Some(alias) = &alias {
+                    RemoteForwarder::create_static(&ctx, r, alias).await?
                 } else {
-                    RemoteForwarder::create(&ctx, r).await?;
-                }
+                    RemoteForwarder::create(&ctx, r).await?
+                };
+
+                // Without an alias the remote address changes, so the
+                // registry entry is moved to the new one:
+                let info = ForwarderInfo::from(info).with_origin(&addr, alias.as_deref());
+                this.registry.forwarders.remove(&key);
+                let key = info.remote_address().to_string();
+                data.put(REMOTE_ADDRESS, key.clone());
+                this.registry
+                    .forwarders
+                    .insert(key, ForwarderRegistryInfo { info, session });
                 Ok(sec)
             };
             match timeout(util::MAX_RECOVERY_TIME, f).await {
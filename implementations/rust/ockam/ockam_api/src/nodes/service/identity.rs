@@ -1,4 +1,5 @@
 use super::{map_anyhow_err, NodeManagerWorker};
+use crate::authenticator::direct::project_member_schema;
 use crate::nodes::models::identity::{
     CreateIdentityResponse, LongIdentityResponse, ShortIdentityResponse,
 };
@@ -30,6 +31,7 @@ impl NodeManager {
         let vault = self.vault()?;
 
         let identity = Identity::create(ctx, vault).await?;
+        identity.schemas().register(project_member_schema());
         let identifier = identity.identifier().clone();
         let exported_identity = identity.export().await?;
 
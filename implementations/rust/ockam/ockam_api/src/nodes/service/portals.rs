@@ -1,20 +1,22 @@
 use crate::error::ApiError;
 use crate::nodes::models::portal::{
-    CreateInlet, CreateOutlet, InletList, InletStatus, OutletList, OutletStatus,
+    ConnectionStatus, CreateInlet, CreateOutlet, InletList, InletStatus, OutletList, OutletStatus,
+    PortalTransport, Traffic,
 };
-use crate::nodes::registry::{InletInfo, OutletInfo, Registry};
-use crate::nodes::service::random_alias;
-use crate::session::{util, Data, Replacer, Session};
+use crate::nodes::registry::{InletInfo, OutletInfo};
+use crate::nodes::service::{not_found, random_alias};
+use crate::session::{util, Data, Key, Replacer, Session, Status};
 use crate::{actions, resources};
 use crate::{multiaddr_to_route, try_multiaddr_to_addr};
+use either::Either;
 use minicbor::Decoder;
 use ockam::compat::asynchronous::RwLock;
 use ockam::compat::tokio::time::timeout;
-use ockam::tcp::{InletOptions, OutletOptions};
-use ockam::{Address, Result};
+use ockam::tcp::{InletOptions, OutletOptions, PortalCounters};
+use ockam::{Address, Result, TcpTransport};
 use ockam_abac::expr::{and, eq, ident, str};
 use ockam_abac::{Action, Env, PolicyAccessControl, PolicyStorage, Resource};
-use ockam_core::api::{Request, Response, ResponseBuilder};
+use ockam_core::api::{Error, Request, Response, ResponseBuilder};
 use ockam_core::{AccessControl, AllowAll};
 use ockam_identity::IdentityIdentifier;
 use ockam_multiaddr::proto::{Project, Secure, Service};
@@ -61,47 +63,185 @@ impl NodeManager {
             Ok(Arc::new(AllowAll))
         }
     }
+
+    /// Determine the connection status of a portal or forwarder.
+    ///
+    /// A missing worker means creation failed. Otherwise the session
+    /// (if any) tells if the route is currently being re-established.
+    pub(super) fn connection_status(
+        &self,
+        worker: &Address,
+        session: Option<&Key>,
+    ) -> ConnectionStatus {
+        if worker.address().is_empty() {
+            return ConnectionStatus::Failed;
+        }
+        let sessions = self.sessions.lock().unwrap();
+        match session.and_then(|k| sessions.session(k)) {
+            Some(s) if s.status() == Status::Down => ConnectionStatus::Reconnecting,
+            _ => ConnectionStatus::Connected,
+        }
+    }
+
+    fn inlet_status<'a>(&self, alias: &'a str, info: &'a InletInfo) -> InletStatus<'a> {
+        InletStatus::new(
+            &info.bind_addr,
+            info.worker_addr.to_string(),
+            alias,
+            None,
+            info.outlet_route.to_string(),
+        )
+        .with_status(
+            self.connection_status(&info.worker_addr, info.session.as_ref()),
+            Traffic::from(&*info.counters),
+        )
+        .with_config(
+            info.outlet_addr.to_string(),
+            info.authorized.clone(),
+            info.check_credential,
+        )
+    }
+
+    fn outlet_status<'a>(&self, alias: &'a str, info: &'a OutletInfo) -> OutletStatus<'a> {
+        OutletStatus::new(&info.tcp_addr, info.worker_addr.to_string(), alias, None)
+            .with_status(
+                self.connection_status(&info.worker_addr, None),
+                Traffic::from(&*info.counters),
+            )
+            .with_check_credential(info.check_credential)
+    }
 }
 
 impl NodeManagerWorker {
     pub(super) fn get_inlets<'a>(
         &self,
         req: &Request<'a>,
-        registry: &'a Registry,
+        node_manager: &'a NodeManager,
     ) -> ResponseBuilder<InletList<'a>> {
         Response::ok(req.id()).body(InletList::new(
-            registry
+            node_manager
+                .registry
                 .inlets
                 .iter()
-                .map(|(alias, info)| {
-                    InletStatus::new(
-                        &info.bind_addr,
-                        info.worker_addr.to_string(),
-                        alias,
-                        None,
-                        info.outlet_route.to_string(),
-                    )
-                })
+                .map(|(alias, info)| node_manager.inlet_status(alias, info))
                 .collect(),
         ))
     }
 
+    pub(super) fn get_inlet<'a>(
+        &self,
+        req: &'a Request<'a>,
+        node_manager: &'a NodeManager,
+        alias: &str,
+    ) -> Either<ResponseBuilder<Error<'a>>, ResponseBuilder<InletStatus<'a>>> {
+        match node_manager.registry.inlets.get_key_value(alias) {
+            Some((alias, info)) => {
+                Either::Right(Response::ok(req.id()).body(node_manager.inlet_status(alias, info)))
+            }
+            None => Either::Left(not_found(req, "inlet not found")),
+        }
+    }
+
+    pub(super) async fn delete_inlet<'a>(
+        &self,
+        req: &'a Request<'a>,
+        alias: &str,
+    ) -> Result<Either<ResponseBuilder<Error<'a>>, ResponseBuilder<InletStatus<'a>>>> {
+        let mut node_manager = self.node_manager.write().await;
+        let info = match node_manager.registry.inlets.remove(alias) {
+            Some(info) => info,
+            None => return Ok(Either::Left(not_found(req, "inlet not found"))),
+        };
+
+        info!(%alias, "Handling request to delete inlet portal");
+
+        // The session is removed first, so that the inlet is not recreated:
+        if let Some(key) = &info.session {
+            node_manager.sessions.lock().unwrap().remove(key);
+        }
+        if !info.worker_addr.address().is_empty() {
+            if let Err(e) = node_manager
+                .tcp_transport
+                .stop_inlet(info.worker_addr.clone())
+                .await
+            {
+                warn!(%alias, err = %e, "failed to stop tcp inlet")
+            }
+        }
+
+        let status = InletStatus::new(
+            info.bind_addr,
+            info.worker_addr.to_string(),
+            alias.to_string(),
+            None,
+            info.outlet_route.to_string(),
+        )
+        .with_status(ConnectionStatus::Deleted, Traffic::from(&*info.counters));
+        Ok(Either::Right(Response::ok(req.id()).body(status)))
+    }
+
     pub(super) fn get_outlets<'a>(
         &self,
         req: &Request<'a>,
-        registry: &'a Registry,
+        node_manager: &'a NodeManager,
     ) -> ResponseBuilder<OutletList<'a>> {
         Response::ok(req.id()).body(OutletList::new(
-            registry
+            node_manager
+                .registry
                 .outlets
                 .iter()
-                .map(|(alias, info)| {
-                    OutletStatus::new(&info.tcp_addr, info.worker_addr.to_string(), alias, None)
-                })
+                .map(|(alias, info)| node_manager.outlet_status(alias, info))
                 .collect(),
         ))
     }
 
+    pub(super) fn get_outlet<'a>(
+        &self,
+        req: &'a Request<'a>,
+        node_manager: &'a NodeManager,
+        alias: &str,
+    ) -> Either<ResponseBuilder<Error<'a>>, ResponseBuilder<OutletStatus<'a>>> {
+        match node_manager.registry.outlets.get_key_value(alias) {
+            Some((alias, info)) => {
+                Either::Right(Response::ok(req.id()).body(node_manager.outlet_status(alias, info)))
+            }
+            None => Either::Left(not_found(req, "outlet not found")),
+        }
+    }
+
+    pub(super) async fn delete_outlet<'a>(
+        &self,
+        req: &'a Request<'a>,
+        alias: &str,
+    ) -> Result<Either<ResponseBuilder<Error<'a>>, ResponseBuilder<OutletStatus<'a>>>> {
+        let mut node_manager = self.node_manager.write().await;
+        let info = match node_manager.registry.outlets.remove(alias) {
+            Some(info) => info,
+            None => return Ok(Either::Left(not_found(req, "outlet not found"))),
+        };
+
+        info!(%alias, "Handling request to delete outlet portal");
+
+        if !info.worker_addr.address().is_empty() {
+            if let Err(e) = node_manager
+                .tcp_transport
+                .stop_outlet(info.worker_addr.clone())
+                .await
+            {
+                warn!(%alias, err = %e, "failed to stop tcp outlet")
+            }
+        }
+
+        let status = OutletStatus::new(
+            info.tcp_addr,
+            info.worker_addr.to_string(),
+            alias.to_string(),
+            None,
+        )
+        .with_status(ConnectionStatus::Deleted, Traffic::from(&*info.counters));
+        Ok(Either::Right(Response::ok(req.id()).body(status)))
+    }
+
     pub(super) async fn create_inlet<'a>(
         &mut self,
         req: &Request<'_>,
@@ -112,7 +252,15 @@ impl NodeManagerWorker {
         let rid = req.id();
         let req: CreateInlet = dec.decode()?;
 
-        let listen_addr = req.listen_addr().to_string();
+        let transport = req.transport();
+        let listen_addr = match (transport, req.listen_path()) {
+            (PortalTransport::Unix, Some(path)) => path.to_string(),
+            (PortalTransport::Unix, None) => {
+                return Ok(Response::bad_request(rid)
+                    .body(InletStatus::bad_request("missing unix socket path")))
+            }
+            _ => req.listen_addr().to_string(),
+        };
         let alias = req
             .alias()
             .map(|a| a.to_string())
@@ -121,7 +269,8 @@ impl NodeManagerWorker {
         info!("Handling request to create inlet portal");
 
         debug! {
-            listen_addr = %req.listen_addr(),
+            %transport,
+            %listen_addr,
             outlet_addr = %req.outlet_addr(),
             %alias,
             "Creating inlet portal"
@@ -186,24 +335,19 @@ impl NodeManagerWorker {
             .access_control(&resource, &actions::HANDLE_MESSAGE, project_id)
             .await?;
 
+        let counters = Arc::new(PortalCounters::new());
         let options = InletOptions::new(
             listen_addr.clone(),
             outlet_route.clone(),
             access_control.clone(),
-        );
+        )
+        .with_counters(counters.clone());
 
-        let res = node_manager
-            .tcp_transport
-            .create_inlet_extended(options)
-            .await;
+        let res = start_inlet(&node_manager.tcp_transport, transport, options).await;
 
         Ok(match res {
-            Ok((worker_addr, _)) => {
-                // TODO: Use better way to store inlets?
-                node_manager.registry.inlets.insert(
-                    alias.clone(),
-                    InletInfo::new(&listen_addr, Some(&worker_addr), &outlet_route),
-                );
+            Ok(worker_addr) => {
+                let mut session = None;
                 if !outer.is_empty() {
                     let mut s = Session::new(without_outlet_address(rest));
                     s.data().put(INLET_WORKER, worker_addr.clone());
@@ -211,29 +355,50 @@ impl NodeManagerWorker {
                     let repl = replacer(
                         manager,
                         s.data(),
+                        alias.clone(),
+                        transport,
                         listen_addr.clone(),
                         req.outlet_addr().clone(),
                         req.authorized(),
                         access_control.clone(),
+                        counters.clone(),
                     );
                     s.set_replacer(repl);
-                    node_manager.sessions.lock().unwrap().add(s);
+                    session = Some(node_manager.sessions.lock().unwrap().add(s));
                 }
+                // TODO: Use better way to store inlets?
+                node_manager.registry.inlets.insert(
+                    alias.clone(),
+                    InletInfo::new(&listen_addr, Some(&worker_addr), &outlet_route, counters)
+                        .with_session(session)
+                        .with_config(req.outlet_addr(), req.authorized(), check_credential),
+                );
 
-                Response::ok(rid).body(InletStatus::new(
-                    listen_addr,
-                    worker_addr.to_string(),
-                    alias,
-                    None,
-                    outlet_route.to_string(),
-                ))
+                Response::ok(rid).body(
+                    InletStatus::new(
+                        listen_addr,
+                        worker_addr.to_string(),
+                        alias,
+                        None,
+                        outlet_route.to_string(),
+                    )
+                    .with_config(
+                        req.outlet_addr().to_string(),
+                        req.authorized(),
+                        check_credential,
+                    ),
+                )
             }
             Err(e) => {
                 warn!(to = %req.outlet_addr(), err = %e, "failed to create tcp inlet");
                 // TODO: Use better way to store inlets?
                 node_manager.registry.inlets.insert(
                     alias.clone(),
-                    InletInfo::new(&listen_addr, None, &outlet_route),
+                    InletInfo::new(&listen_addr, None, &outlet_route, counters).with_config(
+                        req.outlet_addr(),
+                        req.authorized(),
+                        check_credential,
+                    ),
                 );
 
                 Response::bad_request(rid).body(InletStatus::new(
@@ -258,6 +423,7 @@ impl NodeManagerWorker {
             worker_addr,
             alias,
             check_credential,
+            transport,
             ..
         } = dec.decode()?;
         let tcp_addr = tcp_addr.to_string();
@@ -284,34 +450,36 @@ impl NodeManagerWorker {
             .access_control(&resource, &actions::HANDLE_MESSAGE, project_id)
             .await?;
 
-        let options = OutletOptions::new(worker_addr.clone(), tcp_addr.clone(), access_control);
+        let counters = Arc::new(PortalCounters::new());
+        let options = OutletOptions::new(worker_addr.clone(), tcp_addr.clone(), access_control)
+            .with_counters(counters.clone());
 
-        let res = node_manager
-            .tcp_transport
-            .create_outlet_extended(options)
-            .await;
+        let tcp = &node_manager.tcp_transport;
+        let res = match transport.unwrap_or(PortalTransport::Tcp) {
+            PortalTransport::Tcp => tcp.create_outlet_extended(options).await,
+            PortalTransport::Udp => tcp.create_udp_outlet(options).await,
+            PortalTransport::Unix => tcp.create_unix_outlet(options).await,
+        };
 
         Ok(match res {
             Ok(_) => {
                 // TODO: Use better way to store outlets?
                 node_manager.registry.outlets.insert(
                     alias.clone(),
-                    OutletInfo::new(&tcp_addr, Some(&worker_addr)),
+                    OutletInfo::new(&tcp_addr, Some(&worker_addr), counters, check_credential),
                 );
 
-                Response::ok(req.id()).body(OutletStatus::new(
-                    tcp_addr,
-                    worker_addr.to_string(),
-                    alias,
-                    None,
-                ))
+                Response::ok(req.id()).body(
+                    OutletStatus::new(tcp_addr, worker_addr.to_string(), alias, None)
+                        .with_check_credential(check_credential),
+                )
             }
             Err(e) => {
                 // TODO: Use better way to store outlets?
-                node_manager
-                    .registry
-                    .outlets
-                    .insert(alias.clone(), OutletInfo::new(&tcp_addr, None));
+                node_manager.registry.outlets.insert(
+                    alias.clone(),
+                    OutletInfo::new(&tcp_addr, None, counters, check_credential),
+                );
 
                 Response::bad_request(req.id()).body(OutletStatus::new(
                     tcp_addr,
@@ -329,15 +497,21 @@ impl NodeManagerWorker {
 /// This returns a function that accepts the previous ping address (e.g.
 /// the secure channel worker address) and constructs the whole route
 /// again.
+#[allow(clippy::too_many_arguments)]
 fn replacer(
     manager: Arc<RwLock<NodeManager>>,
     data: Data,
+    alias: String,
+    transport: PortalTransport,
     bind: String,
     addr: MultiAddr,
     auth: Option<IdentityIdentifier>,
     access: Arc<dyn AccessControl>,
+    counters: Arc<PortalCounters>,
 ) -> Replacer {
     Box::new(move |prev| {
+        let alias = alias.clone();
+        let counters = counters.clone();
         let addr = addr.clone();
         let auth = auth.clone();
         let bind = bind.clone();
@@ -352,6 +526,11 @@ fn replacer(
                 let mut this = manager.write().await;
                 let timeout = Some(util::MAX_CONNECT_TIME);
 
+                // The inlet may have been deleted in the meantime:
+                if !this.registry.inlets.contains_key(&alias) {
+                    return Err(ApiError::generic("inlet has been deleted"));
+                }
+
                 // First the previous secure channel is deleted, and -- if secure
                 // channels were nested -- the outer one as well:
 
@@ -390,8 +569,11 @@ fn replacer(
                 }
 
                 // Finally attempt to create a new inlet using the new route:
-                let opts = InletOptions::new(bind, r, access);
-                let wa = this.tcp_transport.create_inlet_extended(opts).await?.0;
+                let opts = InletOptions::new(bind, r, access).with_counters(counters);
+                let wa = start_inlet(&this.tcp_transport, transport, opts).await?;
+                if let Some(info) = this.registry.inlets.get_mut(&alias) {
+                    info.worker_addr = wa.clone();
+                }
                 data.put(INLET_WORKER, wa);
 
                 Ok(without_outlet_address(rest))
@@ -413,6 +595,19 @@ fn replacer(
     })
 }
 
+/// Start an inlet accepting `transport` connections, returns its address
+async fn start_inlet(
+    tcp: &TcpTransport,
+    transport: PortalTransport,
+    options: InletOptions,
+) -> Result<Address> {
+    match transport {
+        PortalTransport::Tcp => Ok(tcp.create_inlet_extended(options).await?.0),
+        PortalTransport::Udp => Ok(tcp.create_udp_inlet(options).await?.0),
+        PortalTransport::Unix => tcp.create_unix_inlet(options).await,
+    }
+}
+
 fn without_outlet_address(mut addr: MultiAddr) -> MultiAddr {
     if let Some(p) = addr.last() {
         if let Some(a) = p.cast::<Service>() {
@@ -4,10 +4,12 @@ use super::{map_multiaddr_err, NodeManagerWorker};
 use crate::error::ApiError;
 use crate::nodes::models::secure_channel::{
     CreateSecureChannelListenerRequest, CreateSecureChannelRequest, CreateSecureChannelResponse,
-    CredentialExchangeMode, DeleteSecureChannelRequest, DeleteSecureChannelResponse,
-    ShowSecureChannelRequest, ShowSecureChannelResponse,
+    CredentialExchangeMode, DeleteSecureChannelListenerRequest,
+    DeleteSecureChannelListenerResponse, DeleteSecureChannelRequest, DeleteSecureChannelResponse,
+    ShowSecureChannelListenerRequest, ShowSecureChannelListenerResponse, ShowSecureChannelRequest,
+    ShowSecureChannelResponse,
 };
-use crate::nodes::registry::Registry;
+use crate::nodes::registry::{Registry, SecureChannelListenerInfo};
 use crate::nodes::NodeManager;
 use crate::DefaultAddress;
 use minicbor::Decoder;
@@ -114,6 +116,9 @@ impl NodeManager {
                     .present_credential(route![sc_addr.clone(), DefaultAddress::CREDENTIAL_SERVICE])
                     .await?;
                 debug!(%sc_addr, "One-way credential presentation success");
+                self.registry
+                    .secure_channels
+                    .set_credential_exchange_mode(&sc_addr, actual_exchange_mode);
             }
             CredentialExchangeMode::Mutual => {
                 debug!(%sc_addr, "Mutual credential presentation");
@@ -127,6 +132,9 @@ impl NodeManager {
                     )
                     .await?;
                 debug!(%sc_addr, "Mutual credential presentation success");
+                self.registry
+                    .secure_channels
+                    .set_credential_exchange_mode(&sc_addr, actual_exchange_mode);
             }
         }
 
@@ -146,7 +154,7 @@ impl NodeManager {
 
         let identity = self.identity()?;
 
-        match authorized_identifiers {
+        match authorized_identifiers.clone() {
             Some(ids) => {
                 identity
                     .create_secure_channel_listener(
@@ -169,11 +177,24 @@ impl NodeManager {
 
         self.registry
             .secure_channel_listeners
-            .insert(addr, Default::default());
+            .insert(addr, SecureChannelListenerInfo::new(authorized_identifiers));
 
         Ok(())
     }
 
+    pub(super) async fn delete_secure_channel_listener(&mut self, addr: &Address) -> Result<()> {
+        debug!(%addr, "deleting secure channel listener");
+        if self
+            .registry
+            .secure_channel_listeners
+            .remove(addr)
+            .is_none()
+        {
+            return Err(ApiError::generic("unknown secure channel listener"));
+        }
+        self.identity()?.stop_secure_channel_listener(addr).await
+    }
+
     pub(super) async fn delete_secure_channel(&mut self, addr: &Address) -> Result<()> {
         debug!(%addr, "deleting secure channel");
         let identity = self.identity()?;
@@ -302,6 +323,49 @@ impl NodeManagerWorker {
         Ok(Response::ok(req.id()).body(ShowSecureChannelResponse::new(info)))
     }
 
+    pub(super) async fn show_secure_channel_listener<'a>(
+        &mut self,
+        req: &Request<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<ResponseBuilder<ShowSecureChannelListenerResponse<'a>>> {
+        let node_manager = self.node_manager.read().await;
+        let body: ShowSecureChannelListenerRequest = dec.decode()?;
+        let addr = Address::from(body.addr.as_ref());
+
+        debug!(%addr, "On show secure channel listener");
+
+        let res = match node_manager.registry.secure_channel_listeners.get(&addr) {
+            Some(info) => ShowSecureChannelListenerResponse::new(
+                Some(&addr),
+                info.authorized_identifiers.as_deref(),
+            ),
+            None => ShowSecureChannelListenerResponse::new(None, None),
+        };
+        Ok(Response::ok(req.id()).body(res))
+    }
+
+    pub(super) async fn delete_secure_channel_listener<'a>(
+        &mut self,
+        req: &Request<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<ResponseBuilder<DeleteSecureChannelListenerResponse<'a>>> {
+        let body: DeleteSecureChannelListenerRequest = dec.decode()?;
+        let addr = Address::from(body.addr.as_ref());
+        info!(%addr, "Handling request to delete secure channel listener");
+        let mut node_manager = self.node_manager.write().await;
+        let res = match node_manager.delete_secure_channel_listener(&addr).await {
+            Ok(()) => {
+                trace!(%addr, "Removed secure channel listener");
+                Some(addr)
+            }
+            Err(err) => {
+                trace!(%addr, %err, "Error removing secure channel listener");
+                None
+            }
+        };
+        Ok(Response::ok(req.id()).body(DeleteSecureChannelListenerResponse::new(res)))
+    }
+
     pub(super) async fn create_secure_channel_listener(
         &mut self,
         req: &Request<'_>,
@@ -1,20 +1,26 @@
 use crate::auth::Server;
+use crate::authenticator::direct::project_member_schema;
 use crate::echoer::Echoer;
 use crate::error::ApiError;
 use crate::identity::IdentityService;
 use crate::nodes::models::services::{
     ServiceList, ServiceStatus, StartAuthenticatedServiceRequest, StartAuthenticatorRequest,
     StartCredentialsService, StartEchoerServiceRequest, StartIdentityServiceRequest,
-    StartOktaIdentityProviderRequest, StartUppercaseServiceRequest, StartVaultServiceRequest,
-    StartVerifierService,
+    StartOidcIdentityProviderRequest, StartOktaIdentityProviderRequest, StartPreKeyService,
+    StartUppercaseServiceRequest, StartVaultServiceRequest, StartVerifierService,
+};
+use crate::nodes::registry::{
+    CredentialsServiceInfo, PreKeyServiceInfo, Registry, VerifierServiceInfo,
 };
-use crate::nodes::registry::{CredentialsServiceInfo, Registry, VerifierServiceInfo};
 use crate::nodes::NodeManager;
 use crate::uppercase::Uppercase;
 use crate::vault::VaultService;
 use minicbor::Decoder;
-use ockam::{Address, AsyncTryClone, Context, Result};
+use ockam::{Address, AsyncTryClone, Context, Result, WorkerBuilder};
+use ockam_abac::Resource;
 use ockam_core::api::{Request, Response, ResponseBuilder};
+use ockam_core::AllowAll;
+use std::sync::Arc;
 
 use super::NodeManagerWorker;
 
@@ -23,15 +29,25 @@ impl NodeManager {
         &mut self,
         ctx: &Context,
         addr: Address,
+        deny_export: bool,
     ) -> Result<()> {
         if self.registry.vault_services.contains_key(&addr) {
             return Err(ApiError::generic("Vault service exists at this address"));
         }
 
         let vault = self.vault()?.async_try_clone().await?;
-        let service = VaultService::new(vault);
+        let service = if deny_export {
+            VaultService::new(vault).deny_export()
+        } else {
+            VaultService::new(vault)
+        };
 
-        ctx.start_worker(addr.clone(), service).await?;
+        let ac = self
+            .api_access_control()
+            .for_service(Resource::new("service:vault"));
+        WorkerBuilder::with_access_control(ac, Arc::new(AllowAll), addr.clone(), service)
+            .start(ctx)
+            .await?;
 
         self.registry
             .vault_services
@@ -50,7 +66,10 @@ impl NodeManager {
         }
 
         let vault = self.vault()?.async_try_clone().await?;
-        IdentityService::create(ctx, addr.clone(), vault).await?;
+        let ac = self
+            .api_access_control()
+            .for_service(Resource::new("service:identity"));
+        IdentityService::create_with_access_control(ctx, addr.clone(), vault, ac).await?;
 
         self.registry
             .identity_services
@@ -103,7 +122,12 @@ impl NodeManager {
 
         let s = self.authenticated_storage.async_try_clone().await?;
         let server = Server::new(s);
-        ctx.start_worker(addr.clone(), server).await?;
+        let ac = self
+            .api_access_control()
+            .for_service(Resource::new("service:authenticated"));
+        WorkerBuilder::with_access_control(ac, Arc::new(AllowAll), addr.clone(), server)
+            .start(ctx)
+            .await?;
 
         self.registry
             .authenticated_services
@@ -165,7 +189,12 @@ impl NodeManager {
         let db = self.authenticated_storage.async_try_clone().await?;
         let id = self.identity()?.async_try_clone().await?;
         let au = crate::authenticator::direct::Server::new(proj.to_vec(), db, path, id);
-        ctx.start_worker(addr.clone(), au).await?;
+        let ac = self
+            .api_access_control()
+            .for_service(Resource::new("service:authenticator"));
+        WorkerBuilder::with_access_control(ac, Arc::new(AllowAll), addr.clone(), au)
+            .start(ctx)
+            .await?;
         self.registry
             .authenticator_service
             .insert(addr, AuthenticatorServiceInfo::default());
@@ -200,6 +229,31 @@ impl NodeManager {
             .insert(addr, OktaIdentityProviderServiceInfo::default());
         Ok(())
     }
+
+    pub(super) async fn start_oidc_identity_provider_service_impl(
+        &mut self,
+        ctx: &Context,
+        addr: Address,
+        provider: crate::oidc::Provider,
+    ) -> Result<()> {
+        use crate::nodes::registry::OidcIdentityProviderServiceInfo;
+        if self
+            .registry
+            .oidc_identity_provider_services
+            .contains_key(&addr)
+        {
+            return Err(ApiError::generic(
+                "OIDC Identity Provider service already started",
+            ));
+        }
+        let db = self.authenticated_storage.async_try_clone().await?;
+        let au = crate::oidc::Server::new(db, provider);
+        ctx.start_worker(addr.clone(), au).await?;
+        self.registry
+            .oidc_identity_provider_services
+            .insert(addr, OidcIdentityProviderServiceInfo::default());
+        Ok(())
+    }
 }
 
 impl NodeManagerWorker {
@@ -212,7 +266,9 @@ impl NodeManagerWorker {
         let mut node_manager = self.node_manager.write().await;
         let req_body: StartVaultServiceRequest = dec.decode()?;
         let addr = req_body.addr.to_string().into();
-        node_manager.start_vault_service_impl(ctx, addr).await?;
+        node_manager
+            .start_vault_service_impl(ctx, addr, req_body.deny_export)
+            .await?;
         Ok(Response::ok(req.id()))
     }
 
@@ -315,6 +371,27 @@ impl NodeManagerWorker {
         Ok(Response::ok(req.id()))
     }
 
+    pub(super) async fn start_oidc_identity_provider_service(
+        &mut self,
+        ctx: &Context,
+        req: &Request<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<ResponseBuilder> {
+        let mut node_manager = self.node_manager.write().await;
+        let body: StartOidcIdentityProviderRequest = dec.decode()?;
+        let addr: Address = body.address().into();
+        let provider = crate::oidc::Provider::new(
+            body.issuer(),
+            body.audience(),
+            body.claims(),
+            body.certificate(),
+        )?;
+        node_manager
+            .start_oidc_identity_provider_service_impl(ctx, addr, provider)
+            .await?;
+        Ok(Response::ok(req.id()))
+    }
+
     pub(super) async fn start_verifier_service<'a>(
         &mut self,
         ctx: &Context,
@@ -336,7 +413,13 @@ impl NodeManagerWorker {
         };
 
         let vs = crate::verifier::Verifier::new(vault);
-        ctx.start_worker(addr.clone(), vs).await?;
+        vs.schemas().register(project_member_schema());
+        let ac = node_manager
+            .api_access_control()
+            .for_service(Resource::new("service:verifier"));
+        WorkerBuilder::with_access_control(ac, Arc::new(AllowAll), addr.clone(), vs)
+            .start(ctx)
+            .await?;
 
         node_manager
             .registry
@@ -346,6 +429,32 @@ impl NodeManagerWorker {
         Ok(Response::ok(req.id()))
     }
 
+    pub(super) async fn start_prekey_service<'a>(
+        &mut self,
+        ctx: &Context,
+        req: &'a Request<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<ResponseBuilder> {
+        let mut node_manager = self.node_manager.write().await;
+        let body: StartPreKeyService = dec.decode()?;
+        let addr: Address = body.address().into();
+
+        if node_manager.registry.prekey_services.contains_key(&addr) {
+            return Err(ApiError::generic("PreKey service exists at this address"));
+        }
+
+        let vault = node_manager.vault()?.async_try_clone().await?;
+        ctx.start_worker(addr.clone(), crate::x3dh::Server::new(vault))
+            .await?;
+
+        node_manager
+            .registry
+            .prekey_services
+            .insert(addr, PreKeyServiceInfo::default());
+
+        Ok(Response::ok(req.id()))
+    }
+
     pub(super) async fn start_credentials_service<'a>(
         &mut self,
         _ctx: &Context,
@@ -398,6 +507,18 @@ impl NodeManagerWorker {
             .credentials_services
             .keys()
             .for_each(|addr| list.push(ServiceStatus::new(addr.address(), "credentials")));
+        registry
+            .prekey_services
+            .keys()
+            .for_each(|addr| list.push(ServiceStatus::new(addr.address(), "prekeys")));
+        registry
+            .okta_identity_provider_services
+            .keys()
+            .for_each(|addr| list.push(ServiceStatus::new(addr.address(), "okta")));
+        registry
+            .oidc_identity_provider_services
+            .keys()
+            .for_each(|addr| list.push(ServiceStatus::new(addr.address(), "oidc")));
 
         #[cfg(feature = "direct-authenticator")]
         registry
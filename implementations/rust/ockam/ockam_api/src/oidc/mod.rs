@@ -0,0 +1,326 @@
+//! Enrollment of project members with ID tokens of any OpenID Connect
+//! provider (Keycloak, Azure AD, Okta, Auth0, ...).
+//!
+//! The provider is configured with its issuer URL only: the JSON Web Key
+//! Set its tokens are signed with is found through the discovery document
+//! and ID tokens are verified locally. Verified claims are mapped to
+//! attributes stored for the enrolling identity, which the direct
+//! authenticator then puts in its project member credentials.
+
+use crate::authenticator::direct::project_member_schema;
+use crate::error::ApiError;
+use jsonwebtoken::jwk::{AlgorithmParameters, Jwk, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use minicbor::{Decode, Decoder, Encode};
+use ockam_core::api::{self, Method, Request, Response};
+use ockam_core::{self, CowStr, Result, Routed, Worker};
+use ockam_identity::authenticated_storage::AuthenticatedStorage;
+use ockam_identity::{IdentityIdentifier, IdentitySecureChannelLocalInfo};
+use ockam_node::Context;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+use tracing::trace;
+
+#[cfg(feature = "tag")]
+use ockam_core::TypeTag;
+
+const MEMBER: &str = "member";
+
+/// Minimum time between two fetches of the keys of a provider, by default.
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Maximum number of unknown key ids remembered by a provider.
+const MAX_UNKNOWN_KEYS: usize = 1024;
+
+/// Request body of an enrollment with an ID token.
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct IdToken<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5913416>,
+    #[b(1)] token: CowStr<'a>,
+}
+
+impl<'a> IdToken<'a> {
+    pub fn new(token: impl Into<CowStr<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            token: token.into(),
+        }
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+/// The part of an OpenID Connect discovery document we need.
+#[derive(Debug, Deserialize)]
+struct Discovery {
+    issuer: String,
+    jwks_uri: String,
+}
+
+/// An OpenID Connect provider whose ID tokens are trusted.
+pub struct Provider {
+    issuer: String,
+    audience: String,
+    /// ID token claim name -> attribute name
+    claims: BTreeMap<String, String>,
+    client: reqwest::Client,
+    jwks_uri: Option<String>,
+    keys: JwkSet,
+    refresh_interval: Duration,
+    fetched_at: Option<Instant>,
+    /// Key ids of tokens that no fetched key matched, and when
+    unknown_keys: HashMap<String, Instant>,
+}
+
+impl Provider {
+    /// Create a provider for the given issuer URL, accepting ID tokens
+    /// issued to `audience` (the client ID).
+    ///
+    /// Only the claims in `claims` are kept, under the attribute names
+    /// they are mapped to. If `certificate` is set, it is the only root
+    /// certificate trusted to talk to the provider.
+    pub fn new(
+        issuer: &str,
+        audience: &str,
+        claims: BTreeMap<String, String>,
+        certificate: Option<&str>,
+    ) -> Result<Self> {
+        let mut builder = reqwest::ClientBuilder::new();
+        if let Some(certificate) = certificate {
+            let certificate = reqwest::Certificate::from_pem(certificate.as_bytes())
+                .map_err(|err| ApiError::generic(&err.to_string()))?;
+            builder = builder
+                .tls_built_in_root_certs(false)
+                .add_root_certificate(certificate);
+        }
+        let client = builder
+            .build()
+            .map_err(|err| ApiError::generic(&err.to_string()))?;
+        Ok(Provider {
+            issuer: issuer.trim_end_matches('/').to_string(),
+            audience: audience.to_string(),
+            claims,
+            client,
+            jwks_uri: None,
+            keys: JwkSet { keys: Vec::new() },
+            refresh_interval: REFRESH_INTERVAL,
+            fetched_at: None,
+            unknown_keys: HashMap::new(),
+        })
+    }
+
+    /// Set the minimum time between two fetches of the provider keys.
+    ///
+    /// Defaults to [`REFRESH_INTERVAL`]. Tokens signed with a rotated key
+    /// may be rejected for that long.
+    pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = refresh_interval;
+        self
+    }
+
+    /// Verify an ID token and return the attributes mapped from its claims.
+    pub async fn verify(&mut self, token: &str) -> Result<HashMap<String, String>> {
+        let header = jsonwebtoken::decode_header(token).map_err(invalid_token)?;
+        let kid = header
+            .kid
+            .ok_or_else(|| ApiError::generic("ID token without key id"))?;
+
+        // An unknown key id may mean that the provider rotated its keys.
+        // Anybody can make up key ids though, so the keys are not fetched
+        // more than once per refresh interval, nor for a key id that was
+        // unknown during that interval.
+        if self.keys.find(&kid).is_none() && self.may_fetch(&kid) {
+            self.fetch_keys().await?;
+        }
+        let jwk = match self.keys.find(&kid) {
+            Some(jwk) => jwk,
+            None => {
+                self.remember_unknown_key(kid);
+                return Err(ApiError::generic("ID token signed with an unknown key"));
+            }
+        };
+        let key = decoding_key(jwk, header.alg)?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        let token =
+            jsonwebtoken::decode::<HashMap<String, serde_json::Value>>(token, &key, &validation)
+                .map_err(invalid_token)?;
+
+        Ok(self.map_claims(&token.claims))
+    }
+
+    fn map_claims(&self, claims: &HashMap<String, serde_json::Value>) -> HashMap<String, String> {
+        let mut attrs = HashMap::new();
+        for (claim, attribute) in &self.claims {
+            let value = match claims.get(claim) {
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(serde_json::Value::Bool(b)) => b.to_string(),
+                Some(serde_json::Value::Number(n)) => n.to_string(),
+                Some(other) => {
+                    debug!(%claim, %other, "Skipping claim which isn't a scalar");
+                    continue;
+                }
+                None => continue,
+            };
+            attrs.insert(attribute.clone(), value);
+        }
+        attrs
+    }
+
+    fn may_fetch(&self, kid: &str) -> bool {
+        let interval = self.refresh_interval;
+        !matches!(self.fetched_at, Some(t) if t.elapsed() < interval)
+            && !matches!(self.unknown_keys.get(kid), Some(t) if t.elapsed() < interval)
+    }
+
+    fn remember_unknown_key(&mut self, kid: String) {
+        if self.unknown_keys.len() >= MAX_UNKNOWN_KEYS {
+            let interval = self.refresh_interval;
+            self.unknown_keys.retain(|_, t| t.elapsed() < interval);
+            if self.unknown_keys.len() >= MAX_UNKNOWN_KEYS {
+                // Still rate limited by the time of the last fetch
+                self.unknown_keys.clear();
+            }
+        }
+        self.unknown_keys.insert(kid, Instant::now());
+    }
+
+    async fn fetch_keys(&mut self) -> Result<()> {
+        // Failed fetches count too, so that an unavailable provider is not
+        // asked again for every token
+        self.fetched_at = Some(Instant::now());
+        let jwks_uri = match &self.jwks_uri {
+            Some(uri) => uri.clone(),
+            None => {
+                let url = format!("{}/.well-known/openid-configuration", self.issuer);
+                let discovery: Discovery = self.get_json(&url).await?;
+                if discovery.issuer.trim_end_matches('/') != self.issuer {
+                    return Err(ApiError::generic("discovery document of another issuer"));
+                }
+                self.jwks_uri = Some(discovery.jwks_uri.clone());
+                discovery.jwks_uri
+            }
+        };
+        self.keys = self.get_json(&jwks_uri).await?;
+        debug!(keys = self.keys.keys.len(), "Fetched OIDC provider keys");
+        Ok(())
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let res = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|err| ApiError::generic(&err.to_string()))?;
+        res.json()
+            .await
+            .map_err(|err| ApiError::generic(&err.to_string()))
+    }
+}
+
+fn decoding_key(jwk: &Jwk, alg: Algorithm) -> Result<DecodingKey> {
+    // A symmetric key would let anyone who can read the key set sign tokens
+    if let AlgorithmParameters::OctetKey(_) = jwk.algorithm {
+        return Err(ApiError::generic("symmetric keys are not supported"));
+    }
+    if matches!(jwk.common.algorithm, Some(a) if a != alg) {
+        return Err(ApiError::generic(
+            "ID token algorithm doesn't match its key",
+        ));
+    }
+    DecodingKey::from_jwk(jwk).map_err(invalid_token)
+}
+
+fn invalid_token(err: jsonwebtoken::errors::Error) -> ockam_core::Error {
+    ApiError::generic(&format!("invalid ID token: {err}"))
+}
+
+/// Worker enrolling the identities presenting a valid ID token.
+pub struct Server<S> {
+    store: S,
+    provider: Provider,
+}
+
+#[ockam_core::worker]
+impl<S> Worker for Server<S>
+where
+    S: AuthenticatedStorage,
+{
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        if let Ok(i) = IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
+            let r = self.on_request(i.their_identity_id(), m.as_body()).await?;
+            c.send(m.return_route(), r).await
+        } else {
+            let mut dec = Decoder::new(m.as_body());
+            let req: Request = dec.decode()?;
+            let res = api::forbidden(&req, "secure channel required").to_vec()?;
+            c.send(m.return_route(), res).await
+        }
+    }
+}
+
+impl<S> Server<S>
+where
+    S: AuthenticatedStorage,
+{
+    pub fn new(store: S, provider: Provider) -> Self {
+        Server { store, provider }
+    }
+
+    async fn on_request(&mut self, from: &IdentityIdentifier, data: &[u8]) -> Result<Vec<u8>> {
+        let mut dec = Decoder::new(data);
+        let req: Request = dec.decode()?;
+
+        trace! {
+            target: "ockam_api::oidc::server",
+            from   = %from,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+        let res = match req.method() {
+            Some(Method::Post) => match req.path_segments::<2>().as_slice() {
+                ["v0", "enroll"] => {
+                    let token: IdToken = dec.decode()?;
+                    let attrs = match self.provider.verify(token.token()).await {
+                        Ok(attrs) => attrs,
+                        Err(err) => {
+                            debug!(%from, %err, "Rejected ID token");
+                            return Ok(api::forbidden(&req, "invalid ID token").to_vec()?);
+                        }
+                    };
+                    // The attributes end up in project member credentials
+                    let values = attrs.iter().map(|(k, v)| (k.as_str(), v.as_bytes()));
+                    if let Err(e) = project_member_schema().validate_values(values) {
+                        return Ok(api::bad_request(&req, &e.to_string()).to_vec()?);
+                    }
+                    let encoded_attrs = minicbor::to_vec(attrs)?;
+                    self.store
+                        .set(from.key_id(), MEMBER.to_string(), encoded_attrs)
+                        .await?;
+                    Response::ok(req.id()).to_vec()?
+                }
+                _ => api::unknown_path(&req).to_vec()?,
+            },
+            _ => api::invalid_method(&req).to_vec()?,
+        };
+        Ok(res)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::authenticator::direct::project_member_schema;
 use crate::error::ApiError;
 use core::str;
 use minicbor::Decoder;
@@ -88,6 +89,11 @@ where
                         dec.decode()?;
                     debug!("device code received: {token:#?}");
                     if let Some(attrs) = self.check_token(&token.access_token.0).await? {
+                        // The attributes end up in project member credentials
+                        let values = attrs.iter().map(|(k, v)| (k.as_str(), v.as_bytes()));
+                        if let Err(e) = project_member_schema().validate_values(values) {
+                            return Ok(api::bad_request(&req, &e.to_string()).to_vec()?);
+                        }
                         let encoded_attrs = minicbor::to_vec(attrs)?;
                         self.store
                             .set(from.key_id(), MEMBER.to_string(), encoded_attrs)
@@ -3,7 +3,7 @@ pub(crate) mod util;
 
 use crate::{multiaddr_to_route, DefaultAddress};
 use minicbor::{Decode, Encode};
-use ockam::{LocalMessage, Route, TransportMessage, Worker};
+use ockam::{LocalMessage, Priority, Route, TransportMessage, Worker};
 use ockam_core::compat::sync::{Arc, Mutex};
 use ockam_core::{Address, Decodable, Encodable, Error, Routed, LOCAL};
 use ockam_multiaddr::MultiAddr;
@@ -12,10 +12,10 @@ use ockam_node::tokio::sync::mpsc;
 use ockam_node::tokio::task::JoinSet;
 use ockam_node::tokio::time::{timeout, Duration};
 use ockam_node::Context;
-use sessions::{Key, Ping, Status};
+use sessions::Ping;
 use tracing as log;
 
-pub use sessions::{Data, Replacer, Session, Sessions};
+pub use sessions::{Data, Key, Replacer, Session, Sessions, Status};
 
 const MAX_FAILURES: usize = 3;
 const DELAY: Duration = Duration::from_secs(3);
@@ -94,7 +94,7 @@ impl Medic {
                                 "send ping"
                             }
                             let t = TransportMessage::v1(r, Collector::address(), v);
-                            LocalMessage::new(t, Vec::new())
+                            LocalMessage::new(t, Vec::new()).with_priority(Priority::Control)
                         };
                         let sender = ctx.clone();
                         self.pings
@@ -67,7 +67,18 @@ impl Sessions {
         k
     }
 
-    #[allow(unused)]
+    pub fn remove(&mut self, k: &Key) -> Option<Session> {
+        let s = self.map.remove(k);
+        if s.is_some() {
+            log::debug! {
+                target: "ockam_api::session",
+                key = %k,
+                "session removed"
+            }
+        }
+        s
+    }
+
     pub fn session(&self, k: &Key) -> Option<&Session> {
         self.map.get(k)
     }
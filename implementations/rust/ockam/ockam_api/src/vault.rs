@@ -1,5 +1,9 @@
 pub mod models;
 
+mod remote;
+
+pub use remote::RemoteVault;
+
 use core::convert::Infallible;
 
 use minicbor::encode::Write;
@@ -18,12 +22,25 @@ use tracing::trace;
 /// Vault Service Worker
 pub struct VaultService {
     vault: Vault,
+    deny_export: bool,
 }
 
 impl VaultService {
     /// Constructor
     pub fn new(vault: Vault) -> Self {
-        Self { vault }
+        Self {
+            vault,
+            deny_export: false,
+        }
+    }
+
+    /// Refuse requests to export secret keys
+    ///
+    /// Keys of such a vault never leave it, which makes it suitable
+    /// to hold the keys of other nodes through a [`RemoteVault`].
+    pub fn deny_export(mut self) -> Self {
+        self.deny_export = true;
+        self
     }
 }
 
@@ -119,6 +136,14 @@ impl VaultService {
 
                             Self::ok_response(req, Some(body), enc)
                         }
+                        GetSecretRequestOperation::GetSecretBytes if self.deny_export => {
+                            Self::response_with_error(
+                                Some(req),
+                                Status::Forbidden,
+                                "secret export is denied by this vault",
+                                enc,
+                            )
+                        }
                         GetSecretRequestOperation::GetSecretBytes => {
                             let resp = self.vault.secret_export(&key_id).await?;
                             let body = ExportSecretResponse::new(resp.as_ref());
@@ -0,0 +1,249 @@
+use super::models::*;
+use core::fmt;
+use minicbor::{Decode, Encode};
+use ockam_core::api::{decode_option, is_ok, Request, RequestBuilder};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::vault::{
+    AsymmetricVault, Buffer, Hasher, KeyId, PublicKey, SecretAttributes, SecretKey, SecretVault,
+    Signature, Signer, SmallBuffer, SymmetricVault, Verifier,
+};
+use ockam_core::{async_trait, Address, AsyncTryClone, Result, Route};
+use ockam_node::Context;
+use tracing::trace;
+
+/// A vault whose keys live in a [`VaultService`](super::VaultService)
+/// of another node
+///
+/// `RemoteVault` implements every trait of an `IdentityVault`, so an
+/// identity can be created or imported on top of it while its secret
+/// keys never leave the remote node.  The route should go through a
+/// secure channel to that node, ideally one whose vault service was
+/// started with [`deny_export`](super::VaultService::deny_export).
+pub struct RemoteVault {
+    ctx: Context,
+    route: Route,
+}
+
+impl fmt::Debug for RemoteVault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteVault")
+            .field("route", &self.route)
+            .finish()
+    }
+}
+
+impl RemoteVault {
+    /// Create a client of the vault service at the given route
+    pub async fn create(ctx: &Context, route: impl Into<Route>) -> Result<Self> {
+        let ctx = ctx.new_detached(Address::random_local()).await?;
+        Ok(RemoteVault {
+            ctx,
+            route: route.into(),
+        })
+    }
+
+    /// Encode a request, send it to the vault service and return the
+    /// raw response
+    async fn request<T>(&self, label: &str, req: RequestBuilder<'_, T>) -> Result<Vec<u8>>
+    where
+        T: Encode<()>,
+    {
+        let mut buf = Vec::new();
+        req.encode(&mut buf)?;
+        trace! {
+            target: "ockam_api::vault::remote",
+            id     = %req.header().id(),
+            method = ?req.header().method(),
+            path   = %req.header().path(),
+            body   = %req.header().has_body(),
+            "-> {label}"
+        };
+        self.ctx.send_and_receive(self.route.clone(), buf).await
+    }
+}
+
+/// Decode a response that must have a body
+fn decode<'b, T: Decode<'b, ()>>(label: &str, buf: &'b [u8]) -> Result<T> {
+    decode_option(label, None, buf)?.ok_or_else(|| {
+        ockam_core::Error::new(
+            Origin::Application,
+            Kind::NotFound,
+            format!("{label}: not found"),
+        )
+    })
+}
+
+#[async_trait]
+impl AsyncTryClone for RemoteVault {
+    async fn async_try_clone(&self) -> Result<Self> {
+        RemoteVault::create(&self.ctx, self.route.clone()).await
+    }
+}
+
+#[async_trait]
+impl SecretVault for RemoteVault {
+    async fn secret_generate(&self, attributes: SecretAttributes) -> Result<KeyId> {
+        let req = Request::post("secrets").body(CreateSecretRequest::new_generate(attributes));
+        let buf = self.request("secret_generate", req).await?;
+        let res: CreateSecretResponse = decode("secret_generate", &buf)?;
+        Ok(res.key_id().to_string())
+    }
+
+    async fn secret_import(&self, secret: &[u8], attributes: SecretAttributes) -> Result<KeyId> {
+        let req =
+            Request::post("secrets").body(CreateSecretRequest::new_import(attributes, secret));
+        let buf = self.request("secret_import", req).await?;
+        let res: CreateSecretResponse = decode("secret_import", &buf)?;
+        Ok(res.key_id().to_string())
+    }
+
+    async fn secret_export(&self, key_id: &KeyId) -> Result<SecretKey> {
+        let req = Request::get(format!("secrets/{key_id}")).body(GetSecretRequest::new(
+            GetSecretRequestOperation::GetSecretBytes,
+        ));
+        let buf = self.request("secret_export", req).await?;
+        let res: ExportSecretResponse = decode("secret_export", &buf)?;
+        Ok(SecretKey::new(res.secret().to_vec()))
+    }
+
+    async fn secret_attributes_get(&self, key_id: &KeyId) -> Result<SecretAttributes> {
+        let req = Request::get(format!("secrets/{key_id}")).body(GetSecretRequest::new(
+            GetSecretRequestOperation::GetAttributes,
+        ));
+        let buf = self.request("secret_attributes_get", req).await?;
+        let res: GetSecretAttributesResponse = decode("secret_attributes_get", &buf)?;
+        Ok(*res.attributes())
+    }
+
+    async fn secret_public_key_get(&self, key_id: &KeyId) -> Result<PublicKey> {
+        let req = Request::get(format!("secrets/{key_id}/public_key"));
+        let buf = self.request("secret_public_key_get", req).await?;
+        let res: PublicKeyResponse = decode("secret_public_key_get", &buf)?;
+        Ok(res.public_key().clone())
+    }
+
+    async fn secret_destroy(&self, key_id: KeyId) -> Result<()> {
+        let req = Request::delete(format!("secrets/{key_id}"));
+        let buf = self.request("secret_destroy", req).await?;
+        is_ok("secret_destroy", &buf)
+    }
+}
+
+#[async_trait]
+impl AsymmetricVault for RemoteVault {
+    async fn ec_diffie_hellman(
+        &self,
+        secret: &KeyId,
+        peer_public_key: &PublicKey,
+    ) -> Result<KeyId> {
+        let req =
+            Request::post("ecdh").body(EcdhRequest::new(secret.as_str(), peer_public_key.clone()));
+        let buf = self.request("ec_diffie_hellman", req).await?;
+        let res: EcdhResponse = decode("ec_diffie_hellman", &buf)?;
+        Ok(res.key_id().to_string())
+    }
+
+    async fn compute_key_id_for_public_key(&self, public_key: &PublicKey) -> Result<KeyId> {
+        let req =
+            Request::post("compute_key_id").body(ComputeKeyIdRequest::new(public_key.clone()));
+        let buf = self.request("compute_key_id", req).await?;
+        let res: ComputeKeyIdResponse = decode("compute_key_id", &buf)?;
+        Ok(res.key_id().to_string())
+    }
+}
+
+#[async_trait]
+impl Hasher for RemoteVault {
+    async fn sha256(&self, data: &[u8]) -> Result<[u8; 32]> {
+        let req = Request::post("sha256").body(Sha256Request::new(data));
+        let buf = self.request("sha256", req).await?;
+        let res: Sha256Response = decode("sha256", &buf)?;
+        Ok(res.hash())
+    }
+
+    async fn hkdf_sha256(
+        &self,
+        salt: &KeyId,
+        info: &[u8],
+        ikm: Option<&KeyId>,
+        output_attributes: SmallBuffer<SecretAttributes>,
+    ) -> Result<SmallBuffer<KeyId>> {
+        let req = Request::post("hkdf").body(HkdfSha256Request::new(
+            salt.as_str(),
+            info,
+            ikm.map(|i| i.as_str()),
+            output_attributes,
+        ));
+        let buf = self.request("hkdf_sha256", req).await?;
+        let res: HkdfSha256Response = decode("hkdf_sha256", &buf)?;
+        Ok(res.output().iter().map(|k| k.to_string()).collect())
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteVault {
+    async fn sign(&self, key_id: &KeyId, data: &[u8]) -> Result<Signature> {
+        let req = Request::post("sign").body(SignRequest::new(key_id.as_str(), data));
+        let buf = self.request("sign", req).await?;
+        let res: SignResponse = decode("sign", &buf)?;
+        Ok(Signature::new(res.signature().to_vec()))
+    }
+}
+
+#[async_trait]
+impl Verifier for RemoteVault {
+    async fn verify(
+        &self,
+        signature: &Signature,
+        public_key: &PublicKey,
+        data: &[u8],
+    ) -> Result<bool> {
+        let req = Request::post("verify").body(VerifyRequest::new(
+            signature.as_ref(),
+            public_key.clone(),
+            data,
+        ));
+        let buf = self.request("verify", req).await?;
+        let res: VerifyResponse = decode("verify", &buf)?;
+        Ok(res.verified())
+    }
+}
+
+#[async_trait]
+impl SymmetricVault for RemoteVault {
+    async fn aead_aes_gcm_encrypt(
+        &self,
+        key_id: &KeyId,
+        plaintext: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Buffer<u8>> {
+        let req = Request::post("encrypt").body(EncryptRequest::new(
+            key_id.as_str(),
+            plaintext,
+            nonce,
+            aad,
+        ));
+        let buf = self.request("aead_aes_gcm_encrypt", req).await?;
+        let res: EncryptResponse = decode("aead_aes_gcm_encrypt", &buf)?;
+        Ok(res.ciphertext().to_vec())
+    }
+
+    async fn aead_aes_gcm_decrypt(
+        &self,
+        key_id: &KeyId,
+        cipher_text: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Buffer<u8>> {
+        let req = Request::post("decrypt").body(DecryptRequest::new(
+            key_id.as_str(),
+            cipher_text,
+            nonce,
+            aad,
+        ));
+        let buf = self.request("aead_aes_gcm_decrypt", req).await?;
+        let res: DecryptResponse = decode("aead_aes_gcm_decrypt", &buf)?;
+        Ok(res.plaintext().to_vec())
+    }
+}
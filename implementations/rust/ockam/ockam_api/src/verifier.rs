@@ -5,7 +5,7 @@ use minicbor::Decoder;
 use ockam_core::api::{self, Id, ResponseBuilder};
 use ockam_core::api::{Error, Method, Request, Response};
 use ockam_core::{self, Result, Routed, Worker};
-use ockam_identity::credential::{Credential, CredentialData, Verified};
+use ockam_identity::credential::{Credential, CredentialData, SchemaRegistry, Verified};
 use ockam_identity::{IdentityVault, PublicIdentity};
 use ockam_node::Context;
 use tracing::trace;
@@ -15,6 +15,7 @@ use self::types::{VerifyRequest, VerifyResponse};
 #[derive(Debug)]
 pub struct Verifier<V> {
     vault: V,
+    schemas: SchemaRegistry,
 }
 
 #[ockam_core::worker]
@@ -36,7 +37,15 @@ where
     V: IdentityVault,
 {
     pub fn new(vault: V) -> Self {
-        Self { vault }
+        Self {
+            vault,
+            schemas: SchemaRegistry::new(),
+        }
+    }
+
+    /// The schemas verified credentials are validated against
+    pub fn schemas(&self) -> &SchemaRegistry {
+        &self.schemas
     }
 
     async fn on_request(&mut self, data: &[u8]) -> Result<Vec<u8>> {
@@ -112,6 +121,11 @@ where
             }
         };
 
+        if let Err(err) = self.schemas.validate(&data) {
+            let err = Error::new("/verify").with_message(format!("invalid credential: {}", err));
+            return Ok(Either::Left(Response::forbidden(id).body(err)));
+        }
+
         Ok(Either::Right(data))
     }
 }
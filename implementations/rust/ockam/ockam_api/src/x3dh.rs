@@ -0,0 +1,844 @@
+//! Asynchronous secure messaging with the X3DH key exchange.
+//!
+//! Identities publish a signed prekey and a batch of one-time prekeys
+//! to a prekey [`Server`].  A sender fetches a bundle of the recipient
+//! and completes its side of the key exchange on its own, so the
+//! recipient does not need to be online.  Messages are stored by the
+//! server until the recipient has picked them up and acknowledged them,
+//! and the recipient derives the session keys from the first message of
+//! every session.  Identities sign their prekeys, and senders check that
+//! signature against the identity they expect to talk to.
+//!
+//! Both sides reach the server through a secure channel, which is how
+//! the server knows who publishes prekeys, who sends a message and
+//! who picks messages up.
+
+pub mod types;
+
+use minicbor::{Decode, Decoder, Encode};
+use ockam_core::api::{self, decode_option, is_ok, Method, Request, Response};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::vault::{
+    KeyId, PublicKey, SecretAttributes, SecretPersistence, SecretType, Signature, Verifier,
+    CURVE25519_PUBLIC_LENGTH_USIZE, CURVE25519_SECRET_LENGTH_U32,
+};
+use ockam_core::{self, Address, AsyncTryClone, CowBytes, Result, Route, Routed, Worker};
+use ockam_identity::authenticated_storage::AuthenticatedStorage;
+use ockam_identity::{
+    Identity, IdentityIdentifier, IdentitySecureChannelLocalInfo, IdentityVault, PublicIdentity,
+};
+use ockam_key_exchange_core::KeyExchanger;
+use ockam_key_exchange_x3dh::{PreKeyBundle, X3dhNewKeyExchanger, X3dhVault};
+use ockam_node::Context;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::trace;
+use types::{signed_data, Bundle, Envelope, Header, PreKeys};
+
+/// Maximum number of messages stored for one recipient.
+const MAX_MESSAGES: usize = 1024;
+
+/// Maximum number of messages one sender can store for one recipient.
+const MAX_MESSAGES_PER_SENDER: usize = 64;
+
+/// Maximum size of the messages stored for one recipient, in bytes.
+const MAX_MAILBOX_SIZE: usize = 4 * 1024 * 1024;
+
+/// How long an initiator waits before it is handed another one-time
+/// prekey of the same identity.
+///
+/// Bundles fetched in between only carry the signed prekey, so that
+/// one initiator cannot drain the one-time prekeys of an identity.
+const ONE_TIME_PREKEY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Maximum number of one-time prekeys stored for one identity.
+const MAX_ONE_TIME_PREKEYS: usize = 1024;
+
+/// Length of the signature of a signed prekey.
+const PREKEY_SIGNATURE_LENGTH: usize = 64;
+
+/// How long a replaced signed prekey is kept by default.
+///
+/// Messages to a replaced signed prekey can only be read during that
+/// time.
+pub const SIGNED_PREKEY_GRACE_PERIOD: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Prekey bundle service and message store.
+///
+/// Prekeys and messages are kept in memory.  Messages stay stored
+/// until their recipient acknowledges them.
+///
+/// An initiator is handed at most one one-time prekey of an identity
+/// every [`ONE_TIME_PREKEY_INTERVAL`].  Other bundles, and all bundles
+/// once the one-time prekeys have run out, only carry the signed
+/// prekey.  Messages can only be left for identities that have
+/// published prekeys, and the mailbox of every recipient is limited in
+/// size, overall and per sender.
+#[derive(Debug)]
+pub struct Server<V> {
+    vault: V,
+    prekeys: HashMap<IdentityIdentifier, Published>,
+    messages: HashMap<IdentityIdentifier, Mailbox>,
+    /// When an initiator (first) was last handed a one-time prekey of an
+    /// identity (second).
+    handed_out: HashMap<(IdentityIdentifier, IdentityIdentifier), Instant>,
+}
+
+#[derive(Debug)]
+struct Published {
+    identity_key: Vec<u8>,
+    signed_prekey: Vec<u8>,
+    signature: Vec<u8>,
+    identity_signature: Vec<u8>,
+    one_time_prekeys: VecDeque<Vec<u8>>,
+}
+
+#[derive(Debug, Default)]
+struct Mailbox {
+    next_index: u64,
+    size: usize,
+    messages: VecDeque<Stored>,
+}
+
+#[derive(Debug)]
+struct Stored {
+    index: u64,
+    from: IdentityIdentifier,
+    data: Vec<u8>,
+}
+
+impl Mailbox {
+    /// Return why a message of `len` bytes from `from` is rejected.
+    fn check(&self, from: &IdentityIdentifier, len: usize) -> Option<&'static str> {
+        if self.messages.len() >= MAX_MESSAGES || self.size + len > MAX_MAILBOX_SIZE {
+            return Some("mailbox is full");
+        }
+        let sent = self.messages.iter().filter(|m| &m.from == from).count();
+        if sent >= MAX_MESSAGES_PER_SENDER {
+            return Some("too many messages from this sender");
+        }
+        None
+    }
+}
+
+#[ockam_core::worker]
+impl<V: Verifier + Send + Sync + 'static> Worker for Server<V> {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        if let Ok(i) = IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
+            let r = self.on_request(i.their_identity_id(), m.as_body()).await?;
+            c.send(m.return_route(), r).await
+        } else {
+            let mut dec = Decoder::new(m.as_body());
+            let req: Request = dec.decode()?;
+            let res = api::forbidden(&req, "secure channel required").to_vec()?;
+            c.send(m.return_route(), res).await
+        }
+    }
+}
+
+impl<V: Verifier + Send + Sync + 'static> Server<V> {
+    /// Create a prekey service that verifies prekey signatures with `vault`.
+    pub fn new(vault: V) -> Self {
+        Server {
+            vault,
+            prekeys: HashMap::new(),
+            messages: HashMap::new(),
+            handed_out: HashMap::new(),
+        }
+    }
+
+    async fn on_request(&mut self, from: &IdentityIdentifier, data: &[u8]) -> Result<Vec<u8>> {
+        let mut dec = Decoder::new(data);
+        let req: Request = dec.decode()?;
+
+        trace! {
+            target: "ockam_api::x3dh::server",
+            from   = %from,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+
+        let res = match req.method() {
+            Some(Method::Post) => match req.path_segments::<2>().as_slice() {
+                // An identity publishes its own prekeys.
+                ["prekeys"] => {
+                    let keys: PreKeys = dec.decode()?;
+                    let stored = self
+                        .prekeys
+                        .get(from)
+                        .map(|p| p.one_time_prekeys.len())
+                        .unwrap_or(0);
+                    if let Some(msg) = self.check_prekeys(&keys, stored).await {
+                        return Ok(api::bad_request(&req, msg).to_vec()?);
+                    }
+                    let one_time = keys.one_time_prekeys().iter().map(|k| k.to_vec());
+                    match self.prekeys.get_mut(from) {
+                        Some(p) => {
+                            p.identity_key = keys.identity_key().to_vec();
+                            p.signed_prekey = keys.signed_prekey().to_vec();
+                            p.signature = keys.signature().to_vec();
+                            p.identity_signature = keys.identity_signature().to_vec();
+                            p.one_time_prekeys.extend(one_time)
+                        }
+                        None => {
+                            let p = Published {
+                                identity_key: keys.identity_key().to_vec(),
+                                signed_prekey: keys.signed_prekey().to_vec(),
+                                signature: keys.signature().to_vec(),
+                                identity_signature: keys.identity_signature().to_vec(),
+                                one_time_prekeys: one_time.collect(),
+                            };
+                            self.prekeys.insert(from.clone(), p);
+                        }
+                    }
+                    Response::ok(req.id()).to_vec()?
+                }
+                // Somebody leaves a message for an identity.
+                ["messages", to] => {
+                    let to = IdentityIdentifier::try_from(*to)?;
+                    if !self.prekeys.contains_key(&to) {
+                        // Nobody could have started a session with it.
+                        return Ok(Response::not_found(req.id()).to_vec()?);
+                    }
+                    let env: Envelope = dec.decode()?;
+                    let mailbox = self.messages.entry(to).or_default();
+                    let index = mailbox.next_index;
+                    let data = minicbor::to_vec(env.with_from(from.clone()).with_index(index))?;
+                    if let Some(msg) = mailbox.check(from, data.len()) {
+                        Response::builder(req.id(), api::Status::Conflict)
+                            .body(api::Error::new(req.path()).with_message(msg))
+                            .to_vec()?
+                    } else {
+                        mailbox.size += data.len();
+                        mailbox.messages.push_back(Stored {
+                            index,
+                            from: from.clone(),
+                            data,
+                        });
+                        mailbox.next_index += 1;
+                        Response::ok(req.id()).to_vec()?
+                    }
+                }
+                _ => api::unknown_path(&req).to_vec()?,
+            },
+            Some(Method::Get) => match req.path_segments::<2>().as_slice() {
+                // Somebody wants to start a session with an identity.
+                ["prekeys", id] => {
+                    let id = IdentityIdentifier::try_from(*id)?;
+                    let key = (from.clone(), id);
+                    let now = Instant::now();
+                    let recent = self
+                        .handed_out
+                        .get(&key)
+                        .map(|t| now.duration_since(*t) < ONE_TIME_PREKEY_INTERVAL)
+                        .unwrap_or(false);
+                    match self.prekeys.get_mut(&key.1) {
+                        Some(p) => {
+                            let mut bundle = Vec::new();
+                            bundle.extend_from_slice(&p.identity_key);
+                            bundle.extend_from_slice(&p.signed_prekey);
+                            bundle.extend_from_slice(&p.signature);
+                            let otk = if recent {
+                                None
+                            } else {
+                                p.one_time_prekeys.pop_front()
+                            };
+                            if let Some(otk) = otk {
+                                bundle.extend_from_slice(&otk);
+                                self.handed_out.retain(|_, t| {
+                                    now.duration_since(*t) < ONE_TIME_PREKEY_INTERVAL
+                                });
+                                self.handed_out.insert(key, now);
+                            }
+                            let remaining = p.one_time_prekeys.len() as u32;
+                            let body =
+                                Bundle::new(bundle, remaining, p.identity_signature.as_slice());
+                            Response::ok(req.id()).body(body).to_vec()?
+                        }
+                        None => Response::not_found(req.id()).to_vec()?,
+                    }
+                }
+                // An identity picks up its messages.  They are kept until
+                // acknowledged.
+                ["messages"] => {
+                    let mut envs = Vec::new();
+                    if let Some(mailbox) = self.messages.get(from) {
+                        for m in &mailbox.messages {
+                            envs.push(minicbor::decode::<Envelope>(&m.data)?)
+                        }
+                    }
+                    Response::ok(req.id()).body(envs).to_vec()?
+                }
+                _ => api::unknown_path(&req).to_vec()?,
+            },
+            Some(Method::Delete) => match req.path_segments::<2>().as_slice() {
+                // An identity acknowledges its messages up to an index.
+                ["messages", index] => {
+                    let index: u64 = match index.parse() {
+                        Ok(i) => i,
+                        Err(_) => return Ok(api::bad_request(&req, "invalid index").to_vec()?),
+                    };
+                    if let Some(mailbox) = self.messages.get_mut(from) {
+                        mailbox.messages.retain(|m| m.index > index);
+                        mailbox.size = mailbox.messages.iter().map(|m| m.data.len()).sum();
+                        if mailbox.messages.is_empty() {
+                            self.messages.remove(from);
+                        }
+                    }
+                    Response::ok(req.id()).to_vec()?
+                }
+                _ => api::unknown_path(&req).to_vec()?,
+            },
+            _ => api::invalid_method(&req).to_vec()?,
+        };
+
+        Ok(res)
+    }
+
+    /// Check published prekeys, returning why they are rejected.
+    ///
+    /// `stored` is the number of one-time prekeys that have not been
+    /// handed out yet.
+    async fn check_prekeys(&self, keys: &PreKeys<'_>, stored: usize) -> Option<&'static str> {
+        if keys.identity_key().len() != CURVE25519_PUBLIC_LENGTH_USIZE {
+            return Some("invalid identity key");
+        }
+        if keys.signed_prekey().len() != CURVE25519_PUBLIC_LENGTH_USIZE {
+            return Some("invalid signed prekey");
+        }
+        if keys.signature().len() != PREKEY_SIGNATURE_LENGTH {
+            return Some("invalid prekey signature");
+        }
+        if keys.identity_signature().is_empty() {
+            return Some("missing identity signature");
+        }
+        if keys
+            .one_time_prekeys()
+            .iter()
+            .any(|k| k.len() != CURVE25519_PUBLIC_LENGTH_USIZE)
+        {
+            return Some("invalid one-time prekey");
+        }
+        if stored + keys.one_time_prekeys().len() > MAX_ONE_TIME_PREKEYS {
+            return Some("too many one-time prekeys");
+        }
+        let identity_key = PublicKey::new(keys.identity_key().to_vec(), SecretType::X25519);
+        let signature = Signature::new(keys.signature().to_vec());
+        let verified = self
+            .vault
+            .verify(&signature, &identity_key, keys.signed_prekey())
+            .await
+            .unwrap_or(false);
+        if !verified {
+            return Some("invalid prekey signature");
+        }
+        None
+    }
+}
+
+/// A decrypted message.
+#[derive(Debug, Clone)]
+pub struct Message {
+    from: Option<IdentityIdentifier>,
+    data: Vec<u8>,
+}
+
+impl Message {
+    /// The sender, as authenticated by the prekey service.
+    pub fn from(&self) -> Option<&IdentityIdentifier> {
+        self.from.as_ref()
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// The receiving side of a session, as persisted by a [`Client`].
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+struct Session {
+    #[n(1)] from: Option<IdentityIdentifier>,
+    #[n(2)] decrypt_key: KeyId,
+    #[n(3)] nonce: u64,
+}
+
+/// The signed prekeys of a [`Client`] that have not been destroyed.
+#[derive(Debug, Clone, Default, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+struct SignedPreKeys {
+    #[n(1)] keys: Vec<SignedPreKey>,
+}
+
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+struct SignedPreKey {
+    #[n(1)] key_id: KeyId,
+    /// When a newer signed prekey was published, in seconds since the epoch.
+    #[n(2)] replaced_at: Option<u64>,
+}
+
+/// A message that has been decrypted, but whose session has not been
+/// stored yet.
+struct Opened {
+    message: Message,
+    session: Session,
+    /// The one-time prekey to destroy once the session is stored.
+    one_time_prekey: Option<KeyId>,
+}
+
+/// Client of the prekey service.
+///
+/// The client owns an X25519 identity key in the vault of an Ockam
+/// identity.  It publishes prekeys signed with both, opens
+/// [`OfflineChannel`]s to other identities and decrypts the messages
+/// stored for it.  The sessions and signed prekeys of the client are
+/// kept in an [`AuthenticatedStorage`], so that a client created later
+/// with the same identity, storage and identity key can pick up where
+/// this one left off.
+pub struct Client<V: IdentityVault + X3dhVault, S: AuthenticatedStorage> {
+    ctx: Context,
+    route: Route,
+    identity: Identity<V>,
+    storage: S,
+    identity_key: KeyId,
+    grace_period: Duration,
+}
+
+impl<V: IdentityVault + X3dhVault, S: AuthenticatedStorage> Client<V, S> {
+    const SESSION_KEY_PREFIX: &'static str = "x3dh_session_";
+    const SIGNED_PREKEYS_KEY: &'static str = "x3dh_signed_prekeys";
+
+    /// Create a client for the prekey service at the given route.
+    ///
+    /// `identity_key` must be an X25519 secret of the identity vault.
+    /// It should be persistent, as messages sent to the prekeys signed
+    /// with it can only be decrypted with it.
+    pub async fn new(
+        route: Route,
+        identity: &Identity<V>,
+        storage: S,
+        identity_key: KeyId,
+    ) -> Result<Self> {
+        let ctx = identity.ctx().new_detached(Address::random_local()).await?;
+        Ok(Client {
+            ctx,
+            route,
+            identity: identity.async_try_clone().await?,
+            storage,
+            identity_key,
+            grace_period: SIGNED_PREKEY_GRACE_PERIOD,
+        })
+    }
+
+    /// Set how long a replaced signed prekey is kept.
+    ///
+    /// Defaults to [`SIGNED_PREKEY_GRACE_PERIOD`].
+    pub fn with_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Generate a persistent X25519 identity key for a client.
+    pub async fn generate_identity_key(vault: &V) -> Result<KeyId> {
+        vault.secret_generate(x25519_attributes()).await
+    }
+
+    /// Publish a new signed prekey and `count` one-time prekeys.
+    ///
+    /// Every session takes one of the one-time prekeys, so the client
+    /// should publish more of them before they run out.  The signed
+    /// prekeys replaced by earlier calls are destroyed once they have
+    /// been replaced for longer than the grace period.
+    pub async fn publish(&mut self, count: usize) -> Result<()> {
+        let vault = self.identity.vault();
+        let identity_key = vault.secret_public_key_get(&self.identity_key).await?;
+        let signed_prekey_id = vault.secret_generate(x25519_attributes()).await?;
+        let mut generated = vec![signed_prekey_id.clone()];
+        let mut one_time_prekeys = Vec::with_capacity(count);
+        for _ in 0..count {
+            let k = vault.secret_generate(x25519_attributes()).await?;
+            one_time_prekeys.push(CowBytes::from(
+                vault.secret_public_key_get(&k).await?.data().to_vec(),
+            ));
+            generated.push(k);
+        }
+
+        let res = self
+            .publish_prekeys(&identity_key, &signed_prekey_id, one_time_prekeys)
+            .await;
+        if res.is_err() {
+            // Nobody can use prekeys that were not published.
+            for k in generated {
+                if let Err(e) = self.identity.vault().secret_destroy(k).await {
+                    warn!(error = %e, "failed to destroy unpublished prekey")
+                }
+            }
+            return res;
+        }
+
+        self.replace_signed_prekey(signed_prekey_id).await
+    }
+
+    async fn publish_prekeys(
+        &mut self,
+        identity_key: &PublicKey,
+        signed_prekey_id: &KeyId,
+        one_time_prekeys: Vec<CowBytes<'_>>,
+    ) -> Result<()> {
+        let vault = self.identity.vault();
+        let signed_prekey = vault.secret_public_key_get(signed_prekey_id).await?;
+        let signature = vault.sign(&self.identity_key, signed_prekey.data()).await?;
+        let identity_signature = self
+            .identity
+            .create_signature(
+                &signed_data(identity_key.data(), signed_prekey.data()),
+                None,
+            )
+            .await?;
+        let body = PreKeys::new(
+            identity_key.data(),
+            signed_prekey.data(),
+            signature.as_ref(),
+            one_time_prekeys,
+            identity_signature.as_ref(),
+        );
+        let req = Request::post("prekeys").body(body);
+        let res = self.request("publish-prekeys", req).await?;
+        is_ok("publish-prekeys", &res)
+    }
+
+    /// Record a newly published signed prekey and destroy the replaced
+    /// ones whose grace period is over.
+    async fn replace_signed_prekey(&self, signed_prekey: KeyId) -> Result<()> {
+        let now = now()?;
+        let mut keys: SignedPreKeys = self
+            .get(Self::SIGNED_PREKEYS_KEY)
+            .await?
+            .unwrap_or_default();
+        let mut kept = Vec::with_capacity(keys.keys.len() + 1);
+        for mut k in keys.keys.drain(..) {
+            let replaced_at = *k.replaced_at.get_or_insert(now);
+            if now.saturating_sub(replaced_at) >= self.grace_period.as_secs() {
+                if let Err(e) = self.identity.vault().secret_destroy(k.key_id).await {
+                    warn!(error = %e, "failed to destroy replaced signed prekey")
+                }
+            } else {
+                kept.push(k)
+            }
+        }
+        kept.push(SignedPreKey {
+            key_id: signed_prekey,
+            replaced_at: None,
+        });
+        keys.keys = kept;
+        self.set(Self::SIGNED_PREKEYS_KEY.to_string(), &keys).await
+    }
+
+    /// Start a session with an identity that may be offline.
+    ///
+    /// This takes one of the one-time prekeys that the identity has
+    /// published, after checking that `to` has signed them.  When the
+    /// prekey service hands out no one-time prekey, the session only
+    /// relies on the signed prekey.  The first
+    /// message sent through the returned channel carries what the
+    /// recipient needs to derive the session keys.
+    pub async fn connect(&mut self, to: &PublicIdentity) -> Result<OfflineChannel<V>> {
+        let req = Request::get(format!("prekeys/{}", to.identifier()));
+        let res = self.request("get-prekeys", req).await?;
+        let (bundle, identity_signature) = match decode_option::<Bundle>("get-prekeys", None, &res)?
+        {
+            Some(b) => (
+                PreKeyBundle::try_from(b.bundle())?,
+                Signature::new(b.identity_signature().to_vec()),
+            ),
+            None => {
+                return Err(error(&format!(
+                    "no prekeys published by {}",
+                    to.identifier()
+                )))
+            }
+        };
+
+        let data = signed_data(bundle.identity_key().data(), bundle.signed_prekey().data());
+        let verified = to
+            .verify_signature(&identity_signature, &data, None, self.identity.vault())
+            .await
+            .unwrap_or(false);
+        if !verified {
+            return Err(error(&format!(
+                "prekeys are not signed by {}",
+                to.identifier()
+            )));
+        }
+
+        let vault = self.identity.vault();
+        let exchanger = X3dhNewKeyExchanger::new(vault.async_try_clone().await?);
+        let mut initiator = exchanger
+            .initiator_with_identity_key(self.identity_key.clone())
+            .await?;
+        let initiator_keys = initiator.generate_request(&[]).await?;
+        initiator.handle_response(&bundle.to_bytes()).await?;
+        let keys = initiator.finalize().await?;
+
+        let header = Header::new(
+            initiator_keys,
+            bundle.signed_prekey().data().to_vec(),
+            bundle
+                .one_time_prekey()
+                .map(|k| CowBytes::from(k.data().to_vec())),
+        );
+
+        Ok(OfflineChannel {
+            ctx: self.ctx.new_detached(Address::random_local()).await?,
+            route: self.route.clone(),
+            to: to.identifier().clone(),
+            vault: vault.async_try_clone().await?,
+            session: keys.h().to_vec(),
+            header: Some(header),
+            encrypt_key: keys.encrypt_key().clone(),
+            nonce: 0,
+        })
+    }
+
+    /// Pick up and decrypt the messages stored for this client.
+    ///
+    /// Messages that cannot be decrypted are skipped.  The prekey
+    /// service deletes the messages once their sessions are stored.  If
+    /// a session cannot be stored, the message and the ones after it are
+    /// left with the service and returned again by the next call.
+    pub async fn receive(&mut self) -> Result<Vec<Message>> {
+        let req = Request::get("messages");
+        let res = self.request("get-messages", req).await?;
+        let envs: Vec<Envelope> = decode_option("get-messages", None, &res)?.unwrap_or_default();
+        let mut messages = Vec::with_capacity(envs.len());
+        let mut handled = None;
+        for env in envs {
+            let index = env.index().ok_or_else(|| error("message without index"))?;
+            let session = match self.get(&Self::session_key(env.session())).await {
+                Ok(s) => s,
+                Err(e) => {
+                    warn!(error = %e, "failed to load offline session");
+                    break;
+                }
+            };
+            match self.open(&env, session).await {
+                Ok(opened) => {
+                    let key = Self::session_key(env.session());
+                    if let Err(e) = self.set(key, &opened.session).await {
+                        warn!(error = %e, "failed to store offline session");
+                        break;
+                    }
+                    if let Some(k) = opened.one_time_prekey {
+                        // One-time prekeys are only good for one session.
+                        if let Err(e) = self.identity.vault().secret_destroy(k).await {
+                            warn!(error = %e, "failed to destroy one-time prekey")
+                        }
+                    }
+                    messages.push(opened.message)
+                }
+                Err(e) => warn!(error = %e, "failed to decrypt offline message"),
+            }
+            handled = Some(index)
+        }
+        if let Some(index) = handled {
+            // Messages that are not deleted now come back with the next
+            // call and are rejected as replays.
+            let req = Request::delete(format!("messages/{}", index));
+            match self.request("delete-messages", req).await {
+                Ok(res) => {
+                    if let Err(e) = is_ok("delete-messages", &res) {
+                        warn!(error = %e, "failed to acknowledge offline messages")
+                    }
+                }
+                Err(e) => warn!(error = %e, "failed to acknowledge offline messages"),
+            }
+        }
+        Ok(messages)
+    }
+
+    async fn open(&self, env: &Envelope<'_>, session: Option<Session>) -> Result<Opened> {
+        let (mut session, one_time_prekey) = match (session, env.header()) {
+            (Some(s), _) => (s, None),
+            (None, Some(h)) => self.accept(env, h).await?,
+            (None, None) => return Err(error("unknown session")),
+        };
+        if session.from.as_ref() != env.from() {
+            return Err(error("message sender does not match the session"));
+        }
+        if env.nonce() < session.nonce {
+            return Err(error("message replayed"));
+        }
+        let data = self
+            .identity
+            .vault()
+            .aead_aes_gcm_decrypt(
+                &session.decrypt_key,
+                env.ciphertext(),
+                &nonce(env.nonce()),
+                env.session(),
+            )
+            .await?;
+        session.nonce = env.nonce() + 1;
+        Ok(Opened {
+            message: Message {
+                from: env.from().cloned(),
+                data,
+            },
+            session,
+            one_time_prekey,
+        })
+    }
+
+    /// Complete the key exchange started by the first message of a session.
+    ///
+    /// Returns the session and the one-time prekey it used, if any.
+    async fn accept(&self, env: &Envelope<'_>, h: &Header<'_>) -> Result<(Session, Option<KeyId>)> {
+        let signed_prekey = self.key_id(h.signed_prekey()).await?;
+        let one_time_prekey = match h.one_time_prekey() {
+            Some(k) => Some(self.key_id(k).await?),
+            None => None,
+        };
+
+        let exchanger = X3dhNewKeyExchanger::new(self.identity.vault().async_try_clone().await?);
+        let mut responder = exchanger
+            .responder_with_prekeys(
+                self.identity_key.clone(),
+                signed_prekey,
+                one_time_prekey.clone(),
+            )
+            .await?;
+        responder.handle_response(h.initiator_keys()).await?;
+        let keys = responder.finalize().await?;
+        if keys.h().as_slice() != env.session() {
+            return Err(error("session does not match the key exchange"));
+        }
+
+        let session = Session {
+            from: env.from().cloned(),
+            decrypt_key: keys.decrypt_key().clone(),
+            nonce: 0,
+        };
+        Ok((session, one_time_prekey))
+    }
+
+    /// Find the secret of one of our prekeys.
+    async fn key_id(&self, public_key: &[u8]) -> Result<KeyId> {
+        let vault = self.identity.vault();
+        let public_key = PublicKey::new(public_key.to_vec(), SecretType::X25519);
+        let key_id = vault.compute_key_id_for_public_key(&public_key).await?;
+        // Fails if the key is not ours or has been used already.
+        vault.secret_attributes_get(&key_id).await?;
+        Ok(key_id)
+    }
+
+    fn session_key(session: &[u8]) -> String {
+        format!("{}{}", Self::SESSION_KEY_PREFIX, hex::encode(session))
+    }
+
+    async fn get<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: for<'b> Decode<'b, ()>,
+    {
+        let id = self.identity.identifier().to_string();
+        match self.storage.get(&id, key).await? {
+            Some(v) => Ok(Some(minicbor::decode(&v)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn set<T: Encode<()>>(&self, key: String, val: &T) -> Result<()> {
+        let id = self.identity.identifier().to_string();
+        self.storage.set(&id, key, minicbor::to_vec(val)?).await
+    }
+
+    async fn request<T>(&mut self, label: &str, req: api::RequestBuilder<'_, T>) -> Result<Vec<u8>>
+    where
+        T: minicbor::Encode<()>,
+    {
+        ockam_node::api::request(&mut self.ctx, label, None, self.route.clone(), req).await
+    }
+}
+
+/// The sending side of a session with an identity that may be offline.
+pub struct OfflineChannel<V: X3dhVault> {
+    ctx: Context,
+    route: Route,
+    to: IdentityIdentifier,
+    vault: V,
+    session: Vec<u8>,
+    header: Option<Header<'static>>,
+    encrypt_key: KeyId,
+    nonce: u64,
+}
+
+impl<V: X3dhVault> OfflineChannel<V> {
+    /// The recipient of this channel.
+    pub fn recipient(&self) -> &IdentityIdentifier {
+        &self.to
+    }
+
+    /// Encrypt a message and leave it for the recipient.
+    pub async fn send(&mut self, data: &[u8]) -> Result<()> {
+        let ciphertext = self
+            .vault
+            .aead_aes_gcm_encrypt(&self.encrypt_key, data, &nonce(self.nonce), &self.session)
+            .await?;
+        // The header goes with every message until one has been stored,
+        // so that the recipient can always start the session.
+        let env = Envelope::new(
+            self.session.as_slice(),
+            self.header.clone(),
+            self.nonce,
+            ciphertext,
+        );
+        let req = Request::post(format!("messages/{}", self.to)).body(env);
+        let res =
+            ockam_node::api::request(&mut self.ctx, "send-message", None, self.route.clone(), req)
+                .await?;
+        is_ok("send-message", &res)?;
+        self.header = None;
+        self.nonce += 1;
+        Ok(())
+    }
+}
+
+fn x25519_attributes() -> SecretAttributes {
+    SecretAttributes::new(
+        SecretType::X25519,
+        SecretPersistence::Persistent,
+        CURVE25519_SECRET_LENGTH_U32,
+    )
+}
+
+/// Encode a message counter as an AES-GCM nonce.
+fn nonce(n: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[4..].copy_from_slice(&n.to_be_bytes());
+    nonce
+}
+
+/// Seconds since the epoch.
+fn now() -> Result<u64> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|_| error("system time is before the epoch"))
+}
+
+fn error(msg: &str) -> ockam_core::Error {
+    ockam_core::Error::new(Origin::Application, Kind::Invalid, msg)
+}
@@ -0,0 +1,234 @@
+use minicbor::{Decode, Encode};
+use ockam_core::CowBytes;
+use ockam_identity::IdentityIdentifier;
+
+#[cfg(feature = "tag")]
+use ockam_core::TypeTag;
+
+/// Prekeys published by an identity.
+///
+/// The signed prekey replaces any previously published one, the
+/// one-time prekeys are added to those not yet handed out.  The
+/// identity key and the signed prekey are signed by the Ockam identity
+/// publishing them, see [`signed_data`].
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PreKeys<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4071326>,
+    #[b(1)] identity_key: CowBytes<'a>,
+    #[b(2)] signed_prekey: CowBytes<'a>,
+    #[b(3)] signature: CowBytes<'a>,
+    #[b(4)] one_time_prekeys: Vec<CowBytes<'a>>,
+    #[b(5)] identity_signature: CowBytes<'a>,
+}
+
+impl<'a> PreKeys<'a> {
+    pub fn new(
+        identity_key: impl Into<CowBytes<'a>>,
+        signed_prekey: impl Into<CowBytes<'a>>,
+        signature: impl Into<CowBytes<'a>>,
+        one_time_prekeys: Vec<CowBytes<'a>>,
+        identity_signature: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        PreKeys {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity_key: identity_key.into(),
+            signed_prekey: signed_prekey.into(),
+            signature: signature.into(),
+            one_time_prekeys,
+            identity_signature: identity_signature.into(),
+        }
+    }
+
+    pub fn identity_key(&self) -> &[u8] {
+        &self.identity_key
+    }
+
+    pub fn signed_prekey(&self) -> &[u8] {
+        &self.signed_prekey
+    }
+
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    pub fn one_time_prekeys(&self) -> &[CowBytes<'a>] {
+        &self.one_time_prekeys
+    }
+
+    pub fn identity_signature(&self) -> &[u8] {
+        &self.identity_signature
+    }
+}
+
+/// A prekey bundle handed out to an initiator.
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Bundle<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6150283>,
+    #[b(1)] bundle: CowBytes<'a>,
+    /// Number of one-time prekeys left after this one.
+    ///
+    /// The bundle has no one-time prekey when they have run out, or
+    /// when the initiator has been handed one recently.
+    #[n(2)] remaining: u32,
+    /// Signature of the Ockam identity over the bundle keys.
+    #[b(3)] identity_signature: CowBytes<'a>,
+}
+
+impl<'a> Bundle<'a> {
+    pub fn new(
+        bundle: impl Into<CowBytes<'a>>,
+        remaining: u32,
+        identity_signature: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Bundle {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            bundle: bundle.into(),
+            remaining,
+            identity_signature: identity_signature.into(),
+        }
+    }
+
+    pub fn bundle(&self) -> &[u8] {
+        &self.bundle
+    }
+
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    pub fn identity_signature(&self) -> &[u8] {
+        &self.identity_signature
+    }
+}
+
+/// The key exchange data that starts an offline session.
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Header<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1357016>,
+    /// Identity and ephemeral keys of the initiator.
+    #[b(1)] initiator_keys: CowBytes<'a>,
+    /// The signed prekey of the recipient that was used.
+    #[b(2)] signed_prekey: CowBytes<'a>,
+    /// The one-time prekey of the recipient that was used, if any.
+    #[b(3)] one_time_prekey: Option<CowBytes<'a>>,
+}
+
+impl<'a> Header<'a> {
+    pub fn new(
+        initiator_keys: impl Into<CowBytes<'a>>,
+        signed_prekey: impl Into<CowBytes<'a>>,
+        one_time_prekey: Option<CowBytes<'a>>,
+    ) -> Self {
+        Header {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            initiator_keys: initiator_keys.into(),
+            signed_prekey: signed_prekey.into(),
+            one_time_prekey,
+        }
+    }
+
+    pub fn initiator_keys(&self) -> &[u8] {
+        &self.initiator_keys
+    }
+
+    pub fn signed_prekey(&self) -> &[u8] {
+        &self.signed_prekey
+    }
+
+    pub fn one_time_prekey(&self) -> Option<&[u8]> {
+        self.one_time_prekey.as_deref()
+    }
+}
+
+/// An encrypted message waiting for its recipient.
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Envelope<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8824530>,
+    /// The sender, as authenticated by the prekey service.
+    #[n(1)] from: Option<IdentityIdentifier>,
+    /// Identifier of the session (the X3DH handshake hash).
+    #[b(2)] session: CowBytes<'a>,
+    /// Present in the first message of a session.
+    #[b(3)] header: Option<Header<'a>>,
+    #[n(4)] nonce: u64,
+    #[b(5)] ciphertext: CowBytes<'a>,
+    /// Position in the mailbox of the recipient, set by the prekey service.
+    #[n(6)] index: Option<u64>,
+}
+
+impl<'a> Envelope<'a> {
+    pub fn new(
+        session: impl Into<CowBytes<'a>>,
+        header: Option<Header<'a>>,
+        nonce: u64,
+        ciphertext: impl Into<CowBytes<'a>>,
+    ) -> Self {
+        Envelope {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            from: None,
+            session: session.into(),
+            header,
+            nonce,
+            ciphertext: ciphertext.into(),
+            index: None,
+        }
+    }
+
+    pub fn with_from(mut self, from: IdentityIdentifier) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    pub fn with_index(mut self, index: u64) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    pub fn from(&self) -> Option<&IdentityIdentifier> {
+        self.from.as_ref()
+    }
+
+    pub fn index(&self) -> Option<u64> {
+        self.index
+    }
+
+    pub fn session(&self) -> &[u8] {
+        &self.session
+    }
+
+    pub fn header(&self) -> Option<&Header<'a>> {
+        self.header.as_ref()
+    }
+
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    pub fn ciphertext(&self) -> &[u8] {
+        &self.ciphertext
+    }
+}
+
+/// The data an Ockam identity signs to vouch for its prekeys.
+pub fn signed_data(identity_key: &[u8], signed_prekey: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(identity_key.len() + signed_prekey.len());
+    data.extend_from_slice(identity_key);
+    data.extend_from_slice(signed_prekey);
+    data
+}
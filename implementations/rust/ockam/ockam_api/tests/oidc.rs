@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use ockam::identity::authenticated_storage::mem::InMemoryStorage;
+use ockam::identity::Identity;
+use ockam::route;
+use ockam::vault::Vault;
+use ockam_api::authenticator::direct;
+use ockam_api::authenticator::direct::types::Enroller;
+use ockam_api::oidc::{IdToken, Provider, Server};
+use ockam_core::api::{Request, Response, Status};
+use ockam_core::Result;
+use ockam_identity::{IdentityIdentifier, PublicIdentity, TrustEveryonePolicy};
+use ockam_node::tokio;
+use ockam_node::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use ockam_node::Context;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use serde_json::json;
+use tempfile::NamedTempFile;
+
+const AUDIENCE: &str = "ockam";
+
+/// A signing key of the stub issuer.
+struct Key {
+    kid: String,
+    pkcs8: Vec<u8>,
+}
+
+impl Key {
+    fn new(kid: &str) -> Self {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        Key {
+            kid: kid.to_string(),
+            pkcs8: pkcs8.as_ref().to_vec(),
+        }
+    }
+
+    fn jwk(&self) -> serde_json::Value {
+        let pair = Ed25519KeyPair::from_pkcs8(&self.pkcs8).unwrap();
+        json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "use": "sig",
+            "alg": "EdDSA",
+            "kid": self.kid,
+            "x": base64::encode_config(pair.public_key(), base64::URL_SAFE_NO_PAD),
+        })
+    }
+
+    fn sign(&self, claims: &serde_json::Value) -> String {
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some(self.kid.clone());
+        let key = EncodingKey::from_ed_der(&self.pkcs8);
+        jsonwebtoken::encode(&header, claims, &key).unwrap()
+    }
+}
+
+/// A local OpenID Connect issuer serving its discovery document and the
+/// keys it is given.
+struct StubIssuer {
+    url: String,
+    jwks: Arc<Mutex<Vec<serde_json::Value>>>,
+    fetches: Arc<AtomicUsize>,
+}
+
+impl StubIssuer {
+    async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let jwks = Arc::new(Mutex::new(Vec::new()));
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        let (issuer, keys, count) = (url.clone(), jwks.clone(), fetches.clone());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = vec![0; 4096];
+                let n = stream.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let body = match path {
+                    "/.well-known/openid-configuration" => json!({
+                        "issuer": issuer,
+                        "jwks_uri": format!("{issuer}/keys"),
+                    }),
+                    "/keys" => {
+                        count.fetch_add(1, Ordering::SeqCst);
+                        json!({ "keys": *keys.lock().unwrap() })
+                    }
+                    _ => json!({}),
+                }
+                .to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        StubIssuer { url, jwks, fetches }
+    }
+
+    /// How many times the keys were fetched.
+    fn fetches(&self) -> usize {
+        self.fetches.load(Ordering::SeqCst)
+    }
+
+    fn publish(&self, key: &Key) {
+        self.jwks.lock().unwrap().push(key.jwk());
+    }
+
+    fn claims(&self, extra: serde_json::Value) -> serde_json::Value {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let mut claims = json!({
+            "iss": self.url,
+            "aud": AUDIENCE,
+            "sub": "alice",
+            "iat": now.as_secs(),
+            "exp": (now + Duration::from_secs(300)).as_secs(),
+        });
+        for (k, v) in extra.as_object().unwrap() {
+            claims[k] = v.clone();
+        }
+        claims
+    }
+
+    fn provider(&self) -> Provider {
+        let claims = BTreeMap::from([
+            ("email".to_string(), "email".to_string()),
+            ("role".to_string(), "role".to_string()),
+        ]);
+        Provider::new(&self.url, AUDIENCE, claims, None).unwrap()
+    }
+}
+
+#[ockam_macros::test]
+async fn verify_id_tokens(ctx: &mut Context) -> Result<()> {
+    let issuer = StubIssuer::start().await;
+    let key = Key::new("k1");
+    issuer.publish(&key);
+    let mut provider = issuer.provider();
+
+    // Mapped claims become attributes, others are dropped
+    let token = key.sign(&issuer.claims(json!({
+        "email": "alice@example.com",
+        "role": "admin",
+        "name": "Alice",
+    })));
+    let attrs = provider.verify(&token).await?;
+    assert_eq!(
+        attrs,
+        HashMap::from([
+            ("email".to_string(), "alice@example.com".to_string()),
+            ("role".to_string(), "admin".to_string()),
+        ])
+    );
+
+    // Tokens for another client, from another issuer, or expired are rejected
+    let token = key.sign(&issuer.claims(json!({ "aud": "other" })));
+    assert!(provider.verify(&token).await.is_err());
+    let token = key.sign(&issuer.claims(json!({ "iss": "http://other" })));
+    assert!(provider.verify(&token).await.is_err());
+    let token = key.sign(&issuer.claims(json!({ "exp": 1 })));
+    assert!(provider.verify(&token).await.is_err());
+
+    // A token signed with another key under a known key id is rejected
+    let forged = Key::new("k1");
+    assert!(provider
+        .verify(&forged.sign(&issuer.claims(json!({}))))
+        .await
+        .is_err());
+
+    // Unknown key ids do not make the provider fetch the keys for every
+    // token
+    let fetches = issuer.fetches();
+    let rotated = Key::new("k2");
+    let token = rotated.sign(&issuer.claims(json!({})));
+    for _ in 0..3 {
+        assert!(provider.verify(&token).await.is_err());
+    }
+    issuer.publish(&rotated);
+    assert!(provider.verify(&token).await.is_err());
+    assert_eq!(fetches, issuer.fetches());
+
+    // Rotated keys are fetched again once the refresh interval is over
+    let interval = Duration::from_millis(500);
+    let mut provider = issuer.provider().with_refresh_interval(interval);
+    assert!(provider.verify(&token).await?.is_empty());
+    let rotated = Key::new("k3");
+    let token = rotated.sign(&issuer.claims(json!({})));
+    issuer.publish(&rotated);
+    assert!(provider.verify(&token).await.is_err());
+    tokio::time::sleep(interval).await;
+    assert!(provider.verify(&token).await?.is_empty());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn enroll_with_id_token(ctx: &mut Context) -> Result<()> {
+    let issuer = StubIssuer::start().await;
+    let key = Key::new("k1");
+    issuer.publish(&key);
+
+    let mut tmpf = NamedTempFile::new().unwrap();
+    serde_json::to_writer(&mut tmpf, &HashMap::<IdentityIdentifier, Enroller>::new()).unwrap();
+
+    // Create the authority, with both services sharing the members store:
+    let store = InMemoryStorage::new();
+    let authority = {
+        let a = Identity::create(ctx, &Vault::create()).await?;
+        a.create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+            .await?;
+        let exported = a.export().await?;
+        let oidc = Server::new(store.clone(), issuer.provider());
+        ctx.start_worker("oidc", oidc).await?;
+        let auth = direct::Server::new(b"project42".to_vec(), store, tmpf.path(), a);
+        ctx.start_worker("auth", auth).await?;
+        exported
+    };
+
+    // Open a secure channel from member to authority:
+    let member = Identity::create(ctx, &Vault::create()).await?;
+    let m2a = member
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+
+    // An invalid token doesn't enroll the member
+    let token = key.sign(&issuer.claims(json!({ "aud": "other" })));
+    let status = enroll(ctx, route![m2a.clone(), "oidc"], &token).await?;
+    assert_eq!(Some(Status::Forbidden), status);
+    let mut c = direct::Client::new(route![m2a.clone(), "auth"], ctx).await?;
+    assert!(c.credential().await.is_err());
+
+    // A valid one does, with the claims as attributes of its credential
+    let token = key.sign(&issuer.claims(json!({ "role": "admin" })));
+    let status = enroll(ctx, route![m2a.clone(), "oidc"], &token).await?;
+    assert_eq!(Some(Status::Ok), status);
+    let cred = c.credential().await?;
+    let pkey = PublicIdentity::import(&authority, &Vault::create()).await?;
+    let data = pkey
+        .verify_credential(&cred, member.identifier(), &Vault::create())
+        .await?;
+    assert_eq!(
+        Some(b"project42".as_slice()),
+        data.attributes().get("project_id")
+    );
+    assert_eq!(Some(b"admin".as_slice()), data.attributes().get("role"));
+
+    ctx.stop().await
+}
+
+async fn enroll(ctx: &Context, route: ockam_core::Route, token: &str) -> Result<Option<Status>> {
+    let req = Request::post("v0/enroll").body(IdToken::new(token));
+    let mut buf = Vec::new();
+    req.encode(&mut buf)?;
+    let res: Vec<u8> = ctx.send_and_receive(route, buf).await?;
+    let res: Response = minicbor::decode(&res)?;
+    Ok(res.status())
+}
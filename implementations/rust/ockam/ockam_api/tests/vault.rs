@@ -3,10 +3,11 @@ use ockam_api::vault::models::{
     CreateSecretRequest, CreateSecretResponse, PublicKeyResponse, SignRequest, SignResponse,
     VerifyRequest, VerifyResponse,
 };
-use ockam_api::vault::VaultService;
+use ockam_api::vault::{RemoteVault, VaultService};
 use ockam_core::api::{Request, Response, Status};
-use ockam_core::vault::{SecretAttributes, SecretPersistence, SecretType};
+use ockam_core::vault::{SecretAttributes, SecretPersistence, SecretType, SecretVault};
 use ockam_core::{route, Result};
+use ockam_identity::Identity;
 use ockam_node::Context;
 use ockam_vault::Vault;
 
@@ -117,3 +118,34 @@ async fn full_flow(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn identity_with_remote_vault(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker(
+        "vault_service",
+        VaultService::new(Vault::create()).deny_export(),
+    )
+    .await?;
+
+    let vault = RemoteVault::create(ctx, route!["vault_service"]).await?;
+    let identity = Identity::create(ctx, &vault).await?;
+
+    let signature = identity.create_signature(b"hello", None).await?;
+    let public = identity.to_public().await?;
+    assert!(
+        public
+            .verify_signature(&signature, b"hello", None, &vault)
+            .await?
+    );
+
+    let key_id = vault
+        .secret_generate(SecretAttributes::new(
+            SecretType::X25519,
+            SecretPersistence::Ephemeral,
+            32,
+        ))
+        .await?;
+    assert!(vault.secret_export(&key_id).await.is_err());
+
+    ctx.stop().await
+}
@@ -0,0 +1,54 @@
+use ockam::identity::credential::Credential;
+use ockam::identity::Identity;
+use ockam::route;
+use ockam::vault::Vault;
+use ockam_api::authenticator::direct::{project_member_schema, PROJECT_ID, PROJECT_MEMBER_SCHEMA};
+use ockam_api::verifier::types::{VerifyRequest, VerifyResponse};
+use ockam_api::verifier::Verifier;
+use ockam_core::api::{decode_option, is_ok, Request};
+use ockam_core::Result;
+use ockam_node::Context;
+
+#[ockam_macros::test]
+async fn credentials_violating_their_schema_are_rejected(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+    let verifier = Verifier::new(vault.clone());
+    verifier.schemas().register(project_member_schema());
+    ctx.start_worker("verifier", verifier).await?;
+
+    // The authority does not know the schema, so it signs anything.
+    let authority = Identity::create(ctx, &vault).await?;
+    let member = Identity::create(ctx, &vault).await?;
+    let mut child = ctx.new_detached("client").await?;
+
+    // `role` must be UTF-8
+    let res = verify(&mut child, &authority, &member, &[0xff, 0xfe]).await?;
+    assert!(is_ok("verify", &res).is_err());
+
+    let res = verify(&mut child, &authority, &member, b"member").await?;
+    let verified: Option<VerifyResponse> = decode_option("verify", None, &res)?;
+    assert!(verified.is_some());
+
+    ctx.stop().await
+}
+
+/// Ask the verifier to check a project member credential with the given role.
+async fn verify(
+    ctx: &mut Context,
+    authority: &Identity<Vault>,
+    member: &Identity<Vault>,
+    role: &[u8],
+) -> Result<Vec<u8>> {
+    let credential = authority
+        .issue_credential(
+            Credential::builder(member.identifier().clone())
+                .with_schema(PROJECT_MEMBER_SCHEMA)
+                .with_attribute(PROJECT_ID, b"p1")
+                .with_attribute("role", role),
+        )
+        .await?;
+    let body = VerifyRequest::new(minicbor::to_vec(&credential)?, member.identifier().clone())
+        .with_authority(authority.identifier().clone(), authority.export().await?);
+    let req = Request::post("verify").body(body);
+    ockam_node::api::request(ctx, "verify", None, route!["verifier"], req).await
+}
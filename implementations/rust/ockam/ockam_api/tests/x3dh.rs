@@ -0,0 +1,366 @@
+use core::time::Duration;
+use ockam::identity::authenticated_storage::mem::InMemoryStorage;
+use ockam::identity::Identity;
+use ockam::route;
+use ockam::vault::Vault;
+use ockam_api::x3dh::types::{Bundle, Envelope, PreKeys};
+use ockam_api::x3dh::{Client, Server};
+use ockam_core::api::{decode_option, is_ok, Request};
+use ockam_core::{CowBytes, Result};
+use ockam_identity::TrustEveryonePolicy;
+use ockam_node::Context;
+
+#[ockam_macros::test]
+async fn offline_messages(ctx: &mut Context) -> Result<()> {
+    // The prekey service:
+    let server = Identity::create(ctx, &Vault::create()).await?;
+    server
+        .create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    ctx.start_worker("prekeys", Server::new(Vault::create()))
+        .await?;
+
+    let alice_vault = Vault::create();
+    let alice = Identity::create(ctx, &alice_vault).await?;
+    let bob_vault = Vault::create();
+    let bob = Identity::create(ctx, &bob_vault).await?;
+
+    let a2s = alice
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let b2s = bob
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+
+    // Bob publishes prekeys and goes away.
+    let bob_key = Client::<Vault, InMemoryStorage>::generate_identity_key(&bob_vault).await?;
+    let bob_storage = InMemoryStorage::new();
+    let mut bob_client = Client::new(
+        route![b2s.clone(), "prekeys"],
+        &bob,
+        bob_storage.clone(),
+        bob_key.clone(),
+    )
+    .await?;
+    bob_client.publish(1).await?;
+
+    // Alice sends messages while Bob is offline.
+    let alice_key = Client::<Vault, InMemoryStorage>::generate_identity_key(&alice_vault).await?;
+    let mut alice_client = Client::new(
+        route![a2s, "prekeys"],
+        &alice,
+        InMemoryStorage::new(),
+        alice_key,
+    )
+    .await?;
+    let bob_public = bob.to_public().await?;
+    let mut channel = alice_client.connect(&bob_public).await?;
+    channel.send(b"hello").await?;
+    channel.send(b"bob").await?;
+
+    // The only one-time prekey has been used, the next session only
+    // relies on the signed prekey.
+    let mut fallback = alice_client.connect(&bob_public).await?;
+    fallback.send(b"fallback").await?;
+
+    // Bob comes back and reads his messages.
+    let messages = bob_client.receive().await?;
+    assert_eq!(3, messages.len());
+    assert_eq!(b"hello", messages[0].data());
+    assert_eq!(b"bob", messages[1].data());
+    assert_eq!(b"fallback", messages[2].data());
+    assert_eq!(Some(alice.identifier()), messages[0].from());
+
+    // Later messages of the session are still readable, also by a
+    // client that only shares the storage of the first one.
+    channel.send(b"again").await?;
+    let mut bob_client = Client::new(route![b2s, "prekeys"], &bob, bob_storage, bob_key).await?;
+    let messages = bob_client.receive().await?;
+    assert_eq!(1, messages.len());
+    assert_eq!(b"again", messages[0].data());
+
+    // Nothing is left.
+    assert!(bob_client.receive().await?.is_empty());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn prekeys_signed_by_another_identity(ctx: &mut Context) -> Result<()> {
+    let server = Identity::create(ctx, &Vault::create()).await?;
+    server
+        .create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    ctx.start_worker("prekeys", Server::new(Vault::create()))
+        .await?;
+
+    let vault = Vault::create();
+    let alice = Identity::create(ctx, &vault).await?;
+    let bob = Identity::create(ctx, &vault).await?;
+    let mallory = Identity::create(ctx, &vault).await?;
+
+    // Mallory signs the prekeys published over the channel of Bob.
+    let b2s = bob
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let key = Client::<Vault, InMemoryStorage>::generate_identity_key(&vault).await?;
+    let mut client = Client::new(
+        route![b2s, "prekeys"],
+        &mallory,
+        InMemoryStorage::new(),
+        key,
+    )
+    .await?;
+    client.publish(1).await?;
+
+    let a2s = alice
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let key = Client::<Vault, InMemoryStorage>::generate_identity_key(&vault).await?;
+    let mut client =
+        Client::new(route![a2s, "prekeys"], &alice, InMemoryStorage::new(), key).await?;
+    assert!(client.connect(&bob.to_public().await?).await.is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn invalid_prekeys_are_rejected(ctx: &mut Context) -> Result<()> {
+    let server = Identity::create(ctx, &Vault::create()).await?;
+    server
+        .create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    ctx.start_worker("prekeys", Server::new(Vault::create()))
+        .await?;
+
+    let alice = Identity::create(ctx, &Vault::create()).await?;
+    let a2s = alice
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let mut child = ctx.new_detached("client").await?;
+
+    // Keys of the wrong length:
+    let keys = PreKeys::new(
+        vec![1u8; 31],
+        vec![2u8; 32],
+        vec![3u8; 64],
+        vec![CowBytes::from(vec![4u8; 32])],
+        vec![5u8; 64],
+    );
+    let req = Request::post("prekeys").body(keys);
+    let res = ockam_node::api::request(
+        &mut child,
+        "prekeys",
+        None,
+        route![a2s.clone(), "prekeys"],
+        req,
+    )
+    .await?;
+    assert!(is_ok("prekeys", &res).is_err());
+
+    // A prekey signature that does not verify:
+    let keys = PreKeys::new(
+        vec![1u8; 32],
+        vec![2u8; 32],
+        vec![3u8; 64],
+        vec![CowBytes::from(vec![4u8; 32])],
+        vec![5u8; 64],
+    );
+    let req = Request::post("prekeys").body(keys);
+    let res =
+        ockam_node::api::request(&mut child, "prekeys", None, route![a2s, "prekeys"], req).await?;
+    assert!(is_ok("prekeys", &res).is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn replaced_signed_prekeys_are_destroyed(ctx: &mut Context) -> Result<()> {
+    let server = Identity::create(ctx, &Vault::create()).await?;
+    server
+        .create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    ctx.start_worker("prekeys", Server::new(Vault::create()))
+        .await?;
+
+    let alice_vault = Vault::create();
+    let alice = Identity::create(ctx, &alice_vault).await?;
+    let bob_vault = Vault::create();
+    let bob = Identity::create(ctx, &bob_vault).await?;
+
+    let a2s = alice
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let b2s = bob
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+
+    let bob_key = Client::<Vault, InMemoryStorage>::generate_identity_key(&bob_vault).await?;
+    let mut bob_client = Client::new(
+        route![b2s, "prekeys"],
+        &bob,
+        InMemoryStorage::new(),
+        bob_key,
+    )
+    .await?
+    .with_grace_period(Duration::ZERO);
+    bob_client.publish(2).await?;
+
+    let alice_key = Client::<Vault, InMemoryStorage>::generate_identity_key(&alice_vault).await?;
+    let mut alice_client = Client::new(
+        route![a2s, "prekeys"],
+        &alice,
+        InMemoryStorage::new(),
+        alice_key,
+    )
+    .await?;
+    let mut channel = alice_client.connect(&bob.to_public().await?).await?;
+
+    // Without a grace period, the signed prekey used by Alice is gone
+    // as soon as Bob publishes a new one.
+    bob_client.publish(1).await?;
+    channel.send(b"too late").await?;
+    assert!(bob_client.receive().await?.is_empty());
+
+    // Sessions with the new signed prekey work.
+    let mut channel = alice_client.connect(&bob.to_public().await?).await?;
+    channel.send(b"hello").await?;
+    let messages = bob_client.receive().await?;
+    assert_eq!(1, messages.len());
+    assert_eq!(b"hello", messages[0].data());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn secure_channel_required(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("prekeys", Server::new(Vault::create()))
+        .await?;
+
+    let vault = Vault::create();
+    let identity = Identity::create(ctx, &vault).await?;
+    let key = Client::<Vault, InMemoryStorage>::generate_identity_key(&vault).await?;
+    let mut client = Client::new(route!["prekeys"], &identity, InMemoryStorage::new(), key).await?;
+    assert!(client.publish(1).await.is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn one_time_prekeys_are_not_drained(ctx: &mut Context) -> Result<()> {
+    let server = Identity::create(ctx, &Vault::create()).await?;
+    server
+        .create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    ctx.start_worker("prekeys", Server::new(Vault::create()))
+        .await?;
+
+    let vault = Vault::create();
+    let alice = Identity::create(ctx, &vault).await?;
+    let bob = Identity::create(ctx, &vault).await?;
+
+    let b2s = bob
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let key = Client::<Vault, InMemoryStorage>::generate_identity_key(&vault).await?;
+    let mut client = Client::new(route![b2s, "prekeys"], &bob, InMemoryStorage::new(), key).await?;
+    client.publish(3).await?;
+
+    // Alice is handed one one-time prekey, and then bundles without one.
+    let a2s = alice
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let mut child = ctx.new_detached("client").await?;
+    for (remaining, len) in [(2, 160), (2, 128), (2, 128)] {
+        let req = Request::get(format!("prekeys/{}", bob.identifier()));
+        let res = ockam_node::api::request(
+            &mut child,
+            "get-prekeys",
+            None,
+            route![a2s.clone(), "prekeys"],
+            req,
+        )
+        .await?;
+        let bundle: Bundle = decode_option("get-prekeys", None, &res)?.unwrap();
+        assert_eq!(remaining, bundle.remaining());
+        assert_eq!(len, bundle.bundle().len());
+    }
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn mailboxes_are_limited(ctx: &mut Context) -> Result<()> {
+    let server = Identity::create(ctx, &Vault::create()).await?;
+    server
+        .create_secure_channel_listener("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    ctx.start_worker("prekeys", Server::new(Vault::create()))
+        .await?;
+
+    let vault = Vault::create();
+    let alice = Identity::create(ctx, &vault).await?;
+    let bob = Identity::create(ctx, &vault).await?;
+    let a2s = alice
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let mut child = ctx.new_detached("client").await?;
+    let send = |data: Vec<u8>| {
+        Request::post(format!("messages/{}", bob.identifier())).body(Envelope::new(
+            vec![0u8; 32],
+            None,
+            0,
+            data,
+        ))
+    };
+
+    // Bob has not published prekeys, so nobody can write to him.
+    let res = ockam_node::api::request(
+        &mut child,
+        "send",
+        None,
+        route![a2s.clone(), "prekeys"],
+        send(vec![1]),
+    )
+    .await?;
+    assert!(is_ok("send", &res).is_err());
+
+    let b2s = bob
+        .create_secure_channel("api", TrustEveryonePolicy, &InMemoryStorage::new())
+        .await?;
+    let key = Client::<Vault, InMemoryStorage>::generate_identity_key(&vault).await?;
+    let mut client = Client::new(route![b2s, "prekeys"], &bob, InMemoryStorage::new(), key).await?;
+    client.publish(1).await?;
+
+    // Messages that do not fit are rejected.
+    let res = ockam_node::api::request(
+        &mut child,
+        "send",
+        None,
+        route![a2s.clone(), "prekeys"],
+        send(vec![1; 5 * 1024 * 1024]),
+    )
+    .await?;
+    assert!(is_ok("send", &res).is_err());
+
+    // A single sender can only leave so many messages.
+    let mut sent = 0;
+    loop {
+        let res = ockam_node::api::request(
+            &mut child,
+            "send",
+            None,
+            route![a2s.clone(), "prekeys"],
+            send(vec![1]),
+        )
+        .await?;
+        if is_ok("send", &res).is_err() {
+            break;
+        }
+        sent += 1;
+        assert!(sent <= 64);
+    }
+    assert_eq!(64, sent);
+
+    ctx.stop().await
+}
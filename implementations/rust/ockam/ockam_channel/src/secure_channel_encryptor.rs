@@ -41,6 +41,7 @@ impl<V: SecureChannelVault> SecureChannelEncryptor<V> {
 
         let reply = msg.return_route();
         let mut onward_route = msg.onward_route();
+        let priority = msg.local_message().priority();
         let transport_message = msg.into_transport_message();
         let payload = transport_message.payload;
 
@@ -72,7 +73,8 @@ impl<V: SecureChannelVault> SecureChannelEncryptor<V> {
             res
         };
 
-        ctx.send(self.remote_route.clone(), payload).await
+        ctx.send_with_priority(self.remote_route.clone(), payload, priority)
+            .await
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::configuration::NodeAlias;
 use crate::{util::exitcode, CommandGlobalOpts};
 use anyhow::anyhow;
 use clap::Args;
@@ -11,8 +12,7 @@ pub struct GetCommand {
 impl GetCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         if let Err(e) = run_impl(options, self) {
-            eprintln!("{}", e);
-            std::process::exit(e.code());
+            e.exit()
         }
     }
 }
@@ -20,10 +20,10 @@ impl GetCommand {
 fn run_impl(options: CommandGlobalOpts, cmd: GetCommand) -> crate::Result<()> {
     let lookup = options.config.lookup();
     match lookup.get_node(&cmd.alias) {
-        Some(addr) => {
-            println!("Node: {}\nAddress: {}", cmd.alias, addr);
-            Ok(())
-        }
+        Some(addr) => options.print(&NodeAlias {
+            node: cmd.alias,
+            address: addr.to_string(),
+        }),
         None => Err(crate::error::Error::new(
             exitcode::DATAERR,
             anyhow!(
@@ -43,3 +43,10 @@ impl ConfigurationCommand {
         }
     }
 }
+
+/// The address a node alias stands for
+#[derive(serde::Serialize)]
+pub struct NodeAlias {
+    pub node: String,
+    pub address: String,
+}
@@ -1,10 +1,12 @@
 use clap::Args;
 
 use ockam::Context;
+use ockam_api::authenticator::direct::project_member_schema;
+use ockam_identity::credential::{Credential, SchemaRegistry};
 
 use crate::node::NodeOpts;
 use crate::util::{api, node_rpc, Rpc};
-use crate::CommandGlobalOpts;
+use crate::{CommandGlobalOpts, OutputFormat};
 
 #[derive(Clone, Debug, Args)]
 pub struct GetCredentialCommand {
@@ -36,5 +38,16 @@ async fn run_impl(
     let mut rpc = Rpc::background(ctx, &opts, &cmd.node_opts.api_node)?;
     rpc.request(api::credentials::get_credential(cmd.overwrite))
         .await?;
+    let credential = rpc.parse_response::<Credential>()?;
+
+    match opts.global_args.output_format {
+        OutputFormat::Plain => {
+            // Attributes of known schemas are shown according to their type
+            let schemas = SchemaRegistry::new();
+            schemas.register(project_member_schema());
+            println!("{}", credential.display_with(&schemas));
+        }
+        OutputFormat::Json | OutputFormat::Yaml => opts.print(&credential)?,
+    }
     Ok(())
 }
@@ -1,9 +1,12 @@
 use std::convert::Infallible;
 use std::fmt::{Debug, Display, Formatter};
 
+use serde::Serialize;
+
+use crate::util::output::output_format;
 use crate::util::ConfigError;
 use crate::version::Version;
-use crate::{exitcode, ExitCode};
+use crate::{exitcode, ExitCode, OutputFormat};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -21,6 +24,46 @@ impl Error {
     pub fn code(&self) -> ExitCode {
         self.code
     }
+
+    /// Print the error to stderr, as a structured object if JSON or YAML
+    /// output was selected.
+    pub fn print(&self) {
+        let report = ErrorReport {
+            code: self.code,
+            kind: exitcode::name(self.code),
+            message: self.inner.to_string(),
+            causes: self.inner.chain().skip(1).map(|e| e.to_string()).collect(),
+        };
+        match output_format() {
+            OutputFormat::Plain => eprintln!("{self:?}"),
+            OutputFormat::Json => match serde_json::to_string_pretty(&report) {
+                Ok(s) => eprintln!("{s}"),
+                Err(_) => eprintln!("{self:?}"),
+            },
+            OutputFormat::Yaml => match serde_yaml::to_string(&report) {
+                Ok(s) => eprint!("{s}"),
+                Err(_) => eprintln!("{self:?}"),
+            },
+        }
+    }
+
+    /// Print the error and exit with its exit code.
+    pub fn exit(self) -> ! {
+        self.print();
+        std::process::exit(self.code)
+    }
+}
+
+/// How errors are reported in structured output.
+///
+/// The `kind` is the name of the exit `code`, e.g. `CONFIG`, and is stable.
+#[derive(Serialize)]
+struct ErrorReport {
+    code: ExitCode,
+    kind: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    causes: Vec<String>,
 }
 
 impl Debug for Error {
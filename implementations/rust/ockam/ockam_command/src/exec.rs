@@ -0,0 +1,333 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, Read, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _};
+use clap::Args;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use ockam::authenticated_storage::InMemoryStorage;
+use ockam::identity::{
+    Identity, IdentityIdentifier, TrustEveryonePolicy, TrustMultiIdentifiersPolicy,
+};
+use ockam::{Address, Context, TcpTransport};
+use ockam_api::config::lookup::LookupMeta;
+use ockam_api::{clean_multiaddr, multiaddr_to_route};
+use ockam_core::{Encodable, LocalMessage, Route, TransportMessage};
+use ockam_multiaddr::MultiAddr;
+use ockam_vault::storage::FileStorage;
+use ockam_vault::Vault;
+
+use crate::project::util::clean_projects_multiaddr;
+use crate::util::{exitcode, node_rpc};
+use crate::{help, CommandGlobalOpts, OckamConfig};
+
+const HELP_DETAIL: &str = "\
+About:
+    Send a message from a short-lived node embedded in this command, without
+    creating a background node first.
+
+    The standard input is the message, and the reply is written to the standard
+    output. With --pipe, each line of the standard input is a message, and each
+    reply is written as a line as soon as it arrives.
+
+    Routes may name nodes with /node/NAME and projects with /project/NAME. A secure
+    channel is created to each project, and to the --secure-channel listener when
+    given, using the default identity or the identity of the --identity node.
+
+Examples:
+```sh
+    # Send a message to the uppercase service of the node n1
+    $ echo hello | ockam exec --to /node/n1/service/uppercase
+    HELLO
+
+    # Send it through a secure channel to the api listener of n1
+    $ echo hello | ockam exec --secure-channel /node/n1/service/api --to /service/uppercase
+    HELLO
+
+    # Send a message to a service exposed through a project forwarder
+    $ echo hello | ockam exec --to /project/default/service/forward_to_n1/service/uppercase
+
+    # Send each line as a message, and print the replies as they come
+    $ tail -f requests.log | ockam exec --pipe --to /node/n1/service/uppercase
+```
+";
+
+/// Send the standard input as a message, and print the reply
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct ExecCommand {
+    /// The route to send messages to, after the secure channel if there is one
+    #[arg(long, value_name = "ROUTE")]
+    to: MultiAddr,
+
+    /// Route to a secure channel listener to send the messages through
+    #[arg(long, value_name = "ROUTE")]
+    secure_channel: Option<MultiAddr>,
+
+    /// Identifiers authorized to be presented by the secure channel listener
+    #[arg(long, short, value_name = "IDENTIFIER", requires = "secure_channel")]
+    authorized: Option<Vec<IdentityIdentifier>>,
+
+    /// Use the identity of this node for secure channels, instead of the default identity
+    #[arg(long, value_name = "NODE")]
+    identity: Option<String>,
+
+    /// Send each line of the standard input as a message, and print each reply as a line
+    #[arg(long)]
+    pipe: bool,
+
+    /// Seconds to wait for a reply
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: u64,
+}
+
+impl ExecCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self))
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, ExecCommand)) -> crate::Result<()> {
+    let _tcp = TcpTransport::create(&ctx).await?;
+    // The router owns the identity and vault of the secure channels,
+    // which must outlive the messages sent through them
+    let mut router = Router::new(&ctx, &opts.config, &cmd);
+    let route = router.route().await?;
+    if cmd.pipe {
+        pipe(&ctx, route, &cmd).await
+    } else {
+        send(&ctx, route, &cmd).await
+    }
+}
+
+/// Send the whole standard input as one message
+async fn send(ctx: &Context, route: Route, cmd: &ExecCommand) -> crate::Result<()> {
+    let mut message = Vec::new();
+    std::io::stdin()
+        .read_to_end(&mut message)
+        .context("failed to read the standard input")?;
+    let reply: Vec<u8> = ctx
+        .send_and_receive_with_timeout(route, message, cmd.timeout)
+        .await
+        .map_err(|e| crate::Error::new(exitcode::UNAVAILABLE, anyhow!("No reply: {e}")))?;
+    let mut stdout = std::io::stdout();
+    stdout.write_all(&reply)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Send each line of the standard input as a message, while printing
+/// the replies as they arrive
+async fn pipe(ctx: &Context, route: Route, cmd: &ExecCommand) -> crate::Result<()> {
+    // Replies come back to their own address, so that sending never
+    // waits for a reply
+    let replies = Address::random_local();
+    let mut rx = ctx.new_detached(replies.clone()).await?;
+
+    let (tx, mut lines) = mpsc::channel::<String>(16);
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            match line {
+                Ok(line) => {
+                    if tx.blocking_send(line).is_err() {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+    });
+
+    let timeout = Duration::from_secs(cmd.timeout);
+    // Send times of the messages still waiting for a reply, oldest first.
+    // Replies are matched in order, so the oldest message sets the deadline.
+    let mut pending: VecDeque<Instant> = VecDeque::new();
+    let mut stdin_open = true;
+    loop {
+        let deadline = match pending.front() {
+            Some(sent) => *sent + timeout,
+            None if stdin_open => Instant::now() + timeout,
+            None => return Ok(()),
+        };
+        tokio::select! {
+            line = lines.recv(), if stdin_open => match line {
+                Some(line) => {
+                    let payload = line.into_bytes().encode()?;
+                    let msg = TransportMessage::v1(route.clone(), replies.clone(), payload);
+                    ctx.forward(LocalMessage::new(msg, vec![])).await?;
+                    pending.push_back(Instant::now());
+                }
+                None => stdin_open = false,
+            },
+            reply = rx.receive::<Vec<u8>>() => {
+                let reply = match reply {
+                    Ok(reply) => reply.take().body(),
+                    // No reply yet: the deadline decides when to give up
+                    Err(_) => continue,
+                };
+                let mut stdout = std::io::stdout();
+                stdout.write_all(&reply)?;
+                stdout.write_all(b"\n")?;
+                stdout.flush()?;
+                pending.pop_front();
+            },
+            _ = tokio::time::sleep_until(deadline), if !pending.is_empty() => {
+                return Err(crate::Error::new(
+                    exitcode::UNAVAILABLE,
+                    anyhow!(
+                        "No reply to {} message(s) after {}s",
+                        pending.len(),
+                        cmd.timeout
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Builds the route to send messages to, creating the secure channels
+/// it goes through
+struct Router<'a> {
+    ctx: &'a Context,
+    cfg: &'a OckamConfig,
+    cmd: &'a ExecCommand,
+    identity: Option<Identity<Vault>>,
+    storage: InMemoryStorage,
+    /// Copy of the vault the identity is loaded from
+    _vault_dir: Option<tempfile::TempDir>,
+}
+
+impl<'a> Router<'a> {
+    fn new(ctx: &'a Context, cfg: &'a OckamConfig, cmd: &'a ExecCommand) -> Self {
+        Self {
+            ctx,
+            cfg,
+            cmd,
+            identity: None,
+            storage: InMemoryStorage::new(),
+            _vault_dir: None,
+        }
+    }
+
+    async fn route(&mut self) -> crate::Result<Route> {
+        let channel = match &self.cmd.secure_channel {
+            Some(listener) => {
+                let listener = self.resolve(listener, "--secure-channel").await?;
+                let authorized = self.cmd.authorized.clone();
+                Some(self.secure_channel(listener, authorized).await?)
+            }
+            None => None,
+        };
+        let mut route = self.resolve(&self.cmd.to, "--to").await?;
+        if let Some(channel) = channel {
+            route.modify().prepend(channel);
+        }
+        Ok(route)
+    }
+
+    /// Replace the nodes and projects of `addr` with routes to them
+    async fn resolve(&mut self, addr: &MultiAddr, arg: &str) -> crate::Result<Route> {
+        let lookup = self.cfg.lookup();
+        let (addr, meta) = clean_multiaddr(addr, &lookup)
+            .with_context(|| format!("Argument '{arg}' is invalid"))?;
+        let channels = self.project_channels(&meta).await?;
+        let addr = clean_projects_multiaddr(addr, channels)?;
+        let route = multiaddr_to_route(&addr)
+            .ok_or_else(|| anyhow!("Argument '{arg}' is not a valid route: {addr}"))?;
+        Ok(route)
+    }
+
+    async fn project_channels(&mut self, meta: &LookupMeta) -> crate::Result<Vec<MultiAddr>> {
+        let lookup = self.cfg.lookup();
+        let mut channels = Vec::with_capacity(meta.project.len());
+        for name in meta.project.iter() {
+            let project = lookup.get_project(name).with_context(|| {
+                format!("Unknown project {name}. Run `ockam project list` to refresh the projects")
+            })?;
+            let id = project
+                .identity_id
+                .clone()
+                .context("Project should have identity set")?;
+            let node_route = project
+                .node_route
+                .as_ref()
+                .and_then(multiaddr_to_route)
+                .context("Invalid project node route")?;
+            let channel = self.secure_channel(node_route, Some(vec![id])).await?;
+            channels.push(MultiAddr::from_str(&format!(
+                "/service/{}",
+                channel.address()
+            ))?);
+        }
+        Ok(channels)
+    }
+
+    async fn secure_channel(
+        &mut self,
+        route: Route,
+        authorized: Option<Vec<IdentityIdentifier>>,
+    ) -> crate::Result<Address> {
+        let timeout = Duration::from_secs(self.cmd.timeout);
+        let storage = self.storage.clone();
+        let identity = self.identity().await?;
+        let channel = match authorized {
+            Some(ids) => {
+                identity
+                    .create_secure_channel_extended(
+                        route,
+                        TrustMultiIdentifiersPolicy::new(ids),
+                        &storage,
+                        timeout,
+                    )
+                    .await?
+            }
+            None => {
+                identity
+                    .create_secure_channel_extended(route, TrustEveryonePolicy, &storage, timeout)
+                    .await?
+            }
+        };
+        Ok(channel)
+    }
+
+    /// The identity used for secure channels, loaded on first use
+    async fn identity(&mut self) -> crate::Result<&Identity<Vault>> {
+        if self.identity.is_none() {
+            let (vault_path, exported) = match &self.cmd.identity {
+                Some(node) => {
+                    let state = self.cfg.node(node)?.state().read().clone();
+                    (
+                        state.vault_path.context("The node has no vault")?,
+                        state.identity.context("The node has no identity")?,
+                    )
+                }
+                None => {
+                    let missing = "No default identity. Create one with `ockam node create`";
+                    (
+                        self.cfg.get_default_vault_path().context(missing)?,
+                        self.cfg.get_default_identity().context(missing)?,
+                    )
+                }
+            };
+            // Secure channels add keys to the vault, which must not
+            // overwrite the keys a running node adds to it meanwhile
+            let dir = tempfile::tempdir()?;
+            let copy: PathBuf = dir.path().join("vault.json");
+            std::fs::copy(&vault_path, &copy)
+                .with_context(|| format!("failed to read vault {}", vault_path.display()))?;
+            let storage = FileStorage::create(copy).await?;
+            let vault = Vault::new(Some(Arc::new(storage)));
+            self.identity = Some(Identity::import(self.ctx, &exported, &vault).await?);
+            self._vault_dir = Some(dir);
+        }
+        Ok(self
+            .identity
+            .as_ref()
+            .expect("the identity was just loaded"))
+    }
+}
@@ -7,7 +7,7 @@ use rand::prelude::random;
 use ockam::{Context, TcpTransport};
 use ockam_api::is_local_node;
 use ockam_api::nodes::models::forwarder::{CreateForwarder, ForwarderInfo};
-use ockam_core::api::Request;
+use ockam_core::api::{Request, RequestBuilder};
 use ockam_multiaddr::{MultiAddr, Protocol};
 
 use crate::forwarder::HELP_DETAIL;
@@ -49,27 +49,7 @@ impl CreateCommand {
 async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> Result<()> {
     let tcp = TcpTransport::create(&ctx).await?;
     let api_node = extract_address_value(&cmd.to)?;
-    let at_rust_node = is_local_node(&cmd.at).context("Argument --at is not valid")?;
-
-    let lookup = opts.config.lookup();
-    let ma = process_multi_addr(&cmd.at, &lookup)?;
-
-    let req = {
-        let alias = if at_rust_node {
-            format!("forward_to_{}", cmd.forwarder_name)
-        } else {
-            cmd.forwarder_name.clone()
-        };
-        let body = if cmd.at.matches(0, &[Project::CODE.into()]) {
-            if cmd.authorized.is_some() {
-                return Err(anyhow!("--authorized can not be used with project addresses").into());
-            }
-            CreateForwarder::at_project(ma, Some(alias))
-        } else {
-            CreateForwarder::at_node(ma, Some(alias), at_rust_node, cmd.authorized)
-        };
-        Request::post("/node/forwarder").body(body)
-    };
+    let req = create_forwarder_request(&opts, &cmd.forwarder_name, &cmd.at, cmd.authorized)?;
 
     let mut rpc = RpcBuilder::new(&ctx, &opts, &api_node).tcp(&tcp)?.build();
     rpc.request(req).await?;
@@ -78,6 +58,38 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> R
     Ok(())
 }
 
+/// Build the request creating the forwarder `name` at `at`.
+pub(crate) fn create_forwarder_request<'a>(
+    opts: &CommandGlobalOpts,
+    name: &str,
+    at: &MultiAddr,
+    authorized: Option<IdentityIdentifier>,
+) -> Result<RequestBuilder<'a, CreateForwarder<'a>>> {
+    let lookup = opts.config.lookup();
+    let ma = process_multi_addr(at, &lookup)?;
+    let alias = forwarder_alias(name, at)?;
+    let body = if at.matches(0, &[Project::CODE.into()]) {
+        if authorized.is_some() {
+            return Err(anyhow!("--authorized can not be used with project addresses").into());
+        }
+        CreateForwarder::at_project(ma, Some(alias))
+    } else {
+        let at_rust_node = is_local_node(at).context("Argument --at is not valid")?;
+        CreateForwarder::at_node(ma, Some(alias), at_rust_node, authorized)
+    };
+    Ok(Request::post("/node/forwarder").body(body))
+}
+
+/// The alias under which the forwarder `name` is registered at `at`.
+pub(crate) fn forwarder_alias(name: &str, at: &MultiAddr) -> anyhow::Result<String> {
+    let at_rust_node = is_local_node(at).context("Argument --at is not valid")?;
+    if at_rust_node {
+        Ok(format!("forward_to_{name}"))
+    } else {
+        Ok(name.to_string())
+    }
+}
+
 impl Output for ForwarderInfo<'_> {
     fn output(&self) -> anyhow::Result<String> {
         Ok(format!("/service/{}", self.remote_address()))
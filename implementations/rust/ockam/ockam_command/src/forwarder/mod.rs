@@ -1,10 +1,16 @@
 use clap::{Args, Subcommand};
 
-pub(crate) use create::CreateCommand;
+pub(crate) use create::{create_forwarder_request, forwarder_alias, CreateCommand};
+use delete::DeleteCommand;
+use list::ListCommand;
+use show::ShowCommand;
 
 use crate::{help, CommandGlobalOpts};
 
 mod create;
+mod delete;
+mod list;
+mod show;
 
 const HELP_DETAIL: &str = "\
 About:
@@ -62,12 +68,18 @@ pub struct ForwarderCommand {
 #[derive(Clone, Debug, Subcommand)]
 pub enum ForwarderSubCommand {
     Create(CreateCommand),
+    Delete(DeleteCommand),
+    List(ListCommand),
+    Show(ShowCommand),
 }
 
 impl ForwarderCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
         match self.subcommand {
             ForwarderSubCommand::Create(c) => c.run(opts),
+            ForwarderSubCommand::Delete(c) => c.run(opts),
+            ForwarderSubCommand::List(c) => c.run(opts),
+            ForwarderSubCommand::Show(c) => c.run(opts),
         }
     }
 }
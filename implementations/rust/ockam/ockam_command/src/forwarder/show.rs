@@ -0,0 +1,33 @@
+use clap::Args;
+use ockam::Context;
+use ockam_api::nodes::models::forwarder::ForwarderInfo;
+
+use crate::node::NodeOpts;
+use crate::util::{api, extract_address_value, node_rpc, Rpc};
+use crate::CommandGlobalOpts;
+
+/// Show a Forwarder
+#[derive(Clone, Debug, Args)]
+pub struct ShowCommand {
+    #[command(flatten)]
+    node_opts: NodeOpts,
+
+    /// Remote address of the forwarder, e.g. forward_to_blue
+    pub remote_address: String,
+}
+
+impl ShowCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, ShowCommand)) -> crate::Result<()> {
+    let node = extract_address_value(&cmd.node_opts.api_node)?;
+    let mut rpc = Rpc::background(&ctx, &opts, &node)?;
+    rpc.request(api::show_forwarder(&cmd.remote_address))
+        .await?;
+    let res = rpc.parse_response::<ForwarderInfo>()?;
+    rpc.print_response(vec![res])?;
+    Ok(())
+}
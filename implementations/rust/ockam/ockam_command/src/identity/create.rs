@@ -27,7 +27,6 @@ async fn run_impl(
     let mut rpc = Rpc::background(&ctx, &options, &cmd.node_opts.api_node)?;
     let request = Request::post("/node/identity");
     rpc.request(request).await?;
-    let res = rpc.parse_response::<CreateIdentityResponse>()?;
-    println!("Identity {} created!", res.identity_id);
+    rpc.parse_and_print_response::<CreateIdentityResponse>()?;
     Ok(())
 }
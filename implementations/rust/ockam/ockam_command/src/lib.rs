@@ -8,14 +8,17 @@ mod configuration;
 mod credential;
 mod enroll;
 mod error;
+mod exec;
 mod forwarder;
 mod help;
 mod identity;
 mod message;
 mod node;
 mod policy;
+mod portal;
 mod project;
 mod reset;
+mod route;
 mod secure_channel;
 mod service;
 mod space;
@@ -34,13 +37,16 @@ use configuration::ConfigurationCommand;
 use credential::CredentialCommand;
 use enroll::EnrollCommand;
 use error::{Error, Result};
+use exec::ExecCommand;
 use forwarder::ForwarderCommand;
 use identity::IdentityCommand;
 use message::MessageCommand;
 use node::NodeCommand;
 use policy::PolicyCommand;
+use portal::PortalCommand;
 use project::ProjectCommand;
 use reset::ResetCommand;
+use route::RouteCommand;
 use secure_channel::{listener::SecureChannelListenerCommand, SecureChannelCommand};
 use service::ServiceCommand;
 use space::SpaceCommand;
@@ -49,6 +55,7 @@ use tcp::{
     connection::TcpConnectionCommand, inlet::TcpInletCommand, listener::TcpListenerCommand,
     outlet::TcpOutletCommand,
 };
+use util::output::{set_output_format, Output};
 use util::{exitcode, exitcode::ExitCode, setup_logging, OckamConfig};
 use vault::VaultCommand;
 use version::Version;
@@ -194,14 +201,8 @@ pub struct GlobalArgs {
     #[arg(hide = help::hide(), global = true, long)]
     no_color: bool,
 
-    /// Output format
-    #[arg(
-        hide = help::hide(),
-        global = true,
-        long = "output",
-        value_enum,
-        default_value = "plain"
-    )]
+    /// Output format of command results and errors
+    #[arg(global = true, long = "output", value_enum, default_value = "plain")]
     output_format: OutputFormat,
 
     // if test_argument_parser is true, command arguments are checked
@@ -213,10 +214,11 @@ pub struct GlobalArgs {
     export: ExportCommandArgs,
 }
 
-#[derive(Debug, Clone, ValueEnum, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
 pub enum OutputFormat {
     Plain,
     Json,
+    Yaml,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -260,6 +262,15 @@ impl CommandGlobalOpts {
             config,
         }
     }
+
+    /// Print the result of a command in the selected output format.
+    pub fn print<T>(&self, value: &T) -> Result<()>
+    where
+        T: Output + serde::Serialize,
+    {
+        println!("{}", value.render(self.global_args.output_format)?);
+        Ok(())
+    }
 }
 
 #[derive(Debug, Subcommand)]
@@ -295,6 +306,12 @@ pub enum OckamSubcommand {
     Message(MessageCommand),
     #[command(display_order = 821)]
     Policy(PolicyCommand),
+    #[command(display_order = 822)]
+    Exec(ExecCommand),
+    #[command(display_order = 823)]
+    Portal(PortalCommand),
+    #[command(display_order = 824)]
+    Route(RouteCommand),
 
     #[command(display_order = 900)]
     Completion(CompletionCommand),
@@ -341,6 +358,7 @@ impl OckamCommand {
     pub fn run(self) {
         let config = OckamConfig::load().expect("Failed to load config");
         let options = CommandGlobalOpts::new(self.global_args, config);
+        set_output_format(options.global_args.output_format);
 
         // If test_argument_parser is true, command arguments are checked
         // but the command is not executed. This is useful to test arguments
@@ -353,10 +371,12 @@ impl OckamCommand {
             OckamSubcommand::Authenticated(c) => c.run(),
             OckamSubcommand::Configuration(c) => c.run(options),
             OckamSubcommand::Enroll(c) => c.run(options),
+            OckamSubcommand::Exec(c) => c.run(options),
             OckamSubcommand::Forwarder(c) => c.run(options),
             OckamSubcommand::Message(c) => c.run(options),
             OckamSubcommand::Node(c) => c.run(options),
             OckamSubcommand::Policy(c) => c.run(options),
+            OckamSubcommand::Portal(c) => c.run(options),
             OckamSubcommand::Project(c) => c.run(options),
             OckamSubcommand::Space(c) => c.run(options),
             OckamSubcommand::TcpConnection(c) => c.run(options),
@@ -372,6 +392,7 @@ impl OckamCommand {
             OckamSubcommand::Credential(c) => c.run(options),
             OckamSubcommand::Subscription(c) => c.run(options),
             OckamSubcommand::Reset(c) => c.run(options),
+            OckamSubcommand::Route(c) => c.run(options),
             OckamSubcommand::Admin(c) => c.run(options),
         }
     }
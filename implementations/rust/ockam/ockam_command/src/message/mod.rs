@@ -2,7 +2,7 @@ use crate::{help, CommandGlobalOpts};
 use clap::{Args, Subcommand};
 pub use send::SendCommand;
 
-mod send;
+pub(crate) mod send;
 
 const HELP_DETAIL: &str = "\
 About:
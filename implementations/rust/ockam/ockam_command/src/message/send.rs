@@ -77,10 +77,9 @@ async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, SendCommand)) ->
             .build();
         rpc.request(req(&to, &cmd.message)).await?;
         let res = rpc.parse_response::<Vec<u8>>()?;
-        println!(
-            "{}",
-            String::from_utf8(res).context("Received content is not a valid utf8 string")?
-        );
+        let reply =
+            String::from_utf8(res).context("Received content is not a valid utf8 string")?;
+        opts.print(&MessageReply { reply })?;
 
         // only delete node in case 'from' is empty and embedded node was started before
         if cmd.from.is_none() {
@@ -92,6 +91,12 @@ async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, SendCommand)) ->
     go(&mut ctx, &opts, cmd).await
 }
 
+/// The reply to the message sent by [`SendCommand`]
+#[derive(serde::Serialize)]
+pub struct MessageReply {
+    pub reply: String,
+}
+
 pub(crate) fn req<'a>(to: &'a MultiAddr, message: &'a str) -> RequestBuilder<'a, SendMessage<'a>> {
     Request::post("v0/message").body(SendMessage::new(to, message.as_bytes()))
 }
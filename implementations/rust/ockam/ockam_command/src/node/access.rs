@@ -0,0 +1,92 @@
+use clap::{Args, Subcommand};
+use ockam::identity::IdentityIdentifier;
+use ockam::Context;
+use ockam_api::nodes::models::access::{ApiRole, ApiUserList};
+
+use crate::node::NodeOpts;
+use crate::util::{api, extract_address_value, node_rpc, Rpc};
+use crate::{help, CommandGlobalOpts, Result};
+
+const HELP_DETAIL: &str = "\
+About:
+    Requests to a node which arrive over a secure channel are only served if the
+    identity at the other end of the channel may use the node API. Admins can read
+    and change the node, readers can only read it.
+
+    The policies which grant these rights can be changed with `ockam policy`, using
+    resources such as `node:tcp:listener` and the actions `read` and `write`.
+
+Examples:
+```sh
+    # Allow an identity to manage the node n1
+    $ ockam node access add P6c20e814b56579306f55c64e8747e6c1b4a53d9a3f4ca83c252cc2fbfc72fa94 --role admin -n n1
+
+    # List the identities allowed to use the API of node n1
+    $ ockam node access list -n n1
+
+    # Stop allowing an identity to use the API of node n1
+    $ ockam node access remove P6c20e814b56579306f55c64e8747e6c1b4a53d9a3f4ca83c252cc2fbfc72fa94 -n n1
+```
+";
+
+/// Manage the identities allowed to use the node API
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    after_long_help = help::template(HELP_DETAIL)
+)]
+pub struct AccessCommand {
+    #[command(subcommand)]
+    subcommand: AccessSubcommand,
+
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum AccessSubcommand {
+    /// Allow an identity to use the node API, or change its role
+    Add {
+        /// Identifier of the identity
+        identity: IdentityIdentifier,
+
+        /// Role of the identity: admin or reader
+        #[arg(short, long, default_value = "reader")]
+        role: ApiRole,
+    },
+    /// Stop allowing an identity to use the node API
+    Remove {
+        /// Identifier of the identity
+        identity: IdentityIdentifier,
+    },
+    /// List the identities allowed to use the node API
+    List,
+}
+
+impl AccessCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self))
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, AccessCommand)) -> Result<()> {
+    let node = extract_address_value(&cmd.node_opts.api_node)?;
+    let mut rpc = Rpc::background(&ctx, &opts, &node)?;
+    match cmd.subcommand {
+        AccessSubcommand::Add { identity, role } => {
+            rpc.request(api::add_api_user(identity, role)).await?;
+            rpc.is_ok()?
+        }
+        AccessSubcommand::Remove { identity } => {
+            rpc.request(api::remove_api_user(&identity)).await?;
+            rpc.is_ok()?
+        }
+        AccessSubcommand::List => {
+            rpc.request(api::list_api_users()).await?;
+            let users: ApiUserList = rpc.parse_response()?;
+            rpc.print_response(users.list)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::node::spec::{self, plan, Drift, NodeSpec, NodeState, SpecDrift};
+use crate::util::node_rpc;
+use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+use anyhow::anyhow;
+use clap::Args;
+use ockam::TcpTransport;
+use std::path::PathBuf;
+
+/// Make a node match a node spec
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct ApplyCommand {
+    /// Name of the node.
+    #[arg(default_value = "default")]
+    node_name: String,
+
+    /// Node spec file (YAML, or TOML with a .toml extension)
+    #[arg(short, long = "file")]
+    file: PathBuf,
+
+    /// Also remove what the node runs but the spec does not mention
+    #[arg(long)]
+    prune: bool,
+}
+
+impl ApplyCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: ockam::Context,
+    (opts, cmd): (CommandGlobalOpts, ApplyCommand),
+) -> crate::Result<()> {
+    let spec = NodeSpec::read(&cmd.file)?;
+    let tcp = TcpTransport::create(&ctx).await?;
+    let state = NodeState::fetch(&ctx, &opts, &cmd.node_name, &tcp, &spec).await?;
+
+    let mut applied = AppliedSpec {
+        node: cmd.node_name.clone(),
+        spec: cmd.file.display().to_string(),
+        changes: vec![],
+    };
+    for d in plan(&spec, &state, &opts.config.lookup())? {
+        let (outcome, error) = if matches!(d, Drift::Unexpected(_)) && !cmd.prune {
+            ("kept", None)
+        } else {
+            match spec::apply(&ctx, &opts, &cmd.node_name, &tcp, &spec, &d, cmd.prune).await {
+                Ok(()) => ("applied", None),
+                Err(e) => ("failed", Some(e.to_string())),
+            }
+        };
+        applied.changes.push(AppliedDrift {
+            drift: SpecDrift::from(&d),
+            outcome,
+            error,
+        });
+    }
+    opts.print(&applied)?;
+    let failed = applied.changes.iter().filter(|c| c.error.is_some()).count();
+    if failed > 0 {
+        return Err(anyhow!(
+            "{failed} change(s) could not be applied to node {}",
+            cmd.node_name
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// The changes made by [`ApplyCommand`]
+#[derive(serde::Serialize)]
+pub struct AppliedSpec {
+    pub node: String,
+    pub spec: String,
+    pub changes: Vec<AppliedDrift>,
+}
+
+/// What [`ApplyCommand`] did about one difference with the spec
+#[derive(serde::Serialize)]
+pub struct AppliedDrift {
+    #[serde(flatten)]
+    pub drift: SpecDrift,
+    /// `applied`, `kept` (without `--prune`) or `failed`
+    pub outcome: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -1,6 +1,7 @@
 use clap::Args;
 use minicbor::Decoder;
 use ockam_identity::credential::Credential;
+use ockam_identity::IdentityIdentifier;
 use rand::prelude::random;
 
 use anyhow::{anyhow, Context as _, Result};
@@ -17,7 +18,7 @@ use crate::service::start;
 use crate::util::{bind_to_port_check, embedded_node_that_is_not_stopped, exitcode};
 use crate::{
     help,
-    node::show::print_query_status,
+    node::show::{query_status, NodeStatus},
     node::HELP_DETAIL,
     project,
     util::{find_available_port, startup},
@@ -33,6 +34,7 @@ use ockam::{Address, AsyncTryClone, TCP};
 use ockam::{Context, TcpTransport};
 use ockam_api::{
     authenticator::direct::types::OneTimeCode,
+    nodes::models::access::ApiRole,
     nodes::models::transport::{TransportMode, TransportType},
     nodes::{
         service::{
@@ -98,6 +100,14 @@ pub struct CreateCommand {
 
     #[arg(long, hide = true)]
     pub config: Option<PathBuf>,
+
+    /// Identities which may read and change the node over a secure channel.
+    #[arg(long = "admin", value_name = "IDENTITY_ID")]
+    pub admins: Vec<IdentityIdentifier>,
+
+    /// Identities which may only read the node state over a secure channel.
+    #[arg(long = "reader", value_name = "IDENTITY_ID")]
+    pub readers: Vec<IdentityIdentifier>,
 }
 
 impl Default for CreateCommand {
@@ -113,6 +123,8 @@ impl Default for CreateCommand {
             project: None,
             config: None,
             token: None,
+            admins: Vec::new(),
+            readers: Vec::new(),
         }
     }
 }
@@ -123,8 +135,7 @@ impl CreateCommand {
             // Create a new node in the foreground (i.e. in this OS process)
             if let Err(e) = create_foreground_node(&options, &self) {
                 error!(%e);
-                eprintln!("{e:?}");
-                std::process::exit(e.code());
+                e.exit()
             }
         } else {
             // Create a new node running in the background (i.e. another, new OS process)
@@ -145,6 +156,13 @@ impl CreateCommand {
             ..cmd
         })
     }
+
+    /// The identities given with `--admin` and `--reader`, with their role.
+    fn api_users(&self) -> impl Iterator<Item = (IdentityIdentifier, ApiRole)> + '_ {
+        let admins = self.admins.iter().map(|id| (id.clone(), ApiRole::Admin));
+        let readers = self.readers.iter().map(|id| (id.clone(), ApiRole::Reader));
+        admins.chain(readers)
+    }
 }
 
 async fn run_impl(
@@ -168,20 +186,35 @@ async fn run_impl(
     let addr = SocketAddr::from_str(&cmd.tcp_listener_address)?;
     spawn_background_node(&ctx, &opts, &cmd, addr).await?;
 
-    // Print node status
     let tcp = TcpTransport::create(&ctx).await?;
     let mut rpc = RpcBuilder::new(&ctx, &opts, node_name).tcp(&tcp)?.build();
     let port = cfg.get_node_port(node_name)?;
-    print_query_status(&mut rpc, port, node_name, true).await?;
+    let status = query_status(&mut rpc, port, node_name, true).await?;
 
-    if get_credential {
+    for (id, role) in cmd.api_users() {
+        rpc.request(api::add_api_user(id, role)).await?;
+        rpc.is_ok()?;
+    }
+
+    let credential = if get_credential {
         rpc.request(api::credentials::get_credential(false)).await?;
-        if rpc.parse_and_print_response::<Credential>().is_err() {
-            eprintln!("failed to fetch membership credential");
-            delete_node(&opts, node_name, true);
-            opts.config.persist_config_updates()?
+        match rpc.parse_response::<Credential>() {
+            Ok(c) => Some(c.to_owned()),
+            Err(e) => {
+                delete_node(&opts, node_name, true);
+                opts.config.persist_config_updates()?;
+                return Err(crate::Error::new(
+                    exitcode::UNAVAILABLE,
+                    e.context("failed to fetch membership credential"),
+                ));
+            }
         }
-    }
+    } else {
+        None
+    };
+
+    // The node status, and its credential if any, are one document
+    opts.print(&CreatedNode { status, credential })?;
 
     // Run init and startup commands
     if let Some(config_path) = &cmd.config {
@@ -207,7 +240,7 @@ fn create_foreground_node(opts: &CommandGlobalOpts, cmd: &CreateCommand) -> crat
     // Thus we need to create the node dir so that subsequent
     // calls to it don't fail
     if cfg.get_node_dir(node_name).is_err() {
-        println!("Creating node directory...");
+        eprintln!("Creating node directory...");
         cfg.create_node(node_name, addr, verbose)?;
         cfg.persist_config_updates()?;
     }
@@ -248,6 +281,8 @@ async fn run_foreground_node(
     // Do we need to eagerly fetch a project membership credential?
     let get_credential = !cmd.child_process && cmd.project.is_some() && cmd.token.is_some();
 
+    let api_users: Vec<_> = cmd.api_users().collect();
+
     let tcp = TcpTransport::create(&ctx).await?;
     let bind = cmd.tcp_listener_address;
     tcp.listen(&bind).await?;
@@ -274,6 +309,9 @@ async fn run_foreground_node(
         ),
     )
     .await?;
+    for (id, role) in api_users {
+        node_man.add_api_user(id, role)?;
+    }
     let node_manager_worker = NodeManagerWorker::new(node_man);
 
     ctx.start_worker(NODEMANAGER_ADDR, node_manager_worker)
@@ -293,7 +331,7 @@ async fn run_foreground_node(
         match d.decode::<Response>() {
             Ok(hdr) if hdr.status() == Some(Status::Ok) && hdr.has_body() => {
                 let c: Credential = d.decode()?;
-                println!("{c}")
+                opts.print(&c)?
             }
             Ok(_) | Err(_) => {
                 eprintln!("failed to fetch membership credential");
@@ -328,14 +366,21 @@ async fn start_services(
 
     if let Some(cfg) = config.vault {
         if !cfg.disabled {
-            println!("starting vault service ...");
-            start::start_vault_service(ctx, opts, &node_opts.api_node, &cfg.address, Some(tcp))
-                .await?
+            eprintln!("starting vault service ...");
+            start::start_vault_service(
+                ctx,
+                opts,
+                &node_opts.api_node,
+                &cfg.address,
+                cfg.deny_export,
+                Some(tcp),
+            )
+            .await?
         }
     }
     if let Some(cfg) = config.identity {
         if !cfg.disabled {
-            println!("starting identity service ...");
+            eprintln!("starting identity service ...");
             start::start_identity_service(ctx, opts, &node_opts.api_node, &cfg.address, Some(tcp))
                 .await?
         }
@@ -345,20 +390,28 @@ async fn start_services(
             let adr = Address::from((LOCAL, cfg.address));
             let ids = cfg.authorized_identifiers;
             let rte = addr.clone().into();
-            println!("starting secure-channel listener ...");
-            secure_channel_listener::create_listener(ctx, adr, ids, rte).await?;
+            eprintln!("starting secure-channel listener ...");
+            let listener = secure_channel_listener::create_listener(ctx, adr, ids, rte).await?;
+            eprintln!("{}", listener.address);
         }
     }
     if let Some(cfg) = config.verifier {
         if !cfg.disabled {
-            println!("starting verifier service ...");
+            eprintln!("starting verifier service ...");
             start::start_verifier_service(ctx, opts, &node_opts.api_node, &cfg.address, Some(tcp))
                 .await?
         }
     }
+    if let Some(cfg) = config.prekeys {
+        if !cfg.disabled {
+            eprintln!("starting prekey service ...");
+            start::start_prekey_service(ctx, opts, &node_opts.api_node, &cfg.address, Some(tcp))
+                .await?
+        }
+    }
     if let Some(cfg) = config.authenticator {
         if !cfg.disabled {
-            println!("starting authenticator service ...");
+            eprintln!("starting authenticator service ...");
             start::start_authenticator_service(
                 ctx,
                 opts,
@@ -373,15 +426,32 @@ async fn start_services(
     }
     if let Some(cfg) = config.okta_identity_provider {
         if !cfg.disabled {
-            println!("starting okta identity provider service ...");
+            eprintln!("starting okta identity provider service ...");
             start::start_okta_identity_provider(ctx, opts, &node_opts.api_node, &cfg, Some(tcp))
                 .await?
         }
     }
+    if let Some(cfg) = config.oidc_identity_provider {
+        if !cfg.disabled {
+            eprintln!("starting oidc identity provider service ...");
+            start::start_oidc_identity_provider(ctx, opts, &node_opts.api_node, &cfg, Some(tcp))
+                .await?
+        }
+    }
 
     Ok(())
 }
 
+/// The node created by [`CreateCommand`]
+#[derive(serde::Serialize)]
+pub struct CreatedNode {
+    #[serde(flatten)]
+    pub status: NodeStatus,
+    /// The project membership credential fetched by the node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential: Option<Credential<'static>>,
+}
+
 async fn spawn_background_node(
     ctx: &Context,
     opts: &CommandGlobalOpts,
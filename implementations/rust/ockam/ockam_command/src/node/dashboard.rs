@@ -0,0 +1,658 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Stdout};
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use clap::Args;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use tokio::sync::mpsc;
+use tui::backend::{Backend, CrosstermBackend};
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap};
+use tui::{Frame, Terminal};
+
+use ockam::{Context, TcpTransport};
+use ockam_abac::Resource;
+use ockam_api::nodes::models::forwarder::ForwarderList;
+use ockam_api::nodes::models::policy::PolicyList;
+use ockam_api::resources;
+use ockam_core::api::Request;
+use ockam_core::Address;
+
+use crate::node::show::{query_status, NodeState, NodeStatus};
+use crate::policy::PolicyEntry;
+use crate::util::output::format_duration;
+use crate::util::{api, exitcode, node_rpc, startup, RpcBuilder};
+use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+
+/// Number of log lines shown for the selected node
+const LOG_LINES: usize = 200;
+
+/// Resources whose policies are shown for the selected node
+const POLICY_RESOURCES: [Resource; 3] = [resources::NODE, resources::INLET, resources::OUTLET];
+
+/// Show a live dashboard of the local nodes
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct DashboardCommand {
+    /// Name of the node selected first
+    node_name: Option<String>,
+
+    /// Seconds between two refreshes of the dashboard
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+    interval: u64,
+}
+
+impl DashboardCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, DashboardCommand),
+) -> crate::Result<()> {
+    let names: Vec<String> = opts.config.inner().nodes.keys().cloned().collect();
+    if names.is_empty() {
+        return Err(crate::Error::new(
+            exitcode::IOERR,
+            anyhow!("No nodes registered on this system!"),
+        ));
+    }
+    let mut dashboard = Dashboard::new(names, cmd.node_name.as_deref());
+    let tcp = TcpTransport::create(&ctx).await?;
+
+    // Terminal events are read on their own thread, as reading them blocks
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while let Ok(ev) = event::read() {
+            if tx.send(ev).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut terminal = Screen::new()?;
+    let mut ticks = tokio::time::interval(Duration::from_secs(cmd.interval));
+    dashboard.refresh(&ctx, &opts, &tcp).await;
+    while !dashboard.quit {
+        terminal.0.draw(|f| draw(f, &dashboard))?;
+        tokio::select! {
+            ev = rx.recv() => match ev {
+                Some(Event::Key(key)) => {
+                    if let Some(action) = dashboard.on_key(key) {
+                        dashboard.message = Some(match perform(&ctx, &opts, &tcp, &action).await {
+                            Ok(()) => format!("{action} done"),
+                            Err(e) => format!("{action} failed: {e}"),
+                        });
+                        dashboard.refresh(&ctx, &opts, &tcp).await;
+                    } else if dashboard.stale {
+                        dashboard.refresh(&ctx, &opts, &tcp).await;
+                    }
+                }
+                Some(_) => {}
+                None => break,
+            },
+            _ = ticks.tick() => dashboard.refresh(&ctx, &opts, &tcp).await,
+        }
+    }
+    Ok(())
+}
+
+/// The terminal, in raw mode on the alternate screen until dropped
+struct Screen(Terminal<CrosstermBackend<Stdout>>);
+
+impl Screen {
+    fn new() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+        terminal.hide_cursor()?;
+        Ok(Self(terminal))
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(self.0.backend_mut(), LeaveAlternateScreen);
+        let _ = self.0.show_cursor();
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tab {
+    Overview,
+    Channels,
+    Portals,
+    Forwarders,
+    Policies,
+    Logs,
+}
+
+const TABS: [Tab; 6] = [
+    Tab::Overview,
+    Tab::Channels,
+    Tab::Portals,
+    Tab::Forwarders,
+    Tab::Policies,
+    Tab::Logs,
+];
+
+impl Tab {
+    fn title(&self) -> &'static str {
+        match self {
+            Tab::Overview => "Overview",
+            Tab::Channels => "Secure Channels",
+            Tab::Portals => "Portals",
+            Tab::Forwarders => "Forwarders",
+            Tab::Policies => "Policies",
+            Tab::Logs => "Logs",
+        }
+    }
+}
+
+/// An action on a node, which the user must confirm
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Action {
+    DeleteChannel { node: String, address: String },
+    StopNode(String),
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::DeleteChannel { node, address } => {
+                write!(f, "Deleting secure channel {address} on node {node}")
+            }
+            Action::StopNode(node) => write!(f, "Stopping node {node}"),
+        }
+    }
+}
+
+/// The details fetched for the selected node only
+#[derive(Default)]
+struct Details {
+    secure_channels: Vec<String>,
+    forwarders: Vec<Forwarder>,
+    policies: Vec<PolicyEntry>,
+    logs: Vec<String>,
+}
+
+struct Forwarder {
+    remote_address: String,
+    forwarding_route: String,
+    status: Option<String>,
+}
+
+struct Dashboard {
+    nodes: Vec<NodeStatus>,
+    selected: usize,
+    tab: usize,
+    /// Index of the selected secure channel
+    channel: usize,
+    details: Details,
+    /// Whether the details are those of another node
+    stale: bool,
+    confirm: Option<Action>,
+    message: Option<String>,
+    quit: bool,
+}
+
+impl Dashboard {
+    fn new(names: Vec<String>, selected: Option<&str>) -> Self {
+        let selected = selected
+            .and_then(|s| names.iter().position(|n| n == s))
+            .unwrap_or(0);
+        Self {
+            nodes: names.iter().map(|n| NodeStatus::down(0, n)).collect(),
+            selected,
+            tab: 0,
+            channel: 0,
+            details: Details::default(),
+            stale: true,
+            confirm: None,
+            message: None,
+            quit: false,
+        }
+    }
+
+    fn node(&self) -> &NodeStatus {
+        &self.nodes[self.selected]
+    }
+
+    async fn refresh(&mut self, ctx: &Context, opts: &CommandGlobalOpts, tcp: &TcpTransport) {
+        for status in self.nodes.iter_mut() {
+            let name = status.name.clone();
+            match fetch_status(ctx, opts, tcp, &name).await {
+                Ok(s) => *status = s,
+                Err(e) => self.message = Some(format!("Failed to query node {name}: {e}")),
+            }
+        }
+        let name = self.node().name.clone();
+        let up = self.node().status == NodeState::Up;
+        match fetch_details(ctx, opts, tcp, &name, up).await {
+            Ok(d) => self.details = d,
+            Err(e) => self.message = Some(format!("Failed to query node {name}: {e}")),
+        }
+        self.channel = self
+            .channel
+            .min(self.details.secure_channels.len().saturating_sub(1));
+        self.stale = false;
+    }
+
+    /// Handle a key press, returning the action the user confirmed if any
+    fn on_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if let Some(action) = self.confirm.take() {
+            return match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => Some(action),
+                _ => {
+                    self.message = Some("Cancelled".to_string());
+                    None
+                }
+            };
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Up if self.selected > 0 => self.select(self.selected - 1),
+            KeyCode::Down if self.selected + 1 < self.nodes.len() => self.select(self.selected + 1),
+            KeyCode::Right | KeyCode::Tab => self.tab = (self.tab + 1) % TABS.len(),
+            KeyCode::Left | KeyCode::BackTab => self.tab = (self.tab + TABS.len() - 1) % TABS.len(),
+            KeyCode::Char('j') if self.channel + 1 < self.details.secure_channels.len() => {
+                self.channel += 1
+            }
+            KeyCode::Char('k') => self.channel = self.channel.saturating_sub(1),
+            KeyCode::Char('d') if TABS[self.tab] == Tab::Channels => {
+                if let Some(address) = self.details.secure_channels.get(self.channel) {
+                    self.confirm = Some(Action::DeleteChannel {
+                        node: self.node().name.clone(),
+                        address: address.clone(),
+                    })
+                }
+            }
+            KeyCode::Char('s') => self.confirm = Some(Action::StopNode(self.node().name.clone())),
+            KeyCode::Char('r') => self.stale = true,
+            _ => {}
+        }
+        None
+    }
+
+    fn select(&mut self, node: usize) {
+        self.selected = node;
+        self.channel = 0;
+        self.details = Details::default();
+        self.stale = true;
+    }
+}
+
+async fn fetch_status(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    name: &str,
+) -> anyhow::Result<NodeStatus> {
+    let port = opts.config.get_node_port(name)?;
+    let mut rpc = RpcBuilder::new(ctx, opts, name).tcp(tcp)?.build();
+    query_status(&mut rpc, port, name, false).await
+}
+
+async fn fetch_details(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    name: &str,
+    up: bool,
+) -> anyhow::Result<Details> {
+    let mut details = Details {
+        logs: match opts.config.node_log_paths(name) {
+            Some((log, _)) => tail(&log, LOG_LINES).unwrap_or_default(),
+            None => vec![],
+        },
+        ..Default::default()
+    };
+    if !up {
+        return Ok(details);
+    }
+
+    let mut rpc = RpcBuilder::new(ctx, opts, name).tcp(tcp)?.build();
+    rpc.request(api::list_secure_channels()).await?;
+    details.secure_channels = rpc.parse_response::<Vec<String>>()?;
+
+    let mut rpc = RpcBuilder::new(ctx, opts, name).tcp(tcp)?.build();
+    rpc.request(api::list_forwarders()).await?;
+    details.forwarders = rpc
+        .parse_response::<ForwarderList>()?
+        .list
+        .iter()
+        .map(|f| Forwarder {
+            remote_address: f.remote_address().to_string(),
+            forwarding_route: f.forwarding_route().to_string(),
+            status: f.status().map(|s| s.to_string()),
+        })
+        .collect();
+
+    for resource in &POLICY_RESOURCES {
+        let mut rpc = RpcBuilder::new(ctx, opts, name).tcp(tcp)?.build();
+        rpc.request(Request::get(format!("/policy/{resource}")))
+            .await?;
+        // A node may not let us read its policies
+        if let Ok(list) = rpc.parse_response::<PolicyList>() {
+            details.policies.extend(
+                list.expressions()
+                    .iter()
+                    .map(|(a, e)| PolicyEntry::new(resource, a, e)),
+            );
+        }
+    }
+    Ok(details)
+}
+
+async fn perform(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    action: &Action,
+) -> anyhow::Result<()> {
+    match action {
+        Action::DeleteChannel { node, address } => {
+            let mut rpc = RpcBuilder::new(ctx, opts, node).tcp(tcp)?.build();
+            let address = Address::from_string(address);
+            rpc.request(api::delete_secure_channel(&address)).await?;
+            rpc.is_ok()?;
+        }
+        Action::StopNode(node) => {
+            let cfg = &opts.config;
+            let pid = cfg
+                .get_node_pid(node)?
+                .ok_or_else(|| anyhow!("Node {node} is not running!"))?;
+            startup::stop(pid, false)?;
+            cfg.set_node_pid(node, None)?;
+            cfg.persist_config_updates()?;
+        }
+    }
+    Ok(())
+}
+
+/// The last `n` lines of a log file
+fn tail(path: &Path, n: usize) -> io::Result<Vec<String>> {
+    // Only read the end of large files
+    const MAX_READ: u64 = 64 * 1024;
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(MAX_READ)))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    let text = String::from_utf8_lossy(&buf);
+    let lines: Vec<&str> = text.lines().collect();
+    Ok(lines[lines.len().saturating_sub(n)..]
+        .iter()
+        .map(|l| l.to_string())
+        .collect())
+}
+
+fn draw<B: Backend>(f: &mut Frame<B>, dashboard: &Dashboard) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(f.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(28), Constraint::Min(20)])
+        .split(rows[0]);
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(columns[1]);
+
+    draw_nodes(f, columns[0], dashboard);
+
+    let titles = TABS.iter().map(|t| Spans::from(t.title())).collect();
+    let tabs = Tabs::new(titles)
+        .block(Block::default().borders(Borders::ALL))
+        .select(dashboard.tab)
+        .highlight_style(bold().fg(Color::Yellow));
+    f.render_widget(tabs, right[0]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", dashboard.node().name));
+    match TABS[dashboard.tab] {
+        Tab::Channels => {
+            let items: Vec<ListItem> = dashboard
+                .details
+                .secure_channels
+                .iter()
+                .map(|c| ListItem::new(c.as_str()))
+                .collect();
+            let mut state = ListState::default();
+            if !items.is_empty() {
+                state.select(Some(dashboard.channel));
+            }
+            let list = List::new(items)
+                .block(block)
+                .highlight_style(bold())
+                .highlight_symbol("> ");
+            f.render_stateful_widget(list, right[1], &mut state);
+        }
+        Tab::Logs => {
+            // Show the latest lines that fit
+            let height = right[1].height.saturating_sub(2) as usize;
+            let logs = &dashboard.details.logs;
+            let lines: Vec<Spans> = logs[logs.len().saturating_sub(height)..]
+                .iter()
+                .map(|l| Spans::from(l.as_str()))
+                .collect();
+            f.render_widget(Paragraph::new(lines).block(block), right[1]);
+        }
+        tab => {
+            let lines = tab_lines(tab, dashboard);
+            let paragraph = Paragraph::new(lines)
+                .block(block)
+                .wrap(Wrap { trim: false });
+            f.render_widget(paragraph, right[1]);
+        }
+    }
+
+    let status = match (&dashboard.confirm, &dashboard.message) {
+        (Some(_), _) => "y: confirm   any other key: cancel".to_string(),
+        (None, Some(m)) => m.clone(),
+        (None, None) => "↑↓: node   ←→: tab   j/k: channel   d: delete channel   s: stop node   r: refresh   q: quit".to_string(),
+    };
+    f.render_widget(Paragraph::new(status), rows[1]);
+
+    if let Some(action) = &dashboard.confirm {
+        let area = centered(f.size(), 60, 5);
+        let question = match action {
+            Action::DeleteChannel { node, address } => {
+                format!("Delete secure channel {address} on node {node}? [y/N]")
+            }
+            Action::StopNode(node) => format!("Stop node {node}? [y/N]"),
+        };
+        let popup = Paragraph::new(question)
+            .block(Block::default().borders(Borders::ALL).title(" Confirm "))
+            .wrap(Wrap { trim: true });
+        f.render_widget(Clear, area);
+        f.render_widget(popup, area);
+    }
+}
+
+fn draw_nodes<B: Backend>(f: &mut Frame<B>, area: Rect, dashboard: &Dashboard) {
+    let items: Vec<ListItem> = dashboard
+        .nodes
+        .iter()
+        .map(|n| {
+            let (state, color) = match n.status {
+                NodeState::Up => ("UP", Color::LightGreen),
+                NodeState::Down => ("DOWN", Color::LightRed),
+            };
+            let mut spans = vec![
+                Span::raw(format!("{:<14}", n.name)),
+                Span::styled(format!("{state:<5}"), Style::default().fg(color)),
+            ];
+            if let Some(r) = n.restarts.filter(|r| *r > 0) {
+                spans.push(Span::raw(format!("↻{r}")));
+            }
+            ListItem::new(Spans::from(spans))
+        })
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(dashboard.selected));
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(" Nodes "))
+        .highlight_style(bold())
+        .highlight_symbol("> ");
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn tab_lines(tab: Tab, dashboard: &Dashboard) -> Vec<Spans<'static>> {
+    let node = dashboard.node();
+    let details = &dashboard.details;
+    let mut lines = vec![];
+    let mut line = |s: String| lines.push(Spans::from(s));
+    match tab {
+        Tab::Overview => {
+            line(format!("Route:      {}", node.route));
+            line(format!("Verbose:    {}", node.verbose_route));
+            if let Some(id) = &node.identity {
+                line(format!("Identity:   {id}"));
+            }
+            if let Some(secs) = node.uptime {
+                line(format!("Uptime:     {}", format_duration(secs)));
+            }
+            if let Some(n) = node.restarts {
+                line(format!("Restarts:   {n}"));
+            }
+            if let Some(list) = &node.transports {
+                line(String::new());
+                line("Transports:".to_string());
+                for t in list {
+                    line(format!("  {} {} {}", t.tt, t.tm, t.payload));
+                }
+            }
+            if let Some(list) = &node.secure_channel_listeners {
+                line(String::new());
+                line("Secure Channel Listeners:".to_string());
+                for l in list {
+                    line(format!("  {l}"));
+                }
+            }
+            if let Some(list) = &node.services {
+                line(String::new());
+                line("Services:".to_string());
+                for s in list {
+                    let addr = s.address.as_deref().unwrap_or("-");
+                    line(format!("  {:<20} {addr}", s.service_type));
+                }
+            }
+        }
+        Tab::Portals => {
+            line("Inlets:".to_string());
+            for i in node.inlets.iter().flatten() {
+                let to = i.route_to_outlet.as_deref().unwrap_or("-");
+                line(format!("  {} => {to}", i.listen_address));
+            }
+            line(String::new());
+            line("Outlets:".to_string());
+            for o in node.outlets.iter().flatten() {
+                let addr = o.address.as_deref().unwrap_or("-");
+                line(format!("  {addr} => {}", o.forward_address));
+            }
+        }
+        Tab::Forwarders => {
+            for fw in &details.forwarders {
+                let status = fw.status.as_deref().unwrap_or("-");
+                line(format!(
+                    "{} via {} [{status}]",
+                    fw.remote_address, fw.forwarding_route
+                ));
+            }
+        }
+        Tab::Policies => {
+            for p in &details.policies {
+                line(format!("{}/{}: {}", p.resource, p.action, p.expression));
+            }
+        }
+        Tab::Channels | Tab::Logs => {}
+    }
+    lines
+}
+
+fn bold() -> Style {
+    Style::default().add_modifier(Modifier::BOLD)
+}
+
+/// A rectangle of the given size in the middle of `area`
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui::backend::TestBackend;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn actions_need_confirmation() {
+        let mut dashboard = Dashboard::new(vec!["n1".into(), "n2".into()], Some("n2"));
+        assert_eq!(dashboard.node().name, "n2");
+
+        dashboard.details.secure_channels = vec!["0#sc1".into(), "0#sc2".into()];
+        dashboard.tab = 1;
+        assert_eq!(dashboard.on_key(key(KeyCode::Char('j'))), None);
+        assert_eq!(dashboard.on_key(key(KeyCode::Char('d'))), None);
+        assert_eq!(
+            dashboard.on_key(key(KeyCode::Char('y'))),
+            Some(Action::DeleteChannel {
+                node: "n2".into(),
+                address: "0#sc2".into()
+            })
+        );
+
+        assert_eq!(dashboard.on_key(key(KeyCode::Char('s'))), None);
+        assert_eq!(dashboard.on_key(key(KeyCode::Char('n'))), None);
+        assert!(dashboard.confirm.is_none());
+
+        dashboard.on_key(key(KeyCode::Up));
+        assert_eq!(dashboard.node().name, "n1");
+        assert!(dashboard.stale);
+    }
+
+    #[test]
+    fn draw_nodes_and_confirmation() {
+        let mut dashboard = Dashboard::new(vec!["n1".into(), "n2".into()], None);
+        dashboard.confirm = Some(Action::StopNode("n1".into()));
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|f| draw(f, &dashboard)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol.as_str())
+            .collect();
+        assert!(screen.contains("n2"));
+        assert!(screen.contains("DOWN"));
+        assert!(screen.contains("Stop node n1? [y/N]"));
+    }
+}
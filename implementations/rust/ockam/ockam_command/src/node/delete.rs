@@ -1,4 +1,5 @@
 use crate::node::util::{delete_all_nodes, delete_node};
+use crate::util::output::Deleted;
 use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
 use clap::Args;
 
@@ -22,8 +23,7 @@ pub struct DeleteCommand {
 impl DeleteCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         if let Err(e) = run_impl(options, self) {
-            eprintln!("{}", e);
-            std::process::exit(e.code());
+            e.exit()
         }
     }
 }
@@ -34,7 +34,7 @@ fn run_impl(opts: CommandGlobalOpts, cmd: DeleteCommand) -> crate::Result<()> {
     } else {
         delete_node(&opts, &cmd.node_name, cmd.force);
         opts.config.persist_config_updates()?;
-        println!("Deleted node '{}'", &cmd.node_name);
+        opts.print(&Deleted::new("Node", cmd.node_name))?;
     }
     Ok(())
 }
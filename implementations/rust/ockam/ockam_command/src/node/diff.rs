@@ -0,0 +1,48 @@
+use crate::node::spec::{plan, NodeSpec, NodeState, SpecDrift};
+use crate::util::node_rpc;
+use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+use clap::Args;
+use ockam::TcpTransport;
+use std::path::PathBuf;
+
+/// Show how a node differs from a node spec
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct DiffCommand {
+    /// Name of the node.
+    #[arg(default_value = "default")]
+    node_name: String,
+
+    /// Node spec file (YAML, or TOML with a .toml extension)
+    #[arg(short, long = "file")]
+    file: PathBuf,
+}
+
+impl DiffCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: ockam::Context,
+    (opts, cmd): (CommandGlobalOpts, DiffCommand),
+) -> crate::Result<()> {
+    let spec = NodeSpec::read(&cmd.file)?;
+    let tcp = TcpTransport::create(&ctx).await?;
+    let state = NodeState::fetch(&ctx, &opts, &cmd.node_name, &tcp, &spec).await?;
+    let drift = plan(&spec, &state, &opts.config.lookup())?;
+    opts.print(&NodeDiff {
+        node: cmd.node_name,
+        spec: cmd.file.display().to_string(),
+        drift: drift.iter().map(SpecDrift::from).collect(),
+    })
+}
+
+/// How a node differs from a spec, as found by [`DiffCommand`]
+#[derive(serde::Serialize)]
+pub struct NodeDiff {
+    pub node: String,
+    pub spec: String,
+    pub drift: Vec<SpecDrift>,
+}
@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context};
+use clap::Args;
+use ockam_api::nodes::backup::{Bytes, NodeBackup};
+
+use crate::node::util::read_passphrase;
+use crate::util::{exitcode, OckamConfig};
+use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+
+/// Export a stopped node, with its identity and vault, into an encrypted archive
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct ExportCommand {
+    /// Name of the node
+    node_name: String,
+
+    /// File to write the archive to
+    #[arg(short, long = "file")]
+    file: PathBuf,
+
+    /// Read the passphrase protecting the archive from this file, instead of prompting for it
+    #[arg(long, value_name = "FILE")]
+    passphrase_file: Option<PathBuf>,
+}
+
+impl ExportCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        if let Err(e) = export(&options.config, &self).and_then(|node| options.print(&node)) {
+            e.exit()
+        }
+    }
+}
+
+fn export(cfg: &OckamConfig, cmd: &ExportCommand) -> crate::Result<ExportedNode> {
+    let node = cfg.get_node(&cmd.node_name)?;
+    // The storage of a running node may change while it is being copied
+    if let Some(pid) = node.pid() {
+        if nix::sys::signal::kill(nix::unistd::Pid::from_raw(pid), None).is_ok() {
+            return Err(crate::Error::new(
+                exitcode::UNAVAILABLE,
+                anyhow!(
+                    "Node {} is running. Stop it with `ockam node stop {}` before exporting it",
+                    cmd.node_name,
+                    cmd.node_name
+                ),
+            ));
+        }
+    }
+
+    let node_dir = cfg.get_node_dir(&cmd.node_name)?;
+    let mut backup = NodeBackup::collect(
+        node.name(),
+        &node_dir,
+        &node.addr().to_string(),
+        node.verbose(),
+    )?;
+
+    // A node created with the default identity of the CLI keeps it
+    // on import, so the default identity and its vault go along
+    let state = cfg.node(&cmd.node_name)?.state().read().clone();
+    let default_identity = cfg.get_default_identity();
+    if state.identity_was_overridden
+        && state.identity.is_some()
+        && state.identity == default_identity
+    {
+        if let Some(path) = cfg.get_default_vault_path() {
+            let vault = std::fs::read(&path)
+                .with_context(|| format!("failed to read vault {}", path.display()))?;
+            backup.default_identity = default_identity.map(Bytes);
+            backup.default_vault = Some(Bytes(vault));
+        }
+    }
+
+    let passphrase = read_passphrase(cmd.passphrase_file.as_deref(), true)?;
+    let archive = backup.seal(&passphrase)?;
+    std::fs::write(&cmd.file, archive)
+        .with_context(|| format!("failed to write {}", cmd.file.display()))?;
+    Ok(ExportedNode {
+        node: cmd.node_name.clone(),
+        file: cmd.file.display().to_string(),
+    })
+}
+
+/// The archive written by [`ExportCommand`]
+#[derive(serde::Serialize)]
+pub struct ExportedNode {
+    pub node: String,
+    pub file: String,
+}
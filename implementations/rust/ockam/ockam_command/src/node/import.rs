@@ -0,0 +1,108 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context};
+use clap::Args;
+use ockam_api::config::cli;
+use ockam_api::nodes::backup::NodeBackup;
+
+use crate::node::util::read_passphrase;
+use crate::util::{bind_to_port_check, exitcode, OckamConfig};
+use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+
+/// Import a node from an archive created with `ockam node export`
+///
+/// The node keeps its identity, authenticated storage and policies.
+/// It is left stopped.
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct ImportCommand {
+    /// Archive created with `ockam node export`
+    file: PathBuf,
+
+    /// Name of the imported node, instead of the name of the exported one
+    #[arg(long)]
+    name: Option<String>,
+
+    /// TCP listener address of the imported node, instead of the address of the exported one
+    #[arg(long)]
+    tcp_listener_address: Option<String>,
+
+    /// Read the passphrase protecting the archive from this file, instead of prompting for it
+    #[arg(long, value_name = "FILE")]
+    passphrase_file: Option<PathBuf>,
+}
+
+impl ImportCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        if let Err(e) = import(&options.config, &self).and_then(|node| options.print(&node)) {
+            e.exit()
+        }
+    }
+}
+
+fn import(cfg: &OckamConfig, cmd: &ImportCommand) -> crate::Result<ImportedNode> {
+    let archive = std::fs::read(&cmd.file)
+        .with_context(|| format!("failed to read {}", cmd.file.display()))?;
+    let passphrase = read_passphrase(cmd.passphrase_file.as_deref(), false)?;
+    let backup = NodeBackup::open(&archive, &passphrase)
+        .map_err(|e| crate::Error::new(exitcode::DATAERR, e))?;
+
+    let name = cmd.name.as_ref().unwrap_or(&backup.node_name);
+    if cfg.get_node(name).is_ok() {
+        return Err(crate::Error::new(
+            exitcode::CANTCREAT,
+            anyhow!("Node {name} already exists. Import it under another name with --name"),
+        ));
+    }
+    let address = cmd
+        .tcp_listener_address
+        .as_ref()
+        .unwrap_or(&backup.tcp_listener_address);
+    let addr: SocketAddr = address
+        .parse()
+        .with_context(|| format!("invalid TCP listener address {address}"))?;
+    if !bind_to_port_check(&addr) || cfg.port_is_used(addr.port()) {
+        return Err(crate::Error::new(
+            exitcode::IOERR,
+            anyhow!(
+                "Another process is listening on {addr}. Choose another address with --tcp-listener-address"
+            ),
+        ));
+    }
+
+    cfg.create_node(name, addr, backup.verbose)?;
+    let node_dir = cfg.get_node_dir_unchecked(name);
+    if let Err(e) = backup.restore(&node_dir) {
+        cfg.remove_node(name);
+        let _ = std::fs::remove_dir_all(&node_dir);
+        return Err(e.into());
+    }
+
+    // Nodes created later on get the identity of the exported node
+    // only if there is no default identity yet
+    if let (Some(identity), Some(vault)) = (&backup.default_identity, &backup.default_vault) {
+        if cfg.get_default_identity().is_none() {
+            let path = cfg
+                .get_default_vault_path()
+                .unwrap_or_else(|| cli::OckamConfig::dir().join("default_vault.json"));
+            std::fs::write(&path, &vault.0)
+                .with_context(|| format!("failed to write vault {}", path.display()))?;
+            cfg.set_default_vault_path(Some(path));
+            cfg.set_default_identity(Some(identity.0.clone()));
+        }
+    }
+    cfg.persist_config_updates()?;
+
+    Ok(ImportedNode {
+        node: name.clone(),
+        address: addr.to_string(),
+    })
+}
+
+/// The node imported by [`ImportCommand`]
+#[derive(serde::Serialize)]
+pub struct ImportedNode {
+    pub node: String,
+    pub address: String,
+}
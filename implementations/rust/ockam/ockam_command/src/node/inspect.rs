@@ -0,0 +1,198 @@
+use crate::util::output::Output;
+use crate::util::{api, extract_address_value, node_rpc, Rpc};
+use crate::{help, CommandGlobalOpts};
+use clap::Args;
+use core::fmt::Write;
+use ockam::Context;
+use ockam_api::nodes::models::topology::NodeTopology;
+
+const HELP_DETAIL: &str = "\
+About:
+    Show the workers and processors running on a node, with their aliases
+    and clusters.
+
+    Nodes built with the `debugger` feature also record their mailboxes,
+    access controls and message flows, which are shown as well.
+
+Examples:
+```sh
+    # Show the worker graph of node n1
+    $ ockam node inspect n1
+
+    # Render the worker graph of node n1 with Graphviz
+    $ ockam node inspect n1 --dot | dot -Tpdf -o n1.pdf
+
+    # Show the worker graph of node n1 as JSON
+    $ ockam node inspect n1 --output json
+```
+";
+
+/// Inspect the worker graph of a node
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct InspectCommand {
+    /// Name of the node.
+    #[arg(default_value = "default")]
+    node_name: String,
+
+    /// Print the worker graph in the Graphviz DOT format
+    #[arg(long)]
+    dot: bool,
+}
+
+impl InspectCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, InspectCommand),
+) -> crate::Result<()> {
+    let node_name = extract_address_value(&cmd.node_name)?;
+    let mut rpc = Rpc::background(&ctx, &opts, &node_name)?;
+    rpc.request(api::node_topology()).await?;
+    if cmd.dot {
+        let topology = rpc.parse_response::<NodeTopology>()?;
+        print!("{}", to_dot(&node_name, &topology)?);
+    } else {
+        rpc.parse_and_print_response::<NodeTopology>()?;
+    }
+    Ok(())
+}
+
+impl Output for NodeTopology<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        writeln!(w, "Workers:")?;
+        for worker in &self.workers {
+            let kind = if worker.processor {
+                "processor"
+            } else if worker.detached {
+                "detached"
+            } else {
+                "worker"
+            };
+            writeln!(w, "  {} ({})", worker.address, kind)?;
+            if !worker.aliases.is_empty() {
+                let aliases: Vec<&str> = worker.aliases.iter().map(|a| a.as_ref()).collect();
+                writeln!(w, "    Aliases: {}", aliases.join(", "))?;
+            }
+            if let Some(cluster) = &worker.cluster {
+                writeln!(w, "    Cluster: {}", cluster)?;
+            }
+        }
+
+        if !self.recorded {
+            writeln!(w)?;
+            write!(
+                w,
+                "Mailboxes and message flows were not recorded by this node. \
+                 Build it with the `debugger` feature to record them."
+            )?;
+            return Ok(w);
+        }
+
+        writeln!(w, "Mailboxes:")?;
+        for mailbox in &self.mailboxes {
+            writeln!(w, "  {}", mailbox.address)?;
+            writeln!(w, "    Incoming: {}", mailbox.incoming_access_control)?;
+            writeln!(w, "    Outgoing: {}", mailbox.outgoing_access_control)?;
+        }
+        writeln!(w, "Inheritance:")?;
+        for i in &self.inheritance {
+            writeln!(w, "  {} => {}", i.parent, i.child)?;
+        }
+        write!(w, "Message flows:")?;
+        for edge in &self.edges {
+            write!(
+                w,
+                "\n  {} -> {} ({})",
+                edge.source, edge.destination, edge.count
+            )?;
+        }
+        Ok(w)
+    }
+}
+
+/// Render a worker graph in the Graphviz DOT format
+fn to_dot(node_name: &str, t: &NodeTopology) -> anyhow::Result<String> {
+    fn escape(s: &str) -> String {
+        s.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+
+    let mut w = String::new();
+    writeln!(w, "digraph \"{}\" {{", escape(node_name))?;
+    writeln!(w, "  fontname=Arial;")?;
+    writeln!(w, "  rankdir=LR;")?;
+    writeln!(w, "  node [fontname=Arial, fontsize=12.0, shape=record];")?;
+
+    for worker in &t.workers {
+        let shape = if worker.processor {
+            "Mrecord"
+        } else {
+            "record"
+        };
+        let mut label = escape(&worker.address);
+        for alias in &worker.aliases {
+            write!(label, " | {}", escape(alias))?;
+        }
+        if let Some(mailbox) = t.mailboxes.iter().find(|m| m.address == worker.address) {
+            write!(
+                label,
+                " | in: {} | out: {}",
+                escape(&mailbox.incoming_access_control),
+                escape(&mailbox.outgoing_access_control)
+            )?;
+        }
+        writeln!(
+            w,
+            "  \"{}\" [shape={}, label=\"{{ {} }}\"];",
+            escape(&worker.address),
+            shape,
+            label
+        )?;
+    }
+
+    let mut clusters: Vec<&str> = t
+        .workers
+        .iter()
+        .filter_map(|w| w.cluster.as_deref())
+        .collect();
+    clusters.sort_unstable();
+    clusters.dedup();
+    for (i, cluster) in clusters.iter().enumerate() {
+        writeln!(w, "  subgraph cluster_{} {{", i)?;
+        writeln!(w, "    label=\"{}\";", escape(cluster))?;
+        for worker in t
+            .workers
+            .iter()
+            .filter(|w| w.cluster.as_deref() == Some(cluster))
+        {
+            writeln!(w, "    \"{}\";", escape(&worker.address))?;
+        }
+        writeln!(w, "  }}")?;
+    }
+
+    for i in &t.inheritance {
+        writeln!(
+            w,
+            "  \"{}\" -> \"{}\" [style=dashed, color=\"#1f78b4\"];",
+            escape(&i.parent),
+            escape(&i.child)
+        )?;
+    }
+    for edge in &t.edges {
+        writeln!(
+            w,
+            "  \"{}\" -> \"{}\" [label=\"{}\"];",
+            escape(&edge.source),
+            escape(&edge.destination),
+            edge.count
+        )?;
+    }
+
+    writeln!(w, "}}")?;
+    Ok(w)
+}
@@ -1,5 +1,5 @@
 use crate::util::{exitcode, node_rpc, verify_pids, RpcBuilder};
-use crate::{help, node::show::print_query_status, node::HELP_DETAIL, CommandGlobalOpts};
+use crate::{help, node::show::query_status, node::HELP_DETAIL, CommandGlobalOpts};
 use anyhow::anyhow;
 use clap::Args;
 use ockam::TcpTransport;
@@ -41,11 +41,13 @@ async fn run_impl(
     verify_pids(&ctx, &opts, &tcp, cfg, &node_names).await?;
 
     // Print node states
+    let mut statuses = Vec::with_capacity(node_names.len());
     for node_name in &node_names {
         let mut rpc = RpcBuilder::new(&ctx, &opts, node_name).tcp(&tcp)?.build();
         let port = cfg.get_node_port(node_name)?;
-        print_query_status(&mut rpc, port, node_name, false).await?;
+        statuses.push(query_status(&mut rpc, port, node_name, false).await?);
     }
+    opts.print(&statuses)?;
 
     Ok(())
 }
@@ -1,22 +1,39 @@
 use clap::{Args, Subcommand};
 
+use access::AccessCommand;
+use apply::ApplyCommand;
 pub(crate) use create::CreateCommand;
+use dashboard::DashboardCommand;
 use delete::DeleteCommand;
+use diff::DiffCommand;
+use export::ExportCommand;
+use import::ImportCommand;
+use inspect::InspectCommand;
 use list::ListCommand;
 use run::RunCommand;
 use show::ShowCommand;
 use start::StartCommand;
 use stop::StopCommand;
+use supervise::SuperviseCommand;
 
 use crate::{help, CommandGlobalOpts};
 
-mod create;
+mod access;
+pub(crate) mod apply;
+pub(crate) mod create;
+mod dashboard;
 mod delete;
+pub(crate) mod diff;
+pub(crate) mod export;
+pub(crate) mod import;
+mod inspect;
 mod list;
 mod run;
-mod show;
+pub(crate) mod show;
+pub(crate) mod spec;
 mod start;
 mod stop;
+pub(crate) mod supervise;
 pub mod util;
 
 const HELP_DETAIL: &str = "\
@@ -102,9 +119,35 @@ Examples:
     # Show information about a specific node
     $ ockam node show n1
 
+    # Show the workers running on a specific node
+    $ ockam node inspect n1
+
     # List all created nodes
     $ ockam node list
 
+    # Watch all nodes live, and delete their secure channels or stop them
+    $ ockam node dashboard
+
+    # Stop a node, letting its portals and secure channels drain first
+    $ ockam node stop n1 --graceful
+
+    # Show how a node differs from a node spec, then make it match the spec
+    $ ockam node diff n1 -f n1.yaml
+    $ ockam node apply n1 -f n1.yaml
+
+    # Create a node which the given identity may manage over secure channels
+    $ ockam node create n1 --admin P6c20e814b56579306f55c64e8747e6c1b4a53d9a3f4ca83c252cc2fbfc72fa94
+
+    # Restart crashed nodes and rotate their logs, and do so at login with systemd
+    $ ockam node supervise
+    $ ockam node supervise --systemd-unit > ~/.config/systemd/user/ockam.service
+    $ systemctl --user enable --now ockam
+
+    # Move a stopped node, with its identity, to another machine
+    $ ockam node export n1 -f n1.ockam
+    $ ockam node import n1.ockam --tcp-listener-address 127.0.0.1:6001
+    $ ockam node start n1
+
     # Delete the node
     $ ockam node delete n1
 
@@ -139,11 +182,27 @@ pub enum NodeSubcommand {
     #[command(display_order = 800)]
     Show(ShowCommand),
     #[command(display_order = 800)]
+    Inspect(InspectCommand),
+    #[command(display_order = 800)]
+    Dashboard(DashboardCommand),
+    #[command(display_order = 800)]
     Run(RunCommand),
     #[command(display_order = 800)]
     Start(StartCommand),
     #[command(display_order = 800)]
     Stop(StopCommand),
+    #[command(display_order = 800)]
+    Apply(ApplyCommand),
+    #[command(display_order = 800)]
+    Diff(DiffCommand),
+    #[command(display_order = 800)]
+    Access(AccessCommand),
+    #[command(display_order = 800)]
+    Supervise(SuperviseCommand),
+    #[command(display_order = 800)]
+    Export(ExportCommand),
+    #[command(display_order = 800)]
+    Import(ImportCommand),
 }
 
 impl NodeCommand {
@@ -154,8 +213,16 @@ impl NodeCommand {
             NodeSubcommand::List(c) => c.run(options),
             NodeSubcommand::Run(c) => c.run(options),
             NodeSubcommand::Show(c) => c.run(options),
+            NodeSubcommand::Inspect(c) => c.run(options),
+            NodeSubcommand::Dashboard(c) => c.run(options),
             NodeSubcommand::Start(c) => c.run(options),
             NodeSubcommand::Stop(c) => c.run(options),
+            NodeSubcommand::Apply(c) => c.run(options),
+            NodeSubcommand::Diff(c) => c.run(options),
+            NodeSubcommand::Access(c) => c.run(options),
+            NodeSubcommand::Supervise(c) => c.run(options),
+            NodeSubcommand::Export(c) => c.run(options),
+            NodeSubcommand::Import(c) => c.run(options),
         }
     }
 }
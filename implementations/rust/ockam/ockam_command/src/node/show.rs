@@ -1,17 +1,18 @@
 use crate::util::{api, node_rpc, Rpc, RpcBuilder};
-use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+use crate::{help, node::supervise::SupervisorState, node::HELP_DETAIL, CommandGlobalOpts};
 use clap::Args;
-use colorful::Colorful;
 use core::time::Duration;
 use ockam::TcpTransport;
+use ockam_api::nodes::models;
 use ockam_api::nodes::models::identity::ShortIdentityResponse;
 use ockam_api::nodes::models::portal::{InletList, OutletList};
 use ockam_api::nodes::models::services::ServiceList;
-use ockam_api::nodes::models::transport::TransportList;
+use ockam_api::nodes::models::transport::{TransportList, TransportStatus};
 use ockam_api::{addr_to_multiaddr, route_to_multiaddr};
 use ockam_core::Route;
 use ockam_multiaddr::proto::{DnsAddr, Node, Tcp};
 use ockam_multiaddr::MultiAddr;
+use sysinfo::{PidExt, ProcessExt, System, SystemExt};
 use tokio_retry::strategy::FibonacciBackoff;
 use tracing::debug;
 
@@ -46,98 +47,186 @@ async fn run_impl(
     Ok(())
 }
 
-// TODO: This function should be replaced with a better system of
-// printing the node state in the future but for now we can just tell
-// clippy to stop complainaing about it.
-#[allow(clippy::too_many_arguments)]
-fn print_node_info(
-    node_port: u16,
-    node_name: &str,
-    status_is_up: bool,
-    default_id: Option<&str>,
-    services: Option<&ServiceList>,
-    tcp_listeners: Option<&TransportList>,
-    secure_channel_listeners: Option<&Vec<String>>,
-    inlets_outlets: Option<(&InletList, &OutletList)>,
-) {
-    println!();
-    println!("Node:");
-    println!("  Name: {}", node_name);
-    println!(
-        "  Status: {}",
-        match status_is_up {
-            true => "UP".light_green(),
-            false => "DOWN".light_red(),
-        }
-    );
+/// The state of a node, as shown by `ockam node show` and `ockam node list`
+#[derive(serde::Serialize)]
+pub struct NodeStatus {
+    pub name: String,
+    pub status: NodeState,
+    /// Route to the node, as a short multiaddr using its name
+    pub route: String,
+    /// Route to the node, as a multiaddr using its TCP port
+    pub verbose_route: String,
+    /// Seconds since the node process started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uptime: Option<u64>,
+    /// Times `ockam node supervise` restarted the node after a crash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restarts: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
+    /// The details below are only known if the node is up
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transports: Option<Vec<TransportStatus<'static>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secure_channel_listeners: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inlets: Option<Vec<NodeInlet>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outlets: Option<Vec<NodeOutlet>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub services: Option<Vec<NodeService>>,
+}
 
-    println!("  Route To Node:");
-    let mut m = MultiAddr::default();
-    if m.push_back(Node::new(node_name)).is_ok() {
-        println!("    Short: {}", m);
-    }
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum NodeState {
+    Up,
+    Down,
+}
 
-    let mut m = MultiAddr::default();
-    if m.push_back(DnsAddr::new("localhost")).is_ok() && m.push_back(Tcp::new(node_port)).is_ok() {
-        println!("    Verbose: {}", m);
-    }
+#[derive(serde::Serialize)]
+pub struct NodeInlet {
+    pub listen_address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route_to_outlet: Option<String>,
+}
 
-    if let Some(id) = default_id {
-        println!("  Identity: {}", id);
-    }
+#[derive(serde::Serialize)]
+pub struct NodeOutlet {
+    pub forward_address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+}
 
-    if let Some(list) = tcp_listeners {
-        println!("  Transports:");
-        for e in &list.list {
-            println!("    Transport:");
-            println!("      Type: {}", e.tt);
-            println!("      Mode: {}", e.tm);
-            println!("      Address: {}", e.payload);
-        }
-    }
+#[derive(serde::Serialize)]
+pub struct NodeService {
+    #[serde(rename = "type")]
+    pub service_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+}
 
-    if let Some(list) = secure_channel_listeners {
-        println!("  Secure Channel Listeners:");
-        for e in list {
-            println!("    Listener:");
-            if let Some(ma) = addr_to_multiaddr(e) {
-                println!("      Address: {}", ma);
-            }
+impl NodeStatus {
+    pub(crate) fn down(node_port: u16, node_name: &str) -> Self {
+        let mut route = MultiAddr::default();
+        let _ = route.push_back(Node::new(node_name));
+        let mut verbose_route = MultiAddr::default();
+        let _ = verbose_route
+            .push_back(DnsAddr::new("localhost"))
+            .and_then(|_| verbose_route.push_back(Tcp::new(node_port)));
+        Self {
+            name: node_name.to_string(),
+            status: NodeState::Down,
+            route: route.to_string(),
+            verbose_route: verbose_route.to_string(),
+            uptime: None,
+            restarts: SupervisorState::load(node_name).map(|s| s.restarts),
+            identity: None,
+            transports: None,
+            secure_channel_listeners: None,
+            inlets: None,
+            outlets: None,
+            services: None,
         }
     }
+}
 
-    if let Some((inlets, outlets)) = inlets_outlets {
-        println!("  Inlets:");
-        for e in &inlets.list {
-            println!("    Inlet:");
-            println!("      Listen Address: {}", e.bind_addr);
-            if let Some(r) = Route::parse(e.outlet_route.as_ref()) {
-                if let Some(ma) = route_to_multiaddr(&r) {
-                    println!("      Route To Outlet: {}", ma);
-                }
-            }
-        }
-        println!("  Outlets:");
-        for e in &outlets.list {
-            println!("    Outlet:");
-            println!("      Forward Address: {}", e.tcp_addr);
-
-            if let Some(ma) = addr_to_multiaddr(e.worker_addr.as_ref()) {
-                println!("      Address: {}", ma);
-            }
-        }
+/// Ask a node about its state.
+pub async fn query_status(
+    rpc: &mut Rpc<'_>,
+    node_port: u16,
+    node_name: &str,
+    wait_until_ready: bool,
+) -> anyhow::Result<NodeStatus> {
+    let mut status = NodeStatus::down(node_port, node_name);
+    if !is_node_up(rpc, wait_until_ready).await? {
+        return Ok(status);
     }
-
-    if let Some(list) = services {
-        println!("  Services:");
-        for e in &list.list {
-            println!("    Service:");
-            println!("      Type: {}", e.service_type);
-            if let Some(ma) = addr_to_multiaddr(e.addr.as_ref()) {
-                println!("      Address: {}", ma);
-            }
-        }
+    if let Ok(resp) = rpc.parse_response::<models::base::NodeStatus>() {
+        status.uptime = process_uptime(resp.pid);
     }
+
+    // Get short id for the node
+    rpc.request(api::short_identity()).await?;
+    let default_id = match rpc.parse_response::<ShortIdentityResponse>() {
+        Ok(resp) => String::from(resp.identity_id),
+        Err(_) => String::from("None"),
+    };
+
+    // Get list of services for the node
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_services()).await?;
+    let services = rpc.parse_response::<ServiceList>()?;
+
+    // Get list of TCP listeners for node
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_tcp_listeners()).await?;
+    let tcp_listeners = rpc.parse_response::<TransportList>()?;
+
+    // Get list of Secure Channel Listeners
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_secure_channel_listener()).await?;
+    let secure_channel_listeners = rpc.parse_response::<Vec<String>>()?;
+
+    // Get list of inlets
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_inlets()).await?;
+    let inlets = rpc.parse_response::<InletList>()?;
+
+    // Get list of outlets
+    let mut rpc = rpc.clone();
+    rpc.request(api::list_outlets()).await?;
+    let outlets = rpc.parse_response::<OutletList>()?;
+
+    status.status = NodeState::Up;
+    status.identity = Some(default_id);
+    status.transports = Some(
+        tcp_listeners
+            .list
+            .iter()
+            .map(|t| TransportStatus::new(t.tt, t.tm, t.payload.to_string(), t.tid.to_string()))
+            .collect(),
+    );
+    status.secure_channel_listeners = Some(
+        secure_channel_listeners
+            .iter()
+            .filter_map(|a| addr_to_multiaddr(a.as_str()))
+            .map(|ma| ma.to_string())
+            .collect(),
+    );
+    status.inlets = Some(
+        inlets
+            .list
+            .iter()
+            .map(|e| NodeInlet {
+                listen_address: e.bind_addr.to_string(),
+                route_to_outlet: Route::parse(e.outlet_route.as_ref())
+                    .and_then(|r| route_to_multiaddr(&r))
+                    .map(|ma| ma.to_string()),
+            })
+            .collect(),
+    );
+    status.outlets = Some(
+        outlets
+            .list
+            .iter()
+            .map(|e| NodeOutlet {
+                forward_address: e.tcp_addr.to_string(),
+                address: addr_to_multiaddr(e.worker_addr.as_ref()).map(|ma| ma.to_string()),
+            })
+            .collect(),
+    );
+    status.services = Some(
+        services
+            .list
+            .iter()
+            .map(|e| NodeService {
+                service_type: e.service_type.to_string(),
+                address: addr_to_multiaddr(e.addr.as_ref()).map(|ma| ma.to_string()),
+            })
+            .collect(),
+    );
+    Ok(status)
 }
 
 pub async fn print_query_status(
@@ -146,56 +235,19 @@ pub async fn print_query_status(
     node_name: &str,
     wait_until_ready: bool,
 ) -> anyhow::Result<()> {
-    if !is_node_up(rpc, wait_until_ready).await? {
-        print_node_info(node_port, node_name, false, None, None, None, None, None);
-    } else {
-        // Get short id for the node
-        rpc.request(api::short_identity()).await?;
-        let default_id = match rpc.parse_response::<ShortIdentityResponse>() {
-            Ok(resp) => String::from(resp.identity_id),
-            Err(_) => String::from("None"),
-        };
-
-        // Get list of services for the node
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_services()).await?;
-        let services = rpc.parse_response::<ServiceList>()?;
-
-        // Get list of TCP listeners for node
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_tcp_listeners()).await?;
-        let tcp_listeners = rpc.parse_response::<TransportList>()?;
-
-        // Get list of Secure Channel Listeners
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_secure_channel_listener()).await?;
-        let secure_channel_listeners = rpc.parse_response::<Vec<String>>()?;
-
-        // Get list of inlets
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_inlets()).await?;
-        let inlets = rpc.parse_response::<InletList>()?;
-
-        // Get list of outlets
-        let mut rpc = rpc.clone();
-        rpc.request(api::list_outlets()).await?;
-        let outlets = rpc.parse_response::<OutletList>()?;
-
-        print_node_info(
-            node_port,
-            node_name,
-            true,
-            Some(&default_id),
-            Some(&services),
-            Some(&tcp_listeners),
-            Some(&secure_channel_listeners),
-            Some((&inlets, &outlets)),
-        );
-    }
-
+    let status = query_status(rpc, node_port, node_name, wait_until_ready).await?;
+    rpc.print_response(status)?;
     Ok(())
 }
 
+/// Seconds since the process `pid` started
+fn process_uptime(pid: i32) -> Option<u64> {
+    let pid = sysinfo::Pid::from_u32(pid as u32);
+    let mut system = System::new();
+    system.refresh_process(pid);
+    system.process(pid).map(|p| p.run_time())
+}
+
 /// Send message(s) to a node to determine if it is 'up' and
 /// responding to requests.
 ///
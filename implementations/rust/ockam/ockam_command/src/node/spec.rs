@@ -0,0 +1,1124 @@
+//! Declarative node specs.
+//!
+//! A spec describes what a node should be running: tcp listeners, secure
+//! channel listeners, portals, forwarders, services and policies. It is
+//! compared to what a running node reports through its node manager API,
+//! and the difference can be applied to the node.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::Path;
+
+use anyhow::{anyhow, Context as _};
+use minicbor::Encode;
+use ockam::identity::IdentityIdentifier;
+use ockam::{Address, Context, TcpTransport};
+use ockam_abac::Expr;
+use ockam_api::config::lookup::ConfigLookup;
+use ockam_api::nodes::models::forwarder::ForwarderList;
+use ockam_api::nodes::models::policy::{Policy, PolicyList};
+use ockam_api::nodes::models::portal::{CreateInlet, CreateOutlet, InletList, OutletList};
+use ockam_api::nodes::models::secure_channel::{
+    CreateSecureChannelListenerRequest, DeleteSecureChannelListenerRequest,
+    ShowSecureChannelListenerRequest, ShowSecureChannelListenerResponse,
+};
+use ockam_api::nodes::models::services::ServiceList;
+use ockam_api::nodes::models::transport::{
+    CreateTransport, DeleteTransport, TransportList, TransportMode, TransportType,
+};
+use ockam_core::api::{Request, RequestBuilder};
+use ockam_multiaddr::proto::Project;
+use ockam_multiaddr::{MultiAddr, Protocol};
+use serde::{Deserialize, Deserializer};
+
+use crate::forwarder::{create_forwarder_request, forwarder_alias};
+use crate::service::config::ServiceConfigs;
+use crate::service::start;
+use crate::util::{process_multi_addr, RpcBuilder};
+use crate::CommandGlobalOpts;
+
+const DEFAULT_ACTION: &str = "handle_message";
+
+/// What a node should be running.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NodeSpec {
+    tcp_listeners: Vec<SocketAddr>,
+    secure_channel_listeners: Vec<SecureChannelListenerSpec>,
+    /// Identities authorized by secure channel listeners without their own list
+    trusted_identities: Option<Vec<IdentityIdentifier>>,
+    inlets: Vec<InletSpec>,
+    outlets: Vec<OutletSpec>,
+    forwarders: Vec<ForwarderSpec>,
+    services: Option<ServiceConfigs>,
+    policies: Vec<PolicySpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SecureChannelListenerSpec {
+    address: String,
+    #[serde(default)]
+    authorized_identities: Option<Vec<IdentityIdentifier>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InletSpec {
+    from: SocketAddr,
+    to: MultiAddr,
+    #[serde(default)]
+    alias: Option<String>,
+    #[serde(default)]
+    authorized: Option<IdentityIdentifier>,
+    #[serde(default)]
+    check_credential: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutletSpec {
+    /// Address of the outlet worker
+    from: String,
+    to: SocketAddr,
+    #[serde(default)]
+    alias: Option<String>,
+    #[serde(default)]
+    check_credential: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForwarderSpec {
+    at: MultiAddr,
+    name: String,
+    #[serde(default)]
+    authorized: Option<IdentityIdentifier>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicySpec {
+    resource: String,
+    #[serde(default = "default_action")]
+    action: String,
+    #[serde(deserialize_with = "expression")]
+    expression: Expr,
+}
+
+fn default_action() -> String {
+    DEFAULT_ACTION.to_string()
+}
+
+fn expression<'de, D: Deserializer<'de>>(d: D) -> Result<Expr, D::Error> {
+    let s = String::deserialize(d)?;
+    Expr::try_from(s.as_str()).map_err(serde::de::Error::custom)
+}
+
+impl NodeSpec {
+    /// Read a spec from a TOML file (`.toml`) or a YAML file (anything else).
+    pub fn read<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let s =
+            std::fs::read_to_string(path).with_context(|| anyhow!("failed to read {path:?}"))?;
+        if path.extension().map_or(false, |e| e == "toml") {
+            toml::from_str(&s).with_context(|| anyhow!("invalid node spec {path:?}"))
+        } else {
+            serde_yaml::from_str(&s).with_context(|| anyhow!("invalid node spec {path:?}"))
+        }
+    }
+
+    /// The services the spec enables, as `(service type, address)` pairs.
+    ///
+    /// The service types are the ones the node manager lists services with.
+    fn services(&self) -> Vec<(&'static str, &str)> {
+        let mut list = Vec::new();
+        if let Some(s) = &self.services {
+            if let Some(c) = s.vault.as_ref().filter(|c| !c.disabled) {
+                list.push(("vault", c.address.as_str()))
+            }
+            if let Some(c) = s.identity.as_ref().filter(|c| !c.disabled) {
+                list.push(("identity", c.address.as_str()))
+            }
+            if let Some(c) = s.verifier.as_ref().filter(|c| !c.disabled) {
+                list.push(("verifier", c.address.as_str()))
+            }
+            if let Some(c) = s.prekeys.as_ref().filter(|c| !c.disabled) {
+                list.push(("prekeys", c.address.as_str()))
+            }
+            if let Some(c) = s.authenticator.as_ref().filter(|c| !c.disabled) {
+                list.push(("authenticator", c.address.as_str()))
+            }
+            if let Some(c) = s.okta_identity_provider.as_ref().filter(|c| !c.disabled) {
+                list.push(("okta", c.address.as_str()))
+            }
+            if let Some(c) = s.oidc_identity_provider.as_ref().filter(|c| !c.disabled) {
+                list.push(("oidc", c.address.as_str()))
+            }
+        }
+        list
+    }
+
+    /// All secure channel listeners, including the one of the services section.
+    fn secure_channel_listeners(&self) -> Vec<SecureChannelListenerSpec> {
+        let mut list = self.secure_channel_listeners.clone();
+        if let Some(c) = self
+            .services
+            .as_ref()
+            .and_then(|s| s.secure_channel_listener.as_ref())
+            .filter(|c| !c.disabled)
+        {
+            list.push(SecureChannelListenerSpec {
+                address: c.address.clone(),
+                authorized_identities: c.authorized_identifiers.clone(),
+            })
+        }
+        for l in list.iter_mut() {
+            if l.authorized_identities.is_none() {
+                l.authorized_identities = self.trusted_identities.clone()
+            }
+        }
+        list
+    }
+
+    /// The resources the spec has policies for.
+    fn resources(&self) -> Vec<&str> {
+        let mut list: Vec<&str> = self.policies.iter().map(|p| p.resource.as_str()).collect();
+        list.sort_unstable();
+        list.dedup();
+        list
+    }
+}
+
+/// What a running node reports through its node manager API.
+#[derive(Debug, Default)]
+pub struct NodeState {
+    /// Transport id and bind address of each tcp listener
+    tcp_listeners: Vec<(String, String)>,
+    secure_channel_listeners: Vec<ListenerState>,
+    inlets: Vec<InletState>,
+    outlets: Vec<OutletState>,
+    /// Alias and remote address of each forwarder created by the node
+    forwarders: Vec<(String, String)>,
+    /// Service type and address of each service
+    services: Vec<(String, String)>,
+    /// Expressions of the policies of the spec resources
+    policies: BTreeMap<(String, String), Expr>,
+}
+
+#[derive(Debug, Clone)]
+struct ListenerState {
+    address: String,
+    authorized_identities: Option<Vec<IdentityIdentifier>>,
+}
+
+#[derive(Debug, Clone)]
+struct InletState {
+    alias: String,
+    bind_addr: String,
+    /// The address the inlet was created with, unless the node is too old
+    /// to report it
+    outlet_addr: Option<String>,
+    authorized: Option<IdentityIdentifier>,
+    check_credential: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+struct OutletState {
+    alias: String,
+    worker_addr: String,
+    tcp_addr: String,
+    check_credential: Option<bool>,
+}
+
+impl NodeState {
+    /// Query the node manager of `node` for everything the spec covers.
+    pub async fn fetch(
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        node: &str,
+        tcp: &TcpTransport,
+        spec: &NodeSpec,
+    ) -> anyhow::Result<Self> {
+        let mut state = NodeState::default();
+        // The listener of the node manager API is not part of any spec
+        let api_port = opts.config.get_node_port(node)?;
+
+        let mut rpc = RpcBuilder::new(ctx, opts, node).tcp(tcp)?.build();
+        rpc.request(Request::get("/node/tcp/listener")).await?;
+        for l in rpc.parse_response::<TransportList>()?.list {
+            let is_api = l
+                .payload
+                .parse::<SocketAddr>()
+                .map_or(false, |a| a.port() == api_port);
+            if !is_api {
+                state
+                    .tcp_listeners
+                    .push((l.tid.to_string(), l.payload.to_string()))
+            }
+        }
+
+        let mut rpc = RpcBuilder::new(ctx, opts, node).tcp(tcp)?.build();
+        rpc.request(Request::get("/node/secure_channel_listener"))
+            .await?;
+        for address in rpc.parse_response::<Vec<String>>()? {
+            let mut rpc = RpcBuilder::new(ctx, opts, node).tcp(tcp)?.build();
+            let body = ShowSecureChannelListenerRequest::new(&Address::from_string(&address));
+            rpc.request(Request::get("/node/show_secure_channel_listener").body(body))
+                .await?;
+            let res = rpc.parse_response::<ShowSecureChannelListenerResponse>()?;
+            let authorized_identities = match res.authorized_identifiers {
+                Some(ids) => Some(
+                    ids.iter()
+                        .map(|id| IdentityIdentifier::try_from(id.as_ref()))
+                        .collect::<Result<Vec<_>, _>>()?,
+                ),
+                None => None,
+            };
+            state.secure_channel_listeners.push(ListenerState {
+                address,
+                authorized_identities,
+            })
+        }
+
+        let mut rpc = RpcBuilder::new(ctx, opts, node).tcp(tcp)?.build();
+        rpc.request(Request::get("/node/inlet")).await?;
+        for i in rpc.parse_response::<InletList>()?.list {
+            state.inlets.push(InletState {
+                alias: i.alias.to_string(),
+                bind_addr: i.bind_addr.to_string(),
+                outlet_addr: i.outlet_addr.map(|a| a.to_string()),
+                authorized: i.authorized,
+                check_credential: i.check_credential,
+            })
+        }
+
+        let mut rpc = RpcBuilder::new(ctx, opts, node).tcp(tcp)?.build();
+        rpc.request(Request::get("/node/outlet")).await?;
+        for o in rpc.parse_response::<OutletList>()?.list {
+            state.outlets.push(OutletState {
+                alias: o.alias.to_string(),
+                worker_addr: o.worker_addr.to_string(),
+                tcp_addr: o.tcp_addr.to_string(),
+                check_credential: o.check_credential,
+            })
+        }
+
+        let mut rpc = RpcBuilder::new(ctx, opts, node).tcp(tcp)?.build();
+        rpc.request(Request::get("/node/forwarder")).await?;
+        for f in rpc.parse_response::<ForwarderList>()?.list {
+            if let Some(alias) = f.alias() {
+                state
+                    .forwarders
+                    .push((alias.to_string(), f.remote_address().to_string()))
+            }
+        }
+
+        let mut rpc = RpcBuilder::new(ctx, opts, node).tcp(tcp)?.build();
+        rpc.request(Request::get("/node/services")).await?;
+        for s in rpc.parse_response::<ServiceList>()?.list {
+            state
+                .services
+                .push((s.service_type.to_string(), s.addr.to_string()))
+        }
+
+        for resource in spec.resources() {
+            let mut rpc = RpcBuilder::new(ctx, opts, node).tcp(tcp)?.build();
+            rpc.request(Request::get(format!("/policy/{resource}")))
+                .await?;
+            for (action, expr) in rpc.parse_response::<PolicyList>()?.expressions() {
+                state
+                    .policies
+                    .insert((resource.to_string(), action.to_string()), expr.clone());
+            }
+        }
+
+        Ok(state)
+    }
+}
+
+/// A difference between a spec and a running node.
+#[derive(Debug, Clone)]
+pub enum Drift {
+    /// In the spec but not on the node
+    Missing(Item),
+    /// On the node but not in the spec
+    Unexpected(Extra),
+    /// On the node, but not as in the spec
+    Changed(Change),
+}
+
+/// Something a node runs differently from its spec.
+#[derive(Debug, Clone)]
+pub enum Change {
+    /// A policy whose expression differs from the spec
+    Policy {
+        resource: String,
+        action: String,
+        expected: Expr,
+        actual: Expr,
+    },
+    /// An item created with other settings than the spec ones, which has
+    /// to be recreated
+    Settings {
+        actual: Extra,
+        expected: Item,
+        fields: Vec<&'static str>,
+    },
+}
+
+/// Something a spec requires.
+#[derive(Debug, Clone)]
+pub enum Item {
+    TcpListener(SocketAddr),
+    SecureChannelListener(SecureChannelListenerSpec),
+    Inlet(InletSpec),
+    Outlet(OutletSpec),
+    Forwarder(ForwarderSpec),
+    Service { kind: &'static str, address: String },
+    Policy(PolicySpec),
+}
+
+/// Something a node runs which its spec does not mention.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Extra {
+    TcpListener {
+        tid: String,
+        address: String,
+    },
+    SecureChannelListener {
+        address: String,
+    },
+    Inlet {
+        alias: String,
+    },
+    Outlet {
+        alias: String,
+    },
+    Forwarder {
+        alias: String,
+        remote_address: String,
+    },
+    Policy {
+        resource: String,
+        action: String,
+    },
+}
+
+/// Compare a spec to the state of a node.
+///
+/// Services and secure channel listeners the spec does not mention are
+/// not reported, since every node starts some of them by default.
+/// Node names in inlet targets are resolved with `lookup`, as they are when
+/// the inlets are created.
+pub fn plan(
+    spec: &NodeSpec,
+    state: &NodeState,
+    lookup: &ConfigLookup,
+) -> anyhow::Result<Vec<Drift>> {
+    let mut drift = Vec::new();
+
+    for addr in &spec.tcp_listeners {
+        let found = state
+            .tcp_listeners
+            .iter()
+            .any(|(_, a)| a.parse::<SocketAddr>() == Ok(*addr));
+        if !found {
+            drift.push(Drift::Missing(Item::TcpListener(*addr)))
+        }
+    }
+    for (tid, address) in &state.tcp_listeners {
+        let expected = address
+            .parse::<SocketAddr>()
+            .map_or(false, |a| spec.tcp_listeners.contains(&a));
+        if !expected {
+            drift.push(Drift::Unexpected(Extra::TcpListener {
+                tid: tid.clone(),
+                address: address.clone(),
+            }))
+        }
+    }
+
+    for l in spec.secure_channel_listeners() {
+        let found = state
+            .secure_channel_listeners
+            .iter()
+            .find(|s| s.address == l.address);
+        match found {
+            None => drift.push(Drift::Missing(Item::SecureChannelListener(l))),
+            Some(s) => {
+                let mut fields = Vec::new();
+                if sorted(&s.authorized_identities) != sorted(&l.authorized_identities) {
+                    fields.push("authorized_identities")
+                }
+                if !fields.is_empty() {
+                    drift.push(Drift::Changed(Change::Settings {
+                        actual: Extra::SecureChannelListener {
+                            address: s.address.clone(),
+                        },
+                        expected: Item::SecureChannelListener(l),
+                        fields,
+                    }))
+                }
+            }
+        }
+    }
+
+    let mut inlets = Vec::new();
+    for i in &spec.inlets {
+        let found = state.inlets.iter().find(|s| match &i.alias {
+            Some(a) => *a == s.alias,
+            None => s.bind_addr.parse::<SocketAddr>() == Ok(i.from),
+        });
+        let s = match found {
+            Some(s) => s,
+            None => {
+                drift.push(Drift::Missing(Item::Inlet(i.clone())));
+                continue;
+            }
+        };
+        inlets.push(&s.alias);
+        let mut fields = Vec::new();
+        if s.bind_addr.parse::<SocketAddr>() != Ok(i.from) {
+            fields.push("from")
+        }
+        if let Some(outlet_addr) = &s.outlet_addr {
+            if *outlet_addr != process_multi_addr(&i.to, lookup)?.to_string() {
+                fields.push("to")
+            }
+        }
+        if s.authorized != i.authorized {
+            fields.push("authorized")
+        }
+        if differs(i.check_credential, s.check_credential) {
+            fields.push("check_credential")
+        }
+        if !fields.is_empty() {
+            drift.push(Drift::Changed(Change::Settings {
+                actual: Extra::Inlet {
+                    alias: s.alias.clone(),
+                },
+                expected: Item::Inlet(i.clone()),
+                fields,
+            }))
+        }
+    }
+    for s in &state.inlets {
+        if !inlets.contains(&&s.alias) {
+            drift.push(Drift::Unexpected(Extra::Inlet {
+                alias: s.alias.clone(),
+            }))
+        }
+    }
+
+    let mut outlets = Vec::new();
+    for o in &spec.outlets {
+        let found = state.outlets.iter().find(|s| match &o.alias {
+            Some(a) => *a == s.alias,
+            None => s.worker_addr == o.from,
+        });
+        let s = match found {
+            Some(s) => s,
+            None => {
+                drift.push(Drift::Missing(Item::Outlet(o.clone())));
+                continue;
+            }
+        };
+        outlets.push(&s.alias);
+        let mut fields = Vec::new();
+        if s.worker_addr != o.from {
+            fields.push("from")
+        }
+        if s.tcp_addr.parse::<SocketAddr>() != Ok(o.to) {
+            fields.push("to")
+        }
+        if differs(o.check_credential, s.check_credential) {
+            fields.push("check_credential")
+        }
+        if !fields.is_empty() {
+            drift.push(Drift::Changed(Change::Settings {
+                actual: Extra::Outlet {
+                    alias: s.alias.clone(),
+                },
+                expected: Item::Outlet(o.clone()),
+                fields,
+            }))
+        }
+    }
+    for s in &state.outlets {
+        if !outlets.contains(&&s.alias) {
+            drift.push(Drift::Unexpected(Extra::Outlet {
+                alias: s.alias.clone(),
+            }))
+        }
+    }
+
+    let mut forwarders = Vec::new();
+    for f in &spec.forwarders {
+        let alias = forwarder_alias(&f.name, &f.at)?;
+        if !state.forwarders.iter().any(|(a, _)| *a == alias) {
+            drift.push(Drift::Missing(Item::Forwarder(f.clone())))
+        }
+        forwarders.push(alias)
+    }
+    for (alias, remote_address) in &state.forwarders {
+        if !forwarders.contains(alias) {
+            drift.push(Drift::Unexpected(Extra::Forwarder {
+                alias: alias.clone(),
+                remote_address: remote_address.clone(),
+            }))
+        }
+    }
+
+    for (kind, address) in spec.services() {
+        let found = state
+            .services
+            .iter()
+            .any(|(k, a)| k == kind && a == address);
+        if !found {
+            drift.push(Drift::Missing(Item::Service {
+                kind,
+                address: address.to_string(),
+            }))
+        }
+    }
+
+    for p in &spec.policies {
+        match state.policies.get(&(p.resource.clone(), p.action.clone())) {
+            None => drift.push(Drift::Missing(Item::Policy(p.clone()))),
+            Some(actual) if *actual != p.expression => drift.push(Drift::Changed(Change::Policy {
+                resource: p.resource.clone(),
+                action: p.action.clone(),
+                expected: p.expression.clone(),
+                actual: actual.clone(),
+            })),
+            Some(_) => {}
+        }
+    }
+    for (resource, action) in state.policies.keys() {
+        let expected = spec
+            .policies
+            .iter()
+            .any(|p| p.resource == *resource && p.action == *action);
+        if !expected {
+            drift.push(Drift::Unexpected(Extra::Policy {
+                resource: resource.clone(),
+                action: action.clone(),
+            }))
+        }
+    }
+
+    Ok(drift)
+}
+
+/// Identities in a canonical order, for comparison.
+fn sorted(ids: &Option<Vec<IdentityIdentifier>>) -> Option<Vec<&IdentityIdentifier>> {
+    ids.as_ref().map(|ids| {
+        let mut ids: Vec<&IdentityIdentifier> = ids.iter().collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    })
+}
+
+/// Whether a setting of the spec differs from what the node reports.
+///
+/// Settings left out of the spec take the node default, and nodes too old
+/// to report a setting are assumed to match.
+fn differs(expected: Option<bool>, actual: Option<bool>) -> bool {
+    matches!((expected, actual), (Some(e), Some(a)) if e != a)
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Drift::Missing(item) => write!(f, "+ {item}"),
+            Drift::Unexpected(extra) => write!(f, "- {extra}"),
+            Drift::Changed(change) => write!(f, "~ {change}"),
+        }
+    }
+}
+
+/// A [`Drift`] as printed by `ockam node diff` and `ockam node apply`
+#[derive(serde::Serialize)]
+pub struct SpecDrift {
+    /// `missing`, `unexpected` or `changed`
+    pub kind: &'static str,
+    pub item: String,
+}
+
+impl From<&Drift> for SpecDrift {
+    fn from(d: &Drift) -> Self {
+        let (kind, item) = match d {
+            Drift::Missing(item) => ("missing", item.to_string()),
+            Drift::Unexpected(extra) => ("unexpected", extra.to_string()),
+            Drift::Changed(change) => ("changed", change.to_string()),
+        };
+        Self { kind, item }
+    }
+}
+
+impl fmt::Display for SpecDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = match self.kind {
+            "missing" => '+',
+            "unexpected" => '-',
+            _ => '~',
+        };
+        write!(f, "{sign} {}", self.item)
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Policy {
+                resource,
+                action,
+                expected,
+                actual,
+            } => write!(f, "policy {resource}/{action}: {actual} -> {expected}"),
+            Change::Settings {
+                expected, fields, ..
+            } => write!(f, "{expected} (changed: {})", fields.join(", ")),
+        }
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Item::TcpListener(a) => write!(f, "tcp listener {a}"),
+            Item::SecureChannelListener(l) => {
+                write!(f, "secure channel listener /service/{}", l.address)
+            }
+            Item::Inlet(i) => match &i.alias {
+                Some(a) => write!(f, "tcp inlet {a} ({} -> {})", i.from, i.to),
+                None => write!(f, "tcp inlet {} -> {}", i.from, i.to),
+            },
+            Item::Outlet(o) => match &o.alias {
+                Some(a) => write!(f, "tcp outlet {a} ({} -> {})", o.from, o.to),
+                None => write!(f, "tcp outlet {} -> {}", o.from, o.to),
+            },
+            Item::Forwarder(fw) => write!(f, "forwarder {} at {}", fw.name, fw.at),
+            Item::Service { kind, address } => write!(f, "{kind} service /service/{address}"),
+            Item::Policy(p) => write!(f, "policy {}/{}: {}", p.resource, p.action, p.expression),
+        }
+    }
+}
+
+impl fmt::Display for Extra {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Extra::TcpListener { address, .. } => write!(f, "tcp listener {address}"),
+            Extra::SecureChannelListener { address } => {
+                write!(f, "secure channel listener /service/{address}")
+            }
+            Extra::Inlet { alias } => write!(f, "tcp inlet {alias}"),
+            Extra::Outlet { alias } => write!(f, "tcp outlet {alias}"),
+            Extra::Forwarder { alias, .. } => write!(f, "forwarder {alias}"),
+            Extra::Policy { resource, action } => write!(f, "policy {resource}/{action}"),
+        }
+    }
+}
+
+/// Make the node match the spec for one drift.
+///
+/// Unexpected items are only removed if `prune` is set.
+pub async fn apply(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node: &str,
+    tcp: &TcpTransport,
+    spec: &NodeSpec,
+    drift: &Drift,
+    prune: bool,
+) -> anyhow::Result<()> {
+    let send = Sender {
+        ctx,
+        opts,
+        node,
+        tcp,
+    };
+    match drift {
+        Drift::Missing(item) => send.create(spec, item).await,
+        Drift::Changed(Change::Policy {
+            resource,
+            action,
+            expected,
+            ..
+        }) => send.set_policy(resource, action, expected).await,
+        Drift::Changed(Change::Settings {
+            actual, expected, ..
+        }) => {
+            send.remove(actual).await?;
+            send.create(spec, expected).await
+        }
+        Drift::Unexpected(_) if !prune => Ok(()),
+        Drift::Unexpected(extra) => send.remove(extra).await,
+    }
+}
+
+/// Sends the requests applying a spec to a node.
+struct Sender<'a> {
+    ctx: &'a Context,
+    opts: &'a CommandGlobalOpts,
+    node: &'a str,
+    tcp: &'a TcpTransport,
+}
+
+impl Sender<'_> {
+    async fn create(&self, spec: &NodeSpec, item: &Item) -> anyhow::Result<()> {
+        match item {
+            Item::TcpListener(addr) => {
+                let body = CreateTransport::new(
+                    TransportType::Tcp,
+                    TransportMode::Listen,
+                    addr.to_string(),
+                );
+                self.request(Request::post("/node/tcp/listener").body(body))
+                    .await
+            }
+            Item::SecureChannelListener(l) => {
+                let body = CreateSecureChannelListenerRequest::new(
+                    &Address::from_string(&l.address),
+                    l.authorized_identities.clone(),
+                );
+                self.request(Request::post("/node/secure_channel_listener").body(body))
+                    .await
+            }
+            Item::Inlet(i) => {
+                let to = process_multi_addr(&i.to, &self.opts.config.lookup())?;
+                let mut body = if i.to.matches(0, &[Project::CODE.into()]) {
+                    if i.authorized.is_some() {
+                        return Err(anyhow!(
+                            "inlets to project addresses can not have authorized identities"
+                        ));
+                    }
+                    CreateInlet::via_project(i.from, to, i.check_credential)
+                } else {
+                    CreateInlet::to_node(i.from, to, i.check_credential, i.authorized.clone())
+                };
+                if let Some(a) = &i.alias {
+                    body.set_alias(a.as_str())
+                }
+                self.request(Request::post("/node/inlet").body(body)).await
+            }
+            Item::Outlet(o) => {
+                let body = CreateOutlet::new(
+                    o.to.to_string(),
+                    o.from.as_str(),
+                    o.alias.as_deref().map(Into::into),
+                    o.check_credential,
+                );
+                self.request(Request::post("/node/outlet").body(body)).await
+            }
+            Item::Forwarder(f) => {
+                let req =
+                    create_forwarder_request(self.opts, &f.name, &f.at, f.authorized.clone())?;
+                self.request(req).await
+            }
+            Item::Service { kind, .. } => self.start_service(spec, kind).await,
+            Item::Policy(p) => self.set_policy(&p.resource, &p.action, &p.expression).await,
+        }
+    }
+
+    async fn remove(&self, extra: &Extra) -> anyhow::Result<()> {
+        match extra {
+            Extra::TcpListener { tid, .. } => {
+                let body = DeleteTransport::new(tid.as_str(), false);
+                self.request(Request::delete("/node/tcp/listener").body(body))
+                    .await
+            }
+            Extra::SecureChannelListener { address } => {
+                let body = DeleteSecureChannelListenerRequest::new(&Address::from_string(address));
+                self.request(Request::delete("/node/secure_channel_listener").body(body))
+                    .await
+            }
+            Extra::Inlet { alias } => {
+                self.request(Request::delete(format!("/node/inlet/{alias}")))
+                    .await
+            }
+            Extra::Outlet { alias } => {
+                self.request(Request::delete(format!("/node/outlet/{alias}")))
+                    .await
+            }
+            Extra::Forwarder { remote_address, .. } => {
+                self.request(Request::delete(format!("/node/forwarder/{remote_address}")))
+                    .await
+            }
+            Extra::Policy { resource, action } => {
+                self.request(Request::delete(format!("/policy/{resource}/{action}")))
+                    .await
+            }
+        }
+    }
+
+    async fn request<T: Encode<()>>(&self, req: RequestBuilder<'_, T>) -> anyhow::Result<()> {
+        let mut rpc = RpcBuilder::new(self.ctx, self.opts, self.node)
+            .tcp(self.tcp)?
+            .build();
+        rpc.request(req).await?;
+        rpc.is_ok()
+    }
+
+    async fn set_policy(&self, resource: &str, action: &str, expr: &Expr) -> anyhow::Result<()> {
+        let req =
+            Request::post(format!("/policy/{resource}/{action}")).body(Policy::new(expr.clone()));
+        self.request(req).await
+    }
+
+    async fn start_service(&self, spec: &NodeSpec, kind: &str) -> anyhow::Result<()> {
+        let (ctx, opts, node, tcp) = (self.ctx, self.opts, self.node, Some(self.tcp));
+        let services = spec
+            .services
+            .as_ref()
+            .ok_or_else(|| anyhow!("no services in the node spec"))?;
+        let unknown = || anyhow!("no {kind} service in the node spec");
+        match kind {
+            "vault" => {
+                let c = services.vault.as_ref().ok_or_else(unknown)?;
+                start::start_vault_service(ctx, opts, node, &c.address, c.deny_export, tcp).await
+            }
+            "identity" => {
+                let c = services.identity.as_ref().ok_or_else(unknown)?;
+                start::start_identity_service(ctx, opts, node, &c.address, tcp).await
+            }
+            "verifier" => {
+                let c = services.verifier.as_ref().ok_or_else(unknown)?;
+                start::start_verifier_service(ctx, opts, node, &c.address, tcp).await
+            }
+            "prekeys" => {
+                let c = services.prekeys.as_ref().ok_or_else(unknown)?;
+                start::start_prekey_service(ctx, opts, node, &c.address, tcp).await
+            }
+            "authenticator" => {
+                let c = services.authenticator.as_ref().ok_or_else(unknown)?;
+                start::start_authenticator_service(
+                    ctx,
+                    opts,
+                    node,
+                    &c.address,
+                    &c.enrollers,
+                    &c.project,
+                    tcp,
+                )
+                .await
+            }
+            "okta" => {
+                let c = services
+                    .okta_identity_provider
+                    .as_ref()
+                    .ok_or_else(unknown)?;
+                start::start_okta_identity_provider(ctx, opts, node, c, tcp).await
+            }
+            "oidc" => {
+                let c = services
+                    .oidc_identity_provider
+                    .as_ref()
+                    .ok_or_else(unknown)?;
+                start::start_oidc_identity_provider(ctx, opts, node, c, tcp).await
+            }
+            _ => Err(unknown()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_api::config::lookup::InternetAddress;
+
+    const SPEC: &str = r#"
+tcp_listeners:
+  - 127.0.0.1:4000
+secure_channel_listeners:
+  - address: scl
+inlets:
+  - from: 127.0.0.1:6000
+    to: /node/n2/service/outlet
+    alias: web
+outlets:
+  - from: outlet
+    to: 127.0.0.1:5000
+forwarders:
+  - at: /node/n2
+    name: n1
+services:
+  verifier: {}
+policies:
+  - resource: web
+    expression: (= subject.component "web")
+"#;
+
+    fn lookup() -> ConfigLookup {
+        let mut lookup = ConfigLookup::new();
+        lookup.set_node("n2", InternetAddress::new("127.0.0.1:5001").unwrap());
+        lookup
+    }
+
+    fn listener(address: &str) -> ListenerState {
+        ListenerState {
+            address: address.into(),
+            authorized_identities: None,
+        }
+    }
+
+    fn outlet(alias: &str, worker_addr: &str) -> OutletState {
+        OutletState {
+            alias: alias.into(),
+            worker_addr: worker_addr.into(),
+            tcp_addr: "127.0.0.1:5000".into(),
+            check_credential: Some(true),
+        }
+    }
+
+    fn state() -> NodeState {
+        let to: MultiAddr = "/node/n2/service/outlet".parse().unwrap();
+        NodeState {
+            tcp_listeners: vec![("t1".into(), "127.0.0.1:4000".into())],
+            secure_channel_listeners: vec![listener("api"), listener("scl")],
+            inlets: vec![InletState {
+                alias: "web".into(),
+                bind_addr: "127.0.0.1:6000".into(),
+                outlet_addr: Some(process_multi_addr(&to, &lookup()).unwrap().to_string()),
+                authorized: None,
+                check_credential: Some(true),
+            }],
+            outlets: vec![outlet("a1", "outlet")],
+            forwarders: vec![("forward_to_n1".into(), "forward_to_n1".into())],
+            services: vec![("verifier".into(), "verifier".into())],
+            policies: BTreeMap::from([(
+                ("web".into(), "handle_message".into()),
+                Expr::try_from(r#"(= subject.component "web")"#).unwrap(),
+            )]),
+        }
+    }
+
+    #[test]
+    fn read_yaml_and_toml_specs() {
+        let yaml: NodeSpec = serde_yaml::from_str(SPEC).unwrap();
+        assert_eq!(yaml.inlets[0].alias.as_deref(), Some("web"));
+        assert_eq!(yaml.policies[0].action, DEFAULT_ACTION);
+        assert_eq!(yaml.services(), vec![("verifier", "verifier")]);
+
+        let toml: NodeSpec = toml::from_str(
+            r#"
+            tcp_listeners = ["127.0.0.1:4000"]
+
+            [[outlets]]
+            from = "outlet"
+            to = "127.0.0.1:5000"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(toml.tcp_listeners, yaml.tcp_listeners);
+        assert_eq!(toml.outlets, yaml.outlets);
+
+        assert!(serde_yaml::from_str::<NodeSpec>("listeners: []").is_err());
+        assert!(
+            serde_yaml::from_str::<NodeSpec>("policies: [{resource: r, expression: '(='}]")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn no_drift_when_the_node_matches() {
+        let spec: NodeSpec = serde_yaml::from_str(SPEC).unwrap();
+        assert!(plan(&spec, &state(), &lookup()).unwrap().is_empty())
+    }
+
+    #[test]
+    fn drift_is_reported() {
+        let spec: NodeSpec = serde_yaml::from_str(SPEC).unwrap();
+        let mut state = state();
+        state.tcp_listeners = vec![("t2".into(), "127.0.0.1:4001".into())];
+        state.secure_channel_listeners.clear();
+        state.outlets.push(outlet("a2", "other"));
+        state.services.clear();
+        state.policies.insert(
+            ("web".into(), "handle_message".into()),
+            Expr::try_from("(= subject.component \"db\")").unwrap(),
+        );
+
+        let drift: Vec<String> = plan(&spec, &state, &lookup())
+            .unwrap()
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        assert_eq!(
+            drift,
+            vec![
+                "+ tcp listener 127.0.0.1:4000",
+                "- tcp listener 127.0.0.1:4001",
+                "+ secure channel listener /service/scl",
+                "- tcp outlet a2",
+                "+ verifier service /service/verifier",
+                "~ policy web/handle_message: (= subject.component \"db\") -> (= subject.component \"web\")",
+            ]
+        )
+    }
+
+    #[test]
+    fn changed_settings_are_reported() {
+        let spec: NodeSpec = serde_yaml::from_str(SPEC).unwrap();
+        let id: IdentityIdentifier =
+            "P6474cfdbf547240b6d716bff89c976810859bc3f47be8ea620df12a392ea6cb7"
+                .try_into()
+                .unwrap();
+        let mut state = state();
+        state.secure_channel_listeners[1].authorized_identities = Some(vec![id.clone()]);
+        state.inlets[0].outlet_addr = Some("/ip4/127.0.0.1/tcp/5002/service/outlet".into());
+        state.inlets[0].authorized = Some(id);
+        state.outlets[0].tcp_addr = "127.0.0.1:5001".into();
+        state.outlets[0].check_credential = Some(false);
+
+        let drift = plan(&spec, &state, &lookup()).unwrap();
+        let lines: Vec<String> = drift.iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "~ secure channel listener /service/scl (changed: authorized_identities)",
+                "~ tcp inlet web (127.0.0.1:6000 -> /node/n2/service/outlet) (changed: to, authorized)",
+                "~ tcp outlet outlet -> 127.0.0.1:5000 (changed: to)",
+            ]
+        );
+        // Changed items are recreated, so they must be removed first
+        assert!(matches!(
+            &drift[2],
+            Drift::Changed(Change::Settings {
+                actual: Extra::Outlet { alias },
+                ..
+            }) if alias == "a1"
+        ));
+
+        // Settings a spec leaves out are not compared
+        state.outlets[0].tcp_addr = "127.0.0.1:5000".into();
+        state.outlets[0].check_credential = Some(true);
+        state.inlets = self::state().inlets;
+        state.secure_channel_listeners[1].authorized_identities = None;
+        assert!(plan(&spec, &state, &lookup()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn trusted_identities_apply_to_listeners_without_their_own() {
+        let spec: NodeSpec = serde_yaml::from_str(
+            r#"
+trusted_identities:
+  - P6474cfdbf547240b6d716bff89c976810859bc3f47be8ea620df12a392ea6cb7
+secure_channel_listeners:
+  - address: a
+  - address: b
+    authorized_identities: []
+"#,
+        )
+        .unwrap();
+        let listeners = spec.secure_channel_listeners();
+        assert_eq!(
+            listeners[0].authorized_identities.as_ref().unwrap().len(),
+            1
+        );
+        assert_eq!(listeners[1].authorized_identities, Some(vec![]));
+    }
+}
@@ -1,10 +1,14 @@
 use crate::{
     help,
     node::HELP_DETAIL,
-    util::{exitcode, startup},
+    util::{api, exitcode, node_rpc, startup, RpcBuilder},
     CommandGlobalOpts,
 };
+use anyhow::anyhow;
 use clap::Args;
+use core::time::Duration;
+use nix::unistd::Pid;
+use ockam::TcpTransport;
 use rand::prelude::random;
 
 /// Stop Nodes
@@ -15,41 +19,109 @@ pub struct StopCommand {
     #[arg(hide_default_value = true, default_value_t = hex::encode(&random::<[u8;4]>()))]
     node_name: String,
     /// Whether to use the SIGTERM or SIGKILL signal to stop the node
-    #[arg(long)]
+    #[arg(long, conflicts_with = "graceful")]
     force: bool,
+    /// Drain the node before stopping it: in-flight messages are
+    /// delivered and peers of portals and secure channels are notified
+    #[arg(long)]
+    graceful: bool,
+    /// Seconds to wait for the node to drain (only with --graceful)
+    #[arg(
+        long,
+        default_value_t = 5,
+        requires = "graceful",
+        value_parser = clap::value_parser!(u8).range(1..)
+    )]
+    timeout: u8,
 }
 
 impl StopCommand {
     pub fn run(self, options: CommandGlobalOpts) {
-        let cfg = options.config;
+        if self.graceful {
+            node_rpc(drain_node, (options, self));
+            return;
+        }
+        let cfg = &options.config;
         match cfg.get_node_pid(&self.node_name) {
             Ok(Some(pid)) => {
                 if let Err(e) = startup::stop(pid, self.force) {
-                    eprintln!("{e:?}");
-                    std::process::exit(exitcode::OSERR);
+                    crate::Error::new(exitcode::OSERR, e).exit()
                 } else {
-                    // Clear pid in config, so StartCommand does not have to rely on
-                    // `kill 0 pid` to detect if a node is running.
-                    if let Err(e) = cfg.set_node_pid(&self.node_name, None) {
-                        eprintln!("Failed to update pid for node {}: {}", &self.node_name, e);
-                        std::process::exit(exitcode::IOERR);
-                    }
-
-                    // Save the config update
-                    if let Err(e) = cfg.persist_config_updates() {
-                        eprintln!("Failed to update configuration: {}", e);
-                        std::process::exit(exitcode::IOERR);
-                    }
+                    clear_node_pid(&options, &self.node_name);
                 }
             }
-            Ok(_) => {
-                eprintln!("Node {} is not running!", &self.node_name);
-                std::process::exit(exitcode::IOERR);
-            }
-            Err(_) => {
-                eprintln!("Node {} does not exist!", &self.node_name);
-                std::process::exit(exitcode::IOERR);
-            }
+            Ok(_) => crate::Error::new(
+                exitcode::IOERR,
+                anyhow!("Node {} is not running!", &self.node_name),
+            )
+            .exit(),
+            Err(_) => crate::Error::new(
+                exitcode::IOERR,
+                anyhow!("Node {} does not exist!", &self.node_name),
+            )
+            .exit(),
         };
     }
 }
+
+async fn drain_node(
+    ctx: ockam::Context,
+    (opts, cmd): (CommandGlobalOpts, StopCommand),
+) -> crate::Result<()> {
+    let pid = match opts.config.get_node_pid(&cmd.node_name) {
+        Ok(Some(pid)) => pid,
+        Ok(_) => {
+            return Err(crate::Error::new(
+                exitcode::IOERR,
+                anyhow!("Node {} is not running!", &cmd.node_name),
+            ))
+        }
+        Err(_) => {
+            return Err(crate::Error::new(
+                exitcode::IOERR,
+                anyhow!("Node {} does not exist!", &cmd.node_name),
+            ))
+        }
+    };
+
+    let tcp = TcpTransport::create(&ctx).await?;
+    let mut rpc = RpcBuilder::new(&ctx, &opts, &cmd.node_name)
+        .tcp(&tcp)?
+        .build();
+    rpc.request(api::drain_node(cmd.timeout)).await?;
+    rpc.is_ok()?;
+
+    // The node drains and then shuts down gracefully, each phase
+    // taking at most `timeout` seconds.  Only after that do we
+    // fall back to a SIGKILL.
+    let deadline = Duration::from_secs(2 * cmd.timeout as u64 + 1);
+    let start = std::time::Instant::now();
+    while nix::sys::signal::kill(Pid::from_raw(pid), None).is_ok() {
+        if start.elapsed() > deadline {
+            eprintln!("Node {} did not stop in time, killing it", &cmd.node_name);
+            startup::stop(pid, true)?;
+            break;
+        }
+        ctx.sleep(Duration::from_millis(100)).await;
+    }
+
+    clear_node_pid(&opts, &cmd.node_name);
+    Ok(())
+}
+
+fn clear_node_pid(opts: &CommandGlobalOpts, node_name: &str) {
+    let cfg = &opts.config;
+
+    // Clear pid in config, so StartCommand does not have to rely on
+    // `kill 0 pid` to detect if a node is running.
+    if let Err(e) = cfg.set_node_pid(node_name, None) {
+        let e = anyhow!("Failed to update pid for node {}: {}", node_name, e);
+        crate::Error::new(exitcode::IOERR, e).exit()
+    }
+
+    // Save the config update
+    if let Err(e) = cfg.persist_config_updates() {
+        let e = anyhow!("Failed to update configuration: {}", e);
+        crate::Error::new(exitcode::IOERR, e).exit()
+    }
+}
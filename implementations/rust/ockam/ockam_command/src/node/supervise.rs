@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+use std::env::current_exe;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context};
+use clap::Args;
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+use ockam_api::config::cli;
+use serde::{Deserialize, Serialize};
+use sysinfo::{PidExt, ProcessExt, ProcessStatus, System, SystemExt};
+use tracing::{info, warn};
+
+use crate::util::startup::{rotate_node_logs, spawn_node};
+use crate::util::{exitcode, OckamConfig};
+use crate::{help, node::HELP_DETAIL, CommandGlobalOpts};
+
+/// A node which stays up this long is considered healthy again, and
+/// is restarted with the shortest delay on its next crash.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+/// Supervise background nodes, restarting them when they crash
+///
+/// Nodes stopped with `ockam node stop` are left alone until they are
+/// started again with `ockam node start`.  Nodes which were running
+/// when the supervisor was last stopped, e.g. at shutdown, are started
+/// when it starts.
+#[derive(Clone, Debug, Args)]
+#[command(after_long_help = help::template(HELP_DETAIL))]
+pub struct SuperviseCommand {
+    /// Names of the nodes to supervise. All nodes are supervised if none is given
+    node_names: Vec<String>,
+
+    /// Seconds between two checks of the supervised nodes
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+    interval: u64,
+
+    /// Maximum number of seconds to wait before restarting a node which keeps crashing
+    #[arg(long, default_value_t = 300, value_parser = clap::value_parser!(u64).range(1..))]
+    max_backoff: u64,
+
+    /// Rotate the log files of a node once they grow beyond this many megabytes
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    max_log_size: u64,
+
+    /// Number of rotated copies kept for each log file of a node
+    #[arg(long, default_value_t = 5)]
+    max_log_files: u32,
+
+    /// Print a systemd unit running this supervisor at login, instead of running it
+    #[arg(long)]
+    systemd_unit: bool,
+}
+
+impl SuperviseCommand {
+    pub fn run(self, _options: CommandGlobalOpts) {
+        let res = if self.systemd_unit {
+            systemd_unit(&self).map(|unit| print!("{unit}"))
+        } else {
+            Supervisor::new(self).run()
+        };
+        if let Err(e) = res {
+            e.exit()
+        }
+    }
+}
+
+/// What the supervisor records about a node, in `supervisor.json` in
+/// the node state directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SupervisorState {
+    /// How many times the node was restarted after a crash
+    pub restarts: u32,
+    /// When the node was last restarted, in seconds since the Unix epoch
+    pub last_restart: Option<u64>,
+}
+
+impl SupervisorState {
+    fn path(node_name: &str) -> PathBuf {
+        cli::OckamConfig::node_dir(node_name).join("supervisor.json")
+    }
+
+    /// The state of a node which was ever supervised
+    pub fn load(node_name: &str) -> Option<Self> {
+        let s = std::fs::read_to_string(Self::path(node_name)).ok()?;
+        serde_json::from_str(&s).ok()
+    }
+
+    fn save(&self, node_name: &str) -> anyhow::Result<()> {
+        let s = serde_json::to_string(self)?;
+        std::fs::write(Self::path(node_name), s).context("failed to save supervisor state")
+    }
+}
+
+/// The restarts of a node the supervisor keeps track of
+struct Tracked {
+    /// Crashes since the node was last healthy
+    failures: u32,
+    /// When the node was last (re)started by the supervisor
+    started: Instant,
+    /// When a node which crashed is due to be restarted
+    restart_at: Option<Instant>,
+}
+
+struct Supervisor {
+    cmd: SuperviseCommand,
+    system: System,
+    nodes: BTreeMap<String, Tracked>,
+}
+
+impl Supervisor {
+    fn new(cmd: SuperviseCommand) -> Self {
+        Self {
+            cmd,
+            system: System::new(),
+            nodes: BTreeMap::new(),
+        }
+    }
+
+    fn run(mut self) -> crate::Result<()> {
+        info!("Supervising nodes every {}s", self.cmd.interval);
+        loop {
+            reap_children();
+            // Reload the configuration on every check, to see the nodes
+            // started, stopped and deleted in the meantime
+            let cfg = OckamConfig::load().map_err(|e| crate::Error::new(exitcode::CONFIG, e))?;
+            self.system.refresh_processes();
+            for name in self.node_names(&cfg) {
+                if let Err(e) = self.check(&cfg, &name) {
+                    warn!(node = %name, "Failed to supervise node: {e:?}");
+                }
+            }
+            sleep(Duration::from_secs(self.cmd.interval));
+        }
+    }
+
+    fn node_names(&mut self, cfg: &OckamConfig) -> Vec<String> {
+        let inner = cfg.inner();
+        let names: Vec<String> = if self.cmd.node_names.is_empty() {
+            inner.nodes.keys().cloned().collect()
+        } else {
+            self.cmd
+                .node_names
+                .iter()
+                .filter(|n| inner.nodes.contains_key(*n))
+                .cloned()
+                .collect()
+        };
+        // Forget about deleted nodes
+        self.nodes.retain(|n, _| names.contains(n));
+        names
+    }
+
+    fn check(&mut self, cfg: &OckamConfig, name: &str) -> anyhow::Result<()> {
+        let max_log_size = self.cmd.max_log_size * 1024 * 1024;
+        rotate_node_logs(cfg, name, max_log_size, self.cmd.max_log_files)?;
+
+        // Nodes without a pid were stopped on purpose
+        let node = cfg.get_node(name)?;
+        let pid = match node.pid() {
+            Some(pid) => pid,
+            None => {
+                self.nodes.remove(name);
+                return Ok(());
+            }
+        };
+
+        let now = Instant::now();
+        let tracked = self.nodes.entry(name.to_string()).or_insert(Tracked {
+            failures: 0,
+            started: now,
+            restart_at: None,
+        });
+
+        if is_node_process(&self.system, pid, name) {
+            tracked.restart_at = None;
+            if tracked.failures > 0 && tracked.started.elapsed() >= STABLE_UPTIME {
+                tracked.failures = 0;
+            }
+            return Ok(());
+        }
+
+        // Wait a little even before the first restart, so that a node
+        // being stopped has time to clear its pid
+        let restart_at = *tracked.restart_at.get_or_insert_with(|| {
+            let delay = backoff(tracked.failures, self.cmd.max_backoff);
+            warn!(node = %name, pid, "Node is down, restarting it in {delay:?}");
+            now + delay
+        });
+        if now < restart_at {
+            return Ok(());
+        }
+
+        spawn_node(
+            cfg,
+            node.verbose(),
+            true,
+            node.name(),
+            &node.addr().to_string(),
+            None,
+            None,
+        )?;
+        tracked.failures += 1;
+        tracked.started = now;
+        tracked.restart_at = None;
+
+        let mut state = SupervisorState::load(name).unwrap_or_default();
+        state.restarts += 1;
+        state.last_restart = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs());
+        state.save(name)?;
+        info!(node = %name, restarts = state.restarts, "Node restarted");
+        Ok(())
+    }
+}
+
+/// The delay before restarting a node which crashed `failures` times
+/// since it was last healthy
+fn backoff(failures: u32, max_secs: u64) -> Duration {
+    let secs = 1u64.checked_shl(failures).unwrap_or(u64::MAX);
+    Duration::from_secs(secs.min(max_secs))
+}
+
+/// Collect the exit status of the nodes started by the supervisor, so
+/// that they don't linger as zombie processes
+fn reap_children() {
+    while let Ok(status) = waitpid(Pid::from_raw(-1), Some(WaitPidFlag::WNOHANG)) {
+        if status == WaitStatus::StillAlive {
+            break;
+        }
+    }
+}
+
+/// Whether `pid` is a live process running the node `name`.
+///
+/// Pids are reused, e.g. after a reboot, so the process command line
+/// must name the node too.
+pub(crate) fn is_node_process(system: &System, pid: i32, name: &str) -> bool {
+    match system.process(sysinfo::Pid::from_u32(pid as u32)) {
+        Some(p) => p.status() != ProcessStatus::Zombie && p.cmd().iter().any(|a| a == name),
+        None => false,
+    }
+}
+
+/// A systemd user unit running the supervisor with the same arguments
+fn systemd_unit(cmd: &SuperviseCommand) -> crate::Result<String> {
+    let exe = current_exe().context("failed to find the ockam executable")?;
+    let mut args = vec![
+        "node".to_string(),
+        "supervise".to_string(),
+        format!("--interval={}", cmd.interval),
+        format!("--max-backoff={}", cmd.max_backoff),
+        format!("--max-log-size={}", cmd.max_log_size),
+        format!("--max-log-files={}", cmd.max_log_files),
+    ];
+    args.extend(cmd.node_names.iter().cloned());
+    Ok(format!(
+        "\
+[Unit]
+Description=Ockam node supervisor
+After=network-online.target
+
+[Service]
+Environment=OCKAM_HOME={home}
+ExecStart={exe} {args}
+Restart=on-failure
+# Stopping the supervisor leaves the nodes running
+KillMode=process
+
+[Install]
+WantedBy=default.target
+",
+        home = path_str(&cli::OckamConfig::dir())?,
+        exe = path_str(&exe)?,
+        args = args.join(" ")
+    ))
+}
+
+fn path_str(p: &Path) -> crate::Result<&str> {
+    p.to_str().ok_or_else(|| {
+        crate::Error::new(
+            exitcode::CONFIG,
+            anyhow!("unsupported path {}", p.display()),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        assert_eq!(backoff(0, 300), Duration::from_secs(1));
+        assert_eq!(backoff(3, 300), Duration::from_secs(8));
+        assert_eq!(backoff(9, 300), Duration::from_secs(300));
+        assert_eq!(backoff(100, 300), Duration::from_secs(300));
+    }
+}
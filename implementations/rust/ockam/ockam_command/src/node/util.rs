@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::{anyhow, Context as _, Result};
@@ -251,6 +252,29 @@ fn delete_node_config(opts: &CommandGlobalOpts, node_name: &str) {
     opts.config.remove_node(node_name);
 }
 
+/// Read the passphrase of a node archive from a file, or prompt for
+/// it, twice when `confirm` is set
+pub(super) fn read_passphrase(file: Option<&Path>, confirm: bool) -> Result<String> {
+    let passphrase = match file {
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read passphrase from {}", path.display()))?
+            .trim_end_matches(['\r', '\n'])
+            .to_string(),
+        None => {
+            let mut prompt = dialoguer::Password::new();
+            prompt.with_prompt("Passphrase");
+            if confirm {
+                prompt.with_confirmation("Confirm passphrase", "Passphrases don't match");
+            }
+            prompt.interact()?
+        }
+    };
+    if passphrase.is_empty() {
+        return Err(anyhow!("The passphrase must not be empty"));
+    }
+    Ok(passphrase)
+}
+
 pub mod run {
     use std::env::current_exe;
     #[cfg(test)]
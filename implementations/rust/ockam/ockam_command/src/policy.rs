@@ -86,7 +86,7 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, PolicyCommand)) -> R
             let mut rpc = Rpc::background(&ctx, &opts, &node)?;
             rpc.request(req).await?;
             let pol: Policy = rpc.parse_response()?;
-            println!("{}", pol.expression())
+            opts.print(&PolicyEntry::new(&resource, &action, pol.expression()))?
         }
         PolicySubcommand::Delete { at, resource, action } => {
             let node = extract_address_value(&at)?;
@@ -101,9 +101,12 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, PolicyCommand)) -> R
             let mut rpc = Rpc::background(&ctx, &opts, &node)?;
             rpc.request(req).await?;
             let pol: PolicyList = rpc.parse_response()?;
-            for (a, e) in pol.expressions() {
-                println!("{resource}/{a}: {e}")
-            }
+            let entries: Vec<_> = pol
+                .expressions()
+                .iter()
+                .map(|(a, e)| PolicyEntry::new(&resource, a, e))
+                .collect();
+            opts.print(&entries)?
         }
     }
     Ok(())
@@ -112,3 +115,21 @@ async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, PolicyCommand)) -> R
 fn policy_path(r: &Resource, a: &Action) -> String {
     format!("/policy/{r}/{a}")
 }
+
+/// The policy of an action on a resource
+#[derive(serde::Serialize)]
+pub struct PolicyEntry {
+    pub resource: String,
+    pub action: String,
+    pub expression: String,
+}
+
+impl PolicyEntry {
+    pub(crate) fn new(r: &Resource, a: &Action, e: &Expr) -> Self {
+        Self {
+            resource: r.to_string(),
+            action: a.to_string(),
+            expression: e.to_string(),
+        }
+    }
+}
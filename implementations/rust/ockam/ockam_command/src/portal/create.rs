@@ -0,0 +1,268 @@
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::str::FromStr;
+
+use anyhow::{anyhow, ensure, Context as _};
+use clap::Args;
+use ockam::identity::IdentityIdentifier;
+use ockam::{Context, TcpTransport};
+use ockam_api::config::cli::PortalConfig;
+use ockam_api::nodes::models::forwarder::ForwarderInfo;
+use ockam_api::nodes::models::portal::{CreateInlet, CreateOutlet, PortalTransport};
+use ockam_api::nodes::models::secure_channel::{
+    CreateSecureChannelResponse, CredentialExchangeMode,
+};
+use ockam_core::api::Request;
+use ockam_core::Address;
+use ockam_multiaddr::proto::Project;
+use ockam_multiaddr::{MultiAddr, Protocol as _};
+use rand::prelude::random;
+
+use crate::forwarder::create_forwarder_request;
+use crate::portal::{delete_parts, Endpoint, PortalInfo, HELP_DETAIL};
+use crate::util::{api, bind_to_port_check, exitcode, node_rpc, process_multi_addr, RpcBuilder};
+use crate::{help, CommandGlobalOpts, Result};
+
+/// Create a Portal, with its inlet, outlet and the hops between them
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    after_long_help = help::template(HELP_DETAIL)
+)]
+pub struct CreateCommand {
+    /// Name of the portal (optional)
+    #[arg(hide_default_value = true, default_value_t = hex::encode(&random::<[u8;4]>()), value_parser = name_parser)]
+    name: String,
+
+    /// Address the inlet accepts connections at, e.g. /node/n1/ip4/127.0.0.1/tcp/6000
+    #[arg(long, value_name = "MULTIADDR")]
+    from: MultiAddr,
+
+    /// Address the outlet makes connections to, e.g. /node/n2/ip4/127.0.0.1/tcp/5000
+    #[arg(long, value_name = "MULTIADDR")]
+    to: MultiAddr,
+
+    /// Route to a project or relay node at which to create a forwarder to the outlet node
+    #[arg(long, value_name = "ROUTE")]
+    via: Option<MultiAddr>,
+
+    /// Identity authorized to be presented by the outlet node
+    #[arg(long, value_name = "IDENTIFIER")]
+    authorized: Option<IdentityIdentifier>,
+}
+
+impl CreateCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand)) -> Result<()> {
+    let default_node = opts.config.get_default_node();
+    let from =
+        Endpoint::parse(&cmd.from, default_node.clone()).context("Argument '--from' is invalid")?;
+    let to = Endpoint::parse(&cmd.to, default_node).context("Argument '--to' is invalid")?;
+    let udp = PortalTransport::Udp;
+    if (from.transport == udp) != (to.transport == udp) {
+        return Err(crate::Error::new(
+            exitcode::USAGE,
+            anyhow!(
+                "The inlet address is {} and the outlet address is {}. A udp portal needs udp at both ends",
+                from.transport,
+                to.transport
+            ),
+        ));
+    }
+    if opts.config.get_portal(&cmd.name).is_ok() {
+        return Err(crate::Error::new(
+            exitcode::CANTCREAT,
+            anyhow!("Portal {} already exists", cmd.name),
+        ));
+    }
+    let via_project = cmd
+        .via
+        .as_ref()
+        .map(|via| via.matches(0, &[Project::CODE.into()]))
+        .unwrap_or(false);
+    if via_project && cmd.authorized.is_some() {
+        return Err(anyhow!("--authorized can not be used with project addresses").into());
+    }
+
+    let listen = match from.transport {
+        PortalTransport::Tcp => {
+            let listen = resolve(&from.address)?;
+            if !bind_to_port_check(&listen) {
+                return Err(crate::Error::new(
+                    exitcode::IOERR,
+                    anyhow!("Another process is listening on {listen}"),
+                ));
+            }
+            listen
+        }
+        PortalTransport::Udp => resolve(&from.address)?,
+        // Unix socket inlets listen at the path of the endpoint instead
+        PortalTransport::Unix => SocketAddr::from(([0, 0, 0, 0], 0)),
+    };
+
+    let mut portal = PortalConfig {
+        transport: from.transport.to_string(),
+        from: cmd.from.clone(),
+        to: cmd.to.clone(),
+        via: cmd.via.clone(),
+        inlet_node: from.node.clone(),
+        outlet_node: to.node.clone(),
+        outlet_address: format!("portal_{}", cmd.name),
+        forwarder: None,
+        secure_channel: None,
+    };
+
+    let tcp = TcpTransport::create(&ctx).await?;
+    let mut created = Created::default();
+    let res = create(
+        &ctx,
+        &opts,
+        &tcp,
+        &cmd,
+        listen,
+        &from,
+        &to,
+        via_project,
+        &mut portal,
+        &mut created,
+    )
+    .await;
+    if let Err(e) = res {
+        // Leave nothing behind from a portal that could not be created
+        delete_parts(
+            &ctx,
+            &opts,
+            &tcp,
+            &cmd.name,
+            &portal,
+            created.inlet,
+            created.outlet,
+        )
+        .await;
+        return Err(e);
+    }
+
+    opts.config.add_portal(&cmd.name, portal.clone())?;
+    opts.config.persist_config_updates()?;
+    let info = PortalInfo::query(&ctx, &opts, &tcp, &cmd.name, portal).await;
+    opts.print(&info)
+}
+
+/// The inlet and outlet of a portal which may exist on their nodes
+#[derive(Default)]
+struct Created {
+    inlet: bool,
+    outlet: bool,
+}
+
+/// Create the outlet, the forwarder and secure channel if needed, and
+/// then the inlet
+#[allow(clippy::too_many_arguments)]
+async fn create(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    cmd: &CreateCommand,
+    listen: SocketAddr,
+    from: &Endpoint,
+    to: &Endpoint,
+    via_project: bool,
+    portal: &mut PortalConfig,
+    created: &mut Created,
+) -> Result<()> {
+    let lookup = opts.config.lookup();
+
+    let payload = CreateOutlet::new(
+        to.address.clone(),
+        portal.outlet_address.clone(),
+        Some(cmd.name.clone().into()),
+        None,
+    )
+    .with_transport(to.transport);
+    let mut rpc = RpcBuilder::new(ctx, opts, &portal.outlet_node)
+        .tcp(tcp)?
+        .build();
+    rpc.request(Request::post("/node/outlet").body(payload))
+        .await?;
+    rpc.is_ok()?;
+    created.outlet = true;
+
+    let outlet = format!("/service/{}", portal.outlet_address);
+    let route = match &cmd.via {
+        None => MultiAddr::from_str(&format!("/node/{}/secure/api{outlet}", to.node))?,
+        Some(via) => {
+            let req = create_forwarder_request(opts, &cmd.name, via, None)?;
+            let mut rpc = RpcBuilder::new(ctx, opts, &portal.outlet_node)
+                .tcp(tcp)?
+                .build();
+            rpc.request(req).await?;
+            let forwarder = rpc.parse_response::<ForwarderInfo>()?;
+            let remote = forwarder.remote_address().to_string();
+            portal.forwarder = Some(remote.clone());
+            if via_project {
+                MultiAddr::from_str(&format!("{via}/service/{remote}/secure/api{outlet}"))?
+            } else {
+                // The inlet node only creates a secure channel through a
+                // forwarder when it reaches it through a project
+                let addr = MultiAddr::from_str(&format!("{via}/service/{remote}/service/api"))?;
+                let addr = process_multi_addr(&addr, &lookup)?;
+                let authorized = cmd.authorized.clone().map(|id| vec![id]);
+                let req =
+                    api::create_secure_channel(&addr, authorized, CredentialExchangeMode::Mutual);
+                let mut rpc = RpcBuilder::new(ctx, opts, &portal.inlet_node)
+                    .tcp(tcp)?
+                    .build();
+                rpc.request(req).await?;
+                let channel = rpc.parse_response::<CreateSecureChannelResponse>()?;
+                let channel = Address::from_string(channel.addr.as_ref());
+                portal.secure_channel = Some(channel.address().to_string());
+                MultiAddr::from_str(&format!("/service/{}{outlet}", channel.address()))?
+            }
+        }
+    };
+    let route = process_multi_addr(&route, &lookup)?;
+
+    let mut payload = if via_project {
+        CreateInlet::via_project(listen, route, None)
+    } else if portal.secure_channel.is_some() {
+        CreateInlet::to_node(listen, route, None, None)
+    } else {
+        CreateInlet::to_node(listen, route, None, cmd.authorized.clone())
+    };
+    payload.set_alias(cmd.name.clone());
+    match from.transport {
+        PortalTransport::Tcp => {}
+        PortalTransport::Udp => payload.set_udp(),
+        PortalTransport::Unix => payload.set_unix_path(from.address.clone()),
+    }
+    let mut rpc = RpcBuilder::new(ctx, opts, &portal.inlet_node)
+        .tcp(tcp)?
+        .build();
+    // The node keeps an inlet that failed to start under its alias, so
+    // it has to be deleted even if this request fails
+    created.inlet = true;
+    rpc.request(Request::post("/node/inlet").body(payload))
+        .await?;
+    rpc.is_ok()?;
+    Ok(())
+}
+
+/// Resolve the address the inlet listens at
+fn resolve(address: &str) -> anyhow::Result<SocketAddr> {
+    address
+        .to_socket_addrs()
+        .with_context(|| format!("Failed to resolve {address}"))?
+        .next()
+        .ok_or_else(|| anyhow!("Failed to resolve {address}"))
+}
+
+fn name_parser(arg: &str) -> anyhow::Result<String> {
+    ensure! {
+        !arg.contains(':') && !arg.contains('/'),
+        "a portal name must not contain ':' or '/' characters"
+    }
+    Ok(arg.to_string())
+}
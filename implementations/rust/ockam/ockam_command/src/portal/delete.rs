@@ -0,0 +1,35 @@
+use clap::Args;
+use ockam::{Context, TcpTransport};
+
+use crate::portal::delete_parts;
+use crate::util::node_rpc;
+use crate::util::output::Deleted;
+use crate::CommandGlobalOpts;
+
+/// Delete a Portal, with its inlet, outlet and the hops between them
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true)]
+pub struct DeleteCommand {
+    /// Name of the portal
+    pub name: String,
+}
+
+impl DeleteCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, DeleteCommand)) -> crate::Result<()> {
+    let portal = opts.config.get_portal(&cmd.name)?;
+    let tcp = TcpTransport::create(&ctx).await?;
+    // Inlets, outlets and forwarders do not outlive their node, so the
+    // parts left on a stopped node do not keep the portal alive
+    let failed = delete_parts(&ctx, &opts, &tcp, &cmd.name, &portal, true, true).await;
+    opts.config.remove_portal(&cmd.name);
+    opts.config.persist_config_updates()?;
+    opts.print(&Deleted {
+        failed,
+        ..Deleted::new("Portal", cmd.name)
+    })
+}
@@ -0,0 +1,26 @@
+use clap::Args;
+use ockam::{Context, TcpTransport};
+
+use crate::portal::PortalInfo;
+use crate::util::node_rpc;
+use crate::CommandGlobalOpts;
+
+/// List Portals
+#[derive(Clone, Debug, Args)]
+pub struct ListCommand {}
+
+impl ListCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, _cmd): (CommandGlobalOpts, ListCommand)) -> crate::Result<()> {
+    let portals = opts.config.inner().portals.clone();
+    let tcp = TcpTransport::create(&ctx).await?;
+    let mut infos = Vec::with_capacity(portals.len());
+    for (name, portal) in portals {
+        infos.push(PortalInfo::query(&ctx, &opts, &tcp, &name, portal).await);
+    }
+    opts.print(&infos)
+}
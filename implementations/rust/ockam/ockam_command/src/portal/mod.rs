@@ -0,0 +1,323 @@
+mod create;
+mod delete;
+mod list;
+mod show;
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _};
+use clap::{Args, Subcommand};
+use ockam::{Context, TcpTransport};
+use ockam_api::config::cli::PortalConfig;
+use ockam_api::nodes::models::portal::{InletStatus, PortalTransport as Transport};
+use ockam_core::Address;
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Tcp, Udp, Unix};
+use ockam_multiaddr::{MultiAddr, ProtoValue, Protocol as _};
+
+use crate::util::{api, RpcBuilder};
+use crate::{help, CommandGlobalOpts};
+use create::CreateCommand;
+use delete::DeleteCommand;
+use list::ListCommand;
+use show::ShowCommand;
+
+const HELP_DETAIL: &str = "\
+About:
+    A portal carries the connections accepted by an inlet on one node to an
+    outlet on another node, which makes them to the target service.
+
+    Both ends are multiaddrs: /node/NAME, then the address of the inlet or the
+    target, either /ip4/HOST/tcp/PORT, /ip4/HOST/udp/PORT or /unix/PATH. The
+    default node is used when /node/NAME is left out. TCP and Unix socket
+    ends can be mixed, a UDP inlet needs a UDP target.
+
+    UDP portals carry datagrams of up to 48 KiB. Every client of a UDP inlet
+    gets its own session, which ends after a minute without datagrams.
+
+    The secure channel to the outlet node is created along with the portal,
+    and with --via, the forwarder to the outlet node at a project or a relay
+    node. Deleting the portal deletes all of them.
+
+Examples:
+
+```sh
+    # Create a target service, we'll use a simple http server for this example
+    $ python3 -m http.server --bind 127.0.0.1 5000
+
+    # Create two nodes
+    $ ockam node create n1
+    $ ockam node create n2
+
+    # Create a portal from port 6000 on n1 to the target service, through n2
+    $ ockam portal create web --from /node/n1/ip4/127.0.0.1/tcp/6000 --to /node/n2/ip4/127.0.0.1/tcp/5000
+
+    # Access the service via the portal
+    $ curl 127.0.0.1:6000
+
+    # Or through a Unix socket on n1
+    $ ockam portal create web-unix --from /node/n1/unix/tmp/web.sock --to /node/n2/ip4/127.0.0.1/tcp/5000
+    $ curl --unix-socket /tmp/web.sock http://localhost/
+
+    # Create a portal to the target service through a forwarder at the default project
+    $ ockam portal create web2 --from /node/n1/ip4/127.0.0.1/tcp/7000 --to /node/n2/ip4/127.0.0.1/tcp/5000 --via /project/default
+
+    # Delete the portal, with its inlet, outlet and forwarder
+    $ ockam portal delete web2
+```
+";
+
+/// Manage Portals between nodes
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    after_long_help = help::template(HELP_DETAIL)
+)]
+pub struct PortalCommand {
+    #[command(subcommand)]
+    subcommand: PortalSubCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum PortalSubCommand {
+    Create(CreateCommand),
+    Delete(DeleteCommand),
+    List(ListCommand),
+    Show(ShowCommand),
+}
+
+impl PortalCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            PortalSubCommand::Create(c) => c.run(options),
+            PortalSubCommand::Delete(c) => c.run(options),
+            PortalSubCommand::List(c) => c.run(options),
+            PortalSubCommand::Show(c) => c.run(options),
+        }
+    }
+}
+
+/// One end of a portal: the node of an inlet or outlet, and the address
+/// it accepts or makes connections at
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Endpoint {
+    pub node: String,
+    pub transport: Transport,
+    /// `HOST:PORT` for tcp and udp, the path of the socket for unix
+    pub address: String,
+}
+
+impl Endpoint {
+    pub fn parse(addr: &MultiAddr, default_node: Option<String>) -> anyhow::Result<Self> {
+        let mut protos = addr.iter().peekable();
+        let node = match protos.peek() {
+            Some(p) if p.code() == Node::CODE => {
+                let node = p
+                    .cast::<Node>()
+                    .ok_or_else(|| anyhow!("invalid node in {addr}"))?
+                    .to_string();
+                protos.next();
+                node
+            }
+            _ => default_node
+                .context("There is no default node. Start the address with /node/NAME")?,
+        };
+        let rest: Vec<ProtoValue> = protos.collect();
+        let (transport, address) = match rest.as_slice() {
+            [host, port] if port.code() == Tcp::CODE => {
+                let port = port
+                    .cast::<Tcp>()
+                    .ok_or_else(|| anyhow!("invalid tcp port"))?;
+                (Transport::Tcp, format!("{}:{}", host_str(host)?, *port))
+            }
+            [host, port] if port.code() == Udp::CODE => {
+                let port = port
+                    .cast::<Udp>()
+                    .ok_or_else(|| anyhow!("invalid udp port"))?;
+                (Transport::Udp, format!("{}:{}", host_str(host)?, *port))
+            }
+            [path] if path.code() == Unix::CODE => {
+                let path = path
+                    .cast::<Unix>()
+                    .ok_or_else(|| anyhow!("invalid unix path"))?;
+                (Transport::Unix, path.to_string())
+            }
+            _ => {
+                return Err(anyhow!(
+                    "{addr} is not a portal address. After /node/NAME, it must be \
+                     /ip4/HOST/tcp/PORT, /ip4/HOST/udp/PORT or /unix/PATH"
+                ))
+            }
+        };
+        Ok(Self {
+            node,
+            transport,
+            address,
+        })
+    }
+}
+
+fn host_str(p: &ProtoValue) -> anyhow::Result<String> {
+    let host = match p.code() {
+        Ip4::CODE => p.cast::<Ip4>().map(|ip| ip.to_string()),
+        Ip6::CODE => p.cast::<Ip6>().map(|ip| format!("[{}]", *ip)),
+        DnsAddr::CODE => p.cast::<DnsAddr>().map(|host| host.to_string()),
+        _ => None,
+    };
+    host.ok_or_else(|| anyhow!("expected /ip4, /ip6 or /dnsaddr before the port"))
+}
+
+/// A portal, as shown by `ockam portal show` and `ockam portal list`
+#[derive(serde::Serialize)]
+pub struct PortalInfo {
+    pub name: String,
+    #[serde(flatten)]
+    pub portal: PortalConfig,
+    /// Route of the inlet to the outlet, as resolved by the inlet node
+    pub route: Option<String>,
+    pub status: String,
+}
+
+impl PortalInfo {
+    /// Ask the inlet node for the state of the portal
+    async fn query(
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        tcp: &TcpTransport,
+        name: &str,
+        portal: PortalConfig,
+    ) -> Self {
+        let (route, status) = match inlet_status(ctx, opts, tcp, name, &portal.inlet_node).await {
+            Ok((route, status)) => (Some(route), status),
+            Err(_) => (None, "unavailable".to_string()),
+        };
+        Self {
+            name: name.to_string(),
+            portal,
+            route,
+            status,
+        }
+    }
+}
+
+async fn inlet_status(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    name: &str,
+    node: &str,
+) -> crate::Result<(String, String)> {
+    let mut rpc = RpcBuilder::new(ctx, opts, node).tcp(tcp)?.build();
+    rpc.request_with_timeout(api::show_inlet(name), Duration::from_secs(5))
+        .await?;
+    let inlet = rpc.parse_response::<InletStatus>()?;
+    let status = inlet
+        .status
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    Ok((inlet.outlet_route.to_string(), status))
+}
+
+/// Delete the parts of a portal: its inlet if `inlet` is set, the secure
+/// channel and forwarder it recorded, and its outlet if `outlet` is set
+///
+/// Every part is attempted, and the parts that could not be deleted are
+/// returned with the reason.
+async fn delete_parts(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    tcp: &TcpTransport,
+    name: &str,
+    portal: &PortalConfig,
+    inlet: bool,
+    outlet: bool,
+) -> Vec<String> {
+    let mut failed = vec![];
+    if inlet {
+        let res = async {
+            let mut rpc = RpcBuilder::new(ctx, opts, &portal.inlet_node)
+                .tcp(tcp)?
+                .build();
+            rpc.request(api::delete_inlet(name)).await?;
+            rpc.is_ok()
+        };
+        if let Err(e) = res.await {
+            failed.push(format!("inlet {name} on node {}: {e}", portal.inlet_node));
+        }
+    }
+    if let Some(channel) = &portal.secure_channel {
+        let res = async {
+            let mut rpc = RpcBuilder::new(ctx, opts, &portal.inlet_node)
+                .tcp(tcp)?
+                .build();
+            let addr = Address::from_string(channel);
+            rpc.request(api::delete_secure_channel(&addr)).await?;
+            rpc.is_ok()
+        };
+        if let Err(e) = res.await {
+            failed.push(format!(
+                "secure channel {channel} on node {}: {e}",
+                portal.inlet_node
+            ));
+        }
+    }
+    if let Some(forwarder) = &portal.forwarder {
+        let res = async {
+            let mut rpc = RpcBuilder::new(ctx, opts, &portal.outlet_node)
+                .tcp(tcp)?
+                .build();
+            rpc.request(api::delete_forwarder(forwarder)).await?;
+            rpc.is_ok()
+        };
+        if let Err(e) = res.await {
+            failed.push(format!(
+                "forwarder {forwarder} on node {}: {e}",
+                portal.outlet_node
+            ));
+        }
+    }
+    if outlet {
+        let res = async {
+            let mut rpc = RpcBuilder::new(ctx, opts, &portal.outlet_node)
+                .tcp(tcp)?
+                .build();
+            rpc.request(api::delete_outlet(name)).await?;
+            rpc.is_ok()
+        };
+        if let Err(e) = res.await {
+            failed.push(format!("outlet {name} on node {}: {e}", portal.outlet_node));
+        }
+    }
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn parse(addr: &str) -> anyhow::Result<Endpoint> {
+        Endpoint::parse(&MultiAddr::from_str(addr).unwrap(), Some("n0".into()))
+    }
+
+    #[test]
+    fn endpoints_select_the_transport() {
+        let e = parse("/node/n1/ip4/127.0.0.1/tcp/6000").unwrap();
+        assert_eq!(e.node, "n1");
+        assert_eq!(e.transport, Transport::Tcp);
+        assert_eq!(e.address, "127.0.0.1:6000");
+
+        let e = parse("/ip6/::1/udp/53").unwrap();
+        assert_eq!(e.node, "n0");
+        assert_eq!(e.transport, Transport::Udp);
+        assert_eq!(e.address, "[::1]:53");
+
+        let e = parse("/node/n1/unix/run/app.sock").unwrap();
+        assert_eq!(e.transport, Transport::Unix);
+        assert_eq!(e.address, "/run/app.sock");
+
+        assert!(parse("/node/n1/ip4/127.0.0.1").is_err());
+        assert!(parse("/node/n1/service/outlet").is_err());
+        assert!(Endpoint::parse(&MultiAddr::from_str("/unix/a").unwrap(), None).is_err());
+    }
+}
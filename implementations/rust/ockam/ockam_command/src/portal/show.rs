@@ -0,0 +1,27 @@
+use clap::Args;
+use ockam::{Context, TcpTransport};
+
+use crate::portal::PortalInfo;
+use crate::util::node_rpc;
+use crate::CommandGlobalOpts;
+
+/// Show a Portal
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true)]
+pub struct ShowCommand {
+    /// Name of the portal
+    pub name: String,
+}
+
+impl ShowCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, ShowCommand)) -> crate::Result<()> {
+    let portal = opts.config.get_portal(&cmd.name)?;
+    let tcp = TcpTransport::create(&ctx).await?;
+    let info = PortalInfo::query(&ctx, &opts, &tcp, &cmd.name, portal).await;
+    opts.print(&info)
+}
@@ -2,7 +2,7 @@ use clap::Args;
 
 use anyhow::{anyhow, Context as _};
 use ockam::Context;
-use ockam_api::cloud::enroll::auth0::AuthenticateAuth0Token;
+use ockam_api::cloud::enroll::auth0::{Auth0Token, AuthenticateAuth0Token};
 use ockam_api::cloud::project::OktaAuth0;
 use ockam_core::api::{Request, Status};
 use ockam_multiaddr::MultiAddr;
@@ -19,9 +19,11 @@ use std::path::PathBuf;
 use crate::project::util::create_secure_channel_to_authority;
 use ockam_api::authenticator::direct::Client;
 use ockam_api::config::lookup::ProjectAuthority;
+use ockam_api::oidc::IdToken;
 use ockam_api::DefaultAddress;
 
-/// Authenticate using okta addon
+/// Authenticate using okta addon, or with an ID token of the project's
+/// OpenID Connect identity provider
 #[derive(Clone, Debug, Args)]
 #[command(hide = help::hide())]
 pub struct AuthCommand {
@@ -29,10 +31,20 @@ pub struct AuthCommand {
     #[arg(long = "project", value_name = "PROJECT_JSON_PATH")]
     project: PathBuf,
 
+    /// ID token to enroll with, instead of the okta addon
+    #[arg(long, value_name = "ID_TOKEN")]
+    id_token: Option<String>,
+
     #[command(flatten)]
     cloud_opts: CloudOpts,
 }
 
+/// What to enroll with
+enum EnrollToken<'a> {
+    Auth0(Auth0Token<'a>),
+    Id(String),
+}
+
 impl AuthCommand {
     pub fn run(self, opts: CommandGlobalOpts) {
         node_rpc(run_impl, (opts, self));
@@ -49,10 +61,22 @@ async fn run_impl(
     let s = tokio::fs::read_to_string(cmd.project).await?;
     let p: ProjectInfo = serde_json::from_str(&s)?;
 
-    // Get auth0 token
-    let okta_config: OktaAuth0 = p.okta_config.context("Okta addon not configured")?.into();
-    let auth0 = Auth0Service::new(Auth0Provider::Okta(okta_config));
-    let token = auth0.token().await?;
+    // Get auth0 token, unless we have an ID token
+    let auth0;
+    let (token, enroller) = match cmd.id_token {
+        Some(token) => (
+            EnrollToken::Id(token),
+            DefaultAddress::OIDC_IDENTITY_PROVIDER,
+        ),
+        None => {
+            let okta_config: OktaAuth0 = p.okta_config.context("Okta addon not configured")?.into();
+            auth0 = Auth0Service::new(Auth0Provider::Okta(okta_config));
+            (
+                EnrollToken::Auth0(auth0.token().await?),
+                "okta_authenticator",
+            )
+        }
+    };
     // Create secure channel to the project's authority node
     let secure_channel_addr = {
         let authority =
@@ -63,9 +87,9 @@ async fn run_impl(
             .await?
     };
 
-    // Return address to the "okta_authenticator" worker on the authority node through the secure channel
-    let okta_authenticator_addr = {
-        let service = MultiAddr::try_from("/service/okta_authenticator")?;
+    // Return address to the enrolling worker on the authority node through the secure channel
+    let enroller_addr = {
+        let service = MultiAddr::try_from(format!("/service/{enroller}").as_str())?;
         let mut addr = secure_channel_addr.clone();
         for proto in service.iter() {
             addr.push_back_value(&proto)?;
@@ -85,13 +109,20 @@ async fn run_impl(
     };
 
     // Send enroll request to authority node
-    let token = AuthenticateAuth0Token::new(token);
-    let req = Request::post("v0/enroll").body(token);
     let mut rpc = RpcBuilder::new(&ctx, &opts, &node_name)
-        .to(&okta_authenticator_addr)?
+        .to(&enroller_addr)?
         .build();
-    debug!(addr = %okta_authenticator_addr, "enrolling");
-    rpc.request(req).await?;
+    debug!(addr = %enroller_addr, "enrolling");
+    match token {
+        EnrollToken::Auth0(token) => {
+            let token = AuthenticateAuth0Token::new(token);
+            rpc.request(Request::post("v0/enroll").body(token)).await?
+        }
+        EnrollToken::Id(token) => {
+            let token = IdToken::new(token);
+            rpc.request(Request::post("v0/enroll").body(token)).await?
+        }
+    }
     let (res, dec) = rpc.check_response()?;
     let res = if res.status() == Some(Status::Ok) {
         info!("Enrolled successfully");
@@ -109,7 +109,9 @@ impl Runner {
                 .body(CreateToken::new().with_attributes(self.cmd.attributes()?));
             rpc.request(req).await?;
             let res: OneTimeCode = rpc.parse_response()?;
-            println!("{}", hex::encode(res.code()))
+            self.opts.print(&EnrollmentToken {
+                token: hex::encode(res.code()),
+            })?
         }
 
         delete_embedded_node(&self.opts.config, &node_name).await;
@@ -117,6 +119,12 @@ impl Runner {
     }
 }
 
+/// The enrollment token requested by [`EnrollCommand`]
+#[derive(serde::Serialize)]
+pub struct EnrollmentToken {
+    pub token: String,
+}
+
 /// Get the project authority from the first address protocol.
 ///
 /// If the first protocol is a `/project`, look up the project's config.
@@ -4,7 +4,7 @@ mod auth;
 mod create;
 mod delete;
 mod delete_enroller;
-mod enroll;
+pub(crate) mod enroll;
 mod info;
 mod list;
 mod list_enrollers;
@@ -165,11 +165,11 @@ pub async fn check_project_readiness<'a>(
     config::set_project_id(&opts.config, &project).await?;
 
     if !project.is_ready() {
-        print!("Project created. Waiting for it be ready...");
+        eprint!("Project created. Waiting for it be ready...");
         let cloud_route = &cloud_opts.route();
         loop {
-            print!(".");
-            std::io::stdout().flush()?;
+            eprint!(".");
+            std::io::stderr().flush()?;
             tokio::time::sleep(std::time::Duration::from_secs(2)).await;
             let mut rpc = RpcBuilder::new(ctx, opts, api_node).build();
             rpc.request(api::project::show(&project.id, cloud_route))
@@ -177,26 +177,26 @@ pub async fn check_project_readiness<'a>(
             let p = rpc.parse_response::<Project>()?;
             if p.is_ready() {
                 project = p.to_owned();
-                println!();
+                eprintln!();
                 break;
             }
         }
     }
     if !project.is_reachable().await? {
-        print!("Establishing connection (this can take a few minutes)...");
+        eprint!("Establishing connection (this can take a few minutes)...");
         loop {
-            print!(".");
-            std::io::stdout().flush()?;
+            eprint!(".");
+            std::io::stderr().flush()?;
             tokio::time::sleep(std::time::Duration::from_secs(10)).await;
             if project.is_reachable().await? {
-                println!();
+                eprintln!();
                 break;
             }
         }
     }
     {
-        print!("Establishing secure channel...");
-        std::io::stdout().flush()?;
+        eprint!("Establishing secure channel...");
+        std::io::stderr().flush()?;
         let project_route = project.access_route()?;
         let project_identity = project
             .identity
@@ -220,8 +220,8 @@ pub async fn check_project_readiness<'a>(
             }
             Err(_) => {
                 loop {
-                    print!(".");
-                    std::io::stdout().flush()?;
+                    eprint!(".");
+                    std::io::stderr().flush()?;
                     tokio::time::sleep(std::time::Duration::from_secs(2)).await;
                     if let Ok(sc_addr) = create_secure_channel_to_project(
                         ctx,
@@ -241,9 +241,9 @@ pub async fn check_project_readiness<'a>(
                 }
             }
         }
-        println!();
+        eprintln!();
     }
-    std::io::stdout().flush()?;
+    std::io::stderr().flush()?;
     // Persist project config with all its fields
     config::set_project(&opts.config, &project).await?;
     Ok(project)
@@ -19,8 +19,7 @@ impl ResetCommand {
 fn run_impl(opts: CommandGlobalOpts, cmd: ResetCommand) -> crate::Result<()> {
     if cmd.yes || get_user_confirmation() {
         if let Err(e) = delete_all_nodes(opts, true) {
-            eprintln!("{}", e);
-            std::process::exit(crate::util::exitcode::IOERR);
+            crate::Error::new(crate::util::exitcode::IOERR, e).exit()
         }
     }
     Ok(())
@@ -0,0 +1,402 @@
+use std::time::Instant;
+
+use anyhow::anyhow;
+use clap::Args;
+use ockam::{Context, TcpTransport, TCP};
+use ockam_api::config::lookup::ConfigLookup;
+use ockam_api::{multiaddr_to_route, DefaultAddress};
+use ockam_core::{TransportType, LOCAL};
+use ockam_multiaddr::proto::{
+    DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Udp, Unix,
+};
+use ockam_multiaddr::{MultiAddr, ProtoValue, Protocol as _};
+
+use crate::route::HELP_DETAIL;
+use crate::util::{exitcode, node_rpc};
+use crate::{help, CommandGlobalOpts};
+
+/// Explain how a multiaddr is turned into a route, without sending anything
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, after_long_help = help::template(HELP_DETAIL))]
+pub struct ExplainCommand {
+    /// The route to explain
+    #[arg(value_name = "ROUTE")]
+    addr: MultiAddr,
+
+    /// Send a message to the echo service of each node the route goes through
+    #[arg(long)]
+    check: bool,
+
+    /// Seconds to wait for each reply, with --check
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: u64,
+}
+
+impl ExplainCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        if self.check {
+            node_rpc(rpc, (options, self))
+        } else {
+            let explanation = RouteExplanation::new(&self.addr, &options.config.lookup());
+            if let Err(e) = options
+                .print(&explanation)
+                .and_then(|_| explanation.result())
+            {
+                e.exit()
+            }
+        }
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, ExplainCommand)) -> crate::Result<()> {
+    let mut explanation = RouteExplanation::new(&cmd.addr, &opts.config.lookup());
+    if explanation.problems.is_empty() {
+        let _tcp = TcpTransport::create(&ctx).await?;
+        explanation.check(&ctx, cmd.timeout).await?;
+    }
+    opts.print(&explanation)?;
+    explanation.result()
+}
+
+/// How a multiaddr is resolved into a route
+#[derive(serde::Serialize)]
+pub struct RouteExplanation {
+    pub multiaddr: MultiAddr,
+    pub components: Vec<Component>,
+    /// The multiaddr with its nodes replaced by their addresses.  Projects
+    /// are kept, and replaced by a secure channel when the route is used.
+    pub resolved: Option<MultiAddr>,
+    pub route: Vec<Hop>,
+    pub problems: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<Check>,
+}
+
+#[derive(serde::Serialize)]
+pub struct Component {
+    pub multiaddr: String,
+    pub protocol: String,
+    pub description: String,
+    pub resolves_to: Option<String>,
+}
+
+/// An address of the resulting route
+#[derive(serde::Serialize)]
+pub struct Hop {
+    pub transport: String,
+    pub address: String,
+}
+
+/// The result of sending a message to the echo service at a hop of the route
+#[derive(serde::Serialize)]
+pub struct Check {
+    pub hop: String,
+    pub status: CheckStatus,
+    pub detail: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    NoReply,
+    Skipped,
+}
+
+impl RouteExplanation {
+    pub fn new(addr: &MultiAddr, lookup: &ConfigLookup) -> Self {
+        let protos: Vec<ProtoValue> = addr.iter().collect();
+        let mut components = Vec::with_capacity(protos.len());
+        let mut problems = vec![];
+        let mut resolved = MultiAddr::default();
+
+        for (i, p) in protos.iter().enumerate() {
+            let text = component_str(p);
+            let prev = i.checked_sub(1).map(|j| protos[j].code());
+            let next = protos.get(i + 1).map(|p| p.code());
+            let mut resolves_to = None;
+            let description = match p.code() {
+                Node::CODE => {
+                    let addr = p.cast::<Node>().and_then(|n| lookup.node_address(&n));
+                    match addr {
+                        Some(addr) => {
+                            let _ = resolved.try_extend(&addr);
+                            resolves_to = Some(addr.to_string());
+                        }
+                        None => problems.push(format!(
+                            "{text}: unknown node. Run `ockam node list` to see the local nodes"
+                        )),
+                    }
+                    "node, replaced by the address of its TCP listener".to_string()
+                }
+                Project::CODE => {
+                    let name = p
+                        .cast::<Project>()
+                        .map(|p| p.to_string())
+                        .unwrap_or_default();
+                    match lookup.get_project(&name) {
+                        Some(project) => match (&project.node_route, &project.identity_id) {
+                            (Some(route), Some(_)) => resolves_to = Some(route.to_string()),
+                            _ => problems.push(format!(
+                                "{text}: the project is not ready yet. Run `ockam project list` to refresh the projects"
+                            )),
+                        },
+                        None => problems.push(format!(
+                            "{text}: unknown project. Run `ockam project list` to refresh the projects"
+                        )),
+                    }
+                    let _ = resolved.push_back_value(p);
+                    match lookup
+                        .get_project(&name)
+                        .and_then(|p| p.identity_id.as_ref())
+                    {
+                        Some(id) => format!("project, replaced by a secure channel to its node, which must present the identity {id}"),
+                        None => "project, replaced by a secure channel to its node".to_string(),
+                    }
+                }
+                Space::CODE => {
+                    let name = p.cast::<Space>().map(|s| s.to_string()).unwrap_or_default();
+                    resolves_to = lookup.get_space(&name).map(|s| s.id.clone());
+                    problems.push(format!("{text}: a space can not be part of a route"));
+                    "space".to_string()
+                }
+                Ip4::CODE | Ip6::CODE | DnsAddr::CODE => {
+                    let _ = resolved.push_back_value(p);
+                    if next != Some(Tcp::CODE) && next != Some(Udp::CODE) {
+                        problems.push(format!("{text} must be followed by /tcp"));
+                    }
+                    if next == Some(Udp::CODE) {
+                        "host of a UDP transport address".to_string()
+                    } else {
+                        "host of a TCP transport address".to_string()
+                    }
+                }
+                Tcp::CODE => {
+                    let _ = resolved.push_back_value(p);
+                    if !matches!(prev, Some(Ip4::CODE | Ip6::CODE | DnsAddr::CODE)) {
+                        problems.push(format!("{text} must follow /ip4, /ip6 or /dnsaddr"));
+                    }
+                    "port of a TCP transport address".to_string()
+                }
+                Udp::CODE => {
+                    let _ = resolved.push_back_value(p);
+                    problems.push(format!("{text}: UDP is not supported in routes, only TCP"));
+                    "port of a UDP transport address".to_string()
+                }
+                Unix::CODE => {
+                    let _ = resolved.push_back_value(p);
+                    problems.push(format!("{text}: Unix sockets are not supported in routes"));
+                    "path of a Unix socket".to_string()
+                }
+                Service::CODE => {
+                    let _ = resolved.push_back_value(p);
+                    "worker or service, at the node the message is at".to_string()
+                }
+                Secure::CODE => {
+                    let _ = resolved.push_back_value(p);
+                    "secure channel listener, at the node the message is at".to_string()
+                }
+                _ => {
+                    let _ = resolved.push_back_value(p);
+                    problems.push(format!("{text} can not be part of a route"));
+                    "unknown".to_string()
+                }
+            };
+            components.push(Component {
+                protocol: text.split('/').nth(1).unwrap_or_default().to_string(),
+                multiaddr: text,
+                description,
+                resolves_to,
+            });
+        }
+
+        let mut route = vec![];
+        if problems.is_empty() {
+            // Projects split the route, as they are replaced by secure channels
+            let mut segment = MultiAddr::default();
+            for p in resolved.iter() {
+                if p.code() == Project::CODE {
+                    push_hops(&mut route, &mut problems, &segment);
+                    segment = MultiAddr::default();
+                    let name = p
+                        .cast::<Project>()
+                        .map(|p| p.to_string())
+                        .unwrap_or_default();
+                    let node_route = lookup
+                        .get_project(&name)
+                        .and_then(|p| p.node_route.as_ref())
+                        .map(|r| r.to_string())
+                        .unwrap_or_default();
+                    route.push(Hop {
+                        transport: "secure channel".to_string(),
+                        address: format!("to project {name} at {node_route}"),
+                    });
+                } else {
+                    let _ = segment.push_back_value(&p);
+                }
+            }
+            push_hops(&mut route, &mut problems, &segment);
+        }
+
+        Self {
+            multiaddr: addr.clone(),
+            components,
+            resolved: if problems.is_empty() {
+                Some(resolved)
+            } else {
+                None
+            },
+            route,
+            problems,
+            checks: vec![],
+        }
+    }
+
+    /// Send a message to the echo service of each node the route goes
+    /// through, up to the first secure channel
+    ///
+    /// A hop ends after each TCP transport address, and after each
+    /// service which the route goes through, such as a forwarder.
+    async fn check(&mut self, ctx: &Context, timeout: u64) -> crate::Result<()> {
+        let resolved = match &self.resolved {
+            Some(resolved) => resolved.clone(),
+            None => return Ok(()),
+        };
+        let protos: Vec<ProtoValue> = resolved.iter().collect();
+        let mut prefix = MultiAddr::default();
+        for (i, p) in protos.iter().enumerate() {
+            if p.code() == Project::CODE || p.code() == Secure::CODE {
+                self.checks.push(Check {
+                    hop: format!("{prefix}{}", component_str(p)),
+                    status: CheckStatus::Skipped,
+                    detail: Some("reaching the rest of the route needs a secure channel".into()),
+                });
+                break;
+            }
+            prefix.push_back_value(p)?;
+            let next = protos.get(i + 1).map(|p| p.code());
+            let ends_hop = match p.code() {
+                Tcp::CODE => true,
+                Service::CODE => next.is_some(),
+                _ => false,
+            };
+            if !ends_hop {
+                continue;
+            }
+            let mut echo = prefix.clone();
+            echo.push_back(Service::new(DefaultAddress::ECHO_SERVICE))?;
+            let route = multiaddr_to_route(&echo)
+                .ok_or_else(|| anyhow!("{echo} can not be turned into a route"))?;
+            let start = Instant::now();
+            let reply = ctx
+                .send_and_receive_with_timeout::<_, _, Vec<u8>>(route, b"ping".to_vec(), timeout)
+                .await;
+            let (status, detail) = match reply {
+                Ok(_) => (
+                    CheckStatus::Ok,
+                    format!("{}ms", start.elapsed().as_millis()),
+                ),
+                Err(e) => (CheckStatus::NoReply, e.to_string()),
+            };
+            self.checks.push(Check {
+                hop: prefix.to_string(),
+                status,
+                detail: Some(detail),
+            });
+        }
+        Ok(())
+    }
+
+    /// Fail when the route has problems, or when a hop did not reply
+    fn result(&self) -> crate::Result<()> {
+        if !self.problems.is_empty() {
+            return Err(crate::Error::new(
+                exitcode::DATAERR,
+                anyhow!("The route has {} problem(s)", self.problems.len()),
+            ));
+        }
+        let no_reply = self
+            .checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::NoReply)
+            .count();
+        if no_reply > 0 {
+            return Err(crate::Error::new(
+                exitcode::UNAVAILABLE,
+                anyhow!("{no_reply} hop(s) of the route did not reply"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn push_hops(route: &mut Vec<Hop>, problems: &mut Vec<String>, segment: &MultiAddr) {
+    if segment.is_empty() {
+        return;
+    }
+    match multiaddr_to_route(segment) {
+        Some(r) => route.extend(r.iter().map(|a| Hop {
+            transport: transport_name(a.transport_type()),
+            address: a.address().to_string(),
+        })),
+        None => problems.push(format!("{segment} can not be turned into a route")),
+    }
+}
+
+fn transport_name(t: TransportType) -> String {
+    match t {
+        TCP => "tcp".to_string(),
+        LOCAL => "local".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// A single protocol value, as a multiaddr string
+fn component_str(p: &ProtoValue) -> String {
+    let mut m = MultiAddr::default();
+    match m.push_back_value(p) {
+        Ok(()) => m.to_string(),
+        Err(_) => format!("/{}", p.code()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_api::config::lookup::InternetAddress;
+    use std::str::FromStr;
+
+    fn explain(addr: &str) -> RouteExplanation {
+        let mut lookup = ConfigLookup::new();
+        lookup.set_node("n1", InternetAddress::new("127.0.0.1:4000").unwrap());
+        RouteExplanation::new(&MultiAddr::from_str(addr).unwrap(), &lookup)
+    }
+
+    #[test]
+    fn nodes_are_resolved() {
+        let e = explain("/node/n1/service/uppercase");
+        assert!(e.problems.is_empty());
+        assert_eq!(
+            e.resolved.unwrap().to_string(),
+            "/ip4/127.0.0.1/tcp/4000/service/uppercase"
+        );
+        let route: Vec<_> = e
+            .route
+            .iter()
+            .map(|h| format!("{} {}", h.transport, h.address))
+            .collect();
+        assert_eq!(route, ["tcp 127.0.0.1:4000", "local uppercase"]);
+    }
+
+    #[test]
+    fn unsupported_combinations_are_flagged() {
+        assert_eq!(explain("/ip4/127.0.0.1/service/echo").problems.len(), 1);
+        assert_eq!(explain("/ip4/127.0.0.1/udp/53").problems.len(), 1);
+        assert_eq!(explain("/service/a/tcp/4000").problems.len(), 1);
+        assert_eq!(explain("/node/n2/service/echo").problems.len(), 1);
+        assert_eq!(explain("/project/p/service/echo").problems.len(), 1);
+        let e = explain("/unix/tmp/ockam.sock");
+        assert_eq!(e.problems.len(), 1);
+        assert!(e.resolved.is_none() && e.route.is_empty());
+    }
+}
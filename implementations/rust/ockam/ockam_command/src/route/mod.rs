@@ -0,0 +1,54 @@
+mod explain;
+
+use clap::{Args, Subcommand};
+
+pub use explain::{CheckStatus, ExplainCommand, RouteExplanation};
+
+use crate::{help, CommandGlobalOpts};
+
+const HELP_DETAIL: &str = "\
+About:
+    Routes are written as multiaddrs, such as /node/n1/service/uppercase, and
+    turned into Ockam routes before messages are sent along them.
+
+    Nodes are replaced by the address of their TCP listener, and projects by a
+    secure channel to the project node, as found in the configuration of the
+    CLI. Consecutive /ip4, /ip6 or /dnsaddr and /tcp components become one TCP
+    transport address, and /service and /secure components become local
+    addresses of the node the message is at.
+
+Examples:
+
+```sh
+    # Show how a route to the uppercase service of the node n1 is resolved
+    $ ockam route explain /node/n1/service/uppercase
+
+    # Also send a message to the echo service of each node the route goes through
+    $ ockam route explain /node/relay/service/forward_to_n1/service/uppercase --check
+```
+";
+
+/// Inspect routes
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    after_long_help = help::template(HELP_DETAIL)
+)]
+pub struct RouteCommand {
+    #[command(subcommand)]
+    subcommand: RouteSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum RouteSubcommand {
+    Explain(ExplainCommand),
+}
+
+impl RouteCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        match self.subcommand {
+            RouteSubcommand::Explain(c) => c.run(options),
+        }
+    }
+}
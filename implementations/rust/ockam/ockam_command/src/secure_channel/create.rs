@@ -1,6 +1,6 @@
 use crate::{
     help,
-    util::{api, exitcode, extract_address_value, node_rpc},
+    util::{api, exitcode, extract_address_value, node_rpc, output::serialize},
     CommandGlobalOpts, OutputFormat, Result,
 };
 
@@ -98,10 +98,14 @@ impl CreateCommand {
                     println!("{}", multiaddr)
                 }
 
-                // if output format is json, write json to stdout.
-                if options.global_args.output_format == OutputFormat::Json {
-                    let json = json!([{ "address": multiaddr.to_string() }]);
-                    println!("{}", json);
+                // if output format is json or yaml, write it to stdout.
+                let format = options.global_args.output_format;
+                if format != OutputFormat::Plain {
+                    let out = json!([{ "address": multiaddr.to_string() }]);
+                    match serialize(&out, format) {
+                        Ok(s) => println!("{}", s),
+                        Err(e) => eprintln!("{}", e),
+                    }
                 }
 
                 // if stderr is interactive/tty and we haven't been asked to be quiet
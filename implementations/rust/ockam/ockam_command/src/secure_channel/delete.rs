@@ -1,7 +1,7 @@
 use crate::secure_channel::HELP_DETAIL;
 use crate::{
     help,
-    util::{api, exitcode, extract_address_value, node_rpc, Rpc},
+    util::{api, exitcode, extract_address_value, node_rpc, output::serialize, Rpc},
     CommandGlobalOpts, OutputFormat, Result,
 };
 use std::str::FromStr;
@@ -56,10 +56,14 @@ impl DeleteCommand {
                             println!("{}", multiaddr)
                         }
 
-                        // if output format is json, write json to stdout.
-                        if options.global_args.output_format == OutputFormat::Json {
-                            let json = json!([{ "address": multiaddr.to_string() }]);
-                            println!("{}", json);
+                        // if output format is json or yaml, write it to stdout.
+                        let format = options.global_args.output_format;
+                        if format != OutputFormat::Plain {
+                            let out = json!([{ "address": multiaddr.to_string() }]);
+                            match serialize(&out, format) {
+                                Ok(s) => println!("{}", s),
+                                Err(e) => eprintln!("{}", e),
+                            }
                         }
 
                         // if stderr is interactive/tty and we haven't been asked to be quiet
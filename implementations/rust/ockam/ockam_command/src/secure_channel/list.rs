@@ -13,7 +13,7 @@ use crate::secure_channel::HELP_DETAIL;
 use crate::util::RpcBuilder;
 use crate::{
     exitcode, help,
-    util::{api, node_rpc},
+    util::{api, node_rpc, output::serialize},
     CommandGlobalOpts, OutputFormat,
 };
 
@@ -82,10 +82,11 @@ impl ListCommand {
                 println!("{}", at)
             }
 
-            // if output format is json, write json to stdout.
-            if options.global_args.output_format == OutputFormat::Json {
-                let json = json!([{ "address": at }]);
-                println!("{}", json);
+            // if output format is json or yaml, write it to stdout.
+            let format = options.global_args.output_format;
+            if format != OutputFormat::Plain {
+                let out = json!([{ "address": at }]);
+                println!("{}", serialize(&out, format).map_err(|e| e.to_string())?);
             }
 
             // if stderr is interactive/tty and we haven't been asked to be quiet
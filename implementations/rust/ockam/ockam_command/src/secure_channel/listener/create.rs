@@ -56,7 +56,9 @@ async fn run_impl(
     rpc.request(req).await?;
     match rpc.is_ok() {
         Ok(_) => {
-            println!("/service/{}", cmd.address.address());
+            opts.print(&CreatedSecureChannelListener {
+                address: format!("/service/{}", cmd.address.address()),
+            })?;
             Ok(())
         }
         Err(e) => Err(crate::error::Error::new(
@@ -71,7 +73,7 @@ pub async fn create_listener(
     addr: Address,
     authorized_identifiers: Option<Vec<IdentityIdentifier>>,
     mut base_route: Route,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<CreatedSecureChannelListener> {
     let resp: Vec<u8> = ctx
         .send_and_receive(
             base_route.modify().append(NODEMANAGER_ADDR),
@@ -82,13 +84,19 @@ pub async fn create_listener(
     let response = api::parse_create_secure_channel_listener_response(&resp)?;
 
     match response.status() {
-        Some(Status::Ok) => {
-            println!("/service/{}", addr.address());
-            Ok(())
-        }
+        Some(Status::Ok) => Ok(CreatedSecureChannelListener {
+            address: format!("/service/{}", addr.address()),
+        }),
         _ => {
             eprintln!("An error occurred while creating secure channel listener",);
             std::process::exit(exitcode::CANTCREAT)
         }
     }
 }
+
+/// The listener created by [`CreateCommand`]
+#[derive(serde::Serialize)]
+pub struct CreatedSecureChannelListener {
+    /// Address of the listener, as a multiaddr
+    pub address: String,
+}
@@ -35,14 +35,17 @@ async fn run_impl(
     let mut rpc = Rpc::background(ctx, &opts, &cmd.node_opts.api_node)?;
     rpc.request(api::list_secure_channel_listener()).await?;
     let res = rpc.parse_response::<Vec<String>>()?;
-
-    println!(
-        "Secure channel listeners for node `{}`:",
-        &cmd.node_opts.api_node
-    );
-    for addr in res {
-        println!("  {}", addr);
-    }
-
+    opts.print(&SecureChannelListeners {
+        node: cmd.node_opts.api_node,
+        listeners: res,
+    })?;
     Ok(())
 }
+
+/// The secure channel listeners of a node
+#[derive(serde::Serialize)]
+pub struct SecureChannelListeners {
+    pub node: String,
+    /// Addresses of the listeners
+    pub listeners: Vec<String>,
+}
@@ -2,6 +2,7 @@ use anyhow::{anyhow, Context, Result};
 use ockam::identity::IdentityIdentifier;
 use ockam_api::DefaultAddress;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +12,9 @@ pub struct VaultConfig {
 
     #[serde(default)]
     pub(crate) disabled: bool,
+
+    #[serde(default)]
+    pub(crate) deny_export: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +47,15 @@ pub struct VerifierConfig {
     pub(crate) disabled: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreKeyConfig {
+    #[serde(default = "prekeys_default_addr")]
+    pub(crate) address: String,
+
+    #[serde(default)]
+    pub(crate) disabled: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthenticatorConfig {
     #[serde(default = "authenticator_default_addr")]
@@ -73,14 +86,38 @@ pub struct OktaIdentityProviderConfig {
     pub(crate) disabled: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcIdentityProviderConfig {
+    #[serde(default = "oidc_identity_provider_default_addr")]
+    pub(crate) address: String,
+
+    /// Issuer URL, where the discovery document is found
+    pub(crate) issuer: String,
+
+    /// Client ID the ID tokens must be issued to
+    pub(crate) audience: String,
+
+    /// ID token claim names mapped to attribute names
+    pub(crate) claims: BTreeMap<String, String>,
+
+    /// Only root certificate trusted to connect to the issuer, if any
+    #[serde(default)]
+    pub(crate) certificate: Option<String>,
+
+    #[serde(default)]
+    pub(crate) disabled: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceConfigs {
     pub(crate) vault: Option<VaultConfig>,
     pub(crate) identity: Option<IdentityConfig>,
     pub(crate) secure_channel_listener: Option<SecureChannelListenerConfig>,
     pub(crate) verifier: Option<VerifierConfig>,
+    pub(crate) prekeys: Option<PreKeyConfig>,
     pub(crate) authenticator: Option<AuthenticatorConfig>,
     pub(crate) okta_identity_provider: Option<OktaIdentityProviderConfig>,
+    pub(crate) oidc_identity_provider: Option<OidcIdentityProviderConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -112,6 +149,10 @@ fn verifier_default_addr() -> String {
     DefaultAddress::VERIFIER.to_string()
 }
 
+fn prekeys_default_addr() -> String {
+    DefaultAddress::PREKEY_SERVICE.to_string()
+}
+
 fn authenticator_default_addr() -> String {
     DefaultAddress::AUTHENTICATOR.to_string()
 }
@@ -119,3 +160,7 @@ fn authenticator_default_addr() -> String {
 fn okta_identity_provider_default_addr() -> String {
     DefaultAddress::OKTA_IDENTITY_PROVIDER.to_string()
 }
+
+fn oidc_identity_provider_default_addr() -> String {
+    DefaultAddress::OIDC_IDENTITY_PROVIDER.to_string()
+}
@@ -1,12 +1,14 @@
 use crate::node::NodeOpts;
-use crate::service::config::OktaIdentityProviderConfig;
+use crate::service::config::{OidcIdentityProviderConfig, OktaIdentityProviderConfig};
 use crate::util::{api, node_rpc, RpcBuilder};
 use crate::CommandGlobalOpts;
 use anyhow::{anyhow, Result};
 use clap::{Args, Subcommand};
 use minicbor::Encode;
 use ockam::{Context, TcpTransport};
-use ockam_api::nodes::models::services::StartOktaIdentityProviderRequest;
+use ockam_api::nodes::models::services::{
+    StartOidcIdentityProviderRequest, StartOktaIdentityProviderRequest,
+};
 use ockam_api::DefaultAddress;
 use ockam_core::api::{Request, RequestBuilder, Status};
 use std::path::{Path, PathBuf};
@@ -25,6 +27,10 @@ pub enum StartSubCommand {
     Vault {
         #[arg(default_value_t = vault_default_addr())]
         addr: String,
+
+        /// Refuse requests to export secret keys
+        #[arg(long)]
+        deny_export: bool,
     },
     Identity {
         #[arg(default_value_t = identity_default_addr())]
@@ -38,6 +44,11 @@ pub enum StartSubCommand {
         #[arg(long, default_value_t = verifier_default_addr())]
         addr: String,
     },
+    /// Store prekeys and messages for offline X3DH sessions
+    Prekeys {
+        #[arg(long, default_value_t = prekeys_default_addr())]
+        addr: String,
+    },
     Credentials {
         #[arg(long, default_value_t = credentials_default_addr())]
         addr: String,
@@ -73,6 +84,10 @@ fn verifier_default_addr() -> String {
     DefaultAddress::VERIFIER.to_string()
 }
 
+fn prekeys_default_addr() -> String {
+    DefaultAddress::PREKEY_SERVICE.to_string()
+}
+
 fn credentials_default_addr() -> String {
     DefaultAddress::CREDENTIAL_SERVICE.to_string()
 }
@@ -101,12 +116,16 @@ async fn run_impl(
 ) -> crate::Result<()> {
     let node_name = &cmd.node_opts.api_node;
     let tcp = TcpTransport::create(ctx).await?;
-    match cmd.create_subcommand {
-        StartSubCommand::Vault { addr, .. } => {
-            start_vault_service(ctx, &opts, node_name, &addr, Some(&tcp)).await?
+    let (service, address) = match cmd.create_subcommand {
+        StartSubCommand::Vault {
+            addr, deny_export, ..
+        } => {
+            start_vault_service(ctx, &opts, node_name, &addr, deny_export, Some(&tcp)).await?;
+            ("Vault", addr)
         }
         StartSubCommand::Identity { addr, .. } => {
-            start_identity_service(ctx, &opts, node_name, &addr, Some(&tcp)).await?
+            start_identity_service(ctx, &opts, node_name, &addr, Some(&tcp)).await?;
+            ("Identity", addr)
         }
         StartSubCommand::Authenticated { addr, .. } => {
             let req = api::start_authenticated_service(&addr);
@@ -119,14 +138,22 @@ async fn run_impl(
                 req,
                 Some(&tcp),
             )
-            .await?
+            .await?;
+            ("Authenticated", addr)
         }
         StartSubCommand::Verifier { addr, .. } => {
-            start_verifier_service(ctx, &opts, node_name, &addr, Some(&tcp)).await?
+            start_verifier_service(ctx, &opts, node_name, &addr, Some(&tcp)).await?;
+            ("Verifier", addr)
+        }
+        StartSubCommand::Prekeys { addr, .. } => {
+            start_prekey_service(ctx, &opts, node_name, &addr, Some(&tcp)).await?;
+            ("PreKey", addr)
         }
         StartSubCommand::Credentials { addr, oneway, .. } => {
             let req = api::start_credentials_service(&addr, oneway);
-            start_service_impl(ctx, &opts, node_name, &addr, "Credentials", req, Some(&tcp)).await?
+            start_service_impl(ctx, &opts, node_name, &addr, "Credentials", req, Some(&tcp))
+                .await?;
+            ("Credentials", addr)
         }
         StartSubCommand::Authenticator {
             addr,
@@ -143,11 +170,24 @@ async fn run_impl(
                 &project,
                 Some(&tcp),
             )
-            .await?
+            .await?;
+            ("Authenticator", addr)
         }
-    }
+    };
+
+    opts.print(&StartedService {
+        node: node_name.to_string(),
+        service,
+        address,
+    })
+}
 
-    Ok(())
+/// The service started by [`StartCommand`]
+#[derive(serde::Serialize)]
+pub struct StartedService {
+    pub node: String,
+    pub service: &'static str,
+    pub address: String,
 }
 
 /// Helper function.
@@ -168,14 +208,11 @@ where
 
     let (res, dec) = rpc.check_response()?;
     match res.status() {
-        Some(Status::Ok) => {
-            println!("{serv_name} service started at address: {serv_addr}");
-            Ok(())
-        }
-        _ => {
-            eprintln!("{}", rpc.parse_err_msg(res, dec));
-            Err(anyhow!("Failed to start {serv_name} service"))
-        }
+        Some(Status::Ok) => Ok(()),
+        _ => Err(anyhow!(
+            "Failed to start {serv_name} service at address {serv_addr}: {}",
+            rpc.parse_err_msg(res, dec)
+        )),
     }
 }
 
@@ -185,9 +222,10 @@ pub async fn start_vault_service(
     opts: &CommandGlobalOpts,
     node_name: &str,
     serv_addr: &str,
+    deny_export: bool,
     tcp: Option<&'_ TcpTransport>,
 ) -> Result<()> {
-    let req = api::start_vault_service(serv_addr);
+    let req = api::start_vault_service(serv_addr, deny_export);
     start_service_impl(ctx, opts, node_name, serv_addr, "Vault", req, tcp).await
 }
 
@@ -215,6 +253,18 @@ pub async fn start_verifier_service(
     start_service_impl(ctx, opts, node_name, serv_addr, "Verifier", req, tcp).await
 }
 
+/// Public so `ockam_command::node::create` can use it.
+pub async fn start_prekey_service(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    serv_addr: &str,
+    tcp: Option<&'_ TcpTransport>,
+) -> Result<()> {
+    let req = api::start_prekey_service(serv_addr);
+    start_service_impl(ctx, opts, node_name, serv_addr, "PreKey", req, tcp).await
+}
+
 /// Public so `ockam_command::node::create` can use it.
 pub async fn start_authenticator_service(
     ctx: &Context,
@@ -256,3 +306,34 @@ pub async fn start_okta_identity_provider(
     )
     .await
 }
+
+/// Public so `ockam_command::node::create` can use it.
+pub async fn start_oidc_identity_provider(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    cfg: &OidcIdentityProviderConfig,
+    tcp: Option<&'_ TcpTransport>,
+) -> Result<()> {
+    let payload = StartOidcIdentityProviderRequest::new(
+        &cfg.address,
+        &cfg.issuer,
+        &cfg.audience,
+        cfg.claims
+            .iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect(),
+        cfg.certificate.as_deref().map(Into::into),
+    );
+    let req = Request::post("/node/services/oidc_identity_provider").body(payload);
+    start_service_impl(
+        ctx,
+        opts,
+        node_name,
+        &cfg.address,
+        "OIDC Identity Provider",
+        req,
+        tcp,
+    )
+    .await
+}
@@ -26,12 +26,12 @@ pub struct CreateCommand {
 
 impl CreateCommand {
     pub fn run(self, options: CommandGlobalOpts) {
-        println!(
+        eprintln!(
             "\n{}",
             "Creating a trial space for you (everything in it will be deleted in 15 days) ..."
                 .light_magenta()
         );
-        println!(
+        eprintln!(
             "{}",
             "To learn more about production ready spaces in Ockam Orchestrator, contact us at: hello@ockam.io".light_magenta()
         );
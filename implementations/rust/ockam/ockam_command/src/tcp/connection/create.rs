@@ -7,7 +7,6 @@ use clap::Args;
 use colorful::Colorful;
 use ockam::{route, Route, TCP};
 use ockam_api::{nodes::models, route_to_multiaddr};
-use serde_json::json;
 use std::net::SocketAddrV4;
 
 #[derive(Clone, Debug, Args)]
@@ -64,7 +63,7 @@ impl CreateCommand {
                     );
                 }
             }
-            OutputFormat::Json => {
+            OutputFormat::Json | OutputFormat::Yaml => {
                 let port = options.config.get_node_port(node_name)?;
                 let route: Route = route![(TCP, format!("localhost:{}", port))]
                     .modify()
@@ -72,14 +71,28 @@ impl CreateCommand {
                     .into();
                 let multiaddr = route_to_multiaddr(&route)
                     .context("Couldn't convert given address into `MultiAddr`")?;
-                let json = json!([{"route": multiaddr.to_string() }]);
-                println!("{}", json);
+                options.print(&CreatedTcpConnection {
+                    from: format!("/node/{}", self.node_opts.from),
+                    to: response.payload.to_string(),
+                    route: multiaddr.to_string(),
+                })?;
             }
         }
         Ok(())
     }
 }
 
+/// The connection created by [`CreateCommand`]
+#[derive(serde::Serialize)]
+pub struct CreatedTcpConnection {
+    /// The node that initiated the connection
+    pub from: String,
+    /// The socket address the node connected to
+    pub to: String,
+    /// The route to send messages through the connection
+    pub route: String,
+}
+
 async fn run_impl(
     ctx: ockam::Context,
     (options, command): (CommandGlobalOpts, CreateCommand),
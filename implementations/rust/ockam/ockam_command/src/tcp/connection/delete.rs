@@ -1,3 +1,4 @@
+use crate::util::output::Deleted;
 use crate::util::{extract_address_value, node_rpc, Rpc};
 use crate::{node::NodeOpts, CommandGlobalOpts};
 use clap::Args;
@@ -32,6 +33,5 @@ async fn run_impl(
         .body(models::transport::DeleteTransport::new(&cmd.id, cmd.force));
     rpc.request(req).await?;
     rpc.is_ok()?;
-    println!("Tcp connection `{}` successfully deleted", cmd.id);
-    Ok(())
+    opts.print(&Deleted::new("Tcp connection", cmd.id))
 }
@@ -121,7 +121,7 @@ async fn rpc(ctx: Context, (opts, mut cmd): (CommandGlobalOpts, CreateCommand))
 
     let mut rpc = RpcBuilder::new(&ctx, &opts, &node).tcp(&tcp)?.build();
     rpc.request(req).await?;
-    rpc.parse_response::<InletStatus>()?;
+    rpc.parse_and_print_response::<InletStatus>()?;
 
     Ok(())
 }
@@ -0,0 +1,29 @@
+use clap::Args;
+use ockam::Context;
+use ockam_api::nodes::models::portal::InletList;
+
+use crate::node::NodeOpts;
+use crate::util::{api, extract_address_value, node_rpc, Rpc};
+use crate::CommandGlobalOpts;
+
+/// List TCP Inlets
+#[derive(Clone, Debug, Args)]
+pub struct ListCommand {
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+impl ListCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, ListCommand)) -> crate::Result<()> {
+    let node = extract_address_value(&cmd.node_opts.api_node)?;
+    let mut rpc = Rpc::background(&ctx, &opts, &node)?;
+    rpc.request(api::list_inlets()).await?;
+    let res = rpc.parse_response::<InletList>()?;
+    rpc.print_response(res.list)?;
+    Ok(())
+}
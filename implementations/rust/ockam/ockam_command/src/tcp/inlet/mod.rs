@@ -1,8 +1,14 @@
 mod create;
+mod delete;
+mod list;
+mod show;
 
 use crate::CommandGlobalOpts;
 use clap::{Args, Subcommand};
 use create::CreateCommand;
+use delete::DeleteCommand;
+use list::ListCommand;
+use show::ShowCommand;
 
 /// Manage TCP Inlets
 #[derive(Clone, Debug, Args)]
@@ -14,12 +20,18 @@ pub struct TcpInletCommand {
 #[derive(Clone, Debug, Subcommand)]
 pub enum TcpInletSubCommand {
     Create(CreateCommand),
+    Delete(DeleteCommand),
+    List(ListCommand),
+    Show(ShowCommand),
 }
 
 impl TcpInletCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
             TcpInletSubCommand::Create(c) => c.run(options),
+            TcpInletSubCommand::Delete(c) => c.run(options),
+            TcpInletSubCommand::List(c) => c.run(options),
+            TcpInletSubCommand::Show(c) => c.run(options),
         }
     }
 }
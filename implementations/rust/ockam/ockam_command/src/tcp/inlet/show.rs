@@ -0,0 +1,32 @@
+use clap::Args;
+use ockam::Context;
+use ockam_api::nodes::models::portal::InletStatus;
+
+use crate::node::NodeOpts;
+use crate::util::{api, extract_address_value, node_rpc, Rpc};
+use crate::CommandGlobalOpts;
+
+/// Show a TCP Inlet
+#[derive(Clone, Debug, Args)]
+pub struct ShowCommand {
+    #[command(flatten)]
+    node_opts: NodeOpts,
+
+    /// Alias of the inlet
+    pub alias: String,
+}
+
+impl ShowCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, ShowCommand)) -> crate::Result<()> {
+    let node = extract_address_value(&cmd.node_opts.api_node)?;
+    let mut rpc = Rpc::background(&ctx, &opts, &node)?;
+    rpc.request(api::show_inlet(&cmd.alias)).await?;
+    let res = rpc.parse_response::<InletStatus>()?;
+    rpc.print_response(res)?;
+    Ok(())
+}
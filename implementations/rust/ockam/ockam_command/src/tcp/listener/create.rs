@@ -47,7 +47,21 @@ async fn run_impl(
         .into();
     let multiaddr =
         route_to_multiaddr(&r).context("Couldn't convert given address into `MultiAddr`")?;
-    println!("Tcp listener created! You can send messages to it via this route:\n`{multiaddr}`",);
+    opts.print(&CreatedTcpListener {
+        id: response.tid.to_string(),
+        address: response.payload.to_string(),
+        route: multiaddr.to_string(),
+    })?;
 
     Ok(())
 }
+
+/// The listener created by [`CreateCommand`]
+#[derive(serde::Serialize)]
+pub struct CreatedTcpListener {
+    pub id: String,
+    /// The socket address the listener is bound to
+    pub address: String,
+    /// The route to send messages to the listener
+    pub route: String,
+}
@@ -6,6 +6,7 @@ use ockam::Context;
 use ockam_api::nodes::models;
 use ockam_core::api::Request;
 
+use crate::util::output::Deleted;
 use crate::util::{node_rpc, Rpc};
 use crate::{exitcode, node::NodeOpts, CommandGlobalOpts};
 
@@ -38,8 +39,7 @@ async fn run_impl(
         .body(models::transport::DeleteTransport::new(&cmd.id, cmd.force));
     rpc.request(req).await?;
     if rpc.parse_response::<Vec<u8>>().is_ok() {
-        println!("Tcp listener `{}` successfully deleted", cmd.id);
-        Ok(())
+        opts.print(&Deleted::new("Tcp listener", cmd.id))
     } else {
         let mut msg = "Failed to delete tcp listener".to_string();
         if !cmd.force {
@@ -94,15 +94,29 @@ pub async fn run_impl(
     };
 
     rpc.request(make_api_request(cmd)?).await?;
-    let OutletStatus { worker_addr, .. } = rpc.parse_response()?;
+    let outlet: OutletStatus = rpc.parse_response()?;
 
-    let addr = route_to_multiaddr(&route![worker_addr.to_string()])
+    let addr = route_to_multiaddr(&route![outlet.worker_addr.to_string()])
         .ok_or_else(|| ApiError::generic("Invalid Outlet Address"))?;
-    println!("{}", addr);
+    options.print(&CreatedOutlet {
+        alias: outlet.alias.to_string(),
+        address: addr.to_string(),
+        to: outlet.tcp_addr.to_string(),
+    })?;
 
     Ok(())
 }
 
+/// The outlet created by [`CreateCommand`]
+#[derive(serde::Serialize)]
+pub struct CreatedOutlet {
+    pub alias: String,
+    /// Address of the outlet worker, as a multiaddr
+    pub address: String,
+    /// Address the outlet forwards to
+    pub to: String,
+}
+
 /// Construct a request to create a tcp outlet
 fn make_api_request<'a>(cmd: CreateCommand) -> crate::Result<RequestBuilder<'a, CreateOutlet<'a>>> {
     let tcp_addr = cmd.to.to_string();
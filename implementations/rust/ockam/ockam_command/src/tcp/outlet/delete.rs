@@ -0,0 +1,31 @@
+use clap::Args;
+use ockam::Context;
+
+use crate::node::NodeOpts;
+use crate::util::output::Deleted;
+use crate::util::{api, extract_address_value, node_rpc, Rpc};
+use crate::CommandGlobalOpts;
+
+/// Delete a TCP Outlet
+#[derive(Clone, Debug, Args)]
+pub struct DeleteCommand {
+    #[command(flatten)]
+    node_opts: NodeOpts,
+
+    /// Alias of the outlet
+    pub alias: String,
+}
+
+impl DeleteCommand {
+    pub fn run(self, opts: CommandGlobalOpts) {
+        node_rpc(rpc, (opts, self));
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, DeleteCommand)) -> crate::Result<()> {
+    let node = extract_address_value(&cmd.node_opts.api_node)?;
+    let mut rpc = Rpc::background(&ctx, &opts, &node)?;
+    rpc.request(api::delete_outlet(&cmd.alias)).await?;
+    rpc.is_ok()?;
+    opts.print(&Deleted::new("Tcp outlet", cmd.alias))
+}
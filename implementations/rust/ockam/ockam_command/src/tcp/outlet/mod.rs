@@ -1,8 +1,14 @@
-mod create;
+pub(crate) mod create;
+mod delete;
+mod list;
+mod show;
 
 use crate::CommandGlobalOpts;
 use clap::{Args, Subcommand};
 use create::CreateCommand;
+use delete::DeleteCommand;
+use list::ListCommand;
+use show::ShowCommand;
 
 /// Manage TCP Outlets
 #[derive(Clone, Debug, Args)]
@@ -14,12 +20,18 @@ pub struct TcpOutletCommand {
 #[derive(Clone, Debug, Subcommand)]
 pub enum TcpOutletSubCommand {
     Create(CreateCommand),
+    Delete(DeleteCommand),
+    List(ListCommand),
+    Show(ShowCommand),
 }
 
 impl TcpOutletCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
             TcpOutletSubCommand::Create(c) => c.run(options),
+            TcpOutletSubCommand::Delete(c) => c.run(options),
+            TcpOutletSubCommand::List(c) => c.run(options),
+            TcpOutletSubCommand::Show(c) => c.run(options),
         }
     }
 }
@@ -10,7 +10,8 @@ use clap::Args;
 use minicbor::Decoder;
 use ockam_api::nodes::models::services::{
     StartAuthenticatedServiceRequest, StartAuthenticatorRequest, StartCredentialsService,
-    StartIdentityServiceRequest, StartVaultServiceRequest, StartVerifierService,
+    StartIdentityServiceRequest, StartPreKeyService, StartVaultServiceRequest,
+    StartVerifierService,
 };
 use tracing::trace;
 
@@ -33,6 +34,16 @@ pub(crate) fn query_status() -> RequestBuilder<'static, ()> {
     Request::get("/node")
 }
 
+/// Construct a request to query the worker graph of a node
+pub(crate) fn node_topology() -> RequestBuilder<'static, ()> {
+    Request::get("/node/topology")
+}
+
+/// Construct a request to drain and stop a node
+pub(crate) fn drain_node(timeout: u8) -> RequestBuilder<'static, models::base::DrainNode> {
+    Request::post("/node/actions/drain").body(models::base::DrainNode::new(timeout))
+}
+
 /// Construct a request to query node tcp listeners
 pub(crate) fn list_tcp_listeners() -> RequestBuilder<'static, ()> {
     Request::get("/node/tcp/listener")
@@ -67,11 +78,64 @@ pub(crate) fn list_inlets() -> RequestBuilder<'static, ()> {
     Request::get("/node/inlet")
 }
 
+/// Construct a request to show an inlet of the given node
+pub(crate) fn show_inlet(alias: &str) -> RequestBuilder<'static, ()> {
+    Request::get(format!("/node/inlet/{alias}"))
+}
+
+/// Construct a request to delete an inlet of the given node
+pub(crate) fn delete_inlet(alias: &str) -> RequestBuilder<'static, ()> {
+    Request::delete(format!("/node/inlet/{alias}"))
+}
+
 /// Construct a request to print a list of outlets for the given node
 pub(crate) fn list_outlets() -> RequestBuilder<'static, ()> {
     Request::get("/node/outlet")
 }
 
+/// Construct a request to show an outlet of the given node
+pub(crate) fn show_outlet(alias: &str) -> RequestBuilder<'static, ()> {
+    Request::get(format!("/node/outlet/{alias}"))
+}
+
+/// Construct a request to delete an outlet of the given node
+pub(crate) fn delete_outlet(alias: &str) -> RequestBuilder<'static, ()> {
+    Request::delete(format!("/node/outlet/{alias}"))
+}
+
+/// Construct a request to print a list of forwarders for the given node
+pub(crate) fn list_forwarders() -> RequestBuilder<'static, ()> {
+    Request::get("/node/forwarder")
+}
+
+/// Construct a request to show a forwarder of the given node
+pub(crate) fn show_forwarder(remote_address: &str) -> RequestBuilder<'static, ()> {
+    Request::get(format!("/node/forwarder/{remote_address}"))
+}
+
+/// Construct a request to delete a forwarder of the given node
+pub(crate) fn delete_forwarder(remote_address: &str) -> RequestBuilder<'static, ()> {
+    Request::delete(format!("/node/forwarder/{remote_address}"))
+}
+
+/// Construct a request to list the identities allowed to use the node API
+pub(crate) fn list_api_users() -> RequestBuilder<'static, ()> {
+    Request::get("/node/access")
+}
+
+/// Construct a request to allow an identity to use the node API
+pub(crate) fn add_api_user(
+    identity: IdentityIdentifier,
+    role: models::access::ApiRole,
+) -> RequestBuilder<'static, models::access::ApiUser> {
+    Request::post("/node/access").body(models::access::ApiUser::new(identity, role))
+}
+
+/// Construct a request to stop allowing an identity to use the node API
+pub(crate) fn remove_api_user(identity: &IdentityIdentifier) -> RequestBuilder<'static, ()> {
+    Request::delete(format!("/node/access/{identity}"))
+}
+
 /// Construct a request builder to list all secure channels on the given node
 pub(crate) fn list_secure_channels() -> RequestBuilder<'static, ()> {
     Request::get("/node/secure_channel")
@@ -128,8 +192,11 @@ pub(crate) fn list_secure_channel_listener() -> RequestBuilder<'static, ()> {
 }
 
 /// Construct a request to start a Vault Service
-pub(crate) fn start_vault_service(addr: &str) -> RequestBuilder<'static, StartVaultServiceRequest> {
-    let payload = StartVaultServiceRequest::new(addr);
+pub(crate) fn start_vault_service(
+    addr: &str,
+    deny_export: bool,
+) -> RequestBuilder<'static, StartVaultServiceRequest> {
+    let payload = StartVaultServiceRequest::new(addr, deny_export);
     Request::post("/node/services/vault").body(payload)
 }
 
@@ -149,6 +216,12 @@ pub(crate) fn start_authenticated_service(
     Request::post("/node/services/authenticated").body(payload)
 }
 
+/// Construct a request to start a PreKey Service
+pub(crate) fn start_prekey_service(addr: &str) -> RequestBuilder<'static, StartPreKeyService> {
+    let payload = StartPreKeyService::new(addr);
+    Request::post("/node/services/prekeys").body(payload)
+}
+
 /// Construct a request to start a Verifier Service
 pub(crate) fn start_verifier_service(addr: &str) -> RequestBuilder<'static, StartVerifierService> {
     let payload = StartVerifierService::new(addr);
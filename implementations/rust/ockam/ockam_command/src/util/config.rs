@@ -40,6 +40,10 @@ pub enum ConfigError {
     NotFound(String),
     #[error("node with name {0} is not local")]
     NotLocal(String),
+    #[error("portal with name {0} already exists")]
+    PortalAlreadyExists(String),
+    #[error("portal with name {0} does not exist")]
+    PortalNotFound(String),
 }
 
 impl OckamConfig {
@@ -168,6 +172,16 @@ impl OckamConfig {
         self.inner().lookup().clone()
     }
 
+    /// Get a portal created with `ockam portal create`
+    pub fn get_portal(&self, name: &str) -> Result<cli::PortalConfig> {
+        let inner = self.inner.read();
+        inner
+            .portals
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ConfigError::PortalNotFound(name.into()).into())
+    }
+
     pub fn authorities(&self, node: &str) -> Result<AuthoritiesConfig> {
         let path = self.get_node_dir_unchecked(node);
         AuthoritiesConfig::load(path)
@@ -300,6 +314,21 @@ impl OckamConfig {
         let inner = self.inner.read();
         inner.default.clone()
     }
+
+    /// Record a new portal, failing if there is one with the same name
+    pub fn add_portal(&self, name: &str, portal: cli::PortalConfig) -> Result<()> {
+        let mut inner = self.inner.write();
+        if inner.portals.contains_key(name) {
+            return Err(ConfigError::PortalAlreadyExists(name.to_string()).into());
+        }
+        inner.portals.insert(name.to_string(), portal);
+        Ok(())
+    }
+
+    pub fn remove_portal(&self, name: &str) {
+        let mut inner = self.inner.write();
+        inner.portals.remove(name);
+    }
 }
 
 #[derive(Debug)]
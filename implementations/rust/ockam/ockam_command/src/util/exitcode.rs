@@ -76,3 +76,27 @@ pub const NOPERM: ExitCode = 77;
 
 /// Something was found in an unconfigured or misconfigured state.
 pub const CONFIG: ExitCode = 78;
+
+/// The stable name of an exit code, e.g. `CONFIG`, as used in structured
+/// error output.
+pub fn name(code: ExitCode) -> &'static str {
+    match code {
+        OK => "OK",
+        USAGE => "USAGE",
+        DATAERR => "DATAERR",
+        NOINPUT => "NOINPUT",
+        NOUSER => "NOUSER",
+        NOHOST => "NOHOST",
+        UNAVAILABLE => "UNAVAILABLE",
+        SOFTWARE => "SOFTWARE",
+        OSERR => "OSERR",
+        OSFILE => "OSFILE",
+        CANTCREAT => "CANTCREAT",
+        IOERR => "IOERR",
+        TEMPFAIL => "TEMPFAIL",
+        PROTOCOL => "PROTOCOL",
+        NOPERM => "NOPERM",
+        CONFIG => "CONFIG",
+        _ => "UNKNOWN",
+    }
+}
@@ -27,7 +27,7 @@ use ockam_multiaddr::{
 
 use crate::node::util::start_embedded_node;
 use crate::util::output::Output;
-use crate::{CommandGlobalOpts, OutputFormat};
+use crate::CommandGlobalOpts;
 
 pub mod api;
 pub mod exitcode;
@@ -289,13 +289,7 @@ impl<'a> Rpc<'a> {
     where
         T: Output + serde::Serialize,
     {
-        let o = match self.opts.global_args.output_format {
-            OutputFormat::Plain => b.output().context("Failed to serialize response body")?,
-            OutputFormat::Json => {
-                serde_json::to_string_pretty(&b).context("Failed to serialize response body")?
-            }
-        };
-        println!("{}", o);
+        self.opts.print(&b)?;
         Ok(b)
     }
 }
@@ -323,16 +317,14 @@ where
             let res = f(ctx, a).await;
             if let Err(e) = res {
                 error!(%e);
-                eprintln!("{e:?}");
-                std::process::exit(e.code());
+                e.exit()
             }
             Ok(())
         },
         a,
     );
     if let Err(e) = res {
-        eprintln!("Ockam node failed: {e}");
-        std::process::exit(exitcode::SOFTWARE);
+        crate::Error::new(exitcode::SOFTWARE, anyhow!("Ockam node failed: {e}")).exit()
     }
 }
 
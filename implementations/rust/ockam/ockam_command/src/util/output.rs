@@ -1,16 +1,42 @@
 use anyhow::Context;
 use cli_table::{Cell, Style, Table};
 use core::fmt::Write;
+use core::sync::atomic::{AtomicU8, Ordering};
 use ockam::identity::credential::Credential;
 use ockam_api::cloud::project::{Enroller, Project};
 
+use crate::configuration::NodeAlias;
+use crate::message::send::MessageReply;
+use crate::node::apply::AppliedSpec;
+use crate::node::create::CreatedNode;
+use crate::node::diff::NodeDiff;
+use crate::node::export::ExportedNode;
+use crate::node::import::ImportedNode;
+use crate::node::show::{NodeState, NodeStatus};
+use crate::policy::PolicyEntry;
+use crate::portal::PortalInfo;
+use crate::project::enroll::EnrollmentToken;
 use crate::project::ProjectInfo;
+use crate::route::{CheckStatus, RouteExplanation};
+use crate::secure_channel::listener::create::CreatedSecureChannelListener;
+use crate::secure_channel::listener::list::SecureChannelListeners;
+use crate::service::start::StartedService;
+use crate::tcp::connection::create::CreatedTcpConnection;
+use crate::tcp::listener::create::CreatedTcpListener;
+use crate::tcp::outlet::create::CreatedOutlet;
 use crate::util::comma_separated;
+use crate::vault::create::CreatedVault;
+use crate::OutputFormat;
 use colorful::Colorful;
 use ockam_api::cloud::space::Space;
+use ockam_api::nodes::models::access::ApiUser;
+use ockam_api::nodes::models::forwarder::ForwarderInfo;
+use ockam_api::nodes::models::identity::CreateIdentityResponse;
+use ockam_api::nodes::models::portal::{ConnectionStatus, InletStatus, OutletStatus, Traffic};
 use ockam_api::nodes::models::secure_channel::{
     CreateSecureChannelResponse, ShowSecureChannelResponse,
 };
+use ockam_api::nodes::models::transport::TransportStatus;
 use ockam_api::route_to_multiaddr;
 use ockam_core::route;
 
@@ -40,6 +66,70 @@ use ockam_core::route;
 /// ```
 pub trait Output {
     fn output(&self) -> anyhow::Result<String>;
+
+    /// Format the value as plain text, using [`Output::output`], or serialize it
+    /// as JSON or YAML.
+    fn render(&self, format: OutputFormat) -> anyhow::Result<String>
+    where
+        Self: serde::Serialize,
+    {
+        match format {
+            OutputFormat::Plain => self.output(),
+            OutputFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => Ok(serde_yaml::to_string(self)?),
+        }
+        .context("Failed to serialize output")
+    }
+}
+
+/// Serialize `value` as JSON or YAML, for the commands which write their plain
+/// output by hand.
+pub(crate) fn serialize<T: serde::Serialize>(
+    value: &T,
+    format: OutputFormat,
+) -> anyhow::Result<String> {
+    match format {
+        OutputFormat::Yaml => Ok(serde_yaml::to_string(value)?),
+        OutputFormat::Plain | OutputFormat::Json => Ok(serde_json::to_string(value)?),
+    }
+}
+
+/// The output format selected on the command line, for the output which is
+/// printed without access to the [`CommandGlobalOpts`](crate::CommandGlobalOpts),
+/// such as errors.
+static OUTPUT_FORMAT: AtomicU8 = AtomicU8::new(0);
+
+pub(crate) fn set_output_format(format: OutputFormat) {
+    OUTPUT_FORMAT.store(format as u8, Ordering::Relaxed)
+}
+
+pub(crate) fn output_format() -> OutputFormat {
+    match OUTPUT_FORMAT.load(Ordering::Relaxed) {
+        f if f == OutputFormat::Json as u8 => OutputFormat::Json,
+        f if f == OutputFormat::Yaml as u8 => OutputFormat::Yaml,
+        _ => OutputFormat::Plain,
+    }
+}
+
+/// Something removed by one of the `delete` commands
+#[derive(serde::Serialize)]
+pub struct Deleted {
+    /// What was deleted, e.g. `Tcp inlet`
+    pub kind: &'static str,
+    pub name: String,
+    /// The parts which could not be deleted along with it
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<String>,
+}
+
+impl Deleted {
+    pub fn new(kind: &'static str, name: impl Into<String>) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+            failed: vec![],
+        }
+    }
 }
 
 impl<O: Output> Output for &O {
@@ -236,3 +326,588 @@ impl Output for Credential<'_> {
         Ok(self.to_string())
     }
 }
+
+impl Output for InletStatus<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        write!(w, "Inlet")?;
+        write!(w, "\n  Alias: {}", self.alias)?;
+        write!(w, "\n  Bind address: {}", self.bind_addr)?;
+        write!(w, "\n  Outlet route: {}", self.outlet_route)?;
+        write!(w, "\n  Status: {}", status(self.status))?;
+        write_traffic(&mut w, self.traffic)?;
+        Ok(w)
+    }
+}
+
+impl Output for Vec<InletStatus<'_>> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No inlets found".to_string());
+        }
+        let mut rows = vec![];
+        for i in self {
+            let t = i.traffic.unwrap_or_default();
+            rows.push([
+                i.alias.to_string().cell(),
+                i.bind_addr.to_string().cell(),
+                i.outlet_route.to_string().cell(),
+                status(i.status).cell(),
+                t.connections.cell(),
+                t.bytes_received.cell(),
+                t.bytes_sent.cell(),
+            ]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Alias".cell().bold(true),
+                "Bind Address".cell().bold(true),
+                "Outlet Route".cell().bold(true),
+                "Status".cell().bold(true),
+                "Connections".cell().bold(true),
+                "Bytes Received".cell().bold(true),
+                "Bytes Sent".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for PortalInfo {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        write!(w, "Portal")?;
+        write!(w, "\n  Name: {}", self.name)?;
+        write!(w, "\n  Transport: {}", self.portal.transport)?;
+        write!(w, "\n  From: {}", self.portal.from)?;
+        write!(w, "\n  To: {}", self.portal.to)?;
+        if let Some(via) = &self.portal.via {
+            write!(w, "\n  Via: {via}")?;
+        }
+        if let Some(forwarder) = &self.portal.forwarder {
+            write!(w, "\n  Forwarder: /service/{forwarder}")?;
+        }
+        if let Some(channel) = &self.portal.secure_channel {
+            write!(w, "\n  Secure channel: /service/{channel}")?;
+        }
+        if let Some(route) = &self.route {
+            write!(w, "\n  Route: {route}")?;
+        }
+        write!(w, "\n  Status: {}", self.status)?;
+        Ok(w)
+    }
+}
+
+impl Output for Vec<PortalInfo> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No portals found".to_string());
+        }
+        let mut rows = vec![];
+        for p in self {
+            rows.push([
+                p.name.clone().cell(),
+                p.portal.transport.clone().cell(),
+                p.portal.from.to_string().cell(),
+                p.portal.to.to_string().cell(),
+                p.portal
+                    .via
+                    .as_ref()
+                    .map(|via| via.to_string())
+                    .unwrap_or_default()
+                    .cell(),
+                p.status.clone().cell(),
+            ]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Name".cell().bold(true),
+                "Transport".cell().bold(true),
+                "From".cell().bold(true),
+                "To".cell().bold(true),
+                "Via".cell().bold(true),
+                "Status".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for RouteExplanation {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        write!(w, "Route {}", self.multiaddr)?;
+        write!(w, "\n  Components:")?;
+        let width = self
+            .components
+            .iter()
+            .map(|c| c.multiaddr.len())
+            .max()
+            .unwrap_or(0);
+        for c in &self.components {
+            write!(w, "\n    {:width$}  {}", c.multiaddr, c.description)?;
+            if let Some(to) = &c.resolves_to {
+                write!(w, ": {to}")?;
+            }
+        }
+        if let Some(resolved) = &self.resolved {
+            write!(w, "\n  Resolved: {resolved}")?;
+        }
+        if !self.route.is_empty() {
+            write!(w, "\n  Route:")?;
+            for (i, hop) in self.route.iter().enumerate() {
+                write!(w, "\n    {}. {:<14} {}", i + 1, hop.transport, hop.address)?;
+            }
+        }
+        if !self.problems.is_empty() {
+            write!(w, "\n  Problems:")?;
+            for p in &self.problems {
+                write!(w, "\n    - {p}")?;
+            }
+        }
+        if !self.checks.is_empty() {
+            write!(w, "\n  Checks:")?;
+            for c in &self.checks {
+                let status = match c.status {
+                    CheckStatus::Ok => "ok",
+                    CheckStatus::NoReply => "no reply",
+                    CheckStatus::Skipped => "skipped",
+                };
+                write!(w, "\n    {}: {status}", c.hop)?;
+                if let Some(detail) = &c.detail {
+                    write!(w, " ({detail})")?;
+                }
+            }
+        }
+        Ok(w)
+    }
+}
+
+impl Output for OutletStatus<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        write!(w, "Outlet")?;
+        write!(w, "\n  Alias: {}", self.alias)?;
+        write!(w, "\n  Worker address: {}", self.worker_addr)?;
+        write!(w, "\n  TCP address: {}", self.tcp_addr)?;
+        write!(w, "\n  Status: {}", status(self.status))?;
+        write_traffic(&mut w, self.traffic)?;
+        Ok(w)
+    }
+}
+
+impl Output for Vec<OutletStatus<'_>> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No outlets found".to_string());
+        }
+        let mut rows = vec![];
+        for o in self {
+            let t = o.traffic.unwrap_or_default();
+            rows.push([
+                o.alias.to_string().cell(),
+                o.worker_addr.to_string().cell(),
+                o.tcp_addr.to_string().cell(),
+                status(o.status).cell(),
+                t.connections.cell(),
+                t.bytes_received.cell(),
+                t.bytes_sent.cell(),
+            ]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Alias".cell().bold(true),
+                "Worker Address".cell().bold(true),
+                "TCP Address".cell().bold(true),
+                "Status".cell().bold(true),
+                "Connections".cell().bold(true),
+                "Bytes Received".cell().bold(true),
+                "Bytes Sent".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for Vec<ForwarderInfo<'_>> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No forwarders found".to_string());
+        }
+        let mut rows = vec![];
+        for f in self {
+            rows.push([
+                f.remote_address().cell(),
+                f.alias().unwrap_or("-").cell(),
+                f.at().map(|a| a.to_string()).unwrap_or_default().cell(),
+                f.forwarding_route().cell(),
+                status(f.status()).cell(),
+            ]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Remote Address".cell().bold(true),
+                "Alias".cell().bold(true),
+                "At".cell().bold(true),
+                "Forwarding Route".cell().bold(true),
+                "Status".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+/// Nodes which predate connection tracking do not report a status.
+fn status(s: Option<ConnectionStatus>) -> String {
+    s.map(|s| s.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn write_traffic(w: &mut String, t: Option<Traffic>) -> anyhow::Result<()> {
+    if let Some(t) = t {
+        write!(w, "\n  Connections: {}", t.connections)?;
+        write!(w, "\n  Bytes received: {}", t.bytes_received)?;
+        write!(w, "\n  Bytes sent: {}", t.bytes_sent)?;
+    }
+    Ok(())
+}
+
+impl Output for Vec<TransportStatus<'_>> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No transports found".to_string());
+        }
+        let mut rows = vec![];
+        for t in self {
+            rows.push([
+                t.tid.to_string().cell(),
+                t.tt.cell(),
+                t.tm.cell(),
+                t.payload.to_string().cell(),
+            ]);
+        }
+        let table = rows
+            .table()
+            .title([
+                "Transport ID".cell().bold(true),
+                "Transport Type".cell().bold(true),
+                "Mode".cell().bold(true),
+                "Address bind".cell().bold(true),
+            ])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for CreateIdentityResponse<'_> {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!("Identity {} created!", self.identity_id))
+    }
+}
+
+impl Output for CreatedVault {
+    fn output(&self) -> anyhow::Result<String> {
+        match (&self.node, &self.name) {
+            (Some(node), _) => Ok(format!("Vault created for the Node {node}!")),
+            (_, Some(name)) => Ok(format!("Vault created with name: {name}!")),
+            _ => Ok("Vault created!".to_string()),
+        }
+    }
+}
+
+impl Output for CreatedOutlet {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self.address.clone())
+    }
+}
+
+impl Output for CreatedTcpListener {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "Tcp listener created! You can send messages to it via this route:\n`{}`",
+            self.route
+        ))
+    }
+}
+
+impl Output for CreatedTcpConnection {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        write!(w, "Created TCP Connection:")?;
+        write!(w, "\n  From: {}", self.from)?;
+        write!(w, "\n    To: {}", self.to)?;
+        write!(w, "\n  Route: {}", self.route)?;
+        Ok(w)
+    }
+}
+
+impl Output for CreatedSecureChannelListener {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self.address.clone())
+    }
+}
+
+impl Output for SecureChannelListeners {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        write!(w, "Secure channel listeners for node `{}`:", self.node)?;
+        for addr in &self.listeners {
+            write!(w, "\n  {addr}")?;
+        }
+        Ok(w)
+    }
+}
+
+impl Output for NodeAlias {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!("Node:    {}\nAddress: {}", self.node, self.address))
+    }
+}
+
+impl Output for Vec<NodeAlias> {
+    fn output(&self) -> anyhow::Result<String> {
+        let aliases: anyhow::Result<Vec<_>> = self.iter().map(Output::output).collect();
+        Ok(aliases?.join("\n\n"))
+    }
+}
+
+impl Output for CreatedNode {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = self.status.output()?;
+        if let Some(c) = &self.credential {
+            write!(w, "\n\n{}", c.output()?)?;
+        }
+        Ok(w)
+    }
+}
+
+impl Output for NodeStatus {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        write!(w, "\nNode:")?;
+        write!(w, "\n  Name: {}", self.name)?;
+        let status = match self.status {
+            NodeState::Up => "UP".light_green(),
+            NodeState::Down => "DOWN".light_red(),
+        };
+        write!(w, "\n  Status: {status}")?;
+        if let Some(secs) = self.uptime {
+            write!(w, "\n  Uptime: {}", format_duration(secs))?;
+        }
+        if let Some(n) = self.restarts {
+            write!(w, "\n  Restarts: {n}")?;
+        }
+        write!(w, "\n  Route To Node:")?;
+        write!(w, "\n    Short: {}", self.route)?;
+        write!(w, "\n    Verbose: {}", self.verbose_route)?;
+        if let Some(id) = &self.identity {
+            write!(w, "\n  Identity: {id}")?;
+        }
+        if let Some(list) = &self.transports {
+            write!(w, "\n  Transports:")?;
+            for e in list {
+                write!(w, "\n    Transport:")?;
+                write!(w, "\n      Type: {}", e.tt)?;
+                write!(w, "\n      Mode: {}", e.tm)?;
+                write!(w, "\n      Address: {}", e.payload)?;
+            }
+        }
+        if let Some(list) = &self.secure_channel_listeners {
+            write!(w, "\n  Secure Channel Listeners:")?;
+            for e in list {
+                write!(w, "\n    Listener:")?;
+                write!(w, "\n      Address: {e}")?;
+            }
+        }
+        if let Some(list) = &self.inlets {
+            write!(w, "\n  Inlets:")?;
+            for e in list {
+                write!(w, "\n    Inlet:")?;
+                write!(w, "\n      Listen Address: {}", e.listen_address)?;
+                if let Some(r) = &e.route_to_outlet {
+                    write!(w, "\n      Route To Outlet: {r}")?;
+                }
+            }
+        }
+        if let Some(list) = &self.outlets {
+            write!(w, "\n  Outlets:")?;
+            for e in list {
+                write!(w, "\n    Outlet:")?;
+                write!(w, "\n      Forward Address: {}", e.forward_address)?;
+                if let Some(a) = &e.address {
+                    write!(w, "\n      Address: {a}")?;
+                }
+            }
+        }
+        if let Some(list) = &self.services {
+            write!(w, "\n  Services:")?;
+            for e in list {
+                write!(w, "\n    Service:")?;
+                write!(w, "\n      Type: {}", e.service_type)?;
+                if let Some(a) = &e.address {
+                    write!(w, "\n      Address: {a}")?;
+                }
+            }
+        }
+        Ok(w)
+    }
+}
+
+/// A number of seconds as days, hours, minutes and seconds, e.g. `2h 5m 0s`
+pub(crate) fn format_duration(secs: u64) -> String {
+    let (d, h, m, s) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    match (d, h, m) {
+        (0, 0, 0) => format!("{s}s"),
+        (0, 0, _) => format!("{m}m {s}s"),
+        (0, _, _) => format!("{h}h {m}m {s}s"),
+        _ => format!("{d}d {h}h {m}m {s}s"),
+    }
+}
+
+impl Output for Vec<NodeStatus> {
+    fn output(&self) -> anyhow::Result<String> {
+        let nodes: anyhow::Result<Vec<_>> = self.iter().map(Output::output).collect();
+        Ok(nodes?.join("\n"))
+    }
+}
+
+impl Output for Vec<ApiUser> {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.is_empty() {
+            return Ok("No identities may use the node API".to_string());
+        }
+        let mut rows = vec![];
+        for u in self {
+            rows.push([u.identity.to_string().cell(), u.role.cell()]);
+        }
+        let table = rows
+            .table()
+            .title(["Identity".cell().bold(true), "Role".cell().bold(true)])
+            .display()?
+            .to_string();
+        Ok(table)
+    }
+}
+
+impl Output for PolicyEntry {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self.expression.clone())
+    }
+}
+
+impl Output for Vec<PolicyEntry> {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = String::new();
+        for p in self {
+            writeln!(w, "{}/{}: {}", p.resource, p.action, p.expression)?;
+        }
+        Ok(w.trim_end().to_string())
+    }
+}
+
+impl Output for Deleted {
+    fn output(&self) -> anyhow::Result<String> {
+        let mut w = format!("{} `{}` successfully deleted", self.kind, self.name);
+        for part in &self.failed {
+            write!(w, "\nFailed to delete the {part}")?;
+        }
+        Ok(w)
+    }
+}
+
+impl Output for NodeDiff {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.drift.is_empty() {
+            return Ok(format!("Node {} matches {}", self.node, self.spec));
+        }
+        let drift: Vec<_> = self.drift.iter().map(|d| d.to_string()).collect();
+        Ok(drift.join("\n"))
+    }
+}
+
+impl Output for AppliedSpec {
+    fn output(&self) -> anyhow::Result<String> {
+        if self.changes.is_empty() {
+            return Ok(format!("Node {} matches {}", self.node, self.spec));
+        }
+        let mut w = String::new();
+        for c in &self.changes {
+            match (c.outcome, &c.error) {
+                ("kept", _) => writeln!(w, "{} (kept, use --prune to remove it)", c.drift)?,
+                (_, Some(e)) => writeln!(w, "{} failed: {e}", c.drift)?,
+                _ => writeln!(w, "{}", c.drift)?,
+            }
+        }
+        Ok(w.trim_end().to_string())
+    }
+}
+
+impl Output for StartedService {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "{} service started at address: {}",
+            self.service, self.address
+        ))
+    }
+}
+
+impl Output for EnrollmentToken {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self.token.clone())
+    }
+}
+
+impl Output for MessageReply {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(self.reply.clone())
+    }
+}
+
+impl Output for ExportedNode {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!("Exported node {} to {}", self.node, self.file))
+    }
+}
+
+impl Output for ImportedNode {
+    fn output(&self) -> anyhow::Result<String> {
+        Ok(format!(
+            "Imported node {}. Start it with `ockam node start {}`",
+            self.node, self.node
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_in_each_format() {
+        let alias = NodeAlias {
+            node: "n1".to_string(),
+            address: "127.0.0.1:4000".to_string(),
+        };
+        assert_eq!(
+            alias.render(OutputFormat::Plain).unwrap(),
+            "Node:    n1\nAddress: 127.0.0.1:4000"
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&alias.render(OutputFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["node"], "n1");
+        assert_eq!(json["address"], "127.0.0.1:4000");
+        assert_eq!(
+            alias.render(OutputFormat::Yaml).unwrap(),
+            "node: n1\naddress: 127.0.0.1:4000\n"
+        );
+    }
+}
@@ -110,3 +110,77 @@ pub fn spawn_node(
 
     Ok(())
 }
+
+/// Rotate the log files of a node once they grow beyond `max_size` bytes
+///
+/// A log is copied to `<log>.1`, shifting older copies up to
+/// `<log>.<keep>`, and then truncated.  Nodes open their logs in
+/// append mode, so they carry on writing at the start of the
+/// truncated file.
+pub fn rotate_node_logs(
+    cfg: &OckamConfig,
+    name: &str,
+    max_size: u64,
+    keep: u32,
+) -> anyhow::Result<()> {
+    if let Some((mlog, elog)) = cfg.node_log_paths(name) {
+        rotate_log(&mlog, max_size, keep)?;
+        rotate_log(&elog, max_size, keep)?;
+    }
+    Ok(())
+}
+
+fn rotate_log(log: &Path, max_size: u64, keep: u32) -> anyhow::Result<()> {
+    match std::fs::metadata(log) {
+        Ok(m) if m.len() > max_size => {}
+        _ => return Ok(()),
+    }
+    let rotated = |n: u32| PathBuf::from(format!("{}.{n}", log.display()));
+    if keep > 0 {
+        let _ = std::fs::remove_file(rotated(keep));
+        for n in (1..keep).rev() {
+            if rotated(n).exists() {
+                std::fs::rename(rotated(n), rotated(n + 1))?;
+            }
+        }
+        std::fs::copy(log, rotated(1))
+            .with_context(|| format!("failed to rotate log {}", log.display()))?;
+    }
+    OpenOptions::new()
+        .write(true)
+        .open(log)?
+        .set_len(0)
+        .with_context(|| format!("failed to truncate log {}", log.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn rotate_log_keeps_the_latest_copies() {
+        let dir = tempdir().unwrap();
+        let log = dir.path().join("stdout.log");
+        for content in ["first", "second", "third"] {
+            std::fs::write(&log, content).unwrap();
+            rotate_log(&log, 1, 2).unwrap();
+        }
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("stdout.log.1")).unwrap(),
+            "third"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("stdout.log.2")).unwrap(),
+            "second"
+        );
+        assert!(!dir.path().join("stdout.log.3").exists());
+
+        // Logs below the size limit are left alone
+        std::fs::write(&log, "short").unwrap();
+        rotate_log(&log, 100, 2).unwrap();
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "short");
+    }
+}
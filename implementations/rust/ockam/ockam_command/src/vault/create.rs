@@ -40,7 +40,10 @@ async fn run_impl(ctx: Context, (options, cmd): (CommandGlobalOpts, CreateComman
             let request = Request::post("/node/vault").body(CreateVaultRequest::new(cmd.path));
             rpc.request(request).await?;
             rpc.is_ok()?;
-            println!("Vault created for the Node {}!", node_name);
+            options.print(&CreatedVault {
+                node: Some(node_name),
+                name: None,
+            })?;
         }
         (None, Some(vault_name)) => {
             let dir = cli::OckamConfig::dir().join("vaults").join(&vault_name);
@@ -53,9 +56,21 @@ async fn run_impl(ctx: Context, (options, cmd): (CommandGlobalOpts, CreateComman
             tokio::fs::create_dir_all(dir.as_path()).await?;
             let file = dir.join("vault.json");
             let _ = FileStorage::create(file.clone()).await?;
-            println!("Vault created with name: {}!", vault_name);
+            options.print(&CreatedVault {
+                node: None,
+                name: Some(vault_name),
+            })?;
         }
         _ => unreachable!(),
     }
     Ok(())
 }
+
+/// The vault created by [`CreateCommand`], either for a node or with a name
+#[derive(serde::Serialize)]
+pub struct CreatedVault {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
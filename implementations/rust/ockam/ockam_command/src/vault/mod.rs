@@ -1,4 +1,4 @@
-mod create;
+pub(crate) mod create;
 
 pub(crate) use create::CreateCommand;
 
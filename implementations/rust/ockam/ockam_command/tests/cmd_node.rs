@@ -19,5 +19,46 @@ fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
         .arg("node-name");
     cmd.assert().success();
 
+    // structured output
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("node")
+        .arg("list")
+        .arg("--output")
+        .arg("yaml");
+    cmd.assert().success();
+
+    // supervise nodes
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("node")
+        .arg("supervise")
+        .arg("n1")
+        .arg("n2")
+        .arg("--max-log-size")
+        .arg("5");
+    cmd.assert().success();
+
+    // export and import nodes
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("node")
+        .arg("export")
+        .arg("n1")
+        .arg("-f")
+        .arg("n1.ockam");
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("node")
+        .arg("import")
+        .arg("n1.ockam")
+        .arg("--name")
+        .arg("n2")
+        .arg("--passphrase-file")
+        .arg("passphrase.txt");
+    cmd.assert().success();
+
     Ok(())
 }
@@ -0,0 +1,83 @@
+use assert_cmd::prelude::*;
+use std::process::Command;
+
+#[test]
+fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("portal")
+        .arg("create")
+        .arg("web")
+        .arg("--from")
+        .arg("/node/n1/ip4/127.0.0.1/tcp/6000")
+        .arg("--to")
+        .arg("/node/n2/ip4/127.0.0.1/tcp/5000")
+        .arg("--via")
+        .arg("/project/default");
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("portal")
+        .arg("delete")
+        .arg("web");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn invalid_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    // portal names end up in inlet aliases
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("portal")
+        .arg("create")
+        .arg("a:b")
+        .arg("--from")
+        .arg("/node/n1/ip4/127.0.0.1/tcp/6000")
+        .arg("--to")
+        .arg("/node/n2/ip4/127.0.0.1/tcp/5000");
+    cmd.assert().failure();
+
+    // udp and tcp can't be mixed
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("portal")
+        .arg("create")
+        .arg("dns")
+        .arg("--from")
+        .arg("/node/n1/ip4/127.0.0.1/udp/5353")
+        .arg("--to")
+        .arg("/node/n2/ip4/127.0.0.1/tcp/53");
+    cmd.assert().failure().code(64);
+
+    Ok(())
+}
+
+#[test]
+fn errors_are_structured() -> Result<(), Box<dyn std::error::Error>> {
+    let create = |format: &str| -> Result<_, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("ockam")?;
+        cmd.arg("portal")
+            .arg("create")
+            .arg("dns")
+            .arg("--from")
+            .arg("/node/n1/ip4/127.0.0.1/udp/5353")
+            .arg("--to")
+            .arg("/node/n2/ip4/127.0.0.1/tcp/53")
+            .arg("--output")
+            .arg(format);
+        Ok(cmd.assert().failure().code(64).get_output().stderr.clone())
+    };
+
+    let json: serde_json::Value = serde_json::from_slice(&create("json")?)?;
+    assert_eq!(json["code"], 64);
+    assert_eq!(json["kind"], "USAGE");
+    assert!(json["message"].is_string());
+
+    let yaml: serde_yaml::Value = serde_yaml::from_slice(&create("yaml")?)?;
+    assert_eq!(yaml["code"], 64);
+    assert_eq!(yaml["kind"], "USAGE");
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use assert_cmd::prelude::*;
+use std::process::Command;
+
+#[test]
+fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("route")
+        .arg("explain")
+        .arg("/node/n1/service/forward_to_n2/service/uppercase")
+        .arg("--check")
+        .arg("--timeout")
+        .arg("2");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn invalid_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    // not a multiaddr
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("route")
+        .arg("explain")
+        .arg("127.0.0.1:4000");
+    cmd.assert().failure();
+
+    Ok(())
+}
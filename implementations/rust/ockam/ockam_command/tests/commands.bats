@@ -136,6 +136,19 @@ teardown() {
   assert_output "HELLO"
 }
 
+@test "send the standard input to a node without a background node" {
+  $OCKAM node create n1
+
+  run --separate-stderr bash -c "echo hello | $OCKAM exec --to /node/n1/service/uppercase"
+  assert_success
+  assert_output "HELLO"
+
+  run --separate-stderr bash -c "printf 'a\nb\n' | $OCKAM exec --pipe --secure-channel /node/n1/service/api --to /service/uppercase"
+  assert_success
+  assert_output "A
+B"
+}
+
 @test "create node with a startup command, stop it and restart it" {
   echo '{"on_node_startup": ["secure-channel create --from /node/n1 --to /node/n2/service/api"]}' > "$BATS_TMPDIR/configuration.json"
   $OCKAM node create n2
@@ -273,6 +286,94 @@ teardown() {
   assert_success
 }
 
+@test "create a portal through a forwarder, move tcp traffic through it and delete it" {
+  $OCKAM node create relay
+  $OCKAM node create blue
+  $OCKAM node create green
+
+  $OCKAM portal create web --from /node/green/ip4/127.0.0.1/tcp/7100 \
+    --to /node/blue/ip4/127.0.0.1/tcp/5000 --via /node/relay
+
+  run curl --fail --head 127.0.0.1:7100
+  assert_success
+
+  run $OCKAM portal delete web
+  assert_success
+  run $OCKAM portal show web
+  assert_failure
+}
+
+@test "create a portal from a unix socket and move tcp traffic through it" {
+  $OCKAM node create blue
+  $OCKAM node create green
+
+  socket="$BATS_TEST_TMPDIR/web.sock"
+  $OCKAM portal create web --from "/node/green/unix$socket" --to /node/blue/ip4/127.0.0.1/tcp/5000
+
+  run curl --fail --head --unix-socket "$socket" http://localhost/
+  assert_success
+
+  run $OCKAM portal delete web
+  assert_success
+}
+
+@test "a portal whose inlet can't be created leaves nothing behind" {
+  $OCKAM node create blue
+  $OCKAM node create green
+
+  run $OCKAM portal create web --from /node/green/unix/nonexistent/web.sock \
+    --to /node/blue/ip4/127.0.0.1/tcp/5000
+  assert_failure
+
+  run $OCKAM tcp-inlet list --node green
+  assert_success
+  refute_output --partial "web"
+  run $OCKAM tcp-outlet list --node blue
+  assert_success
+  refute_output --partial "web"
+}
+
+@test "apply a node spec with --prune and remove the inlets, outlets and forwarders it doesn't mention" {
+  $OCKAM node create n1
+  $OCKAM node create n2
+  $OCKAM tcp-outlet create --at /node/n1 --from /service/outlet --to 127.0.0.1:5000
+  $OCKAM tcp-inlet create --at /node/n1 --from 127.0.0.1:6000 --to /node/n2/service/echo
+  $OCKAM forwarder create n1 --at /node/n2 --to /node/n1
+  echo "{}" > "$BATS_TEST_TMPDIR/spec.yaml"
+
+  run $OCKAM node apply n1 --file "$BATS_TEST_TMPDIR/spec.yaml"
+  assert_success
+  assert_output --partial "(kept, use --prune to remove it)"
+
+  run $OCKAM node apply n1 --file "$BATS_TEST_TMPDIR/spec.yaml" --prune
+  assert_success
+  refute_output --partial "failed"
+
+  run $OCKAM node diff n1 --file "$BATS_TEST_TMPDIR/spec.yaml"
+  assert_success
+  assert_output --partial "matches"
+  run $OCKAM tcp-inlet list --node n1
+  assert_output --partial "No inlets found"
+  run $OCKAM tcp-outlet list --node n1
+  assert_output --partial "No outlets found"
+  run $OCKAM forwarder list --node n1
+  assert_output --partial "No forwarders found"
+}
+
+@test "explain a route through a forwarder and check its hops" {
+  $OCKAM node create n1
+  $OCKAM node create relay
+  $OCKAM forwarder create n1 --at /node/relay --to /node/n1
+
+  run $OCKAM route explain /node/relay/service/forward_to_n1/service/uppercase --check
+  assert_success
+  assert_output --partial "/service/forward_to_n1: ok"
+
+  run $OCKAM route explain /ip4/127.0.0.1/service/uppercase
+  assert_failure
+  assert_output --partial "/ip4/127.0.0.1 must be followed by /tcp"
+}
+
 @test "create a node and start services" {
   $OCKAM node create n1
 
@@ -306,6 +407,12 @@ teardown() {
   run $OCKAM service start credentials --addr my_credentials --node n1
   assert_failure
 
+  # Check we can start service, but only once with the same name
+  run $OCKAM service start prekeys --addr my_prekeys --node n1
+  assert_success
+  run $OCKAM service start prekeys --addr my_prekeys --node n1
+  assert_failure
+
   # TODO: add test for authenticator
 }
 
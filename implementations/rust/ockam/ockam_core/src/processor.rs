@@ -19,6 +19,15 @@ pub trait Processor: Send + 'static {
         Ok(())
     }
 
+    /// Define whether the processor stops when its node drains.
+    ///
+    /// Processors that take on new work, like listeners, should stop
+    /// so that the node doesn't accept any during the drain phase.
+    /// Other processors keep running until the node shuts down.
+    fn stop_on_drain(&self) -> bool {
+        false
+    }
+
     /// Define the Processor Worker background execution behaviour.
     ///
     /// The `process()` callback function allows you to define worker
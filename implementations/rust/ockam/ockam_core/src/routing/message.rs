@@ -3,6 +3,9 @@
 mod local_message;
 pub use local_message::*;
 
+mod priority;
+pub use priority::*;
+
 mod relay_message;
 pub use relay_message::*;
 
@@ -1,4 +1,4 @@
-use crate::{compat::string::String, compat::vec::Vec, Message, TransportMessage};
+use crate::{compat::string::String, compat::vec::Vec, Message, Priority, TransportMessage};
 use serde::{Deserialize, Serialize};
 
 /// Contains metadata that will only be routed locally within the
@@ -50,6 +50,9 @@ impl LocalInfo {
 /// order to provide a mechanism for third-party developers to create
 /// custom transport channel routers.
 ///
+/// Every `LocalMessage` also carries a [`Priority`] that decides how
+/// it is scheduled by the mailboxes of this node.
+///
 /// Casual users of Ockam should never have to interact with this type
 /// directly.
 ///
@@ -61,6 +64,7 @@ impl LocalInfo {
 pub struct LocalMessage {
     transport_message: TransportMessage,
     local_info: Vec<LocalInfo>,
+    priority: Priority,
 }
 
 impl LocalMessage {
@@ -80,6 +84,10 @@ impl LocalMessage {
     pub fn local_info(&self) -> &[LocalInfo] {
         &self.local_info
     }
+    /// Return the scheduling class of this message.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
     /// Dissolve
     pub fn dissolve(self) -> (TransportMessage, Vec<LocalInfo>) {
         (self.transport_message, self.local_info)
@@ -104,6 +112,17 @@ impl LocalMessage {
         self.local_info
             .retain(|x| x.type_identifier() != type_identifier)
     }
+
+    /// Set the scheduling class of this message.
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.priority = priority
+    }
+
+    /// Set the scheduling class of this message.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
 }
 
 impl LocalMessage {
@@ -112,6 +131,7 @@ impl LocalMessage {
         LocalMessage {
             transport_message,
             local_info,
+            priority: Priority::default(),
         }
     }
 }
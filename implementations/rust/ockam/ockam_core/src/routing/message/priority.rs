@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// The scheduling class of a [`LocalMessage`](crate::LocalMessage)
+///
+/// Every worker mailbox keeps one queue per class and always takes
+/// the next message from the most urgent class that has one waiting.
+/// To keep lower classes moving under load, a waiting message is
+/// delivered after it has been passed over
+/// [`STARVATION_LIMIT`](Priority::STARVATION_LIMIT) times.
+///
+/// Transport senders are ordinary workers, so their per-class
+/// mailbox queues also decide the order in which messages are
+/// written to the wire.  The class itself is local to the node and
+/// is not sent to the remote peer.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, Ord, PartialOrd, Eq, PartialEq)]
+pub enum Priority {
+    /// Control-plane traffic: node management, health checks,
+    /// credential exchange
+    Control,
+    /// Regular application traffic
+    Normal,
+    /// Bulk data, such as portal payloads
+    Bulk,
+}
+
+impl Priority {
+    /// The number of priority classes
+    pub const COUNT: usize = 3;
+
+    /// The number of times a waiting message may be passed over by
+    /// messages of more urgent classes
+    pub const STARVATION_LIMIT: u8 = 8;
+
+    /// All priority classes, from the most to the least urgent
+    pub const ALL: [Priority; Self::COUNT] = [Priority::Control, Priority::Normal, Priority::Bulk];
+
+    /// The index of this class in [`ALL`](Priority::ALL)
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::Normal
+    }
+}
+
+impl core::fmt::Display for Priority {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Priority::Control => write!(f, "control"),
+            Priority::Normal => write!(f, "normal"),
+            Priority::Bulk => write!(f, "bulk"),
+        }
+    }
+}
@@ -29,6 +29,20 @@ pub trait Worker: Send + 'static {
         Ok(())
     }
 
+    /// Override drain behaviour.
+    ///
+    /// This hook is called once when the node enters drain mode,
+    /// after all messages that were already queued for this worker
+    /// have been handled.  Use it to stop accepting new work and to
+    /// notify peers that this worker is about to go away.
+    ///
+    /// The worker keeps handling messages after this hook returns,
+    /// until it is stopped by the node shutdown that follows the
+    /// drain phase.
+    async fn drain(&mut self, _context: &mut Self::Context) -> Result<()> {
+        Ok(())
+    }
+
     /// Try to authorize an incoming message
     ///
     /// The authorization flow of an incoming message looks like this:
@@ -1,17 +1,23 @@
-use crate::ChangeIdentifier;
+use crate::{ChangeIdentifier, IdentityError, KeyAttributes};
 use core::fmt;
 use ockam_core::compat::vec::Vec;
-use ockam_core::vault::PublicKey;
+use ockam_core::vault::{KeyId, PublicKey, Signer};
 use ockam_core::Result;
 use serde::{Deserialize, Serialize};
 
 pub use crate::signature::*;
 
 mod create_key;
+mod revoke_key;
 mod rotate_key;
+mod set_controllers;
+mod set_key_purposes;
 
 pub use create_key::*;
+pub use revoke_key::*;
 pub use rotate_key::*;
+pub use set_controllers::*;
+pub use set_key_purposes::*;
 
 /// Possible types of [`crate::Identity`] changes
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -20,6 +26,12 @@ pub enum IdentityChange {
     CreateKey(CreateKeyChangeData),
     /// Rotate key
     RotateKey(RotateKeyChangeData),
+    /// Revoke key
+    RevokeKey(RevokeKeyChangeData),
+    /// Set the controllers that must approve further changes
+    SetControllers(SetControllersChangeData),
+    /// Restrict a key to some purposes
+    SetKeyPurposes(SetKeyPurposesChangeData),
 }
 
 impl fmt::Display for IdentityChange {
@@ -27,19 +39,29 @@ impl fmt::Display for IdentityChange {
         match self {
             IdentityChange::CreateKey(data) => write!(f, " CreateKey:{}", data),
             IdentityChange::RotateKey(data) => write!(f, " RotateKey:{}", data),
+            IdentityChange::RevokeKey(data) => write!(f, " RevokeKey:{}", data),
+            IdentityChange::SetControllers(data) => write!(f, " SetControllers:{}", data),
+            IdentityChange::SetKeyPurposes(data) => write!(f, " SetKeyPurposes:{}", data),
         }
     }
 }
 
 impl IdentityChange {
     pub(crate) fn has_label(&self, label: &str) -> bool {
-        self.label() == label
+        self.label() == Some(label)
     }
 
-    pub(crate) fn label(&self) -> &str {
+    pub(crate) fn label(&self) -> Option<&str> {
+        self.key_attributes().map(|a| a.label())
+    }
+
+    /// Attributes of the key this change applies to, if it applies to a key
+    pub fn key_attributes(&self) -> Option<&KeyAttributes> {
         match self {
-            IdentityChange::CreateKey(data) => data.key_attributes().label(),
-            IdentityChange::RotateKey(data) => data.key_attributes().label(),
+            IdentityChange::CreateKey(data) => Some(data.key_attributes()),
+            IdentityChange::RotateKey(data) => Some(data.key_attributes()),
+            IdentityChange::RevokeKey(data) => Some(data.key_attributes()),
+            IdentityChange::SetControllers(_) | IdentityChange::SetKeyPurposes(_) => None,
         }
     }
 
@@ -47,6 +69,10 @@ impl IdentityChange {
         Ok(match self {
             IdentityChange::CreateKey(data) => data.public_key(),
             IdentityChange::RotateKey(data) => data.public_key(),
+            IdentityChange::RevokeKey(data) => data.public_key(),
+            IdentityChange::SetControllers(_) | IdentityChange::SetKeyPurposes(_) => {
+                return Err(IdentityError::InvalidInternalState.into())
+            }
         }
         .clone())
     }
@@ -55,6 +81,9 @@ impl IdentityChange {
         match self {
             IdentityChange::CreateKey(data) => data.prev_change_id(),
             IdentityChange::RotateKey(data) => data.prev_change_id(),
+            IdentityChange::RevokeKey(data) => data.prev_change_id(),
+            IdentityChange::SetControllers(data) => data.prev_change_id(),
+            IdentityChange::SetKeyPurposes(data) => data.prev_change_id(),
         }
     }
 }
@@ -96,6 +125,32 @@ impl IdentitySignedChange {
             signatures,
         }
     }
+
+    /// Sign this change with a controller key held in `vault`
+    ///
+    /// Each controller of an identity adds its signature this way,
+    /// possibly on another node, before the change is applied with
+    /// [`Identity::apply_change`](crate::Identity::apply_change).
+    pub async fn add_controller_signature(
+        &mut self,
+        vault: &impl Signer,
+        key_id: &KeyId,
+    ) -> Result<()> {
+        let signature = vault.sign(key_id, self.identifier.as_ref()).await?;
+        self.signatures
+            .push(Signature::new(SignatureType::ControllerSign, signature));
+        Ok(())
+    }
+
+    /// Serialize this change, e.g. to pass it to other controllers
+    pub fn export(&self) -> Result<Vec<u8>> {
+        serde_bare::to_vec(self).map_err(|_| IdentityError::BareError.into())
+    }
+
+    /// Deserialize a change created with [`export`](Self::export)
+    pub fn import(data: &[u8]) -> Result<Self> {
+        serde_bare::from_slice(data).map_err(|_| IdentityError::BareError.into())
+    }
 }
 
 impl fmt::Display for IdentitySignedChange {
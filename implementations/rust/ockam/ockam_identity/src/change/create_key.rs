@@ -135,10 +135,19 @@ impl<V: IdentityVault> Identity<V> {
             Err(_) => ChangeIdentifier::initial(&self.vault).await,
         };
 
-        let root_secret = self.get_root_secret_key().await?;
-        let root_key = Some(&root_secret);
+        // Controllers sign separately, if the identity has any
+        let root_secret = match change_history.controllers() {
+            Some(_) => None,
+            None => Some(self.get_root_secret_key().await?),
+        };
 
-        Self::make_create_key_change_static(secret, prev_id, key_attributes, root_key, &self.vault)
-            .await
+        Self::make_create_key_change_static(
+            secret,
+            prev_id,
+            key_attributes,
+            root_secret.as_ref(),
+            &self.vault,
+        )
+        .await
     }
 }
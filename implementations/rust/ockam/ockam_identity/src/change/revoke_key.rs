@@ -0,0 +1,109 @@
+use crate::change::{IdentityChange, IdentitySignedChange, Signature, SignatureType};
+use crate::change_history::IdentityChangeHistory;
+use crate::{
+    ChangeIdentifier, Identity, IdentityError, IdentityStateConst, IdentityVault, KeyAttributes,
+};
+use core::fmt;
+use ockam_core::compat::vec::Vec;
+use ockam_core::vault::PublicKey;
+use ockam_core::{Encodable, Result};
+use serde::{Deserialize, Serialize};
+
+/// RevokeKeyChangeData
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RevokeKeyChangeData {
+    prev_change_id: ChangeIdentifier,
+    key_attributes: KeyAttributes,
+    public_key: PublicKey,
+}
+
+impl RevokeKeyChangeData {
+    /// Return key attributes of the revoked key
+    pub fn key_attributes(&self) -> &KeyAttributes {
+        &self.key_attributes
+    }
+    /// Return revoked public key
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+    /// Previous change identifier, used to create a chain
+    pub fn prev_change_id(&self) -> &ChangeIdentifier {
+        &self.prev_change_id
+    }
+}
+
+impl RevokeKeyChangeData {
+    /// Create RevokeKeyChangeData
+    pub fn new(
+        prev_change_id: ChangeIdentifier,
+        key_attributes: KeyAttributes,
+        public_key: PublicKey,
+    ) -> Self {
+        Self {
+            prev_change_id,
+            key_attributes,
+            public_key,
+        }
+    }
+}
+
+impl fmt::Display for RevokeKeyChangeData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "prev_change_id:{} key attibutes:{} public key:{}",
+            self.prev_change_id(),
+            self.key_attributes(),
+            self.public_key()
+        )
+    }
+}
+
+impl<V: IdentityVault> Identity<V> {
+    /// Revoke key change
+    ///
+    /// Only the root key (or the controllers) sign a revocation, so
+    /// that a key can be revoked even if its secret has been lost.
+    pub(crate) async fn make_revoke_key_change(&self, label: &str) -> Result<IdentitySignedChange> {
+        if label == IdentityStateConst::ROOT_LABEL {
+            return Err(IdentityError::InvalidInternalState.into());
+        }
+
+        let change_history = self.change_history.read().await;
+        let prev_change_id = change_history.get_last_change_id()?;
+
+        let last_change_in_chain =
+            IdentityChangeHistory::find_last_valid_key_change(change_history.as_ref(), label)?;
+
+        let data = RevokeKeyChangeData::new(
+            prev_change_id,
+            last_change_in_chain
+                .change()
+                .key_attributes()
+                .ok_or(IdentityError::InvalidInternalState)?
+                .clone(),
+            last_change_in_chain.change().public_key()?,
+        );
+
+        let change_block = IdentityChange::RevokeKey(data);
+        let change_block_binary = change_block
+            .encode()
+            .map_err(|_| IdentityError::BareError)?;
+
+        let change_id = self.vault.sha256(&change_block_binary).await?;
+        let change_id = ChangeIdentifier::from_hash(change_id);
+
+        let mut signatures = Vec::new();
+
+        if change_history.controllers().is_none() {
+            let root_key = self.get_root_secret_key().await?;
+
+            let root_signature = self.vault.sign(&root_key, change_id.as_ref()).await?;
+            signatures.push(Signature::new(SignatureType::RootSign, root_signature));
+        }
+
+        let signed_change = IdentitySignedChange::new(change_id, change_block, signatures);
+
+        Ok(signed_change)
+    }
+}
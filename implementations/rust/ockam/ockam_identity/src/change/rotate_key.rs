@@ -1,7 +1,10 @@
 use crate::change::{IdentityChange, IdentitySignedChange, Signature, SignatureType};
 use crate::change_history::IdentityChangeHistory;
-use crate::{ChangeIdentifier, Identity, IdentityError, IdentityVault, KeyAttributes};
+use crate::{
+    ChangeIdentifier, Identity, IdentityError, IdentityStateConst, IdentityVault, KeyAttributes,
+};
 use core::fmt;
+use ockam_core::compat::string::ToString;
 use ockam_core::vault::PublicKey;
 use ockam_core::{Encodable, Result};
 use serde::{Deserialize, Serialize};
@@ -65,7 +68,7 @@ impl<V: IdentityVault> Identity<V> {
         let change_history = self.change_history.read().await;
         let prev_change_id = change_history.get_last_change_id()?;
 
-        let last_change_in_chain = IdentityChangeHistory::find_last_key_change(
+        let last_change_in_chain = IdentityChangeHistory::find_last_valid_key_change(
             change_history.as_ref(),
             key_attributes.label(),
         )?
@@ -79,6 +82,7 @@ impl<V: IdentityVault> Identity<V> {
         let secret_key = self.vault.secret_generate(secret_attributes).await?;
         let public_key = self.vault.secret_public_key_get(&secret_key).await?;
 
+        let key_attributes_label = key_attributes.label().to_string();
         let data = RotateKeyChangeData::new(prev_change_id, key_attributes, public_key);
 
         let change_block = IdentityChange::RotateKey(data);
@@ -92,22 +96,27 @@ impl<V: IdentityVault> Identity<V> {
         let self_signature = self.vault.sign(&secret_key, change_id.as_ref()).await?;
         let self_signature = Signature::new(SignatureType::SelfSign, self_signature);
 
-        let root_key = self.get_root_secret_key().await?;
+        let mut signatures = vec![self_signature];
 
-        let root_signature = self.vault.sign(&root_key, change_id.as_ref()).await?;
-        let root_signature = Signature::new(SignatureType::RootSign, root_signature);
+        // With controllers, the root key neither approves changes nor signs its own rotation
+        let has_controllers = change_history.controllers().is_some();
 
-        let prev_signature = self
-            .vault
-            .sign(&last_key_in_chain, change_id.as_ref())
-            .await?;
-        let prev_signature = Signature::new(SignatureType::PrevSign, prev_signature);
+        if !has_controllers {
+            let root_key = self.get_root_secret_key().await?;
 
-        let signed_change = IdentitySignedChange::new(
-            change_id,
-            change_block,
-            vec![self_signature, root_signature, prev_signature],
-        );
+            let root_signature = self.vault.sign(&root_key, change_id.as_ref()).await?;
+            signatures.push(Signature::new(SignatureType::RootSign, root_signature));
+        }
+
+        if !has_controllers || key_attributes_label != IdentityStateConst::ROOT_LABEL {
+            let prev_signature = self
+                .vault
+                .sign(&last_key_in_chain, change_id.as_ref())
+                .await?;
+            signatures.push(Signature::new(SignatureType::PrevSign, prev_signature));
+        }
+
+        let signed_change = IdentitySignedChange::new(change_id, change_block, signatures);
 
         Ok(signed_change)
     }
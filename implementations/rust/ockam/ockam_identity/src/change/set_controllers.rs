@@ -0,0 +1,130 @@
+use crate::change::{IdentityChange, IdentitySignedChange, Signature, SignatureType};
+use crate::change_history::IdentityChangeHistory;
+use crate::{ChangeIdentifier, Identity, IdentityError, IdentityVault};
+use core::fmt;
+use ockam_core::compat::vec::Vec;
+use ockam_core::vault::PublicKey;
+use ockam_core::{Encodable, Result};
+use serde::{Deserialize, Serialize};
+
+/// SetControllersChangeData
+///
+/// Once an identity has controllers, every following change must be
+/// signed by at least `threshold` of them instead of by the root key.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetControllersChangeData {
+    prev_change_id: ChangeIdentifier,
+    controllers: Vec<PublicKey>,
+    threshold: u8,
+}
+
+impl SetControllersChangeData {
+    /// Return controller public keys
+    pub fn controllers(&self) -> &[PublicKey] {
+        &self.controllers
+    }
+    /// Return number of controller signatures required for a change
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+    /// Previous change identifier, used to create a chain
+    pub fn prev_change_id(&self) -> &ChangeIdentifier {
+        &self.prev_change_id
+    }
+    /// Whether the threshold can be met and no controller is listed twice
+    pub(crate) fn is_valid(&self) -> bool {
+        if self.threshold == 0 || self.threshold as usize > self.controllers.len() {
+            return false;
+        }
+        self.controllers
+            .iter()
+            .enumerate()
+            .all(|(i, c)| !self.controllers[..i].contains(c))
+    }
+}
+
+impl SetControllersChangeData {
+    /// Create SetControllersChangeData
+    pub fn new(
+        prev_change_id: ChangeIdentifier,
+        controllers: Vec<PublicKey>,
+        threshold: u8,
+    ) -> Self {
+        Self {
+            prev_change_id,
+            controllers,
+            threshold,
+        }
+    }
+}
+
+impl fmt::Display for SetControllersChangeData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "prev_change_id:{} threshold:{} controllers:",
+            self.prev_change_id(),
+            self.threshold()
+        )?;
+        for c in self.controllers() {
+            write!(f, " {}", c)?;
+        }
+        Ok(())
+    }
+}
+
+impl<V: IdentityVault> Identity<V> {
+    /// Set controllers change
+    pub(crate) async fn make_set_controllers_change(
+        &self,
+        controllers: Vec<PublicKey>,
+        threshold: u8,
+    ) -> Result<IdentitySignedChange> {
+        let change_history = self.change_history.read().await;
+        let prev_change_id = change_history.get_last_change_id()?;
+
+        let data = SetControllersChangeData::new(prev_change_id, controllers, threshold);
+        if !data.is_valid() {
+            return Err(IdentityError::InvalidInternalState.into());
+        }
+
+        let change_block = IdentityChange::SetControllers(data);
+        let change_block_binary = change_block
+            .encode()
+            .map_err(|_| IdentityError::BareError)?;
+
+        let change_id = self.vault.sha256(&change_block_binary).await?;
+        let change_id = ChangeIdentifier::from_hash(change_id);
+
+        let mut signatures = Vec::new();
+
+        // Existing controllers sign separately, see `IdentitySignedChange::add_controller_signature`
+        if change_history.controllers().is_none() {
+            let root_key = self.get_root_secret_key().await?;
+
+            let root_signature = self.vault.sign(&root_key, change_id.as_ref()).await?;
+            signatures.push(Signature::new(SignatureType::RootSign, root_signature));
+        }
+
+        Ok(IdentitySignedChange::new(
+            change_id,
+            change_block,
+            signatures,
+        ))
+    }
+}
+
+impl IdentityChangeHistory {
+    /// Return the controllers declared by the last `SetControllers` change
+    pub(crate) fn find_controllers(
+        existing_changes: &[IdentitySignedChange],
+    ) -> Option<&SetControllersChangeData> {
+        existing_changes
+            .iter()
+            .rev()
+            .find_map(|c| match c.change() {
+                IdentityChange::SetControllers(data) => Some(data),
+                _ => None,
+            })
+    }
+}
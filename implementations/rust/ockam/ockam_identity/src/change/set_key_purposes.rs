@@ -0,0 +1,120 @@
+use crate::change::{IdentityChange, IdentitySignedChange, Signature, SignatureType};
+use crate::change_history::IdentityChangeHistory;
+use crate::{ChangeIdentifier, Identity, IdentityError, IdentityVault, KeyPurpose};
+use core::fmt;
+use ockam_core::compat::{string::String, vec::Vec};
+use ockam_core::{Encodable, Result};
+use serde::{Deserialize, Serialize};
+
+/// SetKeyPurposesChangeData
+///
+/// Restricts the key with the given label, including the keys it is
+/// rotated to, to the given purposes.  An empty list lifts the
+/// restriction.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetKeyPurposesChangeData {
+    prev_change_id: ChangeIdentifier,
+    label: String,
+    purposes: Vec<KeyPurpose>,
+}
+
+impl SetKeyPurposesChangeData {
+    /// Return the label of the restricted key
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+    /// Return the purposes the key is restricted to
+    pub fn purposes(&self) -> &[KeyPurpose] {
+        &self.purposes
+    }
+    /// Previous change identifier, used to create a chain
+    pub fn prev_change_id(&self) -> &ChangeIdentifier {
+        &self.prev_change_id
+    }
+}
+
+impl SetKeyPurposesChangeData {
+    /// Create SetKeyPurposesChangeData
+    pub fn new(prev_change_id: ChangeIdentifier, label: String, purposes: Vec<KeyPurpose>) -> Self {
+        Self {
+            prev_change_id,
+            label,
+            purposes,
+        }
+    }
+}
+
+impl fmt::Display for SetKeyPurposesChangeData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "prev_change_id:{} label:{} purposes:",
+            self.prev_change_id(),
+            self.label()
+        )?;
+        for p in self.purposes() {
+            write!(f, " {}", p)?;
+        }
+        Ok(())
+    }
+}
+
+impl<V: IdentityVault> Identity<V> {
+    /// Set key purposes change
+    pub(crate) async fn make_set_key_purposes_change(
+        &self,
+        label: &str,
+        purposes: &[KeyPurpose],
+    ) -> Result<IdentitySignedChange> {
+        let change_history = self.change_history.read().await;
+        let prev_change_id = change_history.get_last_change_id()?;
+
+        // Only keys that can still be used can be restricted
+        IdentityChangeHistory::find_last_valid_key_change(change_history.as_ref(), label)?;
+
+        let data = SetKeyPurposesChangeData::new(prev_change_id, label.into(), purposes.into());
+
+        let change_block = IdentityChange::SetKeyPurposes(data);
+        let change_block_binary = change_block
+            .encode()
+            .map_err(|_| IdentityError::BareError)?;
+
+        let change_id = self.vault.sha256(&change_block_binary).await?;
+        let change_id = ChangeIdentifier::from_hash(change_id);
+
+        let mut signatures = Vec::new();
+
+        if change_history.controllers().is_none() {
+            let root_key = self.get_root_secret_key().await?;
+
+            let root_signature = self.vault.sign(&root_key, change_id.as_ref()).await?;
+            signatures.push(Signature::new(SignatureType::RootSign, root_signature));
+        }
+
+        Ok(IdentitySignedChange::new(
+            change_id,
+            change_block,
+            signatures,
+        ))
+    }
+}
+
+impl IdentityChangeHistory {
+    /// Return the purposes the key with the given label is restricted
+    /// to, empty if it is unrestricted
+    pub(crate) fn find_key_purposes<'a>(
+        existing_changes: &'a [IdentitySignedChange],
+        label: &str,
+    ) -> &'a [KeyPurpose] {
+        existing_changes
+            .iter()
+            .rev()
+            .find_map(|c| match c.change() {
+                IdentityChange::SetKeyPurposes(data) if data.label() == label => {
+                    Some(data.purposes())
+                }
+                _ => None,
+            })
+            .unwrap_or(&[])
+    }
+}
@@ -1,8 +1,11 @@
 //! Identity history
-use crate::change::IdentityChange::{CreateKey, RotateKey};
-use crate::change::{IdentitySignedChange, SignatureType};
+use crate::change::IdentityChange::{
+    CreateKey, RevokeKey, RotateKey, SetControllers, SetKeyPurposes,
+};
+use crate::change::{IdentitySignedChange, SetControllersChangeData, SignatureType};
 use crate::{
     ChangeIdentifier, IdentityError, IdentityIdentifier, IdentityStateConst, IdentityVault,
+    KeyPurpose,
 };
 use core::cmp::Ordering;
 use core::fmt;
@@ -36,7 +39,6 @@ impl fmt::Display for IdentityChangeHistory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Change History:")?;
         for (i_num, ident) in self.0.iter().enumerate() {
-            let public_key = ident.change().public_key().unwrap();
             writeln!(f, "  Change[{}]:", i_num)?;
             writeln!(f, "    identifier: {}", ident.identifier())?;
             writeln!(f, "    change:")?;
@@ -45,8 +47,21 @@ impl fmt::Display for IdentityChangeHistory {
                 "      prev_change_identifier: {}",
                 ident.change().previous_change_identifier()
             )?;
-            writeln!(f, "      label:        {}", ident.change().label())?;
-            writeln!(f, "      public_key:   {}", public_key)?;
+            if let SetControllers(data) = ident.change() {
+                writeln!(f, "      threshold:    {}", data.threshold())?;
+                for controller in data.controllers() {
+                    writeln!(f, "      controller:   {}", controller)?;
+                }
+            } else if let SetKeyPurposes(data) = ident.change() {
+                writeln!(f, "      label:        {}", data.label())?;
+                for purpose in data.purposes() {
+                    writeln!(f, "      purpose:      {}", purpose)?;
+                }
+            } else {
+                let public_key = ident.change().public_key().unwrap();
+                writeln!(f, "      label:        {}", ident.change().label().unwrap())?;
+                writeln!(f, "      public_key:   {}", public_key)?;
+            }
             writeln!(f, "    signatures:")?;
             for (sig_num, sig) in ident.signatures().iter().enumerate() {
                 writeln!(f, "      [{}]: {}", sig_num, sig)?;
@@ -130,6 +145,25 @@ impl IdentityChangeHistory {
         Self::get_public_key_static(self.as_ref(), label)
     }
 
+    /// Return the public key with the given label if it may be used
+    /// for `purpose` and has not been revoked
+    pub fn get_public_key_for(&self, label: &str, purpose: KeyPurpose) -> Result<PublicKey> {
+        let change = Self::find_last_valid_key_change(self.as_ref(), label)?;
+        let purposes = Self::find_key_purposes(self.as_ref(), label);
+        if !purposes.is_empty() && !purposes.contains(&purpose) {
+            return Err(IdentityError::KeyPurposeMismatch.into());
+        }
+        change.change().public_key()
+    }
+
+    /// Whether the key with the given label has been revoked
+    pub fn is_revoked(&self, label: &str) -> bool {
+        matches!(
+            Self::find_last_key_change(self.as_ref(), label).map(|c| c.change()),
+            Ok(RevokeKey(_))
+        )
+    }
+
     pub fn get_first_root_public_key(&self) -> Result<PublicKey> {
         // TODO: Support root key rotation
         let root_change = match self.as_ref().first() {
@@ -147,10 +181,28 @@ impl IdentityChangeHistory {
         Ok(root_create_key_change.public_key().clone())
     }
 
+    /// Labels of the keys of the identity, in order of creation
+    pub fn key_labels(&self) -> Vec<&str> {
+        let mut labels: Vec<&str> = Vec::new();
+        for change in self.0.iter() {
+            if let Some(label) = change.change().label() {
+                if !labels.contains(&label) {
+                    labels.push(label);
+                }
+            }
+        }
+        labels
+    }
+
     pub fn get_root_public_key(&self) -> Result<PublicKey> {
         self.get_public_key(IdentityStateConst::ROOT_LABEL)
     }
 
+    /// Return the current controllers, if the identity has any
+    pub fn controllers(&self) -> Option<&SetControllersChangeData> {
+        Self::find_controllers(self.as_ref())
+    }
+
     pub async fn verify_all_existing_changes(&self, vault: &impl IdentityVault) -> Result<bool> {
         for i in 0..self.0.len() {
             let existing_changes = &self.as_ref()[..i];
@@ -189,11 +241,23 @@ impl IdentityChangeHistory {
             .ok_or_else(|| IdentityError::InvalidInternalState.into())
     }
 
+    /// Like `find_last_key_change`, but fails if the key has been revoked
+    pub(crate) fn find_last_valid_key_change<'a>(
+        existing_changes: &'a [IdentitySignedChange],
+        label: &str,
+    ) -> Result<&'a IdentitySignedChange> {
+        let change = Self::find_last_key_change(existing_changes, label)?;
+        if let RevokeKey(_) = change.change() {
+            return Err(IdentityError::KeyRevoked.into());
+        }
+        Ok(change)
+    }
+
     pub(crate) fn find_last_key_change_public_key(
         existing_changes: &[IdentitySignedChange],
         label: &str,
     ) -> Result<PublicKey> {
-        let last_key_change = Self::find_last_key_change(existing_changes, label)?;
+        let last_key_change = Self::find_last_valid_key_change(existing_changes, label)?;
 
         last_key_change.change().public_key()
     }
@@ -208,7 +272,7 @@ impl IdentityChangeHistory {
         changes: &[IdentitySignedChange],
         label: &str,
     ) -> Result<PublicKey> {
-        let change = Self::find_last_key_change(changes, label)?;
+        let change = Self::find_last_valid_key_change(changes, label)?;
         change.change().public_key()
     }
 
@@ -257,8 +321,63 @@ impl IdentityChangeHistory {
                     root_sign: 1,
                 }
             }
+            RevokeKey(data) => {
+                // The root key can't be revoked, only rotated
+                if data.key_attributes().label() == IdentityStateConst::ROOT_LABEL {
+                    return deny();
+                }
+                // The revoked key must be the current key with that label
+                match Self::find_last_valid_key_change(
+                    existing_changes,
+                    data.key_attributes().label(),
+                ) {
+                    Ok(c) if &c.change().public_key()? == data.public_key() => {}
+                    _ => return deny(),
+                }
+                // Should only have root signature
+                SignaturesCheck {
+                    self_sign: 0,
+                    prev_sign: 0,
+                    root_sign: 1,
+                }
+            }
+            SetKeyPurposes(data) => {
+                // The restricted key must exist and not be revoked
+                if Self::find_last_valid_key_change(existing_changes, data.label()).is_err() {
+                    return deny();
+                }
+                // Should only have root signature
+                SignaturesCheck {
+                    self_sign: 0,
+                    prev_sign: 0,
+                    root_sign: 1,
+                }
+            }
+            SetControllers(data) => {
+                if existing_changes.is_empty() || !data.is_valid() {
+                    return deny();
+                }
+                // Should only have root signature
+                SignaturesCheck {
+                    self_sign: 0,
+                    prev_sign: 0,
+                    root_sign: 1,
+                }
+            }
         };
 
+        // Once an identity has controllers, they approve changes instead of the root key.
+        // Rotating the root key then doesn't need a signature of the previous root key,
+        // so that it can be replaced even if it was lost.
+        let controllers = Self::find_controllers(existing_changes);
+        if controllers.is_some() {
+            signatures_check.root_sign = 0;
+            if new_change.change().label() == Some(IdentityStateConst::ROOT_LABEL) {
+                signatures_check.prev_sign = 0;
+            }
+        }
+        let mut controller_signed = vec![false; controllers.map_or(0, |c| c.controllers().len())];
+
         for signature in new_change.signatures() {
             let counter;
             let public_key = match signature.stype() {
@@ -276,7 +395,38 @@ impl IdentityChangeHistory {
                 }
                 SignatureType::PrevSign => {
                     counter = &mut signatures_check.prev_sign;
-                    Self::get_public_key_static(existing_changes, new_change.change().label())?
+                    // Only changes to a key have a previous key to sign with
+                    let label = match new_change.change().label() {
+                        Some(label) => label,
+                        None => return deny(),
+                    };
+                    Self::get_public_key_static(existing_changes, label)?
+                }
+                SignatureType::ControllerSign => {
+                    let controllers = match controllers {
+                        Some(controllers) => controllers.controllers(),
+                        None => return Err(IdentityError::VerifyFailed.into()),
+                    };
+
+                    // Each controller is counted once
+                    let mut verified = false;
+                    for (i, controller) in controllers.iter().enumerate() {
+                        if controller_signed[i] {
+                            continue;
+                        }
+                        if vault
+                            .verify(signature.data(), controller, change_id.as_ref())
+                            .await?
+                        {
+                            controller_signed[i] = true;
+                            verified = true;
+                            break;
+                        }
+                    }
+                    if !verified {
+                        return deny();
+                    }
+                    continue;
                 }
             };
 
@@ -294,9 +444,12 @@ impl IdentityChangeHistory {
             *counter -= 1;
         }
 
+        let threshold = controllers.map_or(0, |c| c.threshold() as usize);
+
         if signatures_check.prev_sign == 0
             && signatures_check.root_sign == 0
             && signatures_check.self_sign == 0
+            && controller_signed.iter().filter(|s| **s).count() >= threshold
         {
             allow()
         } else {
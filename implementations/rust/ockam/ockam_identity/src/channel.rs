@@ -87,6 +87,10 @@ impl<V: IdentityVault> Identity<V> {
     pub async fn stop_secure_channel(&self, channel: &Address) -> Result<()> {
         self.ctx.stop_worker(channel.clone()).await
     }
+
+    pub async fn stop_secure_channel_listener(&self, address: &Address) -> Result<()> {
+        self.ctx.stop_worker(address.clone()).await
+    }
 }
 
 #[cfg(test)]
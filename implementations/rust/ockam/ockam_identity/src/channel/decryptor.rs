@@ -1,8 +1,8 @@
 use crate::authenticated_storage::AuthenticatedStorage;
 use crate::{
     EncryptorWorker, Identity, IdentityChannelMessage, IdentityError, IdentityIdentifier,
-    IdentitySecureChannelLocalInfo, IdentityVault, PublicIdentity, SecureChannelTrustInfo,
-    TrustPolicy,
+    IdentitySecureChannelLocalInfo, IdentityVault, KeyPurpose, PeerHistoryNotification,
+    PublicIdentity, SecureChannelTrustInfo, TrustPolicy,
 };
 use core::future::Future;
 use core::pin::Pin;
@@ -13,7 +13,7 @@ use ockam_channel::{
 };
 use ockam_core::compat::{boxed::Box, sync::Arc, vec::Vec};
 use ockam_core::vault::Signature;
-use ockam_core::{async_trait, AllowAll, Mailbox, Mailboxes};
+use ockam_core::{async_trait, AllowAll, AllowSourceAddress, DenyAll, Mailbox, Mailboxes};
 use ockam_core::{
     route, Address, Any, Decodable, Encodable, LocalMessage, Message, Result, Route, Routed,
     TransportMessage, Worker,
@@ -71,6 +71,7 @@ enum State {
 pub(crate) struct DecryptorWorker<V: IdentityVault, S: AuthenticatedStorage> {
     is_initiator: bool,
     self_address: Address,
+    notify_address: Address,
     kex_callback_address: Option<Address>,
     identity: Identity<V>,
     storage: S,
@@ -105,6 +106,9 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
         let mut child_ctx = ctx.new_detached_with_mailboxes(mailboxes).await?;
 
         let self_address = Address::random_tagged("IdentitySecureChannel.initiator.decryptor.self");
+        let notify_address =
+            Address::random_tagged("IdentitySecureChannel.initiator.decryptor.notify");
+        let identity_notifier = identity.notifier.address();
 
         let vault = identity.vault.async_try_clone().await?;
         let initiator = XXNewKeyExchanger::new(vault.async_try_clone().await?)
@@ -130,6 +134,7 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
         let worker = DecryptorWorker {
             is_initiator: true,
             self_address: self_address.clone(),
+            notify_address: notify_address.clone(),
             kex_callback_address: None,
             identity,
             trust_policy,
@@ -145,7 +150,12 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
             Arc::new(ockam_core::ToDoAccessControl),
             Arc::new(ockam_core::ToDoAccessControl),
         );
-        WorkerBuilder::with_mailboxes(Mailboxes::new(mailbox, vec![]), worker)
+        let notify_mailbox = Mailbox::new(
+            notify_address,
+            Arc::new(AllowSourceAddress(identity_notifier)),
+            Arc::new(DenyAll),
+        );
+        WorkerBuilder::with_mailboxes(Mailboxes::new(mailbox, vec![notify_mailbox]), worker)
             .start(ctx)
             .await?;
 
@@ -182,6 +192,9 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
         let first_responder_address = Address::decode(custom_payload)?;
 
         let self_address = Address::random_tagged("IdentitySecureChannel.responder.decryptor.self");
+        let notify_address =
+            Address::random_tagged("IdentitySecureChannel.responder.decryptor.notify");
+        let identity_notifier = identity.notifier.address();
 
         let vault = identity.vault.async_try_clone().await?;
         let state = State::ResponderWaitForKex(ResponderWaitForKex {
@@ -194,6 +207,7 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
         let worker = DecryptorWorker {
             is_initiator: false,
             self_address: self_address.clone(),
+            notify_address: notify_address.clone(),
             identity,
             trust_policy,
             storage,
@@ -204,11 +218,18 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
         // TODO: @ac
         let mailboxes = Mailboxes::new(
             Mailbox::allow_all(self_address.clone()),
-            vec![Mailbox::new(
-                kex_callback_address.clone(),
-                Arc::new(AllowAll), // TODO: @ac only kex
-                Arc::new(AllowAll), // TODO: @ac deny all
-            )],
+            vec![
+                Mailbox::new(
+                    kex_callback_address.clone(),
+                    Arc::new(AllowAll), // TODO: @ac only kex
+                    Arc::new(AllowAll), // TODO: @ac deny all
+                ),
+                Mailbox::new(
+                    notify_address,
+                    Arc::new(AllowSourceAddress(identity_notifier)),
+                    Arc::new(DenyAll),
+                ),
+            ],
         );
         WorkerBuilder::with_mailboxes(mailboxes, worker)
             .start(ctx)
@@ -316,7 +337,8 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
 
             // Verify responder posses their Identity key
             let verified = their_identity
-                .verify_signature(
+                .verify_signature_for(
+                    KeyPurpose::SecureChannel,
                     &Signature::new(signature),
                     &state.channel.auth_hash(),
                     None,
@@ -375,6 +397,7 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
                 their_identity_id: their_identity_id.clone(),
                 encryptor_address: encryptor_address.clone(),
             }));
+            self.register(their_identity_id).await;
 
             let encryptor = EncryptorWorker::new(
                 self.is_initiator,
@@ -431,7 +454,8 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
 
             // Verify initiator posses their Identity key
             let verified = their_identity
-                .verify_signature(
+                .verify_signature_for(
+                    KeyPurpose::SecureChannel,
                     &Signature::new(signature),
                     &state.auth_hash,
                     None,
@@ -474,6 +498,7 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
                 their_identity_id: their_identity_id.clone(),
                 encryptor_address: encryptor_address.clone(),
             }));
+            self.register(their_identity_id).await;
 
             let encryptor = EncryptorWorker::new(
                 self.is_initiator,
@@ -495,6 +520,78 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
         }
     }
 
+    async fn handle_control(
+        &mut self,
+        ctx: &mut <Self as Worker>::Context,
+        payload: &[u8],
+        state: Initialized,
+    ) -> Result<()> {
+        match IdentityChannelMessage::decode(payload)? {
+            IdentityChannelMessage::Disconnect => {
+                info!(
+                    "IdentitySecureChannel with {} was closed by the other side",
+                    state.their_identity_id
+                );
+                self.close(ctx, &state).await
+            }
+            _ => Err(IdentityError::UnknownChannelMsgDestination.into()),
+        }
+    }
+
+    /// Make this channel known to the identity directory
+    async fn register(&mut self, their_identity_id: &IdentityIdentifier) {
+        self.identity
+            .secure_channels
+            .write()
+            .await
+            .insert(self.notify_address.clone(), their_identity_id.clone());
+    }
+
+    async fn close(
+        &mut self,
+        ctx: &mut <Self as Worker>::Context,
+        state: &Initialized,
+    ) -> Result<()> {
+        ctx.stop_worker(state.encryptor_address.clone()).await?;
+        ctx.stop_worker(self.self_address.clone()).await
+    }
+
+    /// The change history of our peer changed: check that we still
+    /// trust it, otherwise close the channel
+    async fn handle_notification(
+        &mut self,
+        ctx: &mut <Self as Worker>::Context,
+        msg: Routed<<Self as Worker>::Message>,
+        state: Initialized,
+    ) -> Result<()> {
+        self.state = Some(State::Initialized(state.clone()));
+
+        match PeerHistoryNotification::decode(msg.payload())? {
+            PeerHistoryNotification::Updated => {
+                let trust_info = SecureChannelTrustInfo::new(state.their_identity_id.clone());
+                if self.trust_policy.check(&trust_info).await? {
+                    info!(
+                        "IdentitySecureChannel peer {} has a new change history",
+                        state.their_identity_id
+                    );
+                    return Ok(());
+                }
+                warn!(
+                    "IdentitySecureChannel peer {} is no longer trusted, closing the channel",
+                    state.their_identity_id
+                );
+            }
+            PeerHistoryNotification::Conflict => {
+                warn!(
+                    "IdentitySecureChannel peer {} has a conflicting change history, closing the channel",
+                    state.their_identity_id
+                );
+            }
+        }
+
+        self.close(ctx, &state).await
+    }
+
     // FIXME: Avoid situation where we take state but don't put it back because of an error
     fn take_state(&mut self) -> Result<State> {
         if let Some(s) = self.state.take() {
@@ -536,6 +633,11 @@ impl<V: IdentityVault, S: AuthenticatedStorage> DecryptorWorker<V, S> {
         // Forward to local workers
         let _ = onward_route.step()?;
 
+        // A message addressed to us only is a channel control message
+        if onward_route.next().is_err() {
+            return self.handle_control(ctx, &payload, state).await;
+        }
+
         let return_route = return_route
             .modify()
             .pop_front()
@@ -586,6 +688,17 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Worker for DecryptorWorker<V, S>
         Ok(())
     }
 
+    async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        // However the channel went away, stop notifying it
+        self.identity
+            .secure_channels
+            .write()
+            .await
+            .remove(&self.notify_address);
+
+        Ok(())
+    }
+
     async fn handle_message(
         &mut self,
         ctx: &mut Self::Context,
@@ -625,6 +738,8 @@ impl<V: IdentityVault, S: AuthenticatedStorage> Worker for DecryptorWorker<V, S>
             State::Initialized(s) => {
                 if msg_addr == self.self_address {
                     self.handle_decrypt(ctx, msg, s).await?;
+                } else if msg_addr == self.notify_address {
+                    self.handle_notification(ctx, msg, s).await?;
                 } else {
                     return Err(IdentityError::UnknownChannelMsgDestination.into());
                 }
@@ -1,9 +1,13 @@
 use ockam_core::async_trait;
 use ockam_core::compat::{boxed::Box, vec::Vec};
-use ockam_core::{Address, Any, LocalMessage, Result, Routed, TransportMessage, Worker};
+use ockam_core::{
+    route, Address, Any, Encodable, LocalMessage, Result, Routed, TransportMessage, Worker,
+};
 use ockam_node::Context;
 use tracing::debug;
 
+use crate::IdentityChannelMessage;
+
 pub(crate) struct EncryptorWorker {
     is_initiator: bool,
     remote_identity_secure_channel_address: Address,
@@ -39,6 +43,7 @@ impl EncryptorWorker {
 
         let mut onward_route = msg.onward_route();
         let return_route = msg.return_route();
+        let priority = msg.local_message().priority();
         let payload = msg.payload().to_vec();
 
         // Send to the other party using local regular SecureChannel
@@ -50,7 +55,7 @@ impl EncryptorWorker {
 
         let transport_msg = TransportMessage::v1(onward_route, return_route, payload);
 
-        ctx.forward(LocalMessage::new(transport_msg, Vec::new()))
+        ctx.forward(LocalMessage::new(transport_msg, Vec::new()).with_priority(priority))
             .await?;
 
         Ok(())
@@ -69,4 +74,21 @@ impl Worker for EncryptorWorker {
     ) -> Result<()> {
         self.handle_encrypt(ctx, msg).await
     }
+
+    /// Notify the other party that this channel is going away
+    async fn drain(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        debug!("IdentitySecureChannel sending Disconnect");
+
+        // A message without further onward route is handled by the
+        // remote decryptor itself
+        let onward_route = route![
+            self.local_secure_channel_address.clone(),
+            self.remote_identity_secure_channel_address.clone()
+        ];
+        let payload = IdentityChannelMessage::Disconnect.encode()?;
+        let transport_msg = TransportMessage::v1(onward_route, route![ctx.address()], payload);
+
+        ctx.forward(LocalMessage::new(transport_msg, Vec::new()))
+            .await
+    }
 }
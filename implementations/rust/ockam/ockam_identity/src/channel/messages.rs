@@ -13,4 +13,17 @@ pub(crate) enum IdentityChannelMessage {
         signature: Vec<u8>,
     },
     Confirm,
+    /// Sent through an established channel when one side goes away
+    Disconnect,
+}
+
+/// Sent by the identity directory to the decryptor of every channel
+/// whose peer has a new change history
+#[derive(Clone, Serialize, Deserialize, Message)]
+pub(crate) enum PeerHistoryNotification {
+    /// The peer's history advanced
+    Updated,
+    /// The peer's history doesn't match the one the channel was
+    /// established with
+    Conflict,
 }
@@ -1,13 +1,21 @@
 #![allow(missing_docs)]
 
+mod disclosure;
 mod identity;
 mod public_identity;
+mod schema;
 mod storage_utils;
 mod worker;
 
+#[cfg(feature = "std")]
+mod w3c;
+
 pub mod access_control;
 
+pub use schema::*;
 pub use storage_utils::*;
+#[cfg(feature = "std")]
+pub use w3c::*;
 
 use crate::IdentityIdentifier;
 use core::fmt;
@@ -49,11 +57,45 @@ pub struct Credential<'a> {
     #[b(1)] data: CowBytes<'a>,
     /// Cryptographic signature of attributes data.
     #[b(2)] signature: CowBytes<'a>,
+    /// CBOR-encoded openings of the committed attributes the holder
+    /// discloses, not covered by the signature.
+    #[b(3)] disclosures: Option<CowBytes<'a>>,
 }
 
 impl fmt::Display for Credential<'_> {
     #[cfg(feature = "std")]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with_schema(f, None)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:?}", self)
+    }
+}
+
+impl Credential<'_> {
+    /// Display this credential, with its attributes formatted and
+    /// checked according to its schema if it is registered
+    #[cfg(feature = "std")]
+    pub fn display_with<'r>(&'r self, schemas: &'r SchemaRegistry) -> impl fmt::Display + 'r {
+        struct WithSchema<'r>(&'r Credential<'r>, &'r SchemaRegistry);
+
+        impl fmt::Display for WithSchema<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let schema = CredentialData::try_from(self.0)
+                    .ok()
+                    .and_then(|d| d.schema)
+                    .and_then(|id| self.1.get(id));
+                self.0.fmt_with_schema(f, schema.as_ref())
+            }
+        }
+
+        WithSchema(self, schemas)
+    }
+
+    #[cfg(feature = "std")]
+    fn fmt_with_schema(&self, f: &mut fmt::Formatter<'_>, schema: Option<&Schema>) -> fmt::Result {
         let credential_data = CredentialData::try_from(self).map_err(|_| fmt::Error)?;
         writeln!(f, "---")?;
         writeln!(f, " Subject: {}", credential_data.subject)?;
@@ -62,27 +104,45 @@ impl fmt::Display for Credential<'_> {
             " Issuer: {} ({})",
             credential_data.issuer, credential_data.issuer_key_label
         )?;
+        match (credential_data.schema, schema) {
+            (Some(id), Some(schema)) => writeln!(f, " Schema: {} ({})", schema.name(), id.0)?,
+            (Some(id), None) => writeln!(f, " Schema: {}", id.0)?,
+            (None, _) => {}
+        }
         //TODO: write timestamps on human-readable format. Should we add a dependency for this?
         writeln!(f, " Created: {}", u64::from(credential_data.created))?;
         writeln!(f, " Expires: {}", u64::from(credential_data.expires))?;
         write!(f, " Attributes: ")?;
-        f.debug_map()
-            .entries(
-                credential_data
-                    .attributes
-                    .iter()
-                    .map(|(k, v)| (k, std::str::from_utf8(v).unwrap_or("**binary**"))),
-            )
-            .finish()?;
+        match schema {
+            Some(schema) => f
+                .debug_map()
+                .entries(
+                    credential_data
+                        .attributes
+                        .iter()
+                        .map(|(k, v)| (k, schema.format_value(k, v))),
+                )
+                .finish()?,
+            None => f
+                .debug_map()
+                .entries(
+                    credential_data
+                        .attributes
+                        .iter()
+                        .map(|(k, v)| (k, std::str::from_utf8(v).unwrap_or("**binary**"))),
+                )
+                .finish()?,
+        }
         writeln!(f, "\n")?;
+        if let Some(schema) = schema {
+            let data = credential_data.into_verified();
+            if let Err(e) = schema.validate_data(&data) {
+                writeln!(f, " Schema violation: {}", e)?;
+            }
+        }
         writeln!(f, " Signature: {}", hex::encode(self.signature.deref()))?;
         writeln!(f, "---")
     }
-
-    #[cfg(not(feature = "std"))]
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "{:?}", self)
-    }
 }
 
 #[derive(Debug, Decode, Encode)]
@@ -104,7 +164,21 @@ pub struct CredentialData<'a, T> {
     /// The time this credential expires.
     #[n(7)] expires: Timestamp,
     /// Term to represent the verification status type.
-    #[n(8)] status: Option<PhantomData<T>>
+    #[n(8)] status: Option<PhantomData<T>>,
+    /// Set if the attribute values are commitments, see [`Commitment`].
+    #[n(9)] commitment: Option<Commitment>,
+}
+
+/// How the attribute values of a credential commit to the actual values.
+///
+/// The actual values are disclosed separately, so that a holder can
+/// present only some of them.
+#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum Commitment {
+    /// SHA-256 of a random salt followed by the value.
+    #[n(1)] SaltedSha256,
 }
 
 impl<'a> CredentialData<'a, Unverified> {
@@ -118,6 +192,7 @@ impl<'a> CredentialData<'a, Unverified> {
             created: self.created,
             expires: self.expires,
             status: None::<PhantomData<Verified>>,
+            commitment: self.commitment,
         }
     }
 }
@@ -129,6 +204,7 @@ impl<'a> Credential<'a> {
             subject,
             attrs: Attributes::new(),
             validity: MAX_CREDENTIAL_VALIDITY,
+            selective: false,
         }
     }
 
@@ -150,6 +226,7 @@ impl<'a> Credential<'a> {
             tag: TypeTag,
             data: CowBytes(data.into()),
             signature: CowBytes(signature.into()),
+            disclosures: None,
         }
     }
 
@@ -159,6 +236,7 @@ impl<'a> Credential<'a> {
             tag: TypeTag,
             data: self.data.to_owned(),
             signature: self.signature.to_owned(),
+            disclosures: self.disclosures.as_ref().map(|d| d.to_owned()),
         }
     }
 }
@@ -195,6 +273,10 @@ impl<'a> CredentialData<'a, Verified> {
     pub fn into_attributes(self) -> Attributes<'a> {
         self.attributes
     }
+
+    pub fn commitment(&self) -> Option<Commitment> {
+        self.commitment
+    }
 }
 
 impl<'a> CredentialData<'a, Unverified> {
@@ -204,6 +286,12 @@ impl<'a> CredentialData<'a, Unverified> {
     pub fn unverfied_key_label(&self) -> &str {
         &self.issuer_key_label
     }
+    pub fn unverified_created_at(&self) -> Timestamp {
+        self.created
+    }
+    pub fn unverified_expires_at(&self) -> Timestamp {
+        self.expires
+    }
 }
 
 impl<'a, 'b: 'a> TryFrom<&'b Credential<'a>> for CredentialData<'a, Unverified> {
@@ -315,6 +403,7 @@ pub struct CredentialBuilder<'a> {
     attrs: Attributes<'a>,
     subject: IdentityIdentifier,
     validity: Duration,
+    selective: bool,
 }
 
 impl<'a> CredentialBuilder<'a> {
@@ -330,6 +419,15 @@ impl<'a> CredentialBuilder<'a> {
         self
     }
 
+    /// Commit to each attribute separately, so that the holder can
+    /// disclose only some of them.
+    ///
+    /// See [`Credential::disclose`].
+    pub fn with_selective_disclosure(mut self) -> Self {
+        self.selective = true;
+        self
+    }
+
     /// Set validity duration of the credential.
     ///
     /// # Panics
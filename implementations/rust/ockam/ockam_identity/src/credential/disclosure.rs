@@ -0,0 +1,158 @@
+use crate::credential::{Attributes, Commitment, Credential, CredentialData, Verified};
+use minicbor::bytes::ByteSlice;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::{collections::BTreeMap, rand::random, vec::Vec};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{CowBytes, Error, Result};
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "tag")]
+use crate::TypeTag;
+
+const SALT_LENGTH: usize = 16;
+
+/// Openings of committed attributes, by attribute name.
+#[derive(Debug, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+struct Disclosures<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2961054>,
+    #[b(1)] openings: BTreeMap<&'a str, Opening<'a>>,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+struct Opening<'a> {
+    #[b(1)] salt: &'a ByteSlice,
+    #[b(2)] value: &'a ByteSlice,
+}
+
+impl<'a> Disclosures<'a> {
+    fn new(openings: BTreeMap<&'a str, Opening<'a>>) -> Self {
+        Disclosures {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            openings,
+        }
+    }
+}
+
+fn salted_sha256(salt: &[u8], value: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(value);
+    hasher.finalize().into()
+}
+
+/// Attributes committed to by an issuer, with their openings.
+pub(crate) struct Committed<'a> {
+    commitments: BTreeMap<&'a str, [u8; 32]>,
+    disclosures: Vec<u8>,
+}
+
+impl<'a> Committed<'a> {
+    /// Commit to each attribute with a fresh salt.
+    pub(crate) fn new(attributes: &Attributes<'a>) -> Result<Self> {
+        let salts: Vec<[u8; SALT_LENGTH]> = attributes.iter().map(|_| random()).collect();
+
+        let mut commitments = BTreeMap::new();
+        let mut openings = BTreeMap::new();
+        for ((k, v), salt) in attributes.attrs.iter().zip(&salts) {
+            commitments.insert(*k, salted_sha256(salt, v));
+            let opening = Opening {
+                salt: salt[..].into(),
+                value: v,
+            };
+            openings.insert(*k, opening);
+        }
+
+        Ok(Committed {
+            commitments,
+            disclosures: minicbor::to_vec(Disclosures::new(openings))?,
+        })
+    }
+
+    /// The attributes to sign, whose values are the commitments.
+    pub(crate) fn attributes(&self) -> Attributes<'_> {
+        let mut attributes = Attributes::new();
+        for (k, v) in &self.commitments {
+            attributes.put(k, v);
+        }
+        attributes
+    }
+
+    pub(crate) fn into_disclosures(self) -> Vec<u8> {
+        self.disclosures
+    }
+}
+
+impl<'a> Credential<'a> {
+    /// Return a copy of this credential that discloses only the given
+    /// attributes
+    ///
+    /// The credential must have been issued with
+    /// [`CredentialBuilder::with_selective_disclosure`](crate::credential::CredentialBuilder::with_selective_disclosure).
+    pub fn disclose(&self, names: &[&str]) -> Result<Credential<'static>> {
+        let disclosures = self
+            .disclosures
+            .as_ref()
+            .ok_or_else(|| invalid("credential doesn't support selective disclosure"))?;
+        let disclosures: Disclosures = minicbor::decode(disclosures)?;
+
+        let mut openings = BTreeMap::new();
+        for name in names {
+            let opening = disclosures
+                .openings
+                .get(name)
+                .ok_or_else(|| invalid("unknown attribute"))?;
+            openings.insert(*name, opening.clone());
+        }
+        let disclosures = minicbor::to_vec(Disclosures::new(openings))?;
+
+        let mut credential = self.to_owned();
+        credential.disclosures = Some(CowBytes::from(disclosures));
+        Ok(credential)
+    }
+
+    pub(crate) fn with_disclosures(mut self, disclosures: Vec<u8>) -> Self {
+        self.disclosures = Some(CowBytes::from(disclosures));
+        self
+    }
+}
+
+impl<'a> CredentialData<'a, Verified> {
+    /// Replace committed attributes by the values disclosed in the
+    /// credential, dropping those that aren't disclosed.
+    pub(crate) fn open<'b: 'a>(mut self, credential: &'b Credential<'b>) -> Result<Self> {
+        match self.commitment {
+            None => return Ok(self),
+            Some(Commitment::SaltedSha256) => {}
+        }
+
+        let mut attributes = Attributes::new();
+        if let Some(disclosures) = &credential.disclosures {
+            let disclosures: Disclosures<'b> = minicbor::decode(disclosures)?;
+            for (k, opening) in disclosures.openings {
+                let commitment = self
+                    .attributes
+                    .get(k)
+                    .ok_or_else(|| invalid("unknown attribute disclosed"))?;
+                if opening.salt.len() != SALT_LENGTH
+                    || salted_sha256(opening.salt, opening.value)[..] != *commitment
+                {
+                    return Err(invalid("invalid attribute disclosure"));
+                }
+                attributes.put(k, opening.value);
+            }
+        }
+
+        self.attributes = attributes;
+        Ok(self)
+    }
+}
+
+fn invalid(msg: &'static str) -> Error {
+    Error::new(Origin::Application, Kind::Invalid, msg)
+}
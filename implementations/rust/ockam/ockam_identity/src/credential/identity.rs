@@ -1,8 +1,9 @@
 use crate::authenticated_storage::AuthenticatedStorage;
+use crate::credential::disclosure::Committed;
 use crate::credential::worker::CredentialExchangeWorker;
 use crate::credential::{
-    AttributesEntry, AttributesStorageUtils, Credential, CredentialBuilder, CredentialData,
-    Timestamp, Unverified, Verified,
+    AttributesEntry, AttributesStorageUtils, Commitment, Credential, CredentialBuilder,
+    CredentialData, SchemaRegistry, Timestamp, Unverified, Verified,
 };
 use crate::{
     Identity, IdentityError, IdentityIdentifier, IdentitySecureChannelLocalInfo,
@@ -27,6 +28,11 @@ impl<V: IdentityVault> Identity<V> {
         self.credential.read().await.clone()
     }
 
+    /// The schemas credentials are validated against
+    pub fn schemas(&self) -> &SchemaRegistry {
+        &self.schemas
+    }
+
     /// Create a signed credential based on the given values.
     pub async fn issue_credential<'a>(
         &self,
@@ -36,20 +42,35 @@ impl<V: IdentityVault> Identity<V> {
         let now = Timestamp::now()
             .ok_or_else(|| Error::new(Origin::Core, Kind::Internal, "invalid system time"))?;
         let exp = Timestamp(u64::from(now).saturating_add(builder.validity.as_secs()));
+        self.schemas
+            .validate_issued(builder.schema, &builder.attrs, now, exp)?;
+        let committed = if builder.selective {
+            Some(Committed::new(&builder.attrs)?)
+        } else {
+            None
+        };
         let dat = CredentialData {
             schema: builder.schema,
-            attributes: builder.attrs,
+            attributes: match &committed {
+                Some(c) => c.attributes(),
+                None => builder.attrs,
+            },
             subject: builder.subject,
             issuer: self.identifier().clone(),
             issuer_key_label: CowStr(key_label.into()),
             created: now,
             expires: exp,
             status: None::<PhantomData<Verified>>,
+            commitment: committed.as_ref().map(|_| Commitment::SaltedSha256),
         };
         let bytes = minicbor::to_vec(&dat)?;
 
         let sig = self.create_signature(&bytes, None).await?;
-        Ok(Credential::new(bytes, SignatureVec::from(sig)))
+        let credential = Credential::new(bytes, SignatureVec::from(sig));
+        Ok(match committed {
+            Some(c) => credential.with_disclosures(c.into_disclosures()),
+            None => credential,
+        })
     }
 
     /// Start worker that will be available to receive others attributes and put them into storage,
@@ -68,8 +89,9 @@ impl<V: IdentityVault> Identity<V> {
         self.ctx.start_worker(address.into(), worker).await
     }
 
-    /// Present credential to other party, route shall use secure channel
-    pub async fn present_credential(&self, route: impl Into<Route>) -> Result<()> {
+    /// Return the credential to present, disclosing only the given
+    /// attributes if any
+    async fn credential_to_present(&self, disclose: Option<&[&str]>) -> Result<Credential<'_>> {
         let credentials = self.credential.read().await;
         let credential = credentials.as_ref().ok_or_else(|| {
             Error::new(
@@ -79,6 +101,28 @@ impl<V: IdentityVault> Identity<V> {
             )
         })?;
 
+        match disclose {
+            Some(names) => credential.disclose(names),
+            None => Ok(credential.to_owned()),
+        }
+    }
+
+    /// Present credential to other party, route shall use secure channel
+    pub async fn present_credential(&self, route: impl Into<Route>) -> Result<()> {
+        self.present_credential_disclosing(route, None).await
+    }
+
+    /// Present credential to other party, disclosing only the given
+    /// attributes if any, route shall use secure channel
+    ///
+    /// See [`Credential::disclose`].
+    pub async fn present_credential_disclosing(
+        &self,
+        route: impl Into<Route>,
+        disclose: Option<&[&str]>,
+    ) -> Result<()> {
+        let credential = self.credential_to_present(disclose).await?;
+
         let mut child_ctx = self
             .ctx
             .new_detached(Address::random_tagged(
@@ -90,7 +134,7 @@ impl<V: IdentityVault> Identity<V> {
             "credential",
             None,
             route.into(),
-            Request::post("actions/present").body(credential),
+            Request::post("actions/present").body(&credential),
         )
         .await?;
 
@@ -113,14 +157,22 @@ impl<V: IdentityVault> Identity<V> {
         authorities: impl IntoIterator<Item = &PublicIdentity>,
         authenticated_storage: &impl AuthenticatedStorage,
     ) -> Result<()> {
-        let credentials = self.credential.read().await;
-        let credential = credentials.as_ref().ok_or_else(|| {
-            Error::new(
-                Origin::Application,
-                Kind::Invalid,
-                "no credential to present",
-            )
-        })?;
+        self.present_credential_mutual_disclosing(route, None, authorities, authenticated_storage)
+            .await
+    }
+
+    /// Present credential to other party, disclosing only the given
+    /// attributes if any, and receive its credential in response
+    ///
+    /// See [`present_credential_mutual`](Self::present_credential_mutual).
+    pub async fn present_credential_mutual_disclosing(
+        &self,
+        route: impl Into<Route>,
+        disclose: Option<&[&str]>,
+        authorities: impl IntoIterator<Item = &PublicIdentity>,
+        authenticated_storage: &impl AuthenticatedStorage,
+    ) -> Result<()> {
+        let credential = self.credential_to_present(disclose).await?;
 
         let mut child_ctx = self
             .ctx
@@ -134,7 +186,7 @@ impl<V: IdentityVault> Identity<V> {
             "credential",
             None,
             route.into(),
-            Request::post(path).body(credential),
+            Request::post(path).body(&credential),
         )
         .await?;
 
@@ -170,6 +222,7 @@ impl<V: IdentityVault> Identity<V> {
         credential: &'a Credential<'a>,
         authorities: impl IntoIterator<Item = &PublicIdentity>,
         vault: &impl IdentityVault,
+        schemas: &SchemaRegistry,
     ) -> Result<CredentialData<'a, Verified>> {
         let credential_data: CredentialData<Unverified> = match minicbor::decode(&credential.data) {
             Ok(c) => c,
@@ -189,6 +242,8 @@ impl<V: IdentityVault> Identity<V> {
             Err(_) => return Err(IdentityError::CredentialVerificationFailed.into()),
         };
 
+        schemas.validate(&credential_data)?;
+
         Ok(credential_data)
     }
 
@@ -197,8 +252,14 @@ impl<V: IdentityVault> Identity<V> {
         credential: &'a Credential<'a>,
         authorities: impl IntoIterator<Item = &PublicIdentity>,
     ) -> Result<()> {
-        let _ = Self::verify_credential(self.identifier(), credential, authorities, &self.vault)
-            .await?;
+        let _ = Self::verify_credential(
+            self.identifier(),
+            credential,
+            authorities,
+            &self.vault,
+            &self.schemas,
+        )
+        .await?;
         Ok(())
     }
 
@@ -209,8 +270,14 @@ impl<V: IdentityVault> Identity<V> {
         authorities: impl IntoIterator<Item = &PublicIdentity>,
         authenticated_storage: &impl AuthenticatedStorage,
     ) -> Result<()> {
-        let credential_data =
-            Self::verify_credential(&sender, &credential, authorities, &self.vault).await?;
+        let credential_data = Self::verify_credential(
+            &sender,
+            &credential,
+            authorities,
+            &self.vault,
+            &self.schemas,
+        )
+        .await?;
 
         AttributesStorageUtils::put_attributes(
             &sender,
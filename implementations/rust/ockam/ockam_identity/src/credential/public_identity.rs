@@ -1,7 +1,7 @@
 use crate::authenticated_storage::AuthenticatedStorage;
 use crate::credential::{AttributesStorageUtils, Credential, CredentialData, Timestamp, Verified};
 use crate::PublicIdentity;
-use crate::{IdentityIdentifier, IdentityStateConst, IdentityVault};
+use crate::{IdentityIdentifier, IdentityStateConst, IdentityVault, KeyPurpose};
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::{string::String, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
@@ -56,7 +56,8 @@ impl PublicIdentity {
         let sig = Signature::new(credential.signature().to_vec());
 
         if !self
-            .verify_signature(
+            .verify_signature_for(
+                KeyPurpose::CredentialIssuance,
                 &sig,
                 credential.unverified_data(),
                 Some(dat.unverfied_key_label()),
@@ -70,7 +71,8 @@ impl PublicIdentity {
                 "invalid signature",
             ));
         }
-        Ok(dat.into_verified())
+        // Only the disclosed attributes of a committed credential are verified
+        dat.into_verified().open(credential)
     }
 
     /// Return authenticated non-expired attributes attached to that Identity
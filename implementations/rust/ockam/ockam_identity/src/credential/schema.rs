@@ -0,0 +1,270 @@
+use crate::credential::{Attributes, CredentialData, SchemaId, Timestamp, Verified};
+use core::fmt;
+use core::time::Duration;
+use ockam_core::compat::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    sync::{Arc, RwLock},
+    vec::Vec,
+};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+/// Type of the value of a credential attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeType {
+    /// Any bytes.
+    Bytes,
+    /// A UTF-8 string.
+    Utf8,
+    /// `true` or `false`.
+    Bool,
+    /// A signed decimal integer.
+    Int,
+}
+
+impl AttributeType {
+    fn is_valid(&self, value: &[u8]) -> bool {
+        let s = core::str::from_utf8(value);
+        match self {
+            AttributeType::Bytes => true,
+            AttributeType::Utf8 => s.is_ok(),
+            AttributeType::Bool => matches!(s, Ok("true" | "false")),
+            AttributeType::Int => s.map_or(false, |s| s.parse::<i64>().is_ok()),
+        }
+    }
+}
+
+impl fmt::Display for AttributeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeType::Bytes => write!(f, "bytes"),
+            AttributeType::Utf8 => write!(f, "utf8"),
+            AttributeType::Bool => write!(f, "bool"),
+            AttributeType::Int => write!(f, "int"),
+        }
+    }
+}
+
+/// Definition of an attribute of a [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeSchema {
+    name: String,
+    attribute_type: AttributeType,
+    required: bool,
+}
+
+impl AttributeSchema {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn attribute_type(&self) -> AttributeType {
+        self.attribute_type
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+}
+
+/// What the attributes of credentials with a given [`SchemaId`] mean.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schema {
+    id: SchemaId,
+    name: String,
+    attributes: Vec<AttributeSchema>,
+    max_validity: Duration,
+    additional_attributes: bool,
+}
+
+impl Schema {
+    /// Create a schema without attributes, for credentials valid at
+    /// most `max_validity`.
+    pub fn new(id: SchemaId, name: impl Into<String>, max_validity: Duration) -> Self {
+        Schema {
+            id,
+            name: name.into(),
+            attributes: Vec::new(),
+            max_validity,
+            additional_attributes: false,
+        }
+    }
+
+    /// Add an attribute definition.
+    pub fn with_attribute(
+        mut self,
+        name: impl Into<String>,
+        attribute_type: AttributeType,
+        required: bool,
+    ) -> Self {
+        self.attributes.push(AttributeSchema {
+            name: name.into(),
+            attribute_type,
+            required,
+        });
+        self
+    }
+
+    /// Accept attributes not defined by this schema, as UTF-8 strings.
+    pub fn with_additional_attributes(mut self) -> Self {
+        self.additional_attributes = true;
+        self
+    }
+
+    pub fn id(&self) -> SchemaId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn attributes(&self) -> &[AttributeSchema] {
+        &self.attributes
+    }
+
+    pub fn max_validity(&self) -> Duration {
+        self.max_validity
+    }
+
+    /// Return the definition of an attribute, if any.
+    pub fn attribute(&self, name: &str) -> Option<&AttributeSchema> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+
+    /// Check the given attribute values, without requiring those that
+    /// are missing.
+    pub fn validate_values<'a>(
+        &self,
+        values: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+    ) -> Result<()> {
+        for (name, value) in values {
+            let attribute_type = match self.attribute(name) {
+                Some(a) => a.attribute_type,
+                None if self.additional_attributes => AttributeType::Utf8,
+                None => return Err(invalid(format!("unknown attribute `{}`", name))),
+            };
+            if !attribute_type.is_valid(value) {
+                return Err(invalid(format!(
+                    "attribute `{}` is not of type {}",
+                    name, attribute_type
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the attributes and validity period of a credential.
+    pub fn validate(
+        &self,
+        attributes: &Attributes<'_>,
+        created: Timestamp,
+        expires: Timestamp,
+    ) -> Result<()> {
+        self.validate_values(attributes.iter())?;
+
+        for a in self.attributes.iter().filter(|a| a.required) {
+            if attributes.get(&a.name).is_none() {
+                return Err(invalid(format!("missing attribute `{}`", a.name)));
+            }
+        }
+
+        self.validate_validity(created, expires)
+    }
+
+    fn validate_validity(&self, created: Timestamp, expires: Timestamp) -> Result<()> {
+        match expires.elapsed(created) {
+            Some(validity) if validity <= self.max_validity => Ok(()),
+            _ => Err(invalid(format!(
+                "validity exceeds the maximum of schema `{}`",
+                self.name
+            ))),
+        }
+    }
+
+    /// Check verified credential data.
+    ///
+    /// The values of committed attributes are only known once they are
+    /// disclosed, so only the disclosed ones are checked.
+    pub fn validate_data(&self, data: &CredentialData<'_, Verified>) -> Result<()> {
+        if data.commitment.is_some() {
+            self.validate_values(data.attributes.iter())?;
+            return self.validate_validity(data.created, data.expires);
+        }
+        self.validate(&data.attributes, data.created, data.expires)
+    }
+
+    /// Format a value of the given attribute according to its type.
+    pub fn format_value(&self, name: &str, value: &[u8]) -> String {
+        let attribute_type = match self.attribute(name) {
+            Some(a) => a.attribute_type,
+            None => AttributeType::Utf8,
+        };
+        match (attribute_type, core::str::from_utf8(value)) {
+            (AttributeType::Bytes, _) | (_, Err(_)) => hex::encode(value),
+            (_, Ok(s)) => s.to_string(),
+        }
+    }
+}
+
+/// The schemas known to an identity.
+///
+/// Credentials with a registered schema are validated when they are
+/// issued and when they are received. Clones share the same schemas.
+#[derive(Clone, Default)]
+pub struct SchemaRegistry {
+    schemas: Arc<RwLock<BTreeMap<SchemaId, Schema>>>,
+}
+
+impl fmt::Debug for SchemaRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let schemas = self.schemas.read().unwrap();
+        f.debug_list().entries(schemas.values()).finish()
+    }
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a schema, replacing any schema with the same identifier.
+    pub fn register(&self, schema: Schema) {
+        let mut schemas = self.schemas.write().unwrap();
+        schemas.insert(schema.id, schema);
+    }
+
+    pub fn get(&self, id: SchemaId) -> Option<Schema> {
+        self.schemas.read().unwrap().get(&id).cloned()
+    }
+
+    /// Check credential data against its schema, if it is registered.
+    pub fn validate(&self, data: &CredentialData<'_, Verified>) -> Result<()> {
+        let schemas = self.schemas.read().unwrap();
+        match data.schema.and_then(|id| schemas.get(&id)) {
+            Some(schema) => schema.validate_data(data),
+            None => Ok(()),
+        }
+    }
+
+    /// Check the attributes about to be signed, if their schema is
+    /// registered.
+    pub(crate) fn validate_issued(
+        &self,
+        schema: Option<SchemaId>,
+        attributes: &Attributes<'_>,
+        created: Timestamp,
+        expires: Timestamp,
+    ) -> Result<()> {
+        let schemas = self.schemas.read().unwrap();
+        match schema.and_then(|id| schemas.get(&id)) {
+            Some(schema) => schema.validate(attributes, created, expires),
+            None => Ok(()),
+        }
+    }
+}
+
+fn invalid(msg: String) -> Error {
+    Error::new(Origin::Application, Kind::Invalid, msg)
+}
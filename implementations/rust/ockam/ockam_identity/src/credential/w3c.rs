@@ -0,0 +1,537 @@
+//! Conversion of credentials to and from W3C Verifiable Credentials
+//! and JWTs.
+//!
+//! Identities are represented as `did:ockam` DIDs. The proof of a
+//! converted credential is of the Ockam specific type
+//! `OckamCredentialSignature2022`: its `proofValue` is the signature of
+//! the issuer over the CBOR encoding of the credential data, which is
+//! rebuilt from the JSON document on import, so converting back and forth
+//! is lossless. It is not a Linked Data proof over the canonicalized
+//! document, so other verifiers can't check it. They can check the JWT
+//! form instead, which is signed with EdDSA by the key named in the
+//! `kid` header.
+
+use crate::credential::{Attributes, Credential, CredentialData, SchemaId, Timestamp, Verified};
+use crate::{
+    Identity, IdentityError, IdentityIdentifier, IdentityStateConst, IdentityVault, KeyPurpose,
+    PublicIdentity,
+};
+use core::marker::PhantomData;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::vault::{SecretType, Signature};
+use ockam_core::{CowStr, Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Prefix of the DIDs of Ockam identities.
+pub const DID_PREFIX: &str = "did:ockam:";
+
+const CREDENTIALS_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
+const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+const ED25519_2020_CONTEXT: &str = "https://w3id.org/security/suites/ed25519-2020/v1";
+const CREDENTIAL_TYPE: &str = "VerifiableCredential";
+const PROOF_TYPE: &str = "OckamCredentialSignature2022";
+const PROOF_PURPOSE: &str = "assertionMethod";
+const KEY_TYPE: &str = "Ed25519VerificationKey2020";
+const SCHEMA_TYPE: &str = "OckamCredentialSchema";
+const SCHEMA_PREFIX: &str = "ockam:schema:";
+/// Attribute values that aren't UTF-8 are encoded as `{"bytes": <base64>}`.
+const BYTES_KEY: &str = "bytes";
+/// Multicodec prefix of Ed25519 public keys.
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+impl IdentityIdentifier {
+    /// Return the `did:ockam` DID of this identifier
+    pub fn to_did(&self) -> String {
+        format!("{}{}", DID_PREFIX, self)
+    }
+
+    /// Parse a `did:ockam` DID
+    pub fn from_did(did: &str) -> Result<Self> {
+        did.strip_prefix(DID_PREFIX)
+            .ok_or(IdentityError::InvalidIdentityId)?
+            .try_into()
+    }
+}
+
+impl PublicIdentity {
+    /// Return the DID document of this identity
+    ///
+    /// The document lists the current keys which may issue credentials,
+    /// the root key first, so it changes when keys are rotated, revoked
+    /// or restricted to other purposes.
+    pub fn did_document(&self) -> Result<Value> {
+        let did = self.identifier().to_did();
+        let root = verification_method(&did, IdentityStateConst::ROOT_LABEL);
+
+        let mut methods = Vec::new();
+        let mut assertion = Vec::new();
+        for label in self.changes().key_labels() {
+            let public_key = match self
+                .changes()
+                .get_public_key_for(label, KeyPurpose::CredentialIssuance)
+            {
+                Ok(k) if k.stype() == SecretType::Ed25519 => k,
+                Ok(_) if label == IdentityStateConst::ROOT_LABEL => {
+                    return Err(invalid("root key is not an Ed25519 key"))
+                }
+                _ => continue,
+            };
+            let mut key = ED25519_MULTICODEC.to_vec();
+            key.extend_from_slice(public_key.data());
+            let method = verification_method(&did, label);
+            methods.push(json!({
+                "id": method,
+                "type": KEY_TYPE,
+                "controller": did,
+                "publicKeyMultibase": multibase_encode(&key),
+            }));
+            assertion.push(method);
+        }
+
+        Ok(json!({
+            "@context": [DID_CONTEXT, ED25519_2020_CONTEXT],
+            "id": did,
+            "verificationMethod": methods,
+            "authentication": [root],
+            "assertionMethod": assertion,
+        }))
+    }
+
+    /// Verify a JWT made with [`Identity::credential_to_jwt`] by this
+    /// identity and return the credential it contains
+    ///
+    /// Both the JWT signature and the credential itself are verified.
+    pub async fn verify_credential_jwt(
+        &self,
+        jwt: &str,
+        subject: &IdentityIdentifier,
+        vault: &impl IdentityVault,
+    ) -> Result<Credential<'static>> {
+        let (signing_input, signature) = jwt.rsplit_once('.').ok_or_else(invalid_jwt)?;
+        let (header, claims) = signing_input.split_once('.').ok_or_else(invalid_jwt)?;
+
+        let header: JwtHeader = decode_segment(header)?;
+        if header.alg != "EdDSA" {
+            return Err(invalid("unsupported JWT algorithm"));
+        }
+        let claims: JwtClaims = decode_segment(claims)?;
+        if claims.iss != claims.vc.issuer
+            || Some(&claims.sub) != claims.vc.credential_subject.get("id")
+        {
+            return Err(invalid_jwt());
+        }
+
+        let credential = Credential::from_verifiable_credential(&claims.vc)?;
+
+        let signature =
+            base64::decode_config(signature, base64::URL_SAFE_NO_PAD).map_err(|_| invalid_jwt())?;
+        let label = claims.vc.issuer_key_label()?;
+        if !self
+            .verify_signature_for(
+                KeyPurpose::CredentialIssuance,
+                &Signature::new(signature),
+                signing_input.as_bytes(),
+                Some(label),
+                vault,
+            )
+            .await?
+        {
+            return Err(invalid("invalid signature"));
+        }
+
+        self.verify_credential(&credential, subject, vault).await?;
+
+        Ok(credential)
+    }
+}
+
+impl<V: IdentityVault> Identity<V> {
+    /// Encode a credential issued by this identity as a JWT
+    ///
+    /// The claims of the JWT contain the credential as a
+    /// [`VerifiableCredential`], and the JWT is signed with the key that
+    /// issued it.
+    pub async fn credential_to_jwt(&self, credential: &Credential<'_>) -> Result<String> {
+        let vc = credential.to_verifiable_credential()?;
+        if vc.issuer != self.identifier().to_did() {
+            return Err(invalid("credential issued by another identity"));
+        }
+
+        let header = JwtHeader {
+            alg: "EdDSA".into(),
+            typ: Some("JWT".into()),
+            kid: Some(vc.proof.verification_method.clone()),
+        };
+        let subject = match vc.credential_subject.get("id") {
+            Some(Value::String(s)) => s.clone(),
+            _ => return Err(invalid("credential without subject")),
+        };
+        let created = parse_date(&vc.issuance_date)?;
+        let claims = JwtClaims {
+            iss: vc.issuer.clone(),
+            sub: Value::String(subject),
+            iat: created.into(),
+            nbf: created.into(),
+            exp: parse_date(&vc.expiration_date)?.into(),
+            vc,
+        };
+
+        let signing_input = format!("{}.{}", encode_segment(&header)?, encode_segment(&claims)?);
+        let signature = self
+            .create_signature(
+                signing_input.as_bytes(),
+                Some(claims.vc.issuer_key_label()?),
+            )
+            .await?;
+
+        Ok(format!(
+            "{}.{}",
+            signing_input,
+            base64::encode_config(signature.as_ref(), base64::URL_SAFE_NO_PAD)
+        ))
+    }
+
+    /// Verify a credential in a JWT issued for `subject` by one of the
+    /// given authorities
+    pub async fn verify_credential_jwt(
+        &self,
+        jwt: &str,
+        subject: &IdentityIdentifier,
+        authorities: impl IntoIterator<Item = &PublicIdentity>,
+    ) -> Result<Credential<'static>> {
+        let claims = jwt.split('.').nth(1).ok_or_else(invalid_jwt)?;
+        let claims: JwtClaims = decode_segment(claims)?;
+        let issuer = IdentityIdentifier::from_did(&claims.iss)?;
+
+        let authority = authorities
+            .into_iter()
+            .find(|a| a.identifier() == &issuer)
+            .ok_or(IdentityError::UnknownAuthority)?;
+
+        authority
+            .verify_credential_jwt(jwt, subject, &self.vault)
+            .await
+    }
+}
+
+/// A credential in the W3C Verifiable Credentials data model.
+///
+/// Serialize it with `serde_json` to get the JSON-LD document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiableCredential {
+    #[serde(rename = "@context")]
+    context: Vec<String>,
+    #[serde(rename = "type")]
+    types: Vec<String>,
+    issuer: String,
+    issuance_date: String,
+    expiration_date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    credential_schema: Option<CredentialSchema>,
+    credential_subject: BTreeMap<String, Value>,
+    proof: Proof,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CredentialSchema {
+    id: String,
+    #[serde(rename = "type")]
+    stype: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Proof {
+    #[serde(rename = "type")]
+    ptype: String,
+    created: String,
+    verification_method: String,
+    proof_purpose: String,
+    proof_value: String,
+}
+
+impl VerifiableCredential {
+    /// DID of the issuer
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    /// Creation date, in RFC 3339 format
+    pub fn issuance_date(&self) -> &str {
+        &self.issuance_date
+    }
+
+    /// Expiration date, in RFC 3339 format
+    pub fn expiration_date(&self) -> &str {
+        &self.expiration_date
+    }
+
+    /// Claims about the subject, including its DID as `id`
+    pub fn credential_subject(&self) -> &BTreeMap<String, Value> {
+        &self.credential_subject
+    }
+
+    fn issuer_key_label(&self) -> Result<&str> {
+        self.proof
+            .verification_method
+            .strip_prefix(&self.issuer)
+            .and_then(|s| s.strip_prefix('#'))
+            .ok_or_else(|| invalid("invalid verification method"))
+    }
+}
+
+impl Credential<'_> {
+    /// Convert this credential to a W3C Verifiable Credential
+    ///
+    /// Fails if the credential has an attribute named `id`, which is
+    /// the DID of the subject in a Verifiable Credential, or if its
+    /// attributes are committed to for selective disclosure.
+    pub fn to_verifiable_credential(&self) -> Result<VerifiableCredential> {
+        let data = CredentialData::try_from(self)
+            .map_err(|_| IdentityError::InvalidCredentialFormat)?
+            .into_verified();
+
+        // Committed attributes are disclosed outside of the signed data
+        if data.commitment.is_some() {
+            return Err(invalid(
+                "credentials with committed attributes can't be converted",
+            ));
+        }
+
+        // The proof must verify against the rebuilt encoding
+        if minicbor::to_vec(&data)? != self.unverified_data() {
+            return Err(IdentityError::InvalidCredentialFormat.into());
+        }
+
+        let mut subject = BTreeMap::new();
+        subject.insert("id".to_string(), Value::String(data.subject.to_did()));
+        for (k, v) in data.attributes.iter() {
+            let v = match core::str::from_utf8(v) {
+                Ok(s) => Value::String(s.to_string()),
+                Err(_) => json!({ BYTES_KEY: base64::encode(v) }),
+            };
+            if subject.insert(k.to_string(), v).is_some() {
+                return Err(invalid("attribute `id` can't be converted"));
+            }
+        }
+
+        let issuer = data.issuer.to_did();
+        let created = format_date(data.created);
+
+        Ok(VerifiableCredential {
+            context: vec![CREDENTIALS_CONTEXT.into()],
+            types: vec![CREDENTIAL_TYPE.into()],
+            issuance_date: created.clone(),
+            expiration_date: format_date(data.expires),
+            credential_schema: data.schema.map(|s| CredentialSchema {
+                id: format!("{}{}", SCHEMA_PREFIX, u64::from(s)),
+                stype: SCHEMA_TYPE.into(),
+            }),
+            credential_subject: subject,
+            proof: Proof {
+                ptype: PROOF_TYPE.into(),
+                created,
+                verification_method: verification_method(&issuer, &data.issuer_key_label),
+                proof_purpose: PROOF_PURPOSE.into(),
+                proof_value: multibase_encode(self.signature()),
+            },
+            issuer,
+        })
+    }
+
+    /// Convert a W3C Verifiable Credential back to a credential
+    ///
+    /// The credential is not verified, use
+    /// [`PublicIdentity::verify_credential`] for that.
+    pub fn from_verifiable_credential(vc: &VerifiableCredential) -> Result<Credential<'static>> {
+        if !vc.types.iter().any(|t| t == CREDENTIAL_TYPE) || vc.proof.ptype != PROOF_TYPE {
+            return Err(invalid("unsupported credential type"));
+        }
+
+        let schema = match &vc.credential_schema {
+            None => None,
+            Some(s) => {
+                let id =
+                    s.id.strip_prefix(SCHEMA_PREFIX)
+                        .and_then(|id| id.parse().ok())
+                        .ok_or_else(|| invalid("unsupported credential schema"))?;
+                Some(SchemaId(id))
+            }
+        };
+
+        let mut subject = None;
+        let mut values = BTreeMap::new();
+        for (k, v) in &vc.credential_subject {
+            match (k.as_str(), v) {
+                ("id", Value::String(did)) => subject = Some(IdentityIdentifier::from_did(did)?),
+                (_, Value::String(s)) => {
+                    values.insert(k.as_str(), s.as_bytes().to_vec());
+                }
+                (_, Value::Object(o)) if o.len() == 1 => {
+                    let bytes = o
+                        .get(BYTES_KEY)
+                        .and_then(Value::as_str)
+                        .and_then(|b| base64::decode(b).ok())
+                        .ok_or_else(|| invalid("invalid attribute value"))?;
+                    values.insert(k.as_str(), bytes);
+                }
+                _ => return Err(invalid("invalid attribute value")),
+            }
+        }
+        let mut attributes = Attributes::new();
+        for (k, v) in &values {
+            attributes.put(k, v);
+        }
+
+        let data = CredentialData {
+            schema,
+            attributes,
+            subject: subject.ok_or_else(|| invalid("credential without subject"))?,
+            issuer: IdentityIdentifier::from_did(&vc.issuer)?,
+            issuer_key_label: CowStr(vc.issuer_key_label()?.into()),
+            created: parse_date(&vc.issuance_date)?,
+            expires: parse_date(&vc.expiration_date)?,
+            status: None::<PhantomData<Verified>>,
+            commitment: None,
+        };
+        let signature = multibase_decode(&vc.proof.proof_value)?;
+
+        Ok(Credential::new(minicbor::to_vec(&data)?, signature))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    typ: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kid: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JwtClaims {
+    iss: String,
+    sub: Value,
+    iat: u64,
+    nbf: u64,
+    exp: u64,
+    vc: VerifiableCredential,
+}
+
+fn encode_segment(value: &impl Serialize) -> Result<String> {
+    let json = serde_json::to_vec(value).map_err(|_| invalid_jwt())?;
+    Ok(base64::encode_config(json, base64::URL_SAFE_NO_PAD))
+}
+
+fn decode_segment<T: for<'de> Deserialize<'de>>(segment: &str) -> Result<T> {
+    let json =
+        base64::decode_config(segment, base64::URL_SAFE_NO_PAD).map_err(|_| invalid_jwt())?;
+    serde_json::from_slice(&json).map_err(|_| invalid_jwt())
+}
+
+fn verification_method(did: &str, label: &str) -> String {
+    format!("{}#{}", did, label)
+}
+
+/// Base58btc multibase encoding.
+fn multibase_encode(data: &[u8]) -> String {
+    format!("z{}", bs58::encode(data).into_string())
+}
+
+fn multibase_decode(s: &str) -> Result<Vec<u8>> {
+    s.strip_prefix('z')
+        .and_then(|s| bs58::decode(s).into_vec().ok())
+        .ok_or_else(|| invalid("unsupported multibase encoding"))
+}
+
+/// Format a timestamp as an RFC 3339 date in UTC.
+fn format_date(t: Timestamp) -> String {
+    let secs = u64::from(t);
+    let (days, rem) = (secs / 86400, secs % 86400);
+    let (y, m, d) = civil_from_days(days as i64);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        y,
+        m,
+        d,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// Parse an RFC 3339 date in UTC, as written by [`format_date`].
+fn parse_date(s: &str) -> Result<Timestamp> {
+    let err = || invalid("invalid date");
+    let b = s.as_bytes();
+    if b.len() != 20
+        || b[4] != b'-'
+        || b[7] != b'-'
+        || b[10] != b'T'
+        || b[13] != b':'
+        || b[16] != b':'
+        || b[19] != b'Z'
+    {
+        return Err(err());
+    }
+    let num = |r: core::ops::Range<usize>| -> Result<u64> {
+        let s = &s[r];
+        if !s.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(err());
+        }
+        s.parse().map_err(|_| err())
+    };
+    let (y, m, d) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (h, min, sec) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) || h > 23 || min > 59 || sec > 59 {
+        return Err(err());
+    }
+    let days = days_from_civil(y as i64, m, d);
+    if days < 0 {
+        return Err(err());
+    }
+    // Reject dates like February 30th
+    if civil_from_days(days) != (y as i64, m, d) {
+        return Err(err());
+    }
+
+    Ok(Timestamp(days as u64 * 86400 + h * 3600 + min * 60 + sec))
+}
+
+/// Days since 1970-01-01 of a date of the proleptic Gregorian calendar.
+fn days_from_civil(y: i64, m: u64, d: u64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Date of the proleptic Gregorian calendar, `days` after 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u64, u64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u64;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u64;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+fn invalid(msg: &'static str) -> Error {
+    Error::new(Origin::Identity, Kind::Invalid, msg)
+}
+
+fn invalid_jwt() -> Error {
+    invalid("invalid JWT")
+}
@@ -7,7 +7,7 @@ use minicbor::Decoder;
 use ockam_core::api::{Error, Id, Request, Response, ResponseBuilder, Status};
 use ockam_core::async_trait;
 use ockam_core::compat::{boxed::Box, string::ToString, vec::Vec};
-use ockam_core::{Result, Routed, Worker};
+use ockam_core::{Priority, Result, Routed, Worker};
 use ockam_node::Context;
 use tracing::{debug, error, trace, warn};
 
@@ -163,6 +163,12 @@ impl<S: AuthenticatedStorage, V: IdentityVault> Worker for CredentialExchangeWor
     type Message = Vec<u8>;
     type Context = Context;
 
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        // Credential exchange is control traffic
+        ctx.set_priority(Priority::Control);
+        Ok(())
+    }
+
     async fn handle_message(
         &mut self,
         ctx: &mut Self::Context,
@@ -0,0 +1,22 @@
+//! Sharing of identity change histories between nodes
+//!
+//! Peers normally learn about a new change history (e.g. after a key
+//! rotation) when they establish a secure channel.  An identity
+//! directory keeps the known histories of an [`AuthenticatedStorage`]
+//! up to date in the meantime: other nodes can fetch histories from
+//! it or push newer ones to it, and it periodically fetches the
+//! histories of the identities it is subscribed to.  Histories are
+//! verified before being stored, and the open secure channels with a
+//! peer are notified when its history advances or conflicts with the
+//! known one.
+//!
+//! [`AuthenticatedStorage`]: crate::authenticated_storage::AuthenticatedStorage
+
+mod identity;
+mod sync;
+mod worker;
+
+use core::time::Duration;
+
+/// How often subscribed histories are fetched by default
+pub const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(60);
@@ -0,0 +1,206 @@
+use crate::authenticated_storage::AuthenticatedStorage;
+use crate::change_history::IdentityHistoryComparison;
+use crate::directory::sync::IdentityDirectorySyncWorker;
+use crate::directory::worker::IdentityDirectoryWorker;
+use crate::{
+    Identity, IdentityError, IdentityIdentifier, IdentityStateConst, IdentityVault,
+    PeerHistoryNotification, PublicIdentity,
+};
+use core::time::Duration;
+use ockam_core::api::{decode_option, is_ok, Request};
+use ockam_core::compat::{collections::BTreeMap, string::ToString, sync::Arc, vec::Vec};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Address, AllowAll, AsyncTryClone, CowBytes, Error, Mailboxes, Result, Route};
+use ockam_node::api::request;
+use ockam_node::{Schedule, WorkerBuilder};
+use tracing::{debug, warn};
+
+impl<V: IdentityVault> Identity<V> {
+    /// Start an identity directory worker at `address`
+    ///
+    /// The directory serves the histories known to `storage` and
+    /// accepts newer ones.  Every `sync_interval` a separate worker
+    /// fetches the history of each subscribed identity from the
+    /// directory at the associated route, which should go through a
+    /// secure channel.  `sync_interval` must not be zero.
+    pub async fn start_identity_directory(
+        &self,
+        address: impl Into<Address>,
+        storage: impl AuthenticatedStorage,
+        subscriptions: BTreeMap<IdentityIdentifier, Route>,
+        sync_interval: Duration,
+    ) -> Result<()> {
+        let schedule = Schedule::every(sync_interval)?;
+
+        let worker = IdentityDirectoryWorker::new(
+            self.async_try_clone().await?,
+            storage.async_try_clone().await?,
+            subscriptions.keys().cloned().collect(),
+        );
+        // TODO: @ac
+        WorkerBuilder::with_mailboxes(
+            Mailboxes::main(address, Arc::new(AllowAll), Arc::new(AllowAll)),
+            worker,
+        )
+        .start(&self.ctx)
+        .await?;
+
+        if !subscriptions.is_empty() {
+            let sync_worker = IdentityDirectorySyncWorker::new(
+                self.async_try_clone().await?,
+                storage,
+                subscriptions,
+                schedule,
+            );
+            // TODO: @ac
+            WorkerBuilder::with_mailboxes(
+                Mailboxes::main(
+                    Address::random_tagged("IdentityDirectory.sync"),
+                    Arc::new(AllowAll),
+                    Arc::new(AllowAll),
+                ),
+                sync_worker,
+            )
+            .start(&self.ctx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Push our change history to the identity directory at `route`,
+    /// route shall use secure channel
+    pub async fn push_identity_history(&self, route: impl Into<Route>) -> Result<()> {
+        let history = self.export().await?;
+
+        let mut child_ctx = self
+            .ctx
+            .new_detached(Address::random_tagged(
+                "Identity.push_identity_history.detached",
+            ))
+            .await?;
+        let buf = request(
+            &mut child_ctx,
+            "push_identity_history",
+            None,
+            route.into(),
+            Request::post("identities").body(CowBytes::from(history)),
+        )
+        .await?;
+
+        is_ok("push_identity_history", &buf)
+    }
+
+    /// Fetch the change history of an identity from the identity
+    /// directory at `route` and process it like
+    /// [`receive_identity_history`](Self::receive_identity_history)
+    pub async fn fetch_identity_history(
+        &self,
+        route: impl Into<Route>,
+        identifier: &IdentityIdentifier,
+        storage: &impl AuthenticatedStorage,
+    ) -> Result<IdentityHistoryComparison> {
+        let mut child_ctx = self
+            .ctx
+            .new_detached(Address::random_tagged(
+                "Identity.fetch_identity_history.detached",
+            ))
+            .await?;
+        let buf = request(
+            &mut child_ctx,
+            "fetch_identity_history",
+            None,
+            route.into(),
+            Request::get(format!("identities/{}", identifier)),
+        )
+        .await?;
+
+        let history: CowBytes =
+            decode_option("fetch_identity_history", None, &buf)?.ok_or_else(|| {
+                Error::new(
+                    Origin::Identity,
+                    Kind::NotFound,
+                    format!("unknown identity {}", identifier),
+                )
+            })?;
+
+        let their_identity = PublicIdentity::import(&history, &self.vault).await?;
+        if their_identity.identifier() != identifier {
+            return Err(IdentityError::InvalidIdentityId.into());
+        }
+
+        self.receive_identity_history(their_identity, storage).await
+    }
+
+    /// Compare a change history with the one known to `storage` and
+    /// store it if it is newer
+    ///
+    /// Open secure channels with that identity are notified when its
+    /// history advances or conflicts with the known one.  A history
+    /// that wasn't known yet is stored and reported as `Newer`.
+    pub async fn receive_identity_history(
+        &self,
+        their_identity: PublicIdentity,
+        storage: &impl AuthenticatedStorage,
+    ) -> Result<IdentityHistoryComparison> {
+        let their_identity_id = their_identity.identifier();
+
+        let comparison = match self.get_known_identity(their_identity_id, storage).await? {
+            Some(known) => their_identity.compare(&known),
+            None => IdentityHistoryComparison::Newer,
+        };
+
+        match comparison {
+            IdentityHistoryComparison::Newer => {
+                debug!("Storing new change history of {}", their_identity_id);
+                storage
+                    .set(
+                        &their_identity_id.to_string(),
+                        IdentityStateConst::CHANGE_HISTORY_KEY.to_string(),
+                        their_identity.export()?,
+                    )
+                    .await?;
+                self.notify_secure_channels(their_identity_id, PeerHistoryNotification::Updated)
+                    .await;
+            }
+            IdentityHistoryComparison::Conflict => {
+                warn!(
+                    "Received conflicting change history of {}",
+                    their_identity_id
+                );
+                self.notify_secure_channels(their_identity_id, PeerHistoryNotification::Conflict)
+                    .await;
+            }
+            IdentityHistoryComparison::Equal | IdentityHistoryComparison::Older => {}
+        }
+
+        Ok(comparison)
+    }
+
+    async fn notify_secure_channels(
+        &self,
+        their_identity_id: &IdentityIdentifier,
+        notification: PeerHistoryNotification,
+    ) {
+        let addresses: Vec<Address> = self
+            .secure_channels
+            .read()
+            .await
+            .iter()
+            .filter(|(_, id)| *id == their_identity_id)
+            .map(|(address, _)| address.clone())
+            .collect();
+
+        for address in addresses {
+            if let Err(err) = self
+                .notifier
+                .send(address.clone(), notification.clone())
+                .await
+            {
+                // The channel went away without telling us
+                debug!("{} notifying secure channel at {}", err, address);
+                self.secure_channels.write().await.remove(&address);
+            }
+        }
+    }
+}
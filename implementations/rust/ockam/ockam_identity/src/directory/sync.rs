@@ -0,0 +1,68 @@
+use crate::authenticated_storage::AuthenticatedStorage;
+use crate::{Identity, IdentityIdentifier, IdentityVault};
+use ockam_core::async_trait;
+use ockam_core::compat::{boxed::Box, collections::BTreeMap};
+use ockam_core::{Result, Route, Routed, Worker};
+use ockam_node::{Context, Schedule};
+use tracing::{debug, warn};
+
+/// Worker that periodically fetches the change histories of the
+/// subscribed identities
+///
+/// Fetching can take as long as the slowest remote directory, so it
+/// runs apart from the [`IdentityDirectoryWorker`] serving requests.
+///
+/// [`IdentityDirectoryWorker`]: super::worker::IdentityDirectoryWorker
+pub(crate) struct IdentityDirectorySyncWorker<S: AuthenticatedStorage, V: IdentityVault> {
+    identity: Identity<V>,
+    storage: S,
+    subscriptions: BTreeMap<IdentityIdentifier, Route>,
+    schedule: Schedule,
+}
+
+impl<S: AuthenticatedStorage, V: IdentityVault> IdentityDirectorySyncWorker<S, V> {
+    pub fn new(
+        identity: Identity<V>,
+        storage: S,
+        subscriptions: BTreeMap<IdentityIdentifier, Route>,
+        schedule: Schedule,
+    ) -> Self {
+        Self {
+            identity,
+            storage,
+            subscriptions,
+            schedule,
+        }
+    }
+}
+
+#[async_trait]
+impl<S: AuthenticatedStorage, V: IdentityVault> Worker for IdentityDirectorySyncWorker<S, V> {
+    type Message = ();
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        ctx.send(ctx.address(), ()).await?;
+        ctx.schedule(ctx.address(), (), self.schedule.clone())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, _ctx: &mut Self::Context, _msg: Routed<()>) -> Result<()> {
+        for (identifier, route) in &self.subscriptions {
+            match self
+                .identity
+                .fetch_identity_history(route.clone(), identifier, &self.storage)
+                .await
+            {
+                Ok(comparison) => {
+                    debug!("Synced change history of {}: {:?}", identifier, comparison)
+                }
+                Err(err) => warn!("{} fetching change history of {}", err, identifier),
+            }
+        }
+
+        Ok(())
+    }
+}
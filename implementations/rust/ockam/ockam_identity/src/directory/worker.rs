@@ -0,0 +1,180 @@
+use crate::authenticated_storage::AuthenticatedStorage;
+use crate::change_history::IdentityHistoryComparison;
+use crate::{
+    Identity, IdentityIdentifier, IdentitySecureChannelLocalInfo, IdentityStateConst,
+    IdentityVault, PublicIdentity,
+};
+use minicbor::Decoder;
+use ockam_core::api::{Error, Id, Request, Response, ResponseBuilder, Status};
+use ockam_core::async_trait;
+use ockam_core::compat::{boxed::Box, collections::BTreeSet, string::ToString, vec::Vec};
+use ockam_core::{CowBytes, Result, Routed, Worker};
+use ockam_node::Context;
+use tracing::{debug, error, trace, warn};
+
+const TARGET: &str = "ockam::identity_directory::service";
+
+/// Worker that serves the change histories of known identities
+pub(crate) struct IdentityDirectoryWorker<S: AuthenticatedStorage, V: IdentityVault> {
+    identity: Identity<V>,
+    storage: S,
+    subscriptions: BTreeSet<IdentityIdentifier>,
+}
+
+impl<S: AuthenticatedStorage, V: IdentityVault> IdentityDirectoryWorker<S, V> {
+    pub fn new(
+        identity: Identity<V>,
+        storage: S,
+        subscriptions: BTreeSet<IdentityIdentifier>,
+    ) -> Self {
+        Self {
+            identity,
+            storage,
+            subscriptions,
+        }
+    }
+}
+
+impl<S: AuthenticatedStorage, V: IdentityVault> IdentityDirectoryWorker<S, V> {
+    /// Create a generic bad request response.
+    fn bad_request<'a>(id: Id, path: &'a str, msg: &'a str) -> ResponseBuilder<Error<'a>> {
+        let e = Error::new(path).with_message(msg);
+        Response::bad_request(id).body(e)
+    }
+
+    async fn handle_request(
+        &mut self,
+        req: &Request<'_>,
+        sender: IdentityIdentifier,
+        dec: &mut Decoder<'_>,
+    ) -> Result<Vec<u8>> {
+        trace! {
+            target: TARGET,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+
+        use ockam_core::api::Method::*;
+        let path = req.path();
+        let path_segments = req.path_segments::<3>();
+        let method = match req.method() {
+            Some(m) => m,
+            None => {
+                return Ok(Response::bad_request(req.id())
+                    .body("Invalid method")
+                    .to_vec()?)
+            }
+        };
+
+        let r = match (method, path_segments.as_slice()) {
+            (Get, ["identities", id]) => {
+                let history = if *id == self.identity.identifier().to_string() {
+                    Some(self.identity.export().await?)
+                } else {
+                    self.storage
+                        .get(id, IdentityStateConst::CHANGE_HISTORY_KEY)
+                        .await?
+                };
+                match history {
+                    Some(h) => Response::ok(req.id()).body(CowBytes::from(h)).to_vec()?,
+                    None => Response::not_found(req.id()).to_vec()?,
+                }
+            }
+            (Post, ["identities"]) => {
+                let history: CowBytes = dec.decode()?;
+                let their_identity =
+                    match PublicIdentity::import(&history, self.identity.vault()).await {
+                        Ok(i) => i,
+                        Err(err) => {
+                            return Ok(Self::bad_request(req.id(), path, &err.to_string()).to_vec()?)
+                        }
+                    };
+                let their_identity_id = their_identity.identifier().clone();
+                debug!(
+                    "Received change history of {} from {}",
+                    their_identity_id, sender
+                );
+
+                // Only accept histories of identities we already care about
+                let known = self
+                    .identity
+                    .get_known_identity(&their_identity_id, &self.storage)
+                    .await?
+                    .is_some();
+                if &their_identity_id == self.identity.identifier()
+                    || !(known || self.subscriptions.contains(&their_identity_id))
+                {
+                    return Ok(Response::forbidden(req.id()).to_vec()?);
+                }
+
+                match self
+                    .identity
+                    .receive_identity_history(their_identity, &self.storage)
+                    .await?
+                {
+                    IdentityHistoryComparison::Conflict => {
+                        let e = Error::new(path).with_message("conflicting change history");
+                        Response::builder(req.id(), Status::Conflict)
+                            .body(e)
+                            .to_vec()?
+                    }
+                    _ => Response::ok(req.id()).to_vec()?,
+                }
+            }
+
+            // ==*== Catch-all for Unimplemented APIs ==*==
+            _ => {
+                warn!(%method, %path, "Called invalid endpoint");
+                Response::bad_request(req.id())
+                    .body(format!("Invalid endpoint: {}", path))
+                    .to_vec()?
+            }
+        };
+        Ok(r)
+    }
+}
+
+#[async_trait]
+impl<S: AuthenticatedStorage, V: IdentityVault> Worker for IdentityDirectoryWorker<S, V> {
+    type Message = Vec<u8>;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let mut dec = Decoder::new(msg.as_body());
+        let req: Request = match dec.decode() {
+            Ok(r) => r,
+            Err(e) => {
+                error!("failed to decode request: {:?}", e);
+                return Ok(());
+            }
+        };
+
+        let sender = match IdentitySecureChannelLocalInfo::find_info(msg.local_message()) {
+            Ok(info) => info.their_identity_id().clone(),
+            Err(_) => {
+                warn!(path = %req.path(), "Request without secure channel");
+                let e = Error::new(req.path()).with_message("secure channel required");
+                let r = Response::forbidden(req.id()).body(e).to_vec()?;
+                return ctx.send(msg.return_route(), r).await;
+            }
+        };
+
+        let r = match self.handle_request(&req, sender, &mut dec).await {
+            Ok(r) => r,
+            Err(err) => {
+                error!(?err, "Failed to handle message");
+                Response::builder(req.id(), Status::InternalServerError)
+                    .body(err.to_string())
+                    .to_vec()?
+            }
+        };
+        ctx.send(msg.return_route(), r).await
+    }
+}
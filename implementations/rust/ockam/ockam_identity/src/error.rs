@@ -20,6 +20,9 @@ pub enum IdentityError {
     InvalidCredentialFormat,
     UnknownAuthority,
     CredentialVerificationFailed,
+    KeyRevoked,
+    KeyPurposeMismatch,
+    ThresholdNotMet,
 }
 
 impl ockam_core::compat::error::Error for IdentityError {}
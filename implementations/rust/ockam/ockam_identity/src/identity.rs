@@ -1,20 +1,23 @@
 use crate::authenticated_storage::AuthenticatedStorage;
-use crate::change::IdentitySignedChange;
+use crate::change::{IdentityChange, IdentitySignedChange};
 use crate::change_history::{IdentityChangeHistory, IdentityHistoryComparison};
-use crate::credential::Credential;
+use crate::credential::{Credential, SchemaRegistry};
 use crate::{
-    ChangeIdentifier, IdentityError, IdentityIdentifier, IdentityVault, KeyAttributes,
+    ChangeIdentifier, IdentityError, IdentityIdentifier, IdentityVault, KeyAttributes, KeyPurpose,
     PublicIdentity,
 };
 use ockam_core::compat::{
     boxed::Box,
+    collections::BTreeMap,
     string::{String, ToString},
     sync::Arc,
     vec::Vec,
 };
-use ockam_core::vault::{SecretPersistence, SecretType, Signature, CURVE25519_SECRET_LENGTH_U32};
+use ockam_core::vault::{
+    PublicKey, SecretPersistence, SecretType, Signature, CURVE25519_SECRET_LENGTH_U32,
+};
 use ockam_core::AsyncTryClone;
-use ockam_core::{Address, Result};
+use ockam_core::{Address, AllowAll, DenyAll, Mailboxes, Result};
 use ockam_node::compat::asynchronous::RwLock;
 use ockam_node::Context;
 use ockam_vault::{KeyId, SecretAttributes};
@@ -25,7 +28,14 @@ use ockam_vault::{KeyId, SecretAttributes};
 pub struct Identity<V: IdentityVault> {
     id: IdentityIdentifier,
     pub(crate) credential: Arc<RwLock<Option<Credential<'static>>>>,
+    /// Schemas of the credentials issued and received
+    pub(crate) schemas: SchemaRegistry,
     pub(crate) change_history: Arc<RwLock<IdentityChangeHistory>>,
+    /// Open secure channels, by the address that accepts notifications
+    /// about the history of their peer
+    pub(crate) secure_channels: Arc<RwLock<BTreeMap<Address, IdentityIdentifier>>>,
+    /// The only context secure channels accept these notifications from
+    pub(crate) notifier: Arc<Context>,
     pub(crate) ctx: Context,
     pub(crate) vault: V,
 }
@@ -48,19 +58,30 @@ impl IdentityStateConst {
 
 impl<V: IdentityVault> Identity<V> {
     /// Identity constructor
-    pub(crate) fn new(
+    pub(crate) async fn new(
         id: IdentityIdentifier,
         change_history: IdentityChangeHistory,
         ctx: Context,
         vault: V,
-    ) -> Self {
-        Self {
+    ) -> Result<Self> {
+        let notifier = ctx
+            .new_detached_with_mailboxes(Mailboxes::main(
+                Address::random_tagged("Identity.notifier.detached"),
+                Arc::new(DenyAll),
+                Arc::new(AllowAll),
+            ))
+            .await?;
+
+        Ok(Self {
             id,
             credential: Arc::new(RwLock::new(None)),
+            schemas: SchemaRegistry::new(),
             change_history: Arc::new(RwLock::new(change_history)),
+            secure_channels: Arc::new(RwLock::new(BTreeMap::new())),
+            notifier: Arc::new(notifier),
             ctx,
             vault,
-        }
+        })
     }
 
     pub async fn export(&self) -> Result<Vec<u8>> {
@@ -80,7 +101,7 @@ impl<V: IdentityVault> Identity<V> {
 
         let vault = vault.async_try_clone().await?;
 
-        let identity = Self::new(id, change_history, child_ctx, vault);
+        let identity = Self::new(id, change_history, child_ctx, vault).await?;
 
         Ok(identity)
     }
@@ -134,7 +155,7 @@ impl<V: IdentityVault> Identity<V> {
 
         let vault = vault.async_try_clone().await?;
 
-        let identity = Self::new(id, change_history, child_ctx, vault);
+        let identity = Self::new(id, change_history, child_ctx, vault).await?;
 
         Ok(identity)
     }
@@ -150,11 +171,37 @@ impl<V: IdentityVault> Identity<V> {
         vault.compute_key_id_for_public_key(&public_key).await
     }
 
-    async fn add_change(&self, change: IdentitySignedChange) -> Result<()> {
-        self.change_history
-            .write()
-            .await
-            .check_consistency_and_add_change(change)
+    /// Verify a change against the current history and add it
+    ///
+    /// Changes proposed with the `propose_*` functions must be applied
+    /// this way once enough controllers have signed them.
+    pub async fn apply_change(&self, change: IdentitySignedChange) -> Result<()> {
+        let mut change_history = self.change_history.write().await;
+
+        if !IdentityChangeHistory::verify_change(change_history.as_ref(), &change, &self.vault)
+            .await?
+        {
+            return Err(match change_history.controllers() {
+                Some(_) => IdentityError::ThresholdNotMet,
+                None => IdentityError::IdentityVerificationFailed,
+            }
+            .into());
+        }
+
+        change_history.check_consistency_and_add_change(change)
+    }
+
+    /// Apply a change whose key was generated for it, destroying the
+    /// key if the change is rejected
+    async fn apply_new_key_change(&self, change: IdentitySignedChange) -> Result<()> {
+        let key_id = Self::get_secret_key_from_change(&change, &self.vault).await?;
+
+        if let Err(e) = self.apply_change(change).await {
+            self.vault.secret_destroy(key_id).await?;
+            return Err(e);
+        }
+
+        Ok(())
     }
 }
 
@@ -168,7 +215,30 @@ impl<V: IdentityVault> Identity<V> {
 
         let change = self.make_create_key_change(None, key_attribs).await?;
 
-        self.add_change(change).await
+        self.apply_new_key_change(change).await
+    }
+
+    /// Create a key that may only be used for the given purposes
+    ///
+    /// This applies two changes: one creating the key and one
+    /// restricting it, see [`set_key_purposes`](Self::set_key_purposes).
+    pub async fn create_key_with_purposes(
+        &self,
+        label: String,
+        purposes: &[KeyPurpose],
+    ) -> Result<()> {
+        self.create_key(label.clone()).await?;
+        self.set_key_purposes(&label, purposes).await
+    }
+
+    /// Restrict the key with the given label, and the keys it will be
+    /// rotated to, to the given purposes
+    ///
+    /// An empty list of purposes lifts the restriction.
+    pub async fn set_key_purposes(&self, label: &str, purposes: &[KeyPurpose]) -> Result<()> {
+        let change = self.make_set_key_purposes_change(label, purposes).await?;
+
+        self.apply_change(change).await
     }
 
     pub async fn add_key(&self, label: String, secret: &KeyId) -> Result<()> {
@@ -179,7 +249,7 @@ impl<V: IdentityVault> Identity<V> {
             .make_create_key_change(Some(secret), key_attribs)
             .await?;
 
-        self.add_change(change).await
+        self.apply_change(change).await
     }
 
     pub async fn rotate_key(&self, label: &str) -> Result<()> {
@@ -187,17 +257,93 @@ impl<V: IdentityVault> Identity<V> {
             .make_rotate_key_change(KeyAttributes::default_with_label(label.to_string()))
             .await?;
 
-        self.add_change(change).await
+        self.apply_new_key_change(change).await
+    }
+
+    /// Revoke a key. The label can't be used for another key afterwards.
+    pub async fn revoke_key(&self, label: &str) -> Result<()> {
+        let change = self.make_revoke_key_change(label).await?;
+
+        self.apply_change(change).await
     }
 
     pub async fn rotate_root_key(&self) -> Result<()> {
-        let change = self
-            .make_rotate_key_change(KeyAttributes::default_with_label(
-                IdentityStateConst::ROOT_LABEL.to_string(),
-            ))
-            .await?;
+        let change = self.propose_rotate_root_key().await?;
+
+        self.apply_new_key_change(change).await
+    }
+
+    /// Require `threshold` of the given controller keys to sign every
+    /// further change, instead of the root key
+    ///
+    /// If the identity already has controllers, the new set must be
+    /// approved by them, see [`propose_set_controllers`](Self::propose_set_controllers).
+    pub async fn set_controllers(&self, controllers: Vec<PublicKey>, threshold: u8) -> Result<()> {
+        let change = self.propose_set_controllers(controllers, threshold).await?;
+
+        self.apply_change(change).await
+    }
+}
+
+/// Changes of an identity with controllers are proposed first, then
+/// signed by enough controllers with
+/// [`IdentitySignedChange::add_controller_signature`] and finally
+/// applied with [`Identity::apply_change`].
+impl<V: IdentityVault> Identity<V> {
+    pub async fn propose_create_key(&self, label: String) -> Result<IdentitySignedChange> {
+        let key_attribs = KeyAttributes::default_with_label(label);
+
+        self.make_create_key_change(None, key_attribs).await
+    }
 
-        self.add_change(change).await
+    pub async fn propose_rotate_key(&self, label: &str) -> Result<IdentitySignedChange> {
+        self.make_rotate_key_change(KeyAttributes::default_with_label(label.to_string()))
+            .await
+    }
+
+    pub async fn propose_revoke_key(&self, label: &str) -> Result<IdentitySignedChange> {
+        self.make_revoke_key_change(label).await
+    }
+
+    pub async fn propose_rotate_root_key(&self) -> Result<IdentitySignedChange> {
+        self.make_rotate_key_change(KeyAttributes::default_with_label(
+            IdentityStateConst::ROOT_LABEL.to_string(),
+        ))
+        .await
+    }
+
+    /// Destroy the key generated for a proposed change that won't be
+    /// applied
+    ///
+    /// Proposals creating or rotating a key generate the new key in the
+    /// vault of this identity. Changes that were applied, or that
+    /// don't carry a new key, are left alone.
+    pub async fn discard_change(&self, change: &IdentitySignedChange) -> Result<()> {
+        match change.change() {
+            IdentityChange::CreateKey(_) | IdentityChange::RotateKey(_) => {}
+            _ => return Ok(()),
+        }
+
+        let change_history = self.change_history.read().await;
+        if change_history
+            .as_ref()
+            .iter()
+            .any(|c| c.identifier() == change.identifier())
+        {
+            return Ok(());
+        }
+
+        let key_id = Self::get_secret_key_from_change(change, &self.vault).await?;
+        self.vault.secret_destroy(key_id).await
+    }
+
+    pub async fn propose_set_controllers(
+        &self,
+        controllers: Vec<PublicKey>,
+        threshold: u8,
+    ) -> Result<IdentitySignedChange> {
+        self.make_set_controllers_change(controllers, threshold)
+            .await
     }
 
     /// Get [`Secret`] key. Key is uniquely identified by label in [`KeyAttributes`]
@@ -206,7 +352,7 @@ impl<V: IdentityVault> Identity<V> {
     }
 
     pub(crate) async fn get_secret_key(&self, label: &str) -> Result<KeyId> {
-        let change = IdentityChangeHistory::find_last_key_change(
+        let change = IdentityChangeHistory::find_last_valid_key_change(
             self.change_history.read().await.as_ref(),
             label,
         )?
@@ -299,7 +445,7 @@ mod test {
     use ockam_core::errcode::{Kind, Origin};
     use ockam_core::vault::PublicKey;
     use ockam_core::Error;
-    use ockam_vault::Vault;
+    use ockam_vault::{SecretVault, Vault};
 
     fn test_error<S: Into<String>>(error: S) -> Result<()> {
         Err(Error::new_without_cause(Origin::Identity, Kind::Unknown).context("msg", error.into()))
@@ -380,4 +526,202 @@ mod test {
 
         Ok(())
     }
+
+    #[ockam_macros::test]
+    async fn test_revoke_key(ctx: &mut Context) -> Result<()> {
+        let vault = Vault::create();
+
+        let identity = Identity::create(ctx, &vault).await?;
+        identity.create_key("Truck management".to_string()).await?;
+        let signature = identity
+            .create_signature(b"data", Some("Truck management"))
+            .await?;
+
+        identity.revoke_key("Truck management").await?;
+
+        if !identity.verify_changes().await? {
+            return test_error("verify_changes failed");
+        }
+
+        if identity.get_secret_key("Truck management").await.is_ok() {
+            return test_error("revoked key is still usable");
+        }
+
+        if identity.rotate_key("Truck management").await.is_ok() {
+            return test_error("revoked key was rotated");
+        }
+
+        if identity
+            .create_key("Truck management".to_string())
+            .await
+            .is_ok()
+        {
+            return test_error("revoked label was reused");
+        }
+
+        if identity
+            .revoke_key(IdentityStateConst::ROOT_LABEL)
+            .await
+            .is_ok()
+        {
+            return test_error("root key was revoked");
+        }
+
+        // The revocation survives an export
+        let public = PublicIdentity::import(&identity.export().await?, &vault).await?;
+        if public
+            .verify_signature(&signature, b"data", Some("Truck management"), &vault)
+            .await
+            .is_ok()
+        {
+            return test_error("signature of a revoked key was accepted");
+        }
+
+        ctx.stop().await?;
+
+        Ok(())
+    }
+
+    #[ockam_macros::test]
+    async fn test_key_purposes(ctx: &mut Context) -> Result<()> {
+        let vault = Vault::create();
+
+        let identity = Identity::create(ctx, &vault).await?;
+        identity
+            .create_key_with_purposes("channel".to_string(), &[KeyPurpose::SecureChannel])
+            .await?;
+        identity.rotate_key("channel").await?;
+
+        if !identity.verify_changes().await? {
+            return test_error("verify_changes failed");
+        }
+
+        let signature = identity.create_signature(b"data", Some("channel")).await?;
+        let public = identity.to_public().await?;
+
+        if public
+            .verify_signature(&signature, b"data", Some("channel"), &vault)
+            .await
+            .is_ok()
+        {
+            return test_error("key was used for the wrong purpose");
+        }
+
+        if !public
+            .verify_signature_for(
+                KeyPurpose::SecureChannel,
+                &signature,
+                b"data",
+                Some("channel"),
+                &vault,
+            )
+            .await?
+        {
+            return test_error("key was refused for its purpose");
+        }
+
+        // The root key is not restricted
+        let signature = identity.create_signature(b"data", None).await?;
+        if !public
+            .verify_signature_for(
+                KeyPurpose::CredentialIssuance,
+                &signature,
+                b"data",
+                None,
+                &vault,
+            )
+            .await?
+        {
+            return test_error("root key was refused");
+        }
+
+        ctx.stop().await?;
+
+        Ok(())
+    }
+
+    #[ockam_macros::test]
+    async fn test_controllers_threshold(ctx: &mut Context) -> Result<()> {
+        let vault = Vault::create();
+
+        let identity = Identity::create(ctx, &vault).await?;
+
+        // Each controller keeps its key in its own vault
+        let mut controllers = Vec::new();
+        for _ in 0..3 {
+            let controller_vault = Vault::create();
+            let key_id = controller_vault
+                .secret_generate(SecretAttributes::new(
+                    SecretType::Ed25519,
+                    SecretPersistence::Ephemeral,
+                    CURVE25519_SECRET_LENGTH_U32,
+                ))
+                .await?;
+            let public_key = controller_vault.secret_public_key_get(&key_id).await?;
+            controllers.push((controller_vault, key_id, public_key));
+        }
+
+        let public_keys = controllers.iter().map(|c| c.2.clone()).collect();
+        identity.set_controllers(public_keys, 2).await?;
+
+        // The root key alone can't change the identity anymore
+        if identity.rotate_root_key().await.is_ok() {
+            return test_error("root key rotated without controllers");
+        }
+
+        // An abandoned proposal doesn't leave its key behind
+        let abandoned = identity.propose_create_key("Abandoned".to_string()).await?;
+        let key_id = Identity::get_secret_key_from_change(&abandoned, &vault).await?;
+        if identity.apply_change(abandoned.clone()).await.is_ok() {
+            return test_error("change applied without controller signatures");
+        }
+        identity.discard_change(&abandoned).await?;
+        if vault.secret_attributes_get(&key_id).await.is_ok() {
+            return test_error("discarded key still in the vault");
+        }
+
+        let old_root = identity.get_root_public_key().await?;
+        let mut change = identity.propose_rotate_root_key().await?;
+
+        change
+            .add_controller_signature(&controllers[0].0, &controllers[0].1)
+            .await?;
+        if identity.apply_change(change.clone()).await.is_ok() {
+            return test_error("change applied with one controller signature");
+        }
+
+        // Signing twice with the same controller doesn't meet the threshold
+        let mut twice = change.clone();
+        twice
+            .add_controller_signature(&controllers[0].0, &controllers[0].1)
+            .await?;
+        if identity.apply_change(twice).await.is_ok() {
+            return test_error("controller signature counted twice");
+        }
+
+        // The change travels to another controller
+        let mut change = IdentitySignedChange::import(&change.export()?)?;
+        change
+            .add_controller_signature(&controllers[2].0, &controllers[2].1)
+            .await?;
+        identity.apply_change(change).await?;
+
+        if !identity.verify_changes().await? {
+            return test_error("verify_changes failed");
+        }
+
+        if identity.get_root_public_key().await? == old_root {
+            return test_error("root key did not change");
+        }
+
+        // A new identity imported from the history checks the signatures too
+        let imported = Identity::import(ctx, &identity.export().await?, &vault).await?;
+        if !imported.verify_changes().await? {
+            return test_error("verify_changes failed after import");
+        }
+
+        ctx.stop().await?;
+
+        Ok(())
+    }
 }
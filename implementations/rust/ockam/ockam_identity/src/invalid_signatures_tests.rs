@@ -38,12 +38,7 @@ impl<V: IdentityVault> Identity<V> {
             new_history.check_consistency_and_add_change(change)?
         }
 
-        Ok(Identity::new(
-            self.identifier().clone(),
-            new_history,
-            self.ctx,
-            self.vault,
-        ))
+        Identity::new(self.identifier().clone(), new_history, self.ctx, self.vault).await
     }
 
     async fn random_change(&self) -> Result<()> {
@@ -73,7 +68,9 @@ impl<V: IdentityVault> Identity<V> {
             Action::RotateKey => {
                 let mut present_keys = HashSet::<String>::new();
                 for change in self.change_history.read().await.as_ref() {
-                    present_keys.insert(change.change().label().to_string());
+                    if let Some(label) = change.change().label() {
+                        present_keys.insert(label.to_string());
+                    }
                 }
                 let present_keys: Vec<String> = present_keys.into_iter().collect();
                 let index = thread_rng().gen_range(0..present_keys.len());
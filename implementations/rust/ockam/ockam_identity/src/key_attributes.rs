@@ -4,6 +4,30 @@ use ockam_core::vault::{SecretPersistence, SecretType, CURVE25519_SECRET_LENGTH_
 use ockam_vault::SecretAttributes;
 use serde::{Deserialize, Serialize};
 
+/// What a key of an [`crate::Identity`] may be used for
+///
+/// Purposes are set with a [`crate::change::SetKeyPurposesChangeData`]
+/// change, and a key without such a change may be used for anything.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum KeyPurpose {
+    /// Signing arbitrary data
+    Signing,
+    /// Issuing credentials
+    CredentialIssuance,
+    /// Authenticating secure channels
+    SecureChannel,
+}
+
+impl fmt::Display for KeyPurpose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyPurpose::Signing => write!(f, "signing"),
+            KeyPurpose::CredentialIssuance => write!(f, "credential-issuance"),
+            KeyPurpose::SecureChannel => write!(f, "secure-channel"),
+        }
+    }
+}
+
 /// Attributes that are used to identify key
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 pub struct KeyAttributes {
@@ -1,5 +1,5 @@
 use crate::change_history::{IdentityChangeHistory, IdentityHistoryComparison};
-use crate::{IdentityError, IdentityIdentifier, IdentityVault};
+use crate::{IdentityError, IdentityIdentifier, IdentityStateConst, IdentityVault, KeyPurpose};
 use ockam_core::compat::vec::Vec;
 use ockam_core::vault::Signature;
 use ockam_core::Result;
@@ -46,14 +46,11 @@ impl PublicIdentity {
         &self.id
     }
 
-    pub(crate) fn get_root_public_key(&self) -> Result<PublicKey> {
-        self.change_history.get_root_public_key()
-    }
-
     pub(crate) fn get_public_key(&self, label: &str) -> Result<PublicKey> {
         self.change_history.get_public_key(label)
     }
 
+    /// Verify a signature made with a key usable for [`KeyPurpose::Signing`]
     pub async fn verify_signature(
         &self,
         signature: &Signature,
@@ -61,10 +58,24 @@ impl PublicIdentity {
         key_label: Option<&str>,
         vault: &impl IdentityVault,
     ) -> Result<bool> {
-        let public_key = match key_label {
-            Some(label) => self.get_public_key(label)?,
-            None => self.get_root_public_key()?,
-        };
+        self.verify_signature_for(KeyPurpose::Signing, signature, data, key_label, vault)
+            .await
+    }
+
+    /// Verify a signature made with a key usable for `purpose`
+    ///
+    /// Fails if the key has been revoked or is restricted to other
+    /// purposes.
+    pub async fn verify_signature_for(
+        &self,
+        purpose: KeyPurpose,
+        signature: &Signature,
+        data: &[u8],
+        key_label: Option<&str>,
+        vault: &impl IdentityVault,
+    ) -> Result<bool> {
+        let label = key_label.unwrap_or(IdentityStateConst::ROOT_LABEL);
+        let public_key = self.change_history.get_public_key_for(label, purpose)?;
 
         vault.verify(signature, &public_key, data).await
     }
@@ -11,6 +11,8 @@ pub enum SignatureType {
     SelfSign,
     /// Signature using previous key
     PrevSign,
+    /// Signature of one of the identity controllers
+    ControllerSign,
 }
 
 /// Signature, its type and data
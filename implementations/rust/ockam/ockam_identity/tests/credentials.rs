@@ -3,8 +3,12 @@ use ockam_core::{async_trait, AllowAll, Any};
 use ockam_core::{route, Result, Routed, Worker};
 use ockam_identity::authenticated_storage::mem::InMemoryStorage;
 use ockam_identity::credential::access_control::CredentialAccessControl;
-use ockam_identity::credential::{AttributesStorageUtils, Credential};
-use ockam_identity::{Identity, TrustEveryonePolicy, TrustIdentifierPolicy};
+use ockam_identity::credential::{
+    AttributeType, AttributesStorageUtils, Credential, Schema, SchemaId, VerifiableCredential,
+};
+use ockam_identity::{
+    Identity, IdentityIdentifier, KeyPurpose, TrustEveryonePolicy, TrustIdentifierPolicy,
+};
 use ockam_node::{Context, WorkerBuilder};
 use ockam_vault::Vault;
 use std::sync::atomic::{AtomicI8, Ordering};
@@ -228,3 +232,242 @@ async fn access_control(ctx: &mut Context) -> Result<()> {
 
     ctx.stop().await
 }
+
+#[ockam_macros::test]
+async fn verifiable_credential_roundtrip(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+    let authority = Identity::create(ctx, &vault).await?;
+    let client = Identity::create(ctx, &vault).await?;
+
+    let credential = Credential::builder(client.identifier().clone())
+        .with_schema(SchemaId(1))
+        .with_attribute("name", b"alice")
+        .with_attribute("key", &[0xff, 0x00]);
+    let credential = authority.issue_credential(credential).await?;
+
+    let vc = credential.to_verifiable_credential()?;
+    assert_eq!(vc.issuer(), authority.identifier().to_did());
+    assert_eq!(
+        IdentityIdentifier::from_did(vc.issuer())?,
+        *authority.identifier()
+    );
+
+    let json = serde_json::to_string(&vc).unwrap();
+    let vc: VerifiableCredential = serde_json::from_str(&json).unwrap();
+    let imported = Credential::from_verifiable_credential(&vc)?;
+    assert_eq!(imported.unverified_data(), credential.unverified_data());
+    assert_eq!(imported.signature(), credential.signature());
+
+    let data = authority
+        .to_public()
+        .await?
+        .verify_credential(&imported, client.identifier(), &vault)
+        .await?;
+    assert_eq!(data.attributes().get("name"), Some(&b"alice"[..]));
+    assert_eq!(data.attributes().get("key"), Some(&[0xff, 0x00][..]));
+
+    // Changing a claim breaks the proof
+    let json = json.replace("alice", "mallory");
+    let vc: VerifiableCredential = serde_json::from_str(&json).unwrap();
+    let forged = Credential::from_verifiable_credential(&vc)?;
+    assert!(authority
+        .to_public()
+        .await?
+        .verify_credential(&forged, client.identifier(), &vault)
+        .await
+        .is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn verification_method_resolves_in_did_document(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+    let authority = Identity::create(ctx, &vault).await?;
+    let client = Identity::create(ctx, &vault).await?;
+    authority
+        .create_key_with_purposes("channel".into(), &[KeyPurpose::SecureChannel])
+        .await?;
+
+    let credential = Credential::builder(client.identifier().clone()).with_attribute("a", b"b");
+    let credential = authority.issue_credential(credential).await?;
+    let vc = serde_json::to_value(credential.to_verifiable_credential()?).unwrap();
+    assert_eq!(vc["proof"]["type"], "OckamCredentialSignature2022");
+    let method = vc["proof"]["verificationMethod"].as_str().unwrap();
+
+    let document = authority.to_public().await?.did_document()?;
+    let methods = document["verificationMethod"].as_array().unwrap();
+    let key = methods.iter().find(|m| m["id"] == method).unwrap();
+    assert_eq!(key["controller"], vc["issuer"]);
+    assert!(document["assertionMethod"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|m| m == method));
+
+    // Keys restricted to other purposes can't issue credentials
+    assert_eq!(methods.len(), 1);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn credential_jwt(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+    let authority = Identity::create(ctx, &vault).await?;
+    let client = Identity::create(ctx, &vault).await?;
+    let other = Identity::create(ctx, &vault).await?;
+
+    let credential =
+        Credential::builder(client.identifier().clone()).with_attribute("is_superuser", b"true");
+    let credential = authority.issue_credential(credential).await?;
+
+    // Only the issuer can sign the JWT
+    assert!(client.credential_to_jwt(&credential).await.is_err());
+    let jwt = authority.credential_to_jwt(&credential).await?;
+
+    let authorities = vec![authority.to_public().await?];
+    let verified = client
+        .verify_credential_jwt(&jwt, client.identifier(), &authorities)
+        .await?;
+    assert_eq!(verified.unverified_data(), credential.unverified_data());
+
+    // Unknown authority
+    let others = vec![other.to_public().await?];
+    assert!(client
+        .verify_credential_jwt(&jwt, client.identifier(), &others)
+        .await
+        .is_err());
+
+    // Wrong subject
+    assert!(client
+        .verify_credential_jwt(&jwt, other.identifier(), &authorities)
+        .await
+        .is_err());
+
+    // Tampered signature
+    let mut tampered = jwt.clone();
+    let last = if tampered.ends_with('A') { "B" } else { "A" };
+    tampered.replace_range(tampered.len() - 1.., last);
+    assert!(client
+        .verify_credential_jwt(&tampered, client.identifier(), &authorities)
+        .await
+        .is_err());
+
+    let document = authority.to_public().await?.did_document()?;
+    assert_eq!(document["id"], authority.identifier().to_did());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn selective_disclosure(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+    let authority = Identity::create(ctx, &vault).await?;
+
+    let server = Identity::create(ctx, &vault).await?;
+    let server_storage = InMemoryStorage::new();
+    server
+        .create_secure_channel_listener("listener", TrustEveryonePolicy, &server_storage)
+        .await?;
+    server
+        .start_credentials_exchange_worker(
+            vec![authority.to_public().await?],
+            "credential_exchange",
+            false,
+            server_storage.clone(),
+        )
+        .await?;
+
+    let client = Identity::create(ctx, &vault).await?;
+    let channel = client
+        .create_secure_channel(
+            route!["listener"],
+            TrustIdentifierPolicy::new(server.identifier().clone()),
+            &InMemoryStorage::new(),
+        )
+        .await?;
+
+    let credential = Credential::builder(client.identifier().clone())
+        .with_selective_disclosure()
+        .with_attribute("role", b"admin")
+        .with_attribute("email", b"alice@example.com");
+    let credential = authority.issue_credential(credential).await?;
+
+    assert!(credential.disclose(&["unknown"]).is_err());
+    assert!(credential.to_verifiable_credential().is_err());
+
+    client.set_credential(Some(credential)).await;
+    client
+        .present_credential_disclosing(route![channel, "credential_exchange"], Some(&["role"]))
+        .await?;
+
+    let attrs = AttributesStorageUtils::get_attributes(client.identifier(), &server_storage)
+        .await?
+        .unwrap();
+    assert_eq!(attrs.get("role"), Some(&b"admin".to_vec()));
+    assert_eq!(attrs.get("email"), None);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn schema_validation(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+    let authority = Identity::create(ctx, &vault).await?;
+    let client = Identity::create(ctx, &vault).await?;
+
+    let schema = Schema::new(SchemaId(7), "employee", Duration::from_secs(3600))
+        .with_attribute("name", AttributeType::Utf8, true)
+        .with_attribute("admin", AttributeType::Bool, false);
+    authority.schemas().register(schema.clone());
+
+    // Missing required attribute
+    let builder = Credential::builder(client.identifier().clone())
+        .with_schema(SchemaId(7))
+        .with_attribute("admin", b"true");
+    assert!(authority.issue_credential(builder).await.is_err());
+
+    // Wrong type
+    let builder = Credential::builder(client.identifier().clone())
+        .with_schema(SchemaId(7))
+        .with_attribute("name", b"alice")
+        .with_attribute("admin", b"yes");
+    assert!(authority.issue_credential(builder).await.is_err());
+
+    // Too long validity
+    let builder = Credential::builder(client.identifier().clone())
+        .with_schema(SchemaId(7))
+        .with_attribute("name", b"alice");
+    assert!(authority.issue_credential(builder).await.is_err());
+
+    let builder = Credential::builder(client.identifier().clone())
+        .with_schema(SchemaId(7))
+        .with_attribute("name", b"alice")
+        .with_attribute("admin", b"false")
+        .valid_for(Duration::from_secs(600));
+    let credential = authority.issue_credential(builder).await?;
+
+    let authorities = vec![authority.to_public().await?];
+    client
+        .verify_self_credential(&credential, &authorities)
+        .await?;
+
+    // A verifier with a stricter definition of the schema rejects it
+    client.schemas().register(
+        Schema::new(SchemaId(7), "employee", Duration::from_secs(3600)).with_attribute(
+            "name",
+            AttributeType::Utf8,
+            true,
+        ),
+    );
+    assert!(client
+        .verify_self_credential(&credential, &authorities)
+        .await
+        .is_err());
+
+    let shown = credential.display_with(authority.schemas()).to_string();
+    assert!(shown.contains("Schema: employee (7)"));
+
+    ctx.stop().await
+}
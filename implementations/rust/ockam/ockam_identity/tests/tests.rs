@@ -1,8 +1,15 @@
+use core::time::Duration;
+use minicbor::Decoder;
+use ockam_core::api::{Request, Response, Status};
+use ockam_core::compat::collections::BTreeMap;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::vault::{SecretAttributes, SecretPersistence, SecretType, SecretVault};
-use ockam_core::{Error, Result};
+use ockam_core::{route, Error, Result};
 use ockam_identity::authenticated_storage::mem::InMemoryStorage;
-use ockam_identity::Identity;
+use ockam_identity::change_history::IdentityHistoryComparison;
+use ockam_identity::directory::DEFAULT_SYNC_INTERVAL;
+use ockam_identity::{Identity, PublicIdentity, TrustIdentifierPolicy};
+use ockam_node::api::request;
 use ockam_node::Context;
 use ockam_vault::Vault;
 use rand::{thread_rng, RngCore};
@@ -180,3 +187,151 @@ async fn add_key(ctx: &mut Context) -> Result<()> {
 
     ctx.stop().await
 }
+
+#[ockam_macros::test]
+async fn identity_directory(ctx: &mut Context) -> Result<()> {
+    let alice_vault = Vault::create();
+    let bob_vault = Vault::create();
+
+    let alice_storage = InMemoryStorage::new();
+    let bob_storage = InMemoryStorage::new();
+
+    let alice = Identity::create(ctx, &alice_vault).await?;
+    let bob = Identity::create(ctx, &bob_vault).await?;
+
+    alice
+        .create_secure_channel_listener(
+            "alice_listener",
+            TrustIdentifierPolicy::new(bob.identifier().clone()),
+            &alice_storage,
+        )
+        .await?;
+    alice
+        .start_identity_directory(
+            "alice_directory",
+            alice_storage.clone(),
+            BTreeMap::new(),
+            DEFAULT_SYNC_INTERVAL,
+        )
+        .await?;
+
+    let bob_channel = bob
+        .create_secure_channel(
+            route!["alice_listener"],
+            TrustIdentifierPolicy::new(alice.identifier().clone()),
+            &bob_storage,
+        )
+        .await?;
+
+    // Requests outside of a secure channel are refused
+    let buf = request(
+        ctx,
+        "identity_directory",
+        None,
+        route!["alice_directory"],
+        Request::get(format!("identities/{}", bob.identifier())),
+    )
+    .await?;
+    let res: Response = Decoder::new(&buf).decode()?;
+    assert_eq!(res.status(), Some(Status::Forbidden));
+
+    // Bob rotates his root key and tells Alice
+    bob.rotate_root_key().await?;
+    bob.push_identity_history(route![bob_channel.clone(), "alice_directory"])
+        .await?;
+
+    let known_bob = alice
+        .get_known_identity(bob.identifier(), &alice_storage)
+        .await?
+        .unwrap();
+    if known_bob.compare(&bob.to_public().await?) != IdentityHistoryComparison::Equal {
+        return test_error("alice did not store bob's new history");
+    }
+
+    // Bob's history is fetched by Alice through her own channel
+    bob.create_secure_channel_listener(
+        "bob_listener",
+        TrustIdentifierPolicy::new(alice.identifier().clone()),
+        &bob_storage,
+    )
+    .await?;
+    bob.start_identity_directory(
+        "bob_directory",
+        bob_storage.clone(),
+        BTreeMap::new(),
+        DEFAULT_SYNC_INTERVAL,
+    )
+    .await?;
+    let alice_channel = alice
+        .create_secure_channel(
+            route!["bob_listener"],
+            TrustIdentifierPolicy::new(bob.identifier().clone()),
+            &alice_storage,
+        )
+        .await?;
+
+    bob.create_key("Truck management".to_string()).await?;
+    let comparison = alice
+        .fetch_identity_history(
+            route![alice_channel.clone(), "bob_directory"],
+            bob.identifier(),
+            &alice_storage,
+        )
+        .await?;
+    assert_eq!(comparison, IdentityHistoryComparison::Newer);
+
+    // A fork of Bob's history conflicts with the one Alice knows,
+    // her channels with Bob get closed
+    let forked_bob = Identity::import(ctx, &bob.export().await?, &bob_vault).await?;
+    forked_bob.rotate_root_key().await?;
+    bob.rotate_root_key().await?;
+    let comparison = alice
+        .fetch_identity_history(
+            route![alice_channel.clone(), "bob_directory"],
+            bob.identifier(),
+            &alice_storage,
+        )
+        .await?;
+    assert_eq!(comparison, IdentityHistoryComparison::Newer);
+
+    if forked_bob
+        .push_identity_history(route![bob_channel, "alice_directory"])
+        .await
+        .is_ok()
+    {
+        return test_error("conflicting history was accepted");
+    }
+
+    ctx.sleep(Duration::from_millis(250)).await;
+    if ctx
+        .send(route![alice_channel, ctx.address()], "Hello".to_string())
+        .await
+        .is_ok()
+    {
+        return test_error("channel with conflicting peer is still open");
+    }
+
+    ctx.stop().await
+}
+
+/// Change history exported before key purposes and revocations were
+/// added: the root key and a key labelled "extra", each rotated once
+const IDENTITY_BEFORE_KEY_PURPOSES: &str =
+    include_str!("fixtures/identity_before_key_purposes.hex");
+
+#[ockam_macros::test]
+async fn test_import_identity_exported_before_key_purposes(ctx: &mut Context) -> Result<()> {
+    let vault = Vault::create();
+    let data = hex::decode(IDENTITY_BEFORE_KEY_PURPOSES.trim()).unwrap();
+
+    let identity = PublicIdentity::import(&data, &vault).await?;
+    assert_eq!(
+        identity.identifier().to_string(),
+        "P929a0519ef493677b8d3ca87466f01a8c891c67dbea51e60e5a29592c97910c5"
+    );
+    if identity.export()? != data {
+        return test_error("re-exported history differs from the original");
+    }
+
+    ctx.stop().await
+}
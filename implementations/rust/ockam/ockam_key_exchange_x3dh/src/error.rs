@@ -11,6 +11,7 @@ pub enum X3DHError {
     MessageLenMismatch,
     SignatureLenMismatch,
     InvalidHash,
+    InvalidSignature,
 }
 
 impl ockam_core::compat::error::Error for X3DHError {}
@@ -21,6 +22,7 @@ impl core::fmt::Display for X3DHError {
             Self::MessageLenMismatch => "message length mismatch".fmt(f),
             Self::SignatureLenMismatch => "signature length mismatch".fmt(f),
             Self::InvalidHash => "invalid hash".fmt(f),
+            Self::InvalidSignature => "invalid prekey signature".fmt(f),
         }
     }
 }
@@ -30,7 +32,7 @@ impl From<X3DHError> for Error {
     fn from(err: X3DHError) -> Self {
         use X3DHError::*;
         let kind = match err {
-            InvalidState | InvalidHash => Kind::Invalid,
+            InvalidState | InvalidHash | InvalidSignature => Kind::Invalid,
             MessageLenMismatch | SignatureLenMismatch => Kind::Misuse,
         };
 
@@ -123,13 +123,17 @@ impl<V: X3dhVault> KeyExchanger for Initiator<V> {
                     .ok_or(X3DHError::InvalidState)?;
 
                 // Check the prekey_bundle signature
-                self.vault
+                let verified = self
+                    .vault
                     .verify(
                         &GenericSignature::new(prekey_bundle.signature_prekey.as_ref().to_vec()),
                         &prekey_bundle.identity_key,
                         prekey_bundle.signed_prekey.data(),
                     )
                     .await?;
+                if !verified {
+                    return Err(X3DHError::InvalidSignature.into());
+                }
 
                 let dh1 = self
                     .vault
@@ -143,15 +147,17 @@ impl<V: X3dhVault> KeyExchanger for Initiator<V> {
                     .vault
                     .ec_diffie_hellman(ephemeral_identity_key, &prekey_bundle.signed_prekey)
                     .await?;
-                let dh4 = self
-                    .vault
-                    .ec_diffie_hellman(ephemeral_identity_key, &prekey_bundle.one_time_prekey)
-                    .await?;
                 let mut ikm_bytes = vec![0xFFu8; 32]; // FIXME: Why is it here?
                 ikm_bytes.extend_from_slice(self.vault.secret_export(&dh1).await?.as_ref());
                 ikm_bytes.extend_from_slice(self.vault.secret_export(&dh2).await?.as_ref());
                 ikm_bytes.extend_from_slice(self.vault.secret_export(&dh3).await?.as_ref());
-                ikm_bytes.extend_from_slice(self.vault.secret_export(&dh4).await?.as_ref());
+                if let Some(one_time_prekey) = &prekey_bundle.one_time_prekey {
+                    let dh4 = self
+                        .vault
+                        .ec_diffie_hellman(ephemeral_identity_key, one_time_prekey)
+                        .await?;
+                    ikm_bytes.extend_from_slice(self.vault.secret_export(&dh4).await?.as_ref());
+                }
 
                 let ikm = self
                     .vault
@@ -60,18 +60,57 @@ pub struct PreKeyBundle {
     identity_key: PublicKey,
     signed_prekey: PublicKey,
     signature_prekey: Signature,
-    one_time_prekey: PublicKey,
+    one_time_prekey: Option<PublicKey>,
 }
 
 impl PreKeyBundle {
     const SIZE: usize = 32 + 32 + 64 + 32;
+    const SIZE_WITHOUT_ONE_TIME_PREKEY: usize = 32 + 32 + 64;
+
+    /// Create a prekey bundle from published keys
+    ///
+    /// `signature_prekey` is the signature of the signed prekey data
+    /// with the identity key.  A bundle without a one-time prekey is
+    /// handed out once the one-time prekeys of the responder run out;
+    /// the exchange then relies on the signed prekey alone.
+    pub fn new(
+        identity_key: PublicKey,
+        signed_prekey: PublicKey,
+        signature_prekey: Signature,
+        one_time_prekey: Option<PublicKey>,
+    ) -> Self {
+        Self {
+            identity_key,
+            signed_prekey,
+            signature_prekey,
+            one_time_prekey,
+        }
+    }
+
+    /// The long-term identity key of the responder
+    pub fn identity_key(&self) -> &PublicKey {
+        &self.identity_key
+    }
+
+    /// The signed prekey of the responder
+    pub fn signed_prekey(&self) -> &PublicKey {
+        &self.signed_prekey
+    }
+
+    /// The one-time prekey of the responder, if any
+    pub fn one_time_prekey(&self) -> Option<&PublicKey> {
+        self.one_time_prekey.as_ref()
+    }
+
     /// Convert the prekey bundle to a byte array
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut output = Vec::new();
         output.extend_from_slice(self.identity_key.data());
         output.extend_from_slice(self.signed_prekey.data());
         output.extend_from_slice(self.signature_prekey.0.as_ref());
-        output.extend_from_slice(self.one_time_prekey.data());
+        if let Some(one_time_prekey) = &self.one_time_prekey {
+            output.extend_from_slice(one_time_prekey.data());
+        }
         output
     }
 }
@@ -80,14 +119,20 @@ impl TryFrom<&[u8]> for PreKeyBundle {
     type Error = ockam_core::Error;
 
     fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
-        if data.len() != Self::SIZE {
+        if data.len() != Self::SIZE && data.len() != Self::SIZE_WITHOUT_ONE_TIME_PREKEY {
             return Err(X3DHError::MessageLenMismatch.into());
         }
         let identity_key = PublicKey::new(array_ref![data, 0, 32].to_vec(), SecretType::X25519);
         let signed_prekey = PublicKey::new(array_ref![data, 32, 32].to_vec(), SecretType::X25519);
         let signature_prekey = Signature(*array_ref![data, 64, 64]);
-        let one_time_prekey =
-            PublicKey::new(array_ref![data, 128, 32].to_vec(), SecretType::X25519);
+        let one_time_prekey = if data.len() == Self::SIZE {
+            Some(PublicKey::new(
+                array_ref![data, 128, 32].to_vec(),
+                SecretType::X25519,
+            ))
+        } else {
+            None
+        };
         Ok(Self {
             identity_key,
             signed_prekey,
@@ -178,4 +223,136 @@ mod tests {
         assert_eq!(s1, s2);
         ctx.stop().await
     }
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test]
+    async fn offline_flow__published_prekeys__keys_should_match(ctx: &mut Context) -> Result<()> {
+        use ockam_core::vault::{
+            SecretAttributes, SecretPersistence, CURVE25519_SECRET_LENGTH_U32,
+        };
+
+        let vault = Vault::create();
+        let key_exchanger = X3dhNewKeyExchanger::new(vault.async_try_clone().await?);
+
+        let atts = SecretAttributes::new(
+            SecretType::X25519,
+            SecretPersistence::Persistent,
+            CURVE25519_SECRET_LENGTH_U32,
+        );
+        let identity_key = vault.secret_generate(atts).await?;
+        let signed_prekey = vault.secret_generate(atts).await?;
+        let one_time_prekey = vault.secret_generate(atts).await?;
+
+        // The responder publishes its bundle before going offline
+        let signed_prekey_pub = vault.secret_public_key_get(&signed_prekey).await?;
+        let signature = vault.sign(&identity_key, signed_prekey_pub.data()).await?;
+        let bundle = PreKeyBundle::new(
+            vault.secret_public_key_get(&identity_key).await?,
+            signed_prekey_pub,
+            Signature(*array_ref![signature.as_ref(), 0, 64]),
+            Some(vault.secret_public_key_get(&one_time_prekey).await?),
+        );
+
+        let mut initiator = key_exchanger.initiator().await?;
+        let header = initiator.generate_request(&[]).await?;
+        initiator.handle_response(&bundle.to_bytes()).await?;
+        let initiator = initiator.finalize().await?;
+
+        // The responder comes back online and receives the initiator keys
+        let mut responder = key_exchanger
+            .responder_with_prekeys(identity_key, signed_prekey, Some(one_time_prekey))
+            .await?;
+        responder.handle_response(&header).await?;
+        let responder = responder.finalize().await?;
+
+        assert_eq!(initiator.h(), responder.h());
+
+        let s1 = vault.secret_export(initiator.encrypt_key()).await?;
+        let s2 = vault.secret_export(responder.decrypt_key()).await?;
+
+        assert_eq!(s1, s2);
+        ctx.stop().await
+    }
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test]
+    async fn offline_flow__no_one_time_prekey__keys_should_match(ctx: &mut Context) -> Result<()> {
+        use ockam_core::vault::{
+            SecretAttributes, SecretPersistence, CURVE25519_SECRET_LENGTH_U32,
+        };
+
+        let vault = Vault::create();
+        let key_exchanger = X3dhNewKeyExchanger::new(vault.async_try_clone().await?);
+
+        let atts = SecretAttributes::new(
+            SecretType::X25519,
+            SecretPersistence::Persistent,
+            CURVE25519_SECRET_LENGTH_U32,
+        );
+        let identity_key = vault.secret_generate(atts).await?;
+        let signed_prekey = vault.secret_generate(atts).await?;
+
+        let signed_prekey_pub = vault.secret_public_key_get(&signed_prekey).await?;
+        let signature = vault.sign(&identity_key, signed_prekey_pub.data()).await?;
+        let bundle = PreKeyBundle::new(
+            vault.secret_public_key_get(&identity_key).await?,
+            signed_prekey_pub,
+            Signature(*array_ref![signature.as_ref(), 0, 64]),
+            None,
+        );
+        let bundle = PreKeyBundle::try_from(bundle.to_bytes().as_slice())?;
+        assert!(bundle.one_time_prekey().is_none());
+
+        let mut initiator = key_exchanger.initiator().await?;
+        let header = initiator.generate_request(&[]).await?;
+        initiator.handle_response(&bundle.to_bytes()).await?;
+        let initiator = initiator.finalize().await?;
+
+        let mut responder = key_exchanger
+            .responder_with_prekeys(identity_key, signed_prekey, None)
+            .await?;
+        responder.handle_response(&header).await?;
+        let responder = responder.finalize().await?;
+
+        assert_eq!(initiator.h(), responder.h());
+
+        let s1 = vault.secret_export(initiator.encrypt_key()).await?;
+        let s2 = vault.secret_export(responder.decrypt_key()).await?;
+
+        assert_eq!(s1, s2);
+        ctx.stop().await
+    }
+
+    #[allow(non_snake_case)]
+    #[ockam_macros::test]
+    async fn offline_flow__forged_signature__should_fail(ctx: &mut Context) -> Result<()> {
+        use ockam_core::vault::{
+            SecretAttributes, SecretPersistence, CURVE25519_SECRET_LENGTH_U32,
+        };
+
+        let vault = Vault::create();
+        let key_exchanger = X3dhNewKeyExchanger::new(vault.async_try_clone().await?);
+
+        let atts = SecretAttributes::new(
+            SecretType::X25519,
+            SecretPersistence::Ephemeral,
+            CURVE25519_SECRET_LENGTH_U32,
+        );
+        let identity_key = vault.secret_generate(atts).await?;
+        let signed_prekey = vault.secret_generate(atts).await?;
+        let one_time_prekey = vault.secret_generate(atts).await?;
+
+        let bundle = PreKeyBundle::new(
+            vault.secret_public_key_get(&identity_key).await?,
+            vault.secret_public_key_get(&signed_prekey).await?,
+            Signature([0u8; 64]),
+            Some(vault.secret_public_key_get(&one_time_prekey).await?),
+        );
+
+        let mut initiator = key_exchanger.initiator().await?;
+        initiator.generate_request(&[]).await?;
+        assert!(initiator.handle_response(&bundle.to_bytes()).await.is_err());
+
+        ctx.stop().await
+    }
 }
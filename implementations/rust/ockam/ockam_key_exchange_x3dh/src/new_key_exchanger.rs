@@ -1,6 +1,6 @@
 use crate::{Initiator, Responder, X3dhVault};
 use ockam_core::{async_trait, compat::boxed::Box};
-use ockam_core::{AsyncTryClone, Result};
+use ockam_core::{vault::KeyId, AsyncTryClone, Result};
 use ockam_key_exchange_core::NewKeyExchanger;
 
 /// Represents an XX NewKeyExchanger
@@ -21,6 +21,32 @@ impl<V: X3dhVault> X3dhNewKeyExchanger<V> {
     pub fn new(vault: V) -> Self {
         Self { vault }
     }
+
+    /// Create an initiator that uses an existing identity key
+    pub async fn initiator_with_identity_key(&self, identity_key: KeyId) -> Result<Initiator<V>> {
+        Ok(Initiator::new(
+            self.vault.async_try_clone().await?,
+            Some(identity_key),
+        ))
+    }
+
+    /// Create a responder for a prekey bundle that was published earlier
+    ///
+    /// The responder expects the initiator keys through
+    /// `handle_response` and can be finalized right after.
+    pub async fn responder_with_prekeys(
+        &self,
+        identity_key: KeyId,
+        signed_prekey: KeyId,
+        one_time_prekey: Option<KeyId>,
+    ) -> Result<Responder<V>> {
+        Ok(Responder::with_prekeys(
+            self.vault.async_try_clone().await?,
+            identity_key,
+            signed_prekey,
+            one_time_prekey,
+        ))
+    }
 }
 
 #[async_trait]
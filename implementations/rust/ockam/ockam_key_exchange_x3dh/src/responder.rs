@@ -29,6 +29,8 @@ pub struct Responder<V: X3dhVault> {
     identity_key: Option<KeyId>,
     signed_prekey: Option<KeyId>,
     one_time_prekey: Option<KeyId>,
+    /// Set when the initiator was handed a bundle without a one-time prekey
+    without_one_time_prekey: bool,
     state: ResponderState,
     vault: V,
     completed_key_exchange: Option<CompletedKeyExchange>,
@@ -40,6 +42,29 @@ impl<V: X3dhVault> Responder<V> {
             identity_key,
             signed_prekey: None,
             one_time_prekey: None,
+            without_one_time_prekey: false,
+            completed_key_exchange: None,
+            state: ResponderState::HandleInitiatorKeys,
+            vault,
+        }
+    }
+
+    /// Create a responder that uses previously published prekeys
+    ///
+    /// This lets a responder that was offline complete the exchange
+    /// once it receives the initiator keys.  `one_time_prekey` is
+    /// `None` when the initiator was handed a bundle without one.
+    pub(crate) fn with_prekeys(
+        vault: V,
+        identity_key: KeyId,
+        signed_prekey: KeyId,
+        one_time_prekey: Option<KeyId>,
+    ) -> Self {
+        Self {
+            identity_key: Some(identity_key),
+            signed_prekey: Some(signed_prekey),
+            without_one_time_prekey: one_time_prekey.is_none(),
+            one_time_prekey,
             completed_key_exchange: None,
             state: ResponderState::HandleInitiatorKeys,
             vault,
@@ -60,8 +85,12 @@ impl<V: X3dhVault> Responder<V> {
         if self.identity_key.is_none() {
             self.identity_key = Some(self.vault.secret_generate(p_atts).await?);
         }
-        self.signed_prekey = Some(self.vault.secret_generate(p_atts).await?);
-        self.one_time_prekey = Some(self.vault.secret_generate(e_atts).await?);
+        if self.signed_prekey.is_none() {
+            self.signed_prekey = Some(self.vault.secret_generate(p_atts).await?);
+        }
+        if self.one_time_prekey.is_none() && !self.without_one_time_prekey {
+            self.one_time_prekey = Some(self.vault.secret_generate(e_atts).await?);
+        }
         Ok(())
     }
 }
@@ -119,7 +148,7 @@ impl<V: X3dhVault> KeyExchanger for Responder<V> {
                     identity_key,
                     signed_prekey: signed_prekey_pub,
                     signature_prekey: Signature(*signature_array),
-                    one_time_prekey: one_time_prekey_pub,
+                    one_time_prekey: Some(one_time_prekey_pub),
                 };
                 self.state = ResponderState::Done;
                 Ok(bundle.to_bytes())
@@ -144,10 +173,6 @@ impl<V: X3dhVault> KeyExchanger for Responder<V> {
                     PublicKey::new(array_ref![response, 32, 32].to_vec(), SecretType::X25519);
 
                 let signed_prekey = self.signed_prekey.as_ref().ok_or(X3DHError::InvalidState)?;
-                let one_time_prekey = self
-                    .one_time_prekey
-                    .as_ref()
-                    .ok_or(X3DHError::InvalidState)?;
 
                 let local_static_secret =
                     self.identity_key.as_ref().ok_or(X3DHError::InvalidState)?;
@@ -164,15 +189,17 @@ impl<V: X3dhVault> KeyExchanger for Responder<V> {
                     .vault
                     .ec_diffie_hellman(signed_prekey, &other_ephemeral_pubkey)
                     .await?;
-                let dh4 = self
-                    .vault
-                    .ec_diffie_hellman(one_time_prekey, &other_ephemeral_pubkey)
-                    .await?;
                 let mut ikm_bytes = vec![0xFFu8; 32]; // FIXME
                 ikm_bytes.extend_from_slice(self.vault.secret_export(&dh1).await?.as_ref());
                 ikm_bytes.extend_from_slice(self.vault.secret_export(&dh2).await?.as_ref());
                 ikm_bytes.extend_from_slice(self.vault.secret_export(&dh3).await?.as_ref());
-                ikm_bytes.extend_from_slice(self.vault.secret_export(&dh4).await?.as_ref());
+                if let Some(one_time_prekey) = &self.one_time_prekey {
+                    let dh4 = self
+                        .vault
+                        .ec_diffie_hellman(one_time_prekey, &other_ephemeral_pubkey)
+                        .await?;
+                    ikm_bytes.extend_from_slice(self.vault.secret_export(&dh4).await?.as_ref());
+                }
 
                 let ikm = self
                     .vault
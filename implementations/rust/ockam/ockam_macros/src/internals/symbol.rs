@@ -10,6 +10,7 @@ pub(crate) struct Symbol(&'static str);
 pub(crate) const ACCESS_CONTROL: Symbol = Symbol("access_control");
 pub(crate) const NO_MAIN: Symbol = Symbol("no_main");
 pub(crate) const OCKAM_CRATE: Symbol = Symbol("crate");
+pub(crate) const SEED: Symbol = Symbol("seed");
 pub(crate) const TIMEOUT_MS: Symbol = Symbol("timeout");
 
 // Derive's helper attributes
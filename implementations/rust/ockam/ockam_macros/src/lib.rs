@@ -116,6 +116,11 @@ pub fn node(attrs: TokenStream, item: TokenStream) -> TokenStream {
 ///   indefinitely. If the test times out it will panic. Defaults to 30000 (30
 ///   seconds).
 ///
+/// - `#[ockam::test(seed = 42)]`: the macro runs the test in a deterministic
+///   simulation seeded with the given value, with a virtual clock and an
+///   in-memory transport. The timeout then also follows the virtual clock.
+///   Requires the `simulation` feature of `ockam_node`.
+///
 /// Example of use:
 ///
 /// ```ignore
@@ -92,6 +92,10 @@ fn output(mut cont: Container) -> TokenStream {
     let test_fn_ident = &cont.test_fn.sig.ident;
     let ockam_crate = cont.data.attrs.ockam_crate;
     let timeout_ms = cont.data.attrs.timeout_ms;
+    let node_builder = match cont.data.attrs.seed {
+        None => quote! { NodeBuilder::without_access_control() },
+        Some(seed) => quote! { NodeBuilder::without_access_control().simulation(#seed) },
+    };
     cont.original_fn.block = parse2(quote! {
         {
             use core::panic::AssertUnwindSafe;
@@ -99,7 +103,7 @@ fn output(mut cont: Container) -> TokenStream {
             use ockam_core::{Error, errcode::{Origin, Kind}};
             use #ockam_crate::{NodeBuilder, compat::{tokio::time::timeout, futures::FutureExt}};
 
-            let (mut #ctx_ident, mut executor) = NodeBuilder::without_access_control().build();
+            let (mut #ctx_ident, mut executor) = #node_builder.build();
             executor
                 .execute(async move {
                     // Wraps the test function call in a `catch_unwind` to catch possible panics.
@@ -214,12 +218,14 @@ impl<'a> Data<'a> {
 struct Attributes {
     ockam_crate: TokenStream,
     timeout_ms: u64,
+    seed: Option<u64>,
 }
 
 impl Attributes {
     fn from_ast(ctx: &Context, attrs: &AttributeArgs) -> Self {
         let mut ockam_crate = Attr::none(ctx, OCKAM_CRATE);
         let mut timeout_ms = Attr::none(ctx, TIMEOUT_MS);
+        let mut seed = Attr::none(ctx, SEED);
         for attr in attrs {
             match attr {
                 // Parse `#[ockam::test(crate = "ockam")]`
@@ -234,6 +240,12 @@ impl Attributes {
                         timeout_ms.set(&nv.path, timeout);
                     }
                 }
+                // Parse `#[ockam::test(seed = 42)]`
+                NestedMeta::Meta(NameValue(nv)) if nv.path == SEED => {
+                    if let Ok(s) = parse_lit_into_int::<u64>(ctx, SEED, &nv.lit) {
+                        seed.set(&nv.path, s);
+                    }
+                }
                 NestedMeta::Meta(m) => {
                     let path = m.path().into_token_stream().to_string().replace(' ', "");
                     ctx.error_spanned_by(m.path(), format!("unknown attribute `{}`", path));
@@ -246,6 +258,7 @@ impl Attributes {
         Self {
             ockam_crate: ockam_crate.get().unwrap_or(quote! { ockam_node }),
             timeout_ms: timeout_ms.get().unwrap_or(30_000),
+            seed: seed.get(),
         }
     }
 }
@@ -1,5 +1,5 @@
 use super::{Buffer, Checked, Code, Codec, Protocol};
-use crate::proto::{DnsAddr, Node, Project, Secure, Service, Space, Tcp};
+use crate::proto::{DnsAddr, Node, Project, Secure, Service, Space, Tcp, Udp, Unix};
 use crate::{Error, ProtoValue};
 use core::fmt;
 use unsigned_varint::decode;
@@ -9,9 +9,13 @@ pub struct StdCodec;
 impl Codec for StdCodec {
     fn split_str<'a>(
         &self,
-        _prefix: &str,
+        prefix: &str,
         input: &'a str,
     ) -> Result<(Checked<&'a str>, &'a str), Error> {
+        // A socket path contains slashes and extends to the end
+        if prefix == Unix::PREFIX {
+            return Ok((Checked(input), ""));
+        }
         if let Some(p) = input.find('/') {
             let (x, y) = input.split_at(p);
             Ok((Checked(x), y))
@@ -49,7 +53,15 @@ impl Codec for StdCodec {
                 let (x, y) = input.split_at(2);
                 Ok((Checked(x), y))
             }
+            Udp::CODE => {
+                if input.len() < 2 {
+                    return Err(Error::required_bytes(Udp::CODE, 2));
+                }
+                let (x, y) = input.split_at(2);
+                Ok((Checked(x), y))
+            }
             c @ DnsAddr::CODE
+            | c @ Unix::CODE
             | c @ Service::CODE
             | c @ Node::CODE
             | c @ Project::CODE
@@ -73,6 +85,8 @@ impl Codec for StdCodec {
             #[cfg(feature = "std")]
             crate::proto::Ip6::CODE => crate::proto::Ip6::read_bytes(input).is_ok(),
             Tcp::CODE => Tcp::read_bytes(input).is_ok(),
+            Udp::CODE => Udp::read_bytes(input).is_ok(),
+            Unix::CODE => Unix::read_bytes(input).is_ok(),
             DnsAddr::CODE => DnsAddr::read_bytes(input).is_ok(),
             Service::CODE => Service::read_bytes(input).is_ok(),
             Node::CODE => Node::read_bytes(input).is_ok(),
@@ -90,6 +104,8 @@ impl Codec for StdCodec {
             #[cfg(feature = "std")]
             crate::proto::Ip6::CODE => crate::proto::Ip6::read_bytes(val.data())?.write_bytes(buf),
             Tcp::CODE => Tcp::read_bytes(val.data())?.write_bytes(buf),
+            Udp::CODE => Udp::read_bytes(val.data())?.write_bytes(buf),
+            Unix::CODE => Unix::read_bytes(val.data())?.write_bytes(buf),
             DnsAddr::CODE => DnsAddr::read_bytes(val.data())?.write_bytes(buf),
             Service::CODE => Service::read_bytes(val.data())?.write_bytes(buf),
             Node::CODE => Node::read_bytes(val.data())?.write_bytes(buf),
@@ -122,6 +138,14 @@ impl Codec for StdCodec {
                 Tcp::read_str(value)?.write_bytes(buf);
                 Ok(())
             }
+            Udp::PREFIX => {
+                Udp::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
+            Unix::PREFIX => {
+                Unix::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
             DnsAddr::PREFIX => {
                 DnsAddr::read_str(value)?.write_bytes(buf);
                 Ok(())
@@ -171,6 +195,14 @@ impl Codec for StdCodec {
                 Tcp::read_bytes(value)?.write_str(f)?;
                 Ok(())
             }
+            Udp::CODE => {
+                Udp::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
+            Unix::CODE => {
+                Unix::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
             DnsAddr::CODE => {
                 DnsAddr::read_bytes(value)?.write_str(f)?;
                 Ok(())
@@ -32,6 +32,12 @@ impl Error {
         Error(ErrorImpl::InvalidProto(c))
     }
 
+    pub(crate) fn after_unix() -> Self {
+        Error(ErrorImpl::Message(
+            "no protocol can follow a unix socket path".to_string(),
+        ))
+    }
+
     pub(crate) fn into_impl(self) -> ErrorImpl {
         self.0
     }
@@ -24,6 +24,7 @@ use core::hash::{Hash, Hasher};
 use core::ops::Deref;
 use core::str::FromStr;
 use once_cell::race::OnceBox;
+use proto::Unix;
 use tinyvec::{Array, ArrayVec, TinyVec};
 
 pub use error::Error;
@@ -328,8 +329,13 @@ impl MultiAddr {
     pub fn try_from_bytes(input: &[u8], r: Registry) -> Result<Self, Error> {
         let iter = iter::BytesIter::with_registry(input, r.clone());
         let mut b = TinyVec::new();
+        let mut unix = false;
         for item in iter {
             let (_, code, value) = item?;
+            if unix {
+                return Err(Error::after_unix());
+            }
+            unix = code == Unix::CODE;
             let codec = r
                 .get_by_code(code)
                 .ok_or_else(|| Error::unregistered(code))?;
@@ -367,11 +373,22 @@ impl MultiAddr {
         self.as_ref().len()
     }
 
+    /// Does this address end with a unix socket path?
+    ///
+    /// The path takes up the rest of the textual form, so no other
+    /// protocol can follow it.
+    fn ends_with_unix(&self) -> bool {
+        matches!(self.last(), Some(p) if p.code() == Unix::CODE)
+    }
+
     /// Add a protocol to the end of this address.
     pub fn push_back<'a, P: Protocol<'a>>(&mut self, p: P) -> Result<(), Error> {
         if self.reg.get_by_code(P::CODE).is_none() {
             return Err(Error::unregistered(P::CODE));
         }
+        if self.ends_with_unix() {
+            return Err(Error::after_unix());
+        }
         debug_assert!(self.reg.get_by_prefix(P::PREFIX).is_some());
         p.write_bytes(&mut self.dat);
         Ok(())
@@ -379,6 +396,9 @@ impl MultiAddr {
 
     /// Add a protocol value to the end of this address.
     pub fn push_back_value(&mut self, p: &ProtoValue) -> Result<(), Error> {
+        if self.ends_with_unix() {
+            return Err(Error::after_unix());
+        }
         if let Some(codec) = self.reg.get_by_code(p.code()) {
             codec.write_bytes(p, &mut self.dat)
         } else {
@@ -391,6 +411,9 @@ impl MultiAddr {
         if self.reg.get_by_code(P::CODE).is_none() {
             return Err(Error::unregistered(P::CODE));
         }
+        if P::CODE == Unix::CODE && !self.is_empty() {
+            return Err(Error::after_unix());
+        }
         debug_assert!(self.reg.get_by_prefix(P::PREFIX).is_some());
         let mut dat = TinyVec::new();
         p.write_bytes(&mut dat);
@@ -401,6 +424,9 @@ impl MultiAddr {
 
     /// Add a protocol value to the front of this address.
     pub fn push_front_value(&mut self, p: &ProtoValue) -> Result<(), Error> {
+        if p.code() == Unix::CODE && !self.is_empty() {
+            return Err(Error::after_unix());
+        }
         if let Some(codec) = self.reg.get_by_code(p.code()) {
             let mut dat = TinyVec::new();
             codec.write_bytes(p, &mut dat)?;
@@ -153,6 +153,113 @@ impl Protocol<'_> for Tcp {
     }
 }
 
+/// A UDP port number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Udp(pub u16);
+
+impl Udp {
+    pub fn new(v: u16) -> Self {
+        Udp(v)
+    }
+}
+
+impl Deref for Udp {
+    type Target = u16;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Protocol<'_> for Udp {
+    const CODE: Code = Code::new(273);
+    const PREFIX: &'static str = "udp";
+
+    fn read_str(input: Checked<&str>) -> Result<Self, Error> {
+        u16::from_str(&input).map(Udp).map_err(Error::message)
+    }
+
+    fn read_bytes(input: Checked<&[u8]>) -> Result<Self, Error> {
+        let mut b = [0; 2];
+        b.copy_from_slice(&input);
+        Ok(Udp(u16::from_be_bytes(b)))
+    }
+
+    fn write_str(&self, f: &mut fmt::Formatter) -> Result<(), Error> {
+        write!(f, "/{}/{}", Self::PREFIX, self.0)?;
+        Ok(())
+    }
+
+    fn write_bytes(&self, buf: &mut dyn Buffer) {
+        let mut b = encode::u32_buffer();
+        let uvi = encode::u32(Self::CODE.into(), &mut b);
+        buf.extend_with(uvi);
+        buf.extend_with(&self.0.to_be_bytes())
+    }
+}
+
+/// The absolute path of a Unix domain socket.
+///
+/// The path takes up the rest of the textual form, e.g. the path of
+/// `/unix/tmp/app.sock` is `/tmp/app.sock`, so it must come last.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Unix<'a>(Cow<'a, str>);
+
+impl<'a> Unix<'a> {
+    /// Create a socket path. A leading `/` is added if missing.
+    pub fn new<S: Into<Cow<'a, str>>>(s: S) -> Self {
+        let s = s.into();
+        if s.starts_with('/') {
+            Self(s)
+        } else {
+            Self(Cow::Owned(alloc::format!("/{s}")))
+        }
+    }
+}
+
+impl Deref for Unix<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> Protocol<'a> for Unix<'a> {
+    const CODE: Code = Code::new(400);
+    const PREFIX: &'static str = "unix";
+
+    fn read_str(input: Checked<&'a str>) -> Result<Self, Error> {
+        if input.0.is_empty() {
+            return Err(Error::message("empty unix socket path"));
+        }
+        Ok(Self::new(input.0))
+    }
+
+    fn read_bytes(input: Checked<&'a [u8]>) -> Result<Self, Error> {
+        let s = str::from_utf8(&input).map_err(Error::message)?;
+        if !s.starts_with('/') {
+            return Err(Error::message("unix socket path is not absolute"));
+        }
+        Ok(Self(Cow::Borrowed(s)))
+    }
+
+    fn write_str(&self, f: &mut fmt::Formatter) -> Result<(), Error> {
+        write!(f, "/{}{}", Self::PREFIX, self.0)?;
+        Ok(())
+    }
+
+    fn write_bytes(&self, buf: &mut dyn Buffer) {
+        let mut b = encode::u32_buffer();
+        let uvi = encode::u32(Self::CODE.into(), &mut b);
+        buf.extend_with(uvi);
+        let mut b = encode::usize_buffer();
+        let uvi = encode::usize(self.0.len(), &mut b);
+        buf.extend_with(uvi);
+        buf.extend_with(self.0.as_bytes())
+    }
+}
+
 macro_rules! gen_str_proto {
     ($t:ident, $c:literal, $p:literal) => {
         #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
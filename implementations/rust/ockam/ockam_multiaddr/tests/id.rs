@@ -1,5 +1,7 @@
 use core::fmt;
-use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp};
+use ockam_multiaddr::proto::{
+    DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Udp, Unix,
+};
 use ockam_multiaddr::{Code, Match, MultiAddr, Protocol};
 use quickcheck::{quickcheck, Arbitrary, Gen};
 use rand::distributions::{Alphanumeric, DistString};
@@ -95,11 +97,20 @@ quickcheck! {
                 Op::Clone => {
                     addr = addr.clone()
                 }
+                Op::PushBack if prot.back() == Some(&Unix::CODE) => {
+                    if addr.push_back(Tcp::new(0)).is_ok() {
+                        return false
+                    }
+                }
                 Op::PushBack => match *PROTOS.choose(&mut gen).unwrap() {
                     Tcp::CODE => {
                         addr.push_back(Tcp::new(0)).unwrap();
                         prot.push_back(Tcp::CODE);
                     }
+                    Udp::CODE => {
+                        addr.push_back(Udp::new(0)).unwrap();
+                        prot.push_back(Udp::CODE);
+                    }
                     DnsAddr::CODE => {
                         addr.push_back(DnsAddr::new("localhost")).unwrap();
                         prot.push_back(DnsAddr::CODE);
@@ -132,6 +143,10 @@ quickcheck! {
                         addr.push_back(Space::new("space")).unwrap();
                         prot.push_back(Space::CODE);
                     }
+                    Unix::CODE => {
+                        addr.push_back(Unix::new("/tmp/app.sock")).unwrap();
+                        prot.push_back(Unix::CODE);
+                    }
                     _ => unreachable!()
                 }
             }
@@ -145,6 +160,7 @@ quickcheck! {
 
 const PROTOS: &[Code] = &[
     Tcp::CODE,
+    Udp::CODE,
     DnsAddr::CODE,
     Ip4::CODE,
     Ip6::CODE,
@@ -153,6 +169,7 @@ const PROTOS: &[Code] = &[
     Node::CODE,
     Project::CODE,
     Space::CODE,
+    Unix::CODE,
 ];
 
 impl Arbitrary for Addr {
@@ -161,6 +178,7 @@ impl Arbitrary for Addr {
         for _ in 0..g.size() {
             match *g.choose(PROTOS).unwrap() {
                 Tcp::CODE => a.push_back(Tcp::new(u16::arbitrary(g))).unwrap(),
+                Udp::CODE => a.push_back(Udp::new(u16::arbitrary(g))).unwrap(),
                 DnsAddr::CODE => a.push_back(DnsAddr::new(gen_hostname())).unwrap(),
                 Ip4::CODE => a.push_back(Ip4::new(Ipv4Addr::arbitrary(g))).unwrap(),
                 Ip6::CODE => a.push_back(Ip6::new(Ipv6Addr::arbitrary(g))).unwrap(),
@@ -169,6 +187,12 @@ impl Arbitrary for Addr {
                 Project::CODE => a.push_back(Project::new(gen_string())).unwrap(),
                 Space::CODE => a.push_back(Space::new(gen_string())).unwrap(),
                 Node::CODE => a.push_back(Node::new(gen_string())).unwrap(),
+                Unix::CODE => {
+                    // Nothing can follow a socket path
+                    let path = format!("/tmp/{}/{}.sock", gen_string(), gen_string());
+                    a.push_back(Unix::new(path)).unwrap();
+                    break;
+                }
                 _ => unreachable!(),
             }
         }
@@ -176,6 +200,31 @@ impl Arbitrary for Addr {
     }
 }
 
+#[test]
+fn unix_path_extends_to_the_end() {
+    let a = MultiAddr::from_str("/node/n1/unix/tmp/ockam/app.sock").unwrap();
+    let p = a.last().unwrap();
+    assert_eq!(p.code(), Unix::CODE);
+    assert_eq!(&*p.cast::<Unix>().unwrap(), "/tmp/ockam/app.sock");
+    assert_eq!(a.to_string(), "/node/n1/unix/tmp/ockam/app.sock");
+    assert_eq!(a, MultiAddr::try_from(a.as_ref()).unwrap());
+
+    let mut b = MultiAddr::default();
+    b.push_back(Node::new("n1")).unwrap();
+    b.push_back(Unix::new("tmp/ockam/app.sock")).unwrap();
+    assert_eq!(a, b);
+
+    assert!(MultiAddr::from_str("/unix").is_err());
+
+    assert!(b.push_back(Tcp::new(80)).is_err());
+    assert!(b.push_back_value(&a.first().unwrap()).is_err());
+    assert!(b.push_front(Unix::new("/tmp/other.sock")).is_err());
+    let mut bytes = a.as_ref().to_vec();
+    bytes.extend_from_slice(MultiAddr::from_str("/tcp/80").unwrap().as_ref());
+    assert!(MultiAddr::try_from(bytes.as_slice()).is_err());
+    assert_eq!(b.to_string(), "/node/n1/unix/tmp/ockam/app.sock");
+}
+
 /// An operation to perform on a MultiAddr.
 #[derive(Debug, Copy, Clone)]
 enum Op {
@@ -1,7 +1,5 @@
-use crate::tokio::sync::{
-    mpsc::Sender as DefaultSender,
-    oneshot::{self, Receiver, Sender},
-};
+use crate::channel_types::RouterSender;
+use crate::tokio::sync::oneshot::{self, Receiver, Sender};
 use crate::NodeMessage;
 use ockam_core::Address;
 
@@ -19,7 +17,7 @@ use ockam_core::Address;
 /// additional metadata to generate messages.
 pub struct AsyncDrop {
     rx: Receiver<Address>,
-    sender: DefaultSender<NodeMessage>,
+    sender: RouterSender<NodeMessage>,
 }
 
 impl AsyncDrop {
@@ -29,7 +27,7 @@ impl AsyncDrop {
     /// Context that creates this hook, while the `address` field must
     /// refer to the address of the context that will be deallocated
     /// this way.
-    pub fn new(sender: DefaultSender<NodeMessage>) -> (Self, Sender<Address>) {
+    pub fn new(sender: RouterSender<NodeMessage>) -> (Self, Sender<Address>) {
         let (tx, rx) = oneshot::channel();
         (Self { rx, sender }, tx)
     }
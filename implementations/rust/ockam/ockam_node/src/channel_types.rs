@@ -1,31 +1,207 @@
+use crate::tokio::sync::mpsc::{self, error::SendError};
+use core::task::{Context, Poll};
+use futures::future::poll_fn;
+use ockam_core::{Priority, RelayMessage};
+
 /// Sender used to send payload messages
-pub type MessageSender<T> = crate::tokio::sync::mpsc::Sender<T>;
+pub type MessageSender<T> = mpsc::Sender<T>;
 /// Receiver used to receive payload messages
-pub type MessageReceiver<T> = crate::tokio::sync::mpsc::Receiver<T>;
+pub type MessageReceiver<T> = mpsc::Receiver<T>;
 
 /// Create message channel
 pub fn message_channel<T>() -> (MessageSender<T>, MessageReceiver<T>) {
-    crate::tokio::sync::mpsc::channel(16)
+    mpsc::channel(16)
+}
+
+/// Sender used to send messages into a worker mailbox
+///
+/// Every [`Priority`] class has its own queue, so that bulk messages
+/// don't hold up control messages.
+#[derive(Clone, Debug)]
+pub struct MailboxSender {
+    queues: [MessageSender<RelayMessage>; Priority::COUNT],
+}
+
+impl MailboxSender {
+    /// Queue a message according to the priority of its `LocalMessage`
+    pub async fn send(&self, msg: RelayMessage) -> Result<(), SendError<RelayMessage>> {
+        let class = msg.local_msg.priority().index();
+        self.queues[class].send(msg).await
+    }
+}
+
+/// Receiver used to take messages out of a worker mailbox
+///
+/// The next message is taken from the most urgent class that has one
+/// waiting, unless a less urgent class has been passed over
+/// [`Priority::STARVATION_LIMIT`] times.
+#[derive(Debug)]
+pub struct MailboxReceiver {
+    queues: [MessageReceiver<RelayMessage>; Priority::COUNT],
+    /// The head of every queue, once it has been received
+    heads: [Option<RelayMessage>; Priority::COUNT],
+    /// How often the head of every queue has been passed over
+    skipped: [u8; Priority::COUNT],
+}
+
+impl MailboxReceiver {
+    /// Wait for the next message
+    ///
+    /// Returns `None` once all senders are gone and the mailbox is empty.
+    pub async fn recv(&mut self) -> Option<RelayMessage> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Take the next message without waiting
+    pub fn try_recv(&mut self) -> Option<RelayMessage> {
+        let waker = futures::task::noop_waker();
+        match self.poll_recv(&mut Context::from_waker(&waker)) {
+            Poll::Ready(msg) => msg,
+            Poll::Pending => None,
+        }
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<RelayMessage>> {
+        let mut closed = true;
+        for (queue, head) in self.queues.iter_mut().zip(self.heads.iter_mut()) {
+            if head.is_some() {
+                closed = false;
+                continue;
+            }
+            match queue.poll_recv(cx) {
+                Poll::Ready(Some(msg)) => {
+                    *head = Some(msg);
+                    closed = false;
+                }
+                Poll::Ready(None) => {}
+                Poll::Pending => closed = false,
+            }
+        }
+
+        match self.take_next() {
+            Some(msg) => Poll::Ready(Some(msg)),
+            None if closed => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+
+    fn take_next(&mut self) -> Option<RelayMessage> {
+        let waiting = |c: &usize| self.heads[*c].is_some();
+        // A class that was passed over too often goes first
+        let class = (0..Priority::COUNT)
+            .filter(waiting)
+            .find(|c| self.skipped[*c] >= Priority::STARVATION_LIMIT)
+            .or_else(|| (0..Priority::COUNT).find(waiting))?;
+
+        for other in 0..Priority::COUNT {
+            if other == class {
+                self.skipped[other] = 0;
+            } else if self.heads[other].is_some() {
+                self.skipped[other] = self.skipped[other].saturating_add(1);
+            }
+        }
+        self.heads[class].take()
+    }
+}
+
+/// Create a mailbox channel with one queue per priority class
+pub fn mailbox_channel() -> (MailboxSender, MailboxReceiver) {
+    let (control_tx, control_rx) = message_channel();
+    let (normal_tx, normal_rx) = message_channel();
+    let (bulk_tx, bulk_rx) = message_channel();
+    (
+        MailboxSender {
+            queues: [control_tx, normal_tx, bulk_tx],
+        },
+        MailboxReceiver {
+            queues: [control_rx, normal_rx, bulk_rx],
+            heads: [None, None, None],
+            skipped: [0; Priority::COUNT],
+        },
+    )
 }
 
 /// Router sender
-pub type RouterSender<T> = crate::tokio::sync::mpsc::Sender<T>;
+///
+/// The router keeps a separate queue for requests made on behalf of
+/// control messages, so that they don't wait behind regular traffic.
+#[derive(Debug)]
+pub struct RouterSender<T> {
+    normal: mpsc::Sender<T>,
+    control: mpsc::Sender<T>,
+}
+
+impl<T> Clone for RouterSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            normal: self.normal.clone(),
+            control: self.control.clone(),
+        }
+    }
+}
+
+impl<T> RouterSender<T> {
+    /// Send a message to the router
+    pub async fn send(&self, msg: T) -> Result<(), SendError<T>> {
+        self.normal.send(msg).await
+    }
+
+    /// Send a message to the router on behalf of a message of the
+    /// given priority
+    pub async fn send_with_priority(&self, msg: T, priority: Priority) -> Result<(), SendError<T>> {
+        match priority {
+            Priority::Control => self.control.send(msg).await,
+            Priority::Normal | Priority::Bulk => self.normal.send(msg).await,
+        }
+    }
+}
+
 /// Router receiver
-pub type RouterReceiver<T> = crate::tokio::sync::mpsc::Receiver<T>;
+#[derive(Debug)]
+pub struct RouterReceiver<T> {
+    normal: mpsc::Receiver<T>,
+    control: mpsc::Receiver<T>,
+}
+
+impl<T> RouterReceiver<T> {
+    /// Wait for the next message, serving control requests first
+    ///
+    /// Router requests are cheap to handle, so the control queue
+    /// cannot keep the regular queue waiting for long.
+    pub async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| match self.control.poll_recv(cx) {
+            Poll::Ready(Some(msg)) => Poll::Ready(Some(msg)),
+            Poll::Ready(None) => self.normal.poll_recv(cx),
+            Poll::Pending => self.normal.poll_recv(cx),
+        })
+        .await
+    }
+}
 
 /// Create router channel
 pub fn router_channel<T>() -> (RouterSender<T>, RouterReceiver<T>) {
-    crate::tokio::sync::mpsc::channel(64)
+    let (normal_tx, normal_rx) = mpsc::channel(64);
+    let (control_tx, control_rx) = mpsc::channel(64);
+    (
+        RouterSender {
+            normal: normal_tx,
+            control: control_tx,
+        },
+        RouterReceiver {
+            normal: normal_rx,
+            control: control_rx,
+        },
+    )
 }
 
 // TODO: Consider replacing with oneshot
 
 /// Sender for small channels
-pub type SmallSender<T> = crate::tokio::sync::mpsc::Sender<T>;
+pub type SmallSender<T> = mpsc::Sender<T>;
 /// Receiver for small channels
-pub type SmallReceiver<T> = crate::tokio::sync::mpsc::Receiver<T>;
+pub type SmallReceiver<T> = mpsc::Receiver<T>;
 
 /// Create small channel (size 1)
 pub fn small_channel<T>() -> (SmallSender<T>, SmallReceiver<T>) {
-    crate::tokio::sync::mpsc::channel(1)
+    mpsc::channel(1)
 }
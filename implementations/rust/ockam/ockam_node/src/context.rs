@@ -1,6 +1,9 @@
 use crate::async_drop::AsyncDrop;
-use crate::channel_types::{message_channel, small_channel, SmallReceiver, SmallSender};
-use crate::debugger;
+use crate::channel_types::{
+    mailbox_channel, small_channel, MailboxReceiver, RouterSender, SmallReceiver,
+};
+use crate::debugger::{self, Topology};
+use crate::scheduler::{self, JobHandle, Jobs, Schedule};
 use crate::tokio::{self, runtime::Handle, time::timeout};
 use crate::{
     error::*, parser, relay::CtrlSignal, router::SenderPair, Cancel, NodeMessage, ProcessorBuilder,
@@ -13,8 +16,8 @@ use core::{
 use ockam_core::compat::{boxed::Box, string::String, sync::Arc, vec::Vec};
 use ockam_core::{
     errcode::{Kind, Origin},
-    Address, AsyncTryClone, Error, LocalMessage, Mailboxes, Message, Processor, RelayMessage,
-    Result, Route, TransportMessage, TransportType, Worker,
+    Address, AsyncTryClone, Error, LocalMessage, Mailboxes, Message, Priority, Processor,
+    RelayMessage, Result, Route, TransportMessage, TransportType, Worker,
 };
 use ockam_core::{LocalInfo, Mailbox};
 
@@ -49,15 +52,38 @@ pub type RepeaterContext = Context;
 /// Context contains Node state and references to the runtime.
 pub struct Context {
     mailboxes: Mailboxes,
-    sender: SmallSender<NodeMessage>,
+    sender: RouterSender<NodeMessage>,
     rt: Handle,
-    receiver: SmallReceiver<RelayMessage>,
+    receiver: MailboxReceiver,
     async_drop_sender: Option<AsyncDropSender>,
     mailbox_count: Arc<AtomicUsize>,
+    priority: Priority,
+    jobs: Jobs,
+}
+
+/// Sends the drain acknowledgement of a [`Context`], see
+/// [`Context::drain_acker`]
+#[cfg_attr(not(feature = "std"), allow(unused))]
+pub(crate) struct DrainAcker {
+    sender: RouterSender<NodeMessage>,
+    address: Address,
+}
+
+impl DrainAcker {
+    #[cfg_attr(not(feature = "std"), allow(unused))]
+    pub(crate) async fn send(&self) -> Result<()> {
+        self.sender
+            .send(NodeMessage::DrainAck(self.address.clone()))
+            .await
+            .map_err(NodeError::from_send_err)?;
+        Ok(())
+    }
 }
 
 impl Drop for Context {
     fn drop(&mut self) {
+        self.jobs.cancel_all();
+
         if let Some(sender) = self.async_drop_sender.take() {
             trace!("De-allocated detached context {}", self.address());
             if let Err(e) = sender.send(self.address()) {
@@ -101,40 +127,66 @@ impl Context {
     }
 
     /// Return a reference to sender
-    pub(crate) fn sender(&self) -> &SmallSender<NodeMessage> {
+    pub(crate) fn sender(&self) -> &RouterSender<NodeMessage> {
         &self.sender
     }
 
     /// Wait for the next message from the mailbox
     pub(crate) async fn receiver_next(&mut self) -> Result<Option<RelayMessage>> {
         loop {
-            let relay_msg = if let Some(msg) = self.receiver.recv().await.map(|msg| {
-                trace!("{}: received new message!", self.address());
-
-                // First we update the mailbox fill metrics
-                self.mailbox_count.fetch_sub(1, Ordering::Acquire);
-
-                msg
-            }) {
+            let relay_msg = if let Some(msg) = self.receiver.recv().await {
                 msg
             } else {
                 // no more messages
                 return Ok(None);
             };
 
-            debugger::log_incoming_message(self, &relay_msg);
+            if let Some(msg) = self.authorize_incoming(relay_msg).await? {
+                return Ok(Some(msg));
+            }
+        }
+    }
 
-            if !self.mailboxes.is_incoming_authorized(&relay_msg).await? {
-                warn!(
-                    "Message received from {} for {} did not pass incoming access control",
-                    relay_msg.local_msg.transport().return_route,
-                    relay_msg.destination
-                );
-                continue;
+    /// Take the next message from the mailbox without waiting
+    ///
+    /// Returns `None` if no message is currently queued.
+    pub(crate) async fn receiver_next_now(&mut self) -> Result<Option<RelayMessage>> {
+        loop {
+            let relay_msg = if let Some(msg) = self.receiver.try_recv() {
+                msg
+            } else {
+                // no queued messages
+                return Ok(None);
+            };
+
+            if let Some(msg) = self.authorize_incoming(relay_msg).await? {
+                return Ok(Some(msg));
             }
+        }
+    }
+
+    /// Update mailbox metrics and check incoming access control
+    async fn authorize_incoming(
+        &mut self,
+        relay_msg: RelayMessage,
+    ) -> Result<Option<RelayMessage>> {
+        trace!("{}: received new message!", self.address());
+
+        // First we update the mailbox fill metrics
+        self.mailbox_count.fetch_sub(1, Ordering::Acquire);
+
+        debugger::log_incoming_message(self, &relay_msg);
 
-            return Ok(Some(relay_msg));
+        if !self.mailboxes.is_incoming_authorized(&relay_msg).await? {
+            warn!(
+                "Message received from {} for {} did not pass incoming access control",
+                relay_msg.local_msg.transport().return_route,
+                relay_msg.destination
+            );
+            return Ok(None);
         }
+
+        Ok(Some(relay_msg))
     }
 }
 
@@ -148,11 +200,11 @@ impl Context {
     /// Context type (i.e. not backed by a worker relay).
     pub(crate) fn new(
         rt: Handle,
-        sender: SmallSender<NodeMessage>,
+        sender: RouterSender<NodeMessage>,
         mailboxes: Mailboxes,
         async_drop_sender: Option<AsyncDropSender>,
     ) -> (Self, SenderPair, SmallReceiver<CtrlSignal>) {
-        let (mailbox_tx, receiver) = message_channel();
+        let (mailbox_tx, receiver) = mailbox_channel();
         let (ctrl_tx, ctrl_rx) = small_channel();
         (
             Self {
@@ -162,6 +214,8 @@ impl Context {
                 receiver,
                 async_drop_sender,
                 mailbox_count: Arc::new(0.into()),
+                priority: Priority::default(),
+                jobs: Jobs::default(),
             },
             SenderPair {
                 msgs: mailbox_tx,
@@ -186,6 +240,48 @@ impl Context {
         &self.mailboxes
     }
 
+    /// Return the [`Priority`] of messages sent from this context
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Set the [`Priority`] of messages sent from this context
+    ///
+    /// Detached contexts created from this context afterwards inherit
+    /// the priority.  Messages passed to [`forward`](Self::forward)
+    /// keep their own priority.
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.priority = priority
+    }
+
+    /// Send a message to an address or via a fully-qualified route on
+    /// the given [`Schedule`]
+    ///
+    /// The message is sent from a new detached context.  The returned
+    /// [`JobHandle`] cancels the job; dropping it does not.  All jobs
+    /// scheduled from this context are cancelled when this context
+    /// is dropped, i.e. when its worker or processor stops.
+    ///
+    /// ```rust
+    /// # use {ockam_node::{Context, Schedule}, ockam_core::Result};
+    /// # use core::time::Duration;
+    /// # async fn test(ctx: &mut Context) -> Result<()> {
+    /// let job = ctx
+    ///     .schedule("my_worker", String::from("tick"), Schedule::every(Duration::from_secs(5))?)
+    ///     .await?;
+    /// // ...
+    /// job.cancel();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn schedule<R, M>(&self, route: R, msg: M, schedule: Schedule) -> Result<JobHandle>
+    where
+        R: Into<Route>,
+        M: Message + Clone + Send + 'static,
+    {
+        scheduler::start(self, &self.jobs, route.into(), msg, schedule).await
+    }
+
     /// Utility function to sleep tasks from other crates
     #[doc(hidden)]
     pub async fn sleep(&self, dur: Duration) {
@@ -249,12 +345,13 @@ impl Context {
 
         // Create a new context and get access to the mailbox senders
         let addresses = mailboxes.addresses();
-        let (ctx, sender, _) = Self::new(
+        let (mut ctx, sender, _) = Self::new(
             self.rt.clone(),
             self.sender.clone(),
             mailboxes,
             Some(drop_sender),
         );
+        ctx.priority = self.priority;
 
         // Create a "detached relay" and register it with the router
         let (msg, mut rx) =
@@ -411,6 +508,30 @@ impl Context {
         Ok(())
     }
 
+    /// Signal to the local runtime to drain and then shut down
+    ///
+    /// In drain mode the node stops starting new workers and
+    /// processors, and every worker handles the messages that are
+    /// already queued for it before its
+    /// [`Worker::drain`](ockam_core::Worker::drain) hook is called.
+    /// Once all workers have been drained, or the timeout has been
+    /// reached, a graceful shutdown with the same timeout follows.
+    ///
+    /// This call will hang until the shutdown has been completed.
+    pub async fn drain_and_stop(&mut self, seconds: u8) -> Result<()> {
+        let (req, mut rx) = NodeMessage::stop_node(ShutdownType::Drain(seconds));
+        self.sender
+            .send(req)
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        // Wait until we get the all-clear
+        rx.recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??;
+        Ok(())
+    }
+
     /// Using a temporary new context, send a message and then receive a message
     ///
     /// This helper function uses [`new_detached`], [`send`], and
@@ -530,6 +651,18 @@ impl Context {
             .await
     }
 
+    /// Send a message to an address or via a fully-qualified route
+    /// with the given [`Priority`], instead of the priority of this
+    /// context
+    pub async fn send_with_priority<R, M>(&self, route: R, msg: M, priority: Priority) -> Result<()>
+    where
+        R: Into<Route>,
+        M: Message + Send + 'static,
+    {
+        self.send_from_address_impl(route.into(), msg, self.address(), Vec::new(), priority)
+            .await
+    }
+
     /// Send a message to an address or via a fully-qualified route
     /// after attaching the given [`LocalInfo`] to the message.
     pub async fn send_with_local_info<R, M>(
@@ -542,7 +675,7 @@ impl Context {
         R: Into<Route>,
         M: Message + Send + 'static,
     {
-        self.send_from_address_impl(route.into(), msg, self.address(), local_info)
+        self.send_from_address_impl(route.into(), msg, self.address(), local_info, self.priority)
             .await
     }
 
@@ -569,8 +702,14 @@ impl Context {
         R: Into<Route>,
         M: Message + Send + 'static,
     {
-        self.send_from_address_impl(route.into(), msg, sending_address, Vec::new())
-            .await
+        self.send_from_address_impl(
+            route.into(),
+            msg,
+            sending_address,
+            Vec::new(),
+            self.priority,
+        )
+        .await
     }
 
     async fn send_from_address_impl<M>(
@@ -579,6 +718,7 @@ impl Context {
         msg: M,
         sending_address: Address,
         local_info: Vec<LocalInfo>,
+        priority: Priority,
     ) -> Result<()>
     where
         M: Message + Send + 'static,
@@ -601,7 +741,7 @@ impl Context {
 
         let req = NodeMessage::SenderReq(next.clone(), reply_tx);
         self.sender
-            .send(req)
+            .send_with_priority(req, priority)
             .await
             .map_err(NodeError::from_send_err)?;
         let (addr, sender, needs_wrapping) = reply_rx
@@ -619,7 +759,7 @@ impl Context {
             .append(sending_address.clone());
 
         // Pack transport message into a LocalMessage wrapper
-        let local_msg = LocalMessage::new(transport_msg, local_info);
+        let local_msg = LocalMessage::new(transport_msg, local_info).with_priority(priority);
 
         // Pack local message into a RelayMessage wrapper
         let relay_msg = RelayMessage::new(
@@ -679,7 +819,7 @@ impl Context {
         };
         let req = NodeMessage::SenderReq(next.clone(), reply_tx);
         self.sender
-            .send(req)
+            .send_with_priority(req, local_msg.priority())
             .await
             .map_err(NodeError::from_send_err)?;
         let (addr, sender, needs_wrapping) = reply_rx
@@ -836,6 +976,29 @@ impl Context {
             .take_workers()
     }
 
+    /// Return a snapshot of the worker graph of this node
+    ///
+    /// The snapshot contains all running workers and processors.
+    /// When the `debugger` feature is enabled it also contains the
+    /// mailboxes, context inheritance and message flows that were
+    /// recorded since the node started.
+    pub async fn topology(&self) -> Result<Topology> {
+        let (msg, mut reply_rx) = NodeMessage::inspect();
+
+        self.sender
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        let mut topology = reply_rx
+            .recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
+            .take_topology()?;
+        debugger::extend_topology(&mut topology);
+        Ok(topology)
+    }
+
     /// Register a router for a specific address type
     pub async fn register<A: Into<Address>>(&self, type_: TransportType, addr: A) -> Result<()> {
         self.register_impl(type_, addr.into()).await
@@ -850,6 +1013,25 @@ impl Context {
         Ok(())
     }
 
+    /// Send a drain acknowledgement to the router
+    pub(crate) async fn send_drain_ack(&self) -> Result<()> {
+        self.sender
+            .send(NodeMessage::DrainAck(self.address()))
+            .await
+            .map_err(NodeError::from_send_err)?;
+        Ok(())
+    }
+
+    /// Get a handle that sends the drain acknowledgement of this
+    /// context while the context itself is borrowed elsewhere
+    #[cfg_attr(not(feature = "std"), allow(unused))]
+    pub(crate) fn drain_acker(&self) -> DrainAcker {
+        DrainAcker {
+            sender: self.sender.clone(),
+            address: self.address(),
+        }
+    }
+
     async fn register_impl(&self, type_: TransportType, addr: Address) -> Result<()> {
         let (tx, mut rx) = small_channel();
         self.sender
@@ -3,14 +3,16 @@
 use crate::Context;
 use ockam_core::RelayMessage;
 
+use ockam_core::compat::{string::String, vec::Vec};
+use ockam_core::Address;
+
 #[cfg(feature = "debugger")]
-use ockam_core::{Address, Mailbox, Mailboxes};
+use ockam_core::{Mailbox, Mailboxes};
 
 #[cfg(feature = "debugger")]
 use ockam_core::compat::{
     collections::BTreeMap,
     sync::{Arc, RwLock},
-    vec::Vec,
 };
 
 #[cfg(feature = "debugger")]
@@ -202,6 +204,126 @@ pub fn log_inherit_context(_tag: &str, _parent: &Context, _child: &Context) {
     }
 }
 
+/// A snapshot of the live worker graph of a node
+///
+/// The router fills in the workers and processors that are currently
+/// running.  When the `debugger` feature is enabled the mailboxes,
+/// context inheritance and message edges recorded by the debugger
+/// are added as well.
+#[derive(Debug, Clone, Default)]
+pub struct Topology {
+    /// Workers and processors registered with the router
+    pub workers: Vec<WorkerEntry>,
+    /// Mailboxes and their access controls
+    pub mailboxes: Vec<MailboxEntry>,
+    /// Context inheritance from parent to child mailbox address
+    pub inheritance: Vec<(Address, Address)>,
+    /// Message flows between addresses
+    pub edges: Vec<MessageEdge>,
+    /// Indicate whether the debugger recorded the message flows
+    pub recorded: bool,
+}
+
+/// A worker or processor in a [`Topology`]
+#[derive(Debug, Clone)]
+pub struct WorkerEntry {
+    /// The primary address
+    pub address: Address,
+    /// Additional addresses of this worker
+    pub aliases: Vec<Address>,
+    /// Whether this is a processor
+    pub processor: bool,
+    /// Whether this is a detached context without a relay
+    pub detached: bool,
+    /// The cluster this worker belongs to
+    pub cluster: Option<String>,
+}
+
+/// A mailbox in a [`Topology`]
+#[derive(Debug, Clone)]
+pub struct MailboxEntry {
+    /// The mailbox address
+    pub address: Address,
+    /// The incoming access control of this mailbox
+    pub incoming_access_control: String,
+    /// The outgoing access control of this mailbox
+    pub outgoing_access_control: String,
+}
+
+/// A message flow in a [`Topology`]
+#[derive(Debug, Clone)]
+pub struct MessageEdge {
+    /// The sending address
+    pub source: Address,
+    /// The receiving address
+    pub destination: Address,
+    /// The number of messages sent along this edge
+    pub count: usize,
+}
+
+/// Add the data logged by the debugger to a topology snapshot
+pub(crate) fn extend_topology(_topology: &mut Topology) {
+    #[cfg(feature = "debugger")]
+    {
+        use ockam_core::compat::{collections::BTreeSet, format};
+
+        let mut mailboxes = BTreeSet::new();
+        match instance().inherited_mb.read() {
+            Ok(inherited_mb) => {
+                for (parent, children) in inherited_mb.iter() {
+                    mailboxes.insert(parent.clone());
+                    for child in children.iter() {
+                        for mailbox in core::iter::once(child.main_mailbox())
+                            .chain(child.additional_mailboxes().iter())
+                        {
+                            mailboxes.insert(mailbox.clone());
+                            _topology
+                                .inheritance
+                                .push((parent.address().clone(), mailbox.address().clone()));
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::error!("debugger panicked: {}", e);
+                panic!("extend_topology");
+            }
+        }
+        _topology.mailboxes = mailboxes
+            .into_iter()
+            .map(|mailbox| MailboxEntry {
+                address: mailbox.address().clone(),
+                incoming_access_control: format!("{:?}", mailbox.incoming_access_control()),
+                outgoing_access_control: format!("{:?}", mailbox.outgoing_access_control()),
+            })
+            .collect();
+
+        match instance().incoming.read() {
+            Ok(incoming) => {
+                for (destination, sources) in incoming.iter() {
+                    let mut counts: BTreeMap<&Address, usize> = BTreeMap::new();
+                    for source in sources.iter() {
+                        *counts.entry(source).or_insert(0) += 1;
+                    }
+                    for (source, count) in counts {
+                        _topology.edges.push(MessageEdge {
+                            source: source.clone(),
+                            destination: destination.clone(),
+                            count,
+                        });
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::error!("debugger panicked: {}", e);
+                panic!("extend_topology");
+            }
+        }
+
+        _topology.recorded = true;
+    }
+}
+
 /// TODO
 pub fn _log_start_worker() {
     #[cfg(feature = "debugger")]
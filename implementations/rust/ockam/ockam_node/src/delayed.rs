@@ -1,17 +1,19 @@
-use crate::Context;
+use crate::{Context, JobHandle, Schedule};
 use core::time::Duration;
-use futures::future::{AbortHandle, Abortable};
 use ockam_core::compat::sync::Arc;
 use ockam_core::{Address, AllowAll, DenyAll, Mailbox, Mailboxes, Message, Result};
 
 /// Allow to send message to destination address periodically after some delay
 /// Only one scheduled heartbeat allowed at a time
 /// Dropping this handle cancels scheduled heartbeat
+///
+/// See [`Context::schedule`] to schedule several messages, or
+/// periodic ones.
 pub struct DelayedEvent<M: Message + Clone> {
     ctx: Context,
     destination_addr: Address,
     msg: M,
-    abort_handle: Option<AbortHandle>,
+    job: Option<JobHandle>,
 }
 
 impl<M: Message + Clone> Drop for DelayedEvent<M> {
@@ -43,7 +45,7 @@ impl<M: Message + Clone> DelayedEvent<M> {
         let heartbeat = Self {
             ctx: child_ctx,
             destination_addr: destination_addr.into(),
-            abort_handle: None,
+            job: None,
             msg,
         };
 
@@ -54,8 +56,8 @@ impl<M: Message + Clone> DelayedEvent<M> {
 impl<M: Message + Clone> DelayedEvent<M> {
     /// Cancel heartbeat
     pub fn cancel(&mut self) {
-        if let Some(handle) = self.abort_handle.take() {
-            handle.abort()
+        if let Some(job) = self.job.take() {
+            job.cancel()
         }
     }
 
@@ -63,43 +65,15 @@ impl<M: Message + Clone> DelayedEvent<M> {
     pub async fn schedule(&mut self, duration: Duration) -> Result<()> {
         self.cancel();
 
-        // TODO: @ac 0#DelayedEvent.create.detached
-        // in:  n/a
-        // out: self.destination_addr
-        let mailboxes = Mailboxes::new(
-            Mailbox::new(
-                Address::random_tagged("DelayedEvent.schedule.detached"),
-                Arc::new(DenyAll),
-                Arc::new(AllowAll),
-                /* TODO: @ac ockam_core::AllowDestinationAddress(
-                    self.destination_addr.clone(),
-                )), */
-            ),
-            vec![],
-        );
-        let child_ctx = self.ctx.new_detached_with_mailboxes(mailboxes).await?;
-
-        let destination_addr = self.destination_addr.clone();
-        let msg = self.msg.clone();
-
-        let (handle, reg) = AbortHandle::new_pair();
-        let future = Abortable::new(
-            async move {
-                child_ctx.sleep(duration).await;
-
-                let res = child_ctx.send(destination_addr.clone(), msg).await;
-
-                if res.is_err() {
-                    warn!("Error sending heartbeat message to {}", destination_addr);
-                } else {
-                    debug!("Sent heartbeat message to {}", destination_addr);
-                }
-            },
-            reg,
-        );
-
-        self.abort_handle = Some(handle);
-        self.ctx.runtime().spawn(future);
+        let job = self
+            .ctx
+            .schedule(
+                self.destination_addr.clone(),
+                self.msg.clone(),
+                Schedule::once(duration),
+            )
+            .await?;
+        self.job = Some(job);
 
         Ok(())
     }
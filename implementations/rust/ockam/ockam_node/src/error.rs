@@ -117,6 +117,8 @@ pub enum NodeReason {
     Unknown,
     /// The node is shutting down
     Shutdown,
+    /// The node is draining and not accepting new work
+    Draining,
     /// The node has been corrupted
     Corrupt,
 }
@@ -129,6 +131,7 @@ impl fmt::Display for NodeReason {
             match self {
                 Self::Unknown => "unknown node state",
                 Self::Shutdown => "ockam node is shutting down",
+                Self::Draining => "ockam node is draining",
                 Self::Corrupt => "ockam node is corrupt and can not be recovered",
             }
         )
@@ -1,6 +1,6 @@
 // use crate::message::BaseMessage;
 
-use crate::channel_types::SmallSender;
+use crate::channel_types::RouterSender;
 use crate::{
     router::{Router, SenderPair},
     tokio::runtime::{Handle, Runtime},
@@ -12,6 +12,9 @@ use ockam_core::{Address, Result};
 #[cfg(feature = "metrics")]
 use crate::metrics::Metrics;
 
+#[cfg(feature = "simulation")]
+use crate::{simulation::Simulation, tokio::runtime::Builder};
+
 // This import is available on emebedded but we don't use the metrics
 // collector, thus don't need it in scope.
 #[cfg(feature = "metrics")]
@@ -36,6 +39,9 @@ pub struct Executor {
     /// Metrics collection endpoint
     #[cfg(feature = "metrics")]
     metrics: Arc<Metrics>,
+    /// The simulation this executor runs
+    #[cfg(feature = "simulation")]
+    simulation: Option<Simulation>,
 }
 
 impl Default for Executor {
@@ -49,6 +55,8 @@ impl Default for Executor {
             router,
             #[cfg(feature = "metrics")]
             metrics,
+            #[cfg(feature = "simulation")]
+            simulation: None,
         }
     }
 }
@@ -59,8 +67,31 @@ impl Executor {
         Executor::default()
     }
 
+    /// Create a new [`Executor`] that runs a deterministic simulation
+    ///
+    /// The executor uses a single-threaded runtime with a paused
+    /// clock.  See the [`simulation`](crate::simulation) module.
+    #[cfg(feature = "simulation")]
+    pub fn simulation(seed: u64) -> Self {
+        let rt = Builder::new_current_thread()
+            .enable_time()
+            .start_paused(true)
+            .build()
+            .unwrap();
+        let router = Router::new();
+        #[cfg(feature = "metrics")]
+        let metrics = Metrics::new(&rt, router.get_metrics_readout());
+        Self {
+            rt,
+            router,
+            #[cfg(feature = "metrics")]
+            metrics,
+            simulation: Some(Simulation::new(seed)),
+        }
+    }
+
     /// Get access to the internal message sender
-    pub(crate) fn sender(&self) -> SmallSender<NodeMessage> {
+    pub(crate) fn sender(&self) -> RouterSender<NodeMessage> {
         self.router.sender()
     }
 
@@ -94,12 +125,25 @@ impl Executor {
         #[cfg(feature = "metrics")]
         self.rt.spawn(self.metrics.clone().run(alive.clone()));
 
+        // Make the simulation available to all tasks of this executor
+        #[cfg(feature = "simulation")]
+        let _simulation = self.simulation.as_ref().map(|sim| {
+            self.rt.spawn(sim.clone().run());
+            sim.enter()
+        });
+
         // Spawn user code second
         let join_body = self.rt.spawn(future);
 
         // Then block on the execution of the router
         self.rt.block_on(self.router.run())?;
 
+        // Stop the other nodes of the simulation with it
+        #[cfg(feature = "simulation")]
+        if let Some(sim) = self.simulation.as_ref() {
+            self.rt.block_on(sim.stop_nodes());
+        }
+
         // Shut down metrics collector
         #[cfg(feature = "metrics")]
         alive.fetch_or(true, Ordering::Acquire);
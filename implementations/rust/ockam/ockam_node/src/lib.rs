@@ -47,6 +47,13 @@ pub mod api;
 /// Debugger
 pub mod debugger;
 
+/// Scheduled and periodic jobs
+pub mod scheduler;
+
+/// Deterministic simulation runtime
+#[cfg(feature = "simulation")]
+pub mod simulation;
+
 mod async_drop;
 mod cancel;
 mod context;
@@ -70,6 +77,7 @@ pub use executor::*;
 pub use local_info::*;
 pub use messages::*;
 pub use processor_builder::ProcessorBuilder;
+pub use scheduler::{JobHandle, Schedule};
 pub use worker_builder::WorkerBuilder;
 
 pub use node::{NodeBuilder, NullWorker};
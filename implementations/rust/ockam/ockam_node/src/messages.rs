@@ -1,11 +1,12 @@
-use crate::channel_types::{small_channel, MessageSender, SmallReceiver, SmallSender};
+use crate::channel_types::{small_channel, MailboxSender, SmallReceiver, SmallSender};
 use crate::{
+    debugger::Topology,
     error::{NodeError, NodeReason, RouterReason, WorkerReason},
     router::SenderPair,
 };
 use core::{fmt, sync::atomic::AtomicUsize};
 use ockam_core::compat::{string::String, sync::Arc, vec::Vec};
-use ockam_core::{Address, Error, RelayMessage, Result, TransportType};
+use ockam_core::{Address, Error, Result, TransportType};
 
 /// Messages sent from the Node to the Executor
 #[derive(Debug)]
@@ -25,6 +26,8 @@ pub enum NodeMessage {
     },
     /// Return a list of all worker addresses
     ListWorkers(SmallSender<NodeReplyResult>),
+    /// Return a snapshot of all workers and processors
+    Inspect(SmallSender<NodeReplyResult>),
     /// Add an existing address to a cluster
     SetCluster(Address, String, SmallSender<NodeReplyResult>),
     /// Stop an existing worker
@@ -39,6 +42,10 @@ pub enum NodeMessage {
     AbortNode,
     /// Let the router know a particular address has stopped
     StopAck(Address),
+    /// Let the router know a particular address has been drained
+    DrainAck(Address),
+    /// Let the router know the drain deadline has been reached
+    DrainTimeout,
     /// Request the sender for a worker address
     SenderReq(Address, SmallSender<NodeReplyResult>),
     /// Register a new router for a route id type
@@ -54,6 +61,7 @@ impl fmt::Display for NodeMessage {
         match self {
             NodeMessage::StartWorker { .. } => write!(f, "StartWorker"),
             NodeMessage::ListWorkers(_) => write!(f, "ListWorkers"),
+            NodeMessage::Inspect(_) => write!(f, "Inspect"),
             NodeMessage::SetCluster(_, _, _) => write!(f, "SetCluster"),
             NodeMessage::StopWorker(_, _, _) => write!(f, "StopWorker"),
             NodeMessage::StartProcessor(_, _, _) => write!(f, "StartProcessor"),
@@ -61,6 +69,8 @@ impl fmt::Display for NodeMessage {
             NodeMessage::StopNode(_, _) => write!(f, "StopNode"),
             NodeMessage::AbortNode => write!(f, "AbortNode"),
             NodeMessage::StopAck(_) => write!(f, "StopAck"),
+            NodeMessage::DrainAck(_) => write!(f, "DrainAck"),
+            NodeMessage::DrainTimeout => write!(f, "DrainTimeout"),
             NodeMessage::SenderReq(_, _) => write!(f, "SenderReq"),
             NodeMessage::Router(_, _, _) => write!(f, "Router"),
             NodeMessage::SetReady(_) => write!(f, "SetReady"),
@@ -118,6 +128,12 @@ impl NodeMessage {
         (Self::ListWorkers(tx), rx)
     }
 
+    /// Create an inspect message and reply receiver
+    pub fn inspect() -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::Inspect(tx), rx)
+    }
+
     /// Create a set cluster message and reply receiver
     pub fn set_cluster(addr: Address, label: String) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
@@ -164,12 +180,14 @@ pub enum RouterReply {
     Ok,
     /// A list of worker addresses
     Workers(Vec<Address>),
+    /// A snapshot of the worker graph
+    Topology(Topology),
     /// Message sender to a specific worker
     Sender {
         /// The address a message is being sent to
         addr: Address,
         /// The relay sender
-        sender: MessageSender<RelayMessage>,
+        sender: MailboxSender,
         /// Indicate whether the relay message needs to be constructed
         /// with router wrapping.
         /// TODO Is this still used in the code-base?
@@ -214,6 +232,21 @@ pub enum ShutdownType {
     /// selected as a failover, if the `Graceful` strategy reaches its
     /// timeout limit.
     Immediate,
+    /// Drain all workers before executing a graceful shutdown
+    ///
+    /// The following steps will be taken by the internal router
+    /// during the drain phase:
+    ///
+    /// * Reject the creation of new workers and processors
+    /// * Signal all workers to drain: each worker handles the
+    ///   messages already queued in its mailbox and then runs its
+    ///   `drain` hook, which may notify its peers
+    /// * Wait for drain ACKs from all workers
+    ///
+    /// When all workers are drained, or when the timeout is reached,
+    /// the router continues with a `Graceful` shutdown using the same
+    /// timeout.  **A given timeout of `0` will wait forever!**
+    Drain(u8),
 }
 
 impl Default for ShutdownType {
@@ -263,17 +296,18 @@ impl RouterReply {
         Ok(Self::Workers(v))
     }
 
+    /// Return [NodeReply::Topology] for the given snapshot
+    pub fn topology(t: Topology) -> NodeReplyResult {
+        Ok(Self::Topology(t))
+    }
+
     /// Return [NodeReply::Sender] for the given information
-    pub fn sender(
-        addr: Address,
-        sender: MessageSender<RelayMessage>,
-        wrap: bool,
-    ) -> NodeReplyResult {
+    pub fn sender(addr: Address, sender: MailboxSender, wrap: bool) -> NodeReplyResult {
         Ok(RouterReply::Sender { addr, sender, wrap })
     }
 
     /// Consume the wrapper and return [NodeReply::Sender]
-    pub fn take_sender(self) -> Result<(Address, MessageSender<RelayMessage>, bool)> {
+    pub fn take_sender(self) -> Result<(Address, MailboxSender, bool)> {
         match self {
             Self::Sender { addr, sender, wrap } => Ok((addr, sender, wrap)),
             _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
@@ -288,6 +322,14 @@ impl RouterReply {
         }
     }
 
+    /// Consume the wrapper and return [NodeReply::Topology]
+    pub fn take_topology(self) -> Result<Topology> {
+        match self {
+            Self::Topology(t) => Ok(t),
+            _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
+        }
+    }
+
     /// Consume the wrapper and return [NodeReply::State]
     pub fn take_state(self) -> Result<bool> {
         match self {
@@ -21,6 +21,8 @@ where
 {
     access_control: AC,
     logging: bool,
+    #[cfg(feature = "simulation")]
+    simulation: Option<u64>,
 }
 
 impl NodeBuilder<AllowAll> {
@@ -29,6 +31,8 @@ impl NodeBuilder<AllowAll> {
         Self {
             access_control: AllowAll,
             logging: true,
+            #[cfg(feature = "simulation")]
+            simulation: None,
         }
     }
 }
@@ -42,6 +46,8 @@ where
         Self {
             access_control,
             logging: true,
+            #[cfg(feature = "simulation")]
+            simulation: None,
         }
     }
 
@@ -53,6 +59,17 @@ where
         }
     }
 
+    /// Run this node in a deterministic simulation with the given seed
+    ///
+    /// See the [`simulation`](crate::simulation) module.
+    #[cfg(feature = "simulation")]
+    pub fn simulation(self, seed: u64) -> Self {
+        Self {
+            simulation: Some(seed),
+            ..self
+        }
+    }
+
     /// Consume this builder and yield a new Ockam Node
     #[inline]
     pub fn build(self) -> (Context, Executor) {
@@ -65,6 +82,12 @@ where
             self.access_control
         );
 
+        #[cfg(feature = "simulation")]
+        let mut exe = match self.simulation {
+            Some(seed) => Executor::simulation(seed),
+            None => Executor::new(),
+        };
+        #[cfg(not(feature = "simulation"))]
         let mut exe = Executor::new();
         let addr: Address = "app".into();
 
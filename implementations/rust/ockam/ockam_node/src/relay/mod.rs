@@ -4,6 +4,27 @@ mod worker_relay;
 pub use processor_relay::*;
 pub use worker_relay::*;
 
+use crate::tokio::runtime::Handle;
+use core::future::Future;
+
+/// Spawn a task of the node on `rt`
+///
+/// In a simulation, the task is run in an order given by the
+/// simulation seed, see the [`simulation`](crate::simulation) module.
+pub(crate) fn spawn<F>(rt: &Handle, future: F)
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(feature = "simulation")]
+    if let Some(sim) = crate::simulation::Simulation::current() {
+        sim.spawn(rt, future);
+        return;
+    }
+
+    rt.spawn(future);
+}
+
 /// A signal type used to communicate between router and worker relay
 #[derive(Clone, Debug)]
 pub enum CtrlSignal {
@@ -11,4 +32,6 @@ pub enum CtrlSignal {
     Interrupt,
     /// Interrupt current message execution and shut down
     InterruptStop,
+    /// Finish handling queued messages and run the drain hook
+    Drain,
 }
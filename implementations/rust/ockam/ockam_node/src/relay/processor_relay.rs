@@ -40,6 +40,9 @@ where
             error!("Failed to mark processor '{}' as 'ready': {}", ctx_addr, e);
         }
 
+        let stop_on_drain = processor.stop_on_drain();
+        let drain_acker = ctx.drain_acker();
+
         // This future encodes the main processor run loop logic
         let run_loop = async {
             loop {
@@ -62,7 +65,19 @@ where
         #[cfg(feature = "std")]
         {
             // This future resolves when a stop control signal is received
-            let shutdown_signal = async { ctrl_rx.recv().await };
+            let shutdown_signal = async {
+                loop {
+                    match ctrl_rx.recv().await {
+                        // Keep running through the drain phase
+                        Some(CtrlSignal::Drain) if !stop_on_drain => {
+                            if let Err(e) = drain_acker.send().await {
+                                error!("Error occurred during drain ACK sending: {}", e);
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+            };
 
             // Then select over the two futures
             tokio::select! {
@@ -103,6 +118,6 @@ where
         ctrl_rx: SmallReceiver<CtrlSignal>,
     ) {
         let relay = ProcessorRelay::<P>::new(processor, ctx);
-        rt.spawn(relay.run(ctrl_rx));
+        super::spawn(rt, relay.run(ctrl_rx));
     }
 }
@@ -76,6 +76,14 @@ where
             }
         };
 
+        self.handle_relay_message(relay_msg).await?;
+
+        // Signal to the outer loop that we would like to run again
+        Ok(true)
+    }
+
+    /// Authorize and handle a message taken from the mailbox
+    async fn handle_relay_message(&mut self, relay_msg: RelayMessage) -> Result<()> {
         // Call the worker authorization function - pass errors up
         let routed = Self::wrap_direct_message(&relay_msg)?;
         if !self.worker.is_authorized(&mut self.ctx, routed).await? {
@@ -83,15 +91,46 @@ where
                 "Message for {} did not pass worker relay access control",
                 relay_msg.destination
             );
-            return Ok(true);
+            return Ok(());
         }
 
         // Call the worker handle function - pass errors up
         let routed = Self::wrap_direct_message(&relay_msg)?;
-        self.worker.handle_message(&mut self.ctx, routed).await?;
+        self.worker.handle_message(&mut self.ctx, routed).await
+    }
 
-        // Signal to the outer loop that we would like to run again
-        Ok(true)
+    /// Handle all messages that are already queued, then run the
+    /// drain hook of the worker and acknowledge the drain to the router
+    #[cfg_attr(not(feature = "std"), allow(unused))]
+    async fn drain(&mut self) {
+        let address = self.ctx.address();
+
+        loop {
+            match self.ctx.receiver_next_now().await {
+                Ok(Some(relay_msg)) => {
+                    if let Err(e) = self.handle_relay_message(relay_msg).await {
+                        error!(
+                            "Error encountered during '{}' message handling: {}",
+                            address, e
+                        );
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    error!("Failed to drain mailbox of worker '{}': {}", address, e);
+                    break;
+                }
+            }
+        }
+
+        if let Err(e) = self.worker.drain(&mut self.ctx).await {
+            error!("Failure during '{}' worker drain: {}", address, e);
+        }
+
+        trace!("Sending drain ACK");
+        if let Err(e) = self.ctx.send_drain_ack().await {
+            error!("Error occurred during drain ACK sending: {}", e);
+        }
     }
 
     #[cfg_attr(not(feature = "std"), allow(unused_mut))]
@@ -130,12 +169,18 @@ where
                     }
                 },
                 result = ctrl_rx.recv() => {
-                    if result.is_some() {
-                        debug!("Relay received shutdown signal, terminating!");
-                        break;
+                    match result {
+                        Some(CtrlSignal::Drain) => {
+                            debug!("Relay received drain signal");
+                            self.drain().await;
+                        }
+                        Some(_) => {
+                            debug!("Relay received shutdown signal, terminating!");
+                            break;
+                        }
+                        // We are stopping
+                        None => {}
                     }
-
-                    // We are stopping
                 }
             };
         }
@@ -178,6 +223,6 @@ where
     /// Build and spawn a new worker relay, returning a send handle to it
     pub(crate) fn init(rt: &Handle, worker: W, ctx: Context, ctrl_rx: SmallReceiver<CtrlSignal>) {
         let relay = WorkerRelay::<W, M>::new(worker, ctx);
-        rt.spawn(relay.run(ctrl_rx));
+        super::spawn(rt, relay.run(ctrl_rx));
     }
 }
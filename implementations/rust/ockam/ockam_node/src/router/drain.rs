@@ -0,0 +1,103 @@
+use super::{shutdown, Router};
+use crate::channel_types::SmallSender;
+use crate::NodeReplyResult;
+use ockam_core::{Address, Result};
+
+/// Implement the drain phase that precedes a graceful shutdown
+///
+/// All drainable workers and processors are signalled to drain and
+/// the router waits for their drain ACKs (or the timeout) before
+/// stopping the node.  Processors that stop on drain, like listeners,
+/// answer with a stop ACK instead.
+#[cfg_attr(not(feature = "std"), allow(unused_mut))]
+pub(super) async fn begin(
+    router: &mut Router,
+    seconds: u8,
+    reply: SmallSender<NodeReplyResult>,
+) -> Result<bool> {
+    // Without a runtime to run the timeout task (and without relays
+    // listening to control signals) we go straight to the shutdown
+    #[cfg(not(feature = "std"))]
+    return shutdown::graceful(router, seconds, reply).await;
+
+    #[cfg(feature = "std")]
+    {
+        // Mark the router as draining to prevent spawning
+        info!("Initiate node drain");
+        router.state.drain(reply, seconds);
+
+        let mut draining = vec![];
+        for rec in router.map.drainable_workers() {
+            if let Some(first_address) = rec.address_set().first().cloned() {
+                debug!("Draining address {}", first_address);
+                match rec.drain().await {
+                    Ok(()) => draining.push(first_address),
+                    Err(e) => warn!("Failed to drain address {}: {}", first_address, e),
+                }
+            } else {
+                error!("Empty Address Set during node drain");
+            }
+        }
+
+        // If there is nothing to drain we can stop right away
+        if draining.is_empty() {
+            return finish(router).await;
+        }
+
+        draining
+            .into_iter()
+            .for_each(|addr| router.map.init_drain(addr));
+
+        // Start a timeout task to interrupt the drain phase
+        if seconds > 0 {
+            use crate::NodeMessage;
+            use core::time::Duration;
+            use tokio::{task, time};
+
+            let sender = router.sender();
+            let dur = Duration::from_secs(seconds as u64);
+            task::spawn(async move {
+                time::sleep(dur).await;
+                if sender.send(NodeMessage::DrainTimeout).await.is_err() {
+                    error!("Failed to send drain timeout signal to router");
+                }
+            });
+        }
+
+        // Return but DO NOT stop the router
+        Ok(false)
+    }
+}
+
+/// Register a drain ACK
+///
+/// Once the last drainable worker has been drained the node moves on
+/// to its graceful shutdown.
+pub(super) async fn ack(router: &mut Router, addr: Address) -> Result<bool> {
+    debug!("Handling drain ACK for {}", addr);
+
+    if router.map.drain_ack(&addr) {
+        finish(router).await
+    } else {
+        Ok(false)
+    }
+}
+
+/// Abort the drain phase because its deadline was reached
+pub(super) async fn timeout(router: &mut Router) -> Result<bool> {
+    warn!("Drain timeout reached; stopping node!");
+    router.map.clear_drain();
+    finish(router).await
+}
+
+/// End the drain phase and start the graceful shutdown
+async fn finish(router: &mut Router) -> Result<bool> {
+    match router.state.take_drain() {
+        Some((reply, seconds)) => {
+            info!("All workers drained");
+            shutdown::graceful(router, seconds, reply).await
+        }
+        // The drain phase has already ended
+        None => Ok(false),
+    }
+}
@@ -1,3 +1,4 @@
+mod drain;
 mod record;
 mod shutdown;
 mod start_processor;
@@ -13,19 +14,22 @@ use std::sync::atomic::AtomicUsize;
 use record::{AddressMeta, AddressRecord, InternalMap};
 use state::{NodeState, RouterState};
 
-use crate::channel_types::{router_channel, MessageSender, RouterReceiver, SmallSender};
+use crate::channel_types::{
+    router_channel, MailboxSender, RouterReceiver, RouterSender, SmallSender,
+};
 use crate::{
+    debugger::Topology,
     error::{NodeError, NodeReason},
     relay::CtrlSignal,
     NodeMessage, NodeReplyResult, RouterReply, ShutdownType,
 };
 use ockam_core::compat::{collections::BTreeMap, sync::Arc};
-use ockam_core::{Address, RelayMessage, Result, TransportType};
+use ockam_core::{Address, Result, TransportType};
 
 /// A pair of senders to a worker relay
 #[derive(Debug)]
 pub struct SenderPair {
-    pub msgs: MessageSender<RelayMessage>,
+    pub msgs: MailboxSender,
     pub ctrl: SmallSender<CtrlSignal>,
 }
 
@@ -102,7 +106,7 @@ impl Router {
         self.map.addr_map.insert(addr.clone(), addr);
     }
 
-    pub fn sender(&self) -> SmallSender<NodeMessage> {
+    pub fn sender(&self) -> RouterSender<NodeMessage> {
         self.state.sender.clone()
     }
 
@@ -202,6 +206,11 @@ impl Router {
                     };
                 }
             }
+            StopNode(ShutdownType::Drain(timeout), reply) => {
+                if drain::begin(self, timeout, reply).await? {
+                    return self.reply_stopped().await;
+                }
+            }
             StopNode(ShutdownType::Immediate, reply) => {
                 shutdown::immediate(self, reply).await?;
                 return Ok(true);
@@ -227,6 +236,19 @@ impl Router {
                 }
             }
 
+            StopAck(addr) if self.state.draining() => {
+                trace!("Received shutdown ACK for address {}", addr);
+                if let Some(rec) = self.map.internal.remove(&addr) {
+                    rec.address_set().iter().for_each(|addr| {
+                        self.map.addr_map.remove(addr);
+                    });
+                }
+                // A worker that stopped on its own no longer needs draining
+                if drain::ack(self, addr).await? {
+                    return self.reply_stopped().await;
+                }
+            }
+
             StopAck(addr) => {
                 if shutdown::ack(self, addr).await? {
                     info!("No more workers left.  Goodbye!");
@@ -240,6 +262,19 @@ impl Router {
                 }
             }
 
+            DrainAck(addr) if self.state.draining() => {
+                if drain::ack(self, addr).await? {
+                    return self.reply_stopped().await;
+                }
+            }
+            DrainTimeout if self.state.draining() => {
+                if drain::timeout(self).await? {
+                    return self.reply_stopped().await;
+                }
+            }
+            // Late drain messages after the drain phase has ended
+            DrainAck(_) | DrainTimeout => {}
+
             ListWorkers(sender) => sender
                 .send(RouterReply::workers(
                     self.map.internal.keys().cloned().collect(),
@@ -247,6 +282,14 @@ impl Router {
                 .await
                 .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
 
+            Inspect(sender) => sender
+                .send(RouterReply::topology(Topology {
+                    workers: self.map.topology_workers(),
+                    ..Default::default()
+                }))
+                .await
+                .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
+
             SetCluster(addr, label, reply) => {
                 debug!("Setting cluster on address {}", addr);
                 let msg = self.map.set_cluster(label, addr);
@@ -293,6 +336,21 @@ impl Router {
         Ok(false)
     }
 
+    /// Reply to the caller that requested the node to stop
+    async fn reply_stopped(&self) -> Result<bool> {
+        info!("No more workers left.  Goodbye!");
+        match self.state.stop_reply() {
+            Some(sender) => {
+                sender
+                    .send(RouterReply::ok())
+                    .await
+                    .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     /// Block current task running this router.  Return fatal errors
     async fn run_inner(&mut self) -> Result<()> {
         while let Some(msg) = self.get_recv()?.recv().await {
@@ -1,6 +1,7 @@
-use crate::channel_types::{MessageSender, SmallSender};
+use crate::channel_types::{MailboxSender, SmallSender};
 use crate::relay::CtrlSignal;
 use crate::{
+    debugger::WorkerEntry,
     error::{NodeError, NodeReason},
     NodeReplyResult, RouterReply,
 };
@@ -12,7 +13,7 @@ use ockam_core::{
         sync::Arc,
         vec::Vec,
     },
-    Address, RelayMessage, Result,
+    Address, Result,
 };
 
 /// Address states and associated logic
@@ -28,6 +29,8 @@ pub struct InternalMap {
     clusters: BTreeMap<String, BTreeSet<Address>>,
     /// Track stop information
     stopping: BTreeSet<Address>,
+    /// Track drain information
+    draining: BTreeSet<Address>,
     /// Metrics collection and sharing
     #[cfg(feature = "metrics")]
     metrics: (Arc<AtomicUsize>, Arc<AtomicUsize>),
@@ -118,6 +121,56 @@ impl InternalMap {
         self.stopping.is_empty()
     }
 
+    /// Describe all running workers and processors
+    pub(super) fn topology_workers(&self) -> Vec<WorkerEntry> {
+        self.internal
+            .iter()
+            .map(|(primary, rec)| WorkerEntry {
+                address: primary.clone(),
+                aliases: rec
+                    .address_set()
+                    .iter()
+                    .filter(|addr| *addr != primary)
+                    .cloned()
+                    .collect(),
+                processor: rec.meta.processor,
+                detached: rec.meta.detached,
+                cluster: self
+                    .clusters
+                    .iter()
+                    .find(|(_, addrs)| addrs.contains(primary))
+                    .map(|(label, _)| label.clone()),
+            })
+            .collect()
+    }
+
+    /// Get all workers and processors that can be drained
+    ///
+    /// Detached workers have no relay that could run a drain hook, so
+    /// they are left alone.
+    pub(super) fn drainable_workers(&mut self) -> Vec<&mut AddressRecord> {
+        self.internal
+            .values_mut()
+            .filter(|rec| !rec.meta.detached)
+            .collect()
+    }
+
+    /// Mark this address as "having started to drain"
+    pub(super) fn init_drain(&mut self, addr: Address) {
+        self.draining.insert(addr);
+    }
+
+    /// Mark this address as drained and check whether all addresses are
+    pub(super) fn drain_ack(&mut self, addr: &Address) -> bool {
+        self.draining.remove(addr);
+        self.draining.is_empty()
+    }
+
+    /// Forget about all addresses that have not been drained yet
+    pub(super) fn clear_drain(&mut self) {
+        self.draining.clear();
+    }
+
     /// Get all addresses of workers not in a cluster
     pub(super) fn non_cluster_workers(&mut self) -> Vec<&mut AddressRecord> {
         let clustered = self
@@ -163,7 +216,7 @@ pub struct AddressMeta {
 #[derive(Debug)]
 pub struct AddressRecord {
     address_set: Vec<Address>,
-    sender: Option<MessageSender<RelayMessage>>,
+    sender: Option<MailboxSender>,
     ctrl_tx: SmallSender<CtrlSignal>,
     state: AddressState,
     ready: ReadyState,
@@ -175,7 +228,7 @@ impl AddressRecord {
     pub fn address_set(&self) -> &[Address] {
         &self.address_set
     }
-    pub fn sender(&self) -> MessageSender<RelayMessage> {
+    pub fn sender(&self) -> MailboxSender {
         self.sender.clone().expect("No such sender!")
     }
     pub fn sender_drop(&mut self) {
@@ -183,7 +236,7 @@ impl AddressRecord {
     }
     pub fn new(
         address_set: Vec<Address>,
-        sender: MessageSender<RelayMessage>,
+        sender: MailboxSender,
         ctrl_tx: SmallSender<CtrlSignal>,
         msg_count: Arc<AtomicUsize>,
         meta: AddressMeta,
@@ -217,6 +270,14 @@ impl AddressRecord {
         Ok(())
     }
 
+    /// Signal this worker to drain -- it will keep receiving messages
+    pub async fn drain(&mut self) -> Result<()> {
+        self.ctrl_tx
+            .send(CtrlSignal::Drain)
+            .await
+            .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())
+    }
+
     /// Check the integrity of this record
     pub fn check(&self) -> bool {
         self.state == AddressState::Running
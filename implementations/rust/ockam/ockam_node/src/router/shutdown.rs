@@ -26,25 +26,28 @@ pub(super) async fn ack(router: &mut Router, addr: Address) -> Result<bool> {
 }
 
 async fn stop_next_cluster(r: &mut Router) -> Result<bool> {
-    match r.map.next_cluster() {
-        Some(mut vec) => {
-            let mut addrs = vec![];
-
-            for record in vec.iter_mut() {
-                record.stop().await?;
-                if let Some(first_address) = record.address_set().first().cloned() {
-                    addrs.push(first_address);
-                } else {
-                    error!("Empty Address Set during cluster stop");
-                }
+    // Every address of a cluster may have stopped already, e.g.
+    // listeners during a drain: move on to the next one
+    while let Some(mut vec) = r.map.next_cluster() {
+        let mut addrs = vec![];
+
+        for record in vec.iter_mut() {
+            record.stop().await?;
+            if let Some(first_address) = record.address_set().first().cloned() {
+                addrs.push(first_address);
+            } else {
+                error!("Empty Address Set during cluster stop");
             }
+        }
 
+        if !addrs.is_empty() {
             addrs.into_iter().for_each(|addr| r.map.init_stop(addr));
-            Ok(false)
+            return Ok(false);
         }
-        // If not, we are done!
-        None => Ok(true),
     }
+
+    // No cluster left, we are done!
+    Ok(true)
 }
 
 /// Implement the graceful shutdown strategy
@@ -15,6 +15,7 @@ pub(super) async fn exec(
 ) -> Result<()> {
     match router.state.node_state() {
         NodeState::Running => start(router, addrs, senders, reply).await,
+        NodeState::Draining(_, _) => reject_draining(reply).await,
         NodeState::Stopping(_) => reject(reply).await,
         NodeState::Dead => unreachable!(),
     }?;
@@ -77,3 +78,12 @@ async fn reject(reply: &SmallSender<NodeReplyResult>) -> Result<()> {
         .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?;
     Ok(())
 }
+
+async fn reject_draining(reply: &SmallSender<NodeReplyResult>) -> Result<()> {
+    trace!("StartProcessor command rejected: node draining");
+    reply
+        .send(RouterReply::node_rejected(NodeReason::Draining))
+        .await
+        .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?;
+    Ok(())
+}
@@ -21,6 +21,11 @@ pub(super) async fn exec(
 ) -> Result<()> {
     match router.state.node_state() {
         NodeState::Running => start(router, addrs, senders, detached, metrics, reply).await,
+        // Detached contexts are still needed to talk to the draining workers
+        NodeState::Draining(_, _) if detached => {
+            start(router, addrs, senders, detached, metrics, reply).await
+        }
+        NodeState::Draining(_, _) => reject_draining(reply).await,
         NodeState::Stopping(_) => reject(reply).await,
         NodeState::Dead => unreachable!(),
     }?;
@@ -94,3 +99,12 @@ async fn reject(reply: &SmallSender<NodeReplyResult>) -> Result<()> {
         .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?;
     Ok(())
 }
+
+async fn reject_draining(reply: &SmallSender<NodeReplyResult>) -> Result<()> {
+    trace!("StartWorker command rejected: node draining");
+    reply
+        .send(RouterReply::node_rejected(NodeReason::Draining))
+        .await
+        .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?;
+    Ok(())
+}
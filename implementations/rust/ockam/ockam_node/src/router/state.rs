@@ -1,21 +1,22 @@
 //! Router run state utilities
 
-use crate::channel_types::SmallSender;
+use crate::channel_types::{RouterSender, SmallSender};
 use crate::messages::{NodeMessage, NodeReplyResult};
 
 pub enum NodeState {
     Running,
+    Draining(SmallSender<NodeReplyResult>, u8),
     Stopping(SmallSender<NodeReplyResult>),
     Dead,
 }
 
 pub struct RouterState {
-    pub(super) sender: SmallSender<NodeMessage>,
+    pub(super) sender: RouterSender<NodeMessage>,
     node_state: NodeState,
 }
 
 impl RouterState {
-    pub fn new(sender: SmallSender<NodeMessage>) -> Self {
+    pub fn new(sender: RouterSender<NodeMessage>) -> Self {
         Self {
             sender,
             node_state: NodeState::Running,
@@ -27,6 +28,22 @@ impl RouterState {
         self.node_state = NodeState::Stopping(reply)
     }
 
+    /// Toggle this router to drain its workers before shutting down
+    pub(super) fn drain(&mut self, reply: SmallSender<NodeReplyResult>, seconds: u8) {
+        self.node_state = NodeState::Draining(reply, seconds)
+    }
+
+    /// Leave the drain phase, returning the stop request it was started with
+    pub(super) fn take_drain(&mut self) -> Option<(SmallSender<NodeReplyResult>, u8)> {
+        match core::mem::replace(&mut self.node_state, NodeState::Running) {
+            NodeState::Draining(reply, seconds) => Some((reply, seconds)),
+            other => {
+                self.node_state = other;
+                None
+            }
+        }
+    }
+
     /// Ungracefully kill the router
     pub(super) fn kill(&mut self) {
         self.node_state = NodeState::Dead;
@@ -43,6 +60,10 @@ impl RouterState {
         core::matches!(self.node_state, NodeState::Running)
     }
 
+    pub fn draining(&self) -> bool {
+        core::matches!(self.node_state, NodeState::Draining(_, _))
+    }
+
     /// Check if this router is still `running`, meaning allows
     /// spawning new workers and processors
     pub fn node_state(&self) -> &NodeState {
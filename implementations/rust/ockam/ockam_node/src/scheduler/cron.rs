@@ -0,0 +1,290 @@
+use core::fmt;
+use core::time::Duration;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+
+/// How far ahead to look for the next occurrence of a schedule
+const HORIZON: u64 = 5 * 366 * DAY;
+
+/// A cron-like schedule, evaluated in UTC
+///
+/// The expression has the five usual fields: minute (0-59), hour
+/// (0-23), day of month (1-31), month (1-12) and day of week (0-7,
+/// where both 0 and 7 are Sunday).  Every field is either `*` or a
+/// comma separated list of values (`5`), ranges (`1-5`) and steps
+/// (`*/15`, `0-30/10`, `5/20`).
+///
+/// As in cron, when both the day of month and the day of week are
+/// restricted, a day matches if either of them matches.
+///
+/// ```
+/// # use ockam_node::scheduler::CronSchedule;
+/// // Every weekday at 09:30
+/// let schedule = CronSchedule::parse("30 9 * * 1-5").unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expr: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    /// Parse a cron expression
+    pub fn parse(expr: &str) -> Result<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(invalid(expr, "expected 5 fields"));
+        }
+
+        let field = |i: usize, name: &str, min: u32, max: u32| {
+            parse_field(fields[i], min, max).ok_or_else(|| invalid(expr, name))
+        };
+        let mut days_of_week = field(4, "invalid day of week", 0, 7)?;
+        // Both 0 and 7 are Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            expr: fields.join(" "),
+            minutes: field(0, "invalid minute", 0, 59)?,
+            hours: field(1, "invalid hour", 0, 23)?,
+            days_of_month: field(2, "invalid day of month", 1, 31)?,
+            months: field(3, "invalid month", 1, 12)?,
+            days_of_week,
+            any_day_of_month: fields[2].starts_with('*'),
+            any_day_of_week: fields[4].starts_with('*'),
+        })
+    }
+
+    /// Return the first matching time strictly after `secs`, both
+    /// in seconds since the UNIX epoch
+    ///
+    /// Returns `None` if the schedule does not match within the next
+    /// five years.
+    pub fn next_after(&self, secs: u64) -> Option<u64> {
+        let mut t = (secs / MINUTE + 1) * MINUTE;
+        let limit = t + HORIZON;
+        while t < limit {
+            let days = t / DAY;
+            let (year, month, day) = civil_from_days(days);
+            if !matches(self.months, month) {
+                let (year, month) = if month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (year, month + 1)
+                };
+                t = days_from_civil(year, month, 1) * DAY;
+                continue;
+            }
+            if !self.matches_day(day, weekday(days)) {
+                t = (days + 1) * DAY;
+                continue;
+            }
+            let hour = (t % DAY / HOUR) as u32;
+            if !matches(self.hours, hour) {
+                t = days * DAY + (hour as u64 + 1) * HOUR;
+                continue;
+            }
+            let minute = (t % HOUR / MINUTE) as u32;
+            if !matches(self.minutes, minute) {
+                t += MINUTE;
+                continue;
+            }
+            return Some(t);
+        }
+        None
+    }
+
+    /// Return the time to wait from `now` until the next match
+    pub fn delay_until_next(&self, now: SystemTime) -> Option<Duration> {
+        let now = now.duration_since(UNIX_EPOCH).ok()?;
+        let next = self.next_after(now.as_secs())?;
+        Some(Duration::from_secs(next).saturating_sub(now))
+    }
+
+    fn matches_day(&self, day: u32, weekday: u32) -> bool {
+        let dom = matches(self.days_of_month, day);
+        let dow = matches(self.days_of_week, weekday);
+        if self.any_day_of_month || self.any_day_of_week {
+            dom && dow
+        } else {
+            dom || dow
+        }
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expr)
+    }
+}
+
+fn invalid(expr: &str, reason: &str) -> Error {
+    Error::new(
+        Origin::Node,
+        Kind::Invalid,
+        format!("invalid cron expression '{}': {}", expr, reason),
+    )
+}
+
+fn matches(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Parse a cron field into a bit set of values
+fn parse_field(field: &str, min: u32, max: u32) -> Option<u64> {
+    let mut set = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<usize>().ok().filter(|s| *s > 0)?),
+            None => (part, 1),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (lo.parse().ok()?, hi.parse().ok()?)
+        } else {
+            let value = range.parse().ok()?;
+            // `5/20` means "from 5 to the end, every 20"
+            if part.contains('/') {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+        if lo < min || hi > max || lo > hi {
+            return None;
+        }
+        for value in (lo..=hi).step_by(step) {
+            set |= 1 << value;
+        }
+    }
+    Some(set)
+}
+
+/// The day of the week of a day since the UNIX epoch, 0 being Sunday
+fn weekday(days: u64) -> u32 {
+    // 1970-01-01 was a Thursday
+    ((days + 4) % 7) as u32
+}
+
+/// Convert days since the UNIX epoch to a (year, month, day) date
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html>
+fn civil_from_days(days: u64) -> (u64, u32, u32) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Convert a (year, month, day) date to days since the UNIX epoch
+fn days_from_civil(year: u64, month: u32, day: u32) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year % 400;
+    let month = month as u64;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day as u64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: u64, month: u32, day: u32, hour: u64, minute: u64) -> u64 {
+        days_from_civil(year, month, day) * DAY + hour * HOUR + minute * MINUTE
+    }
+
+    #[test]
+    fn civil_dates_round_trip() {
+        for days in [0, 59, 60, 365, 11_016, 19_000, 20_000] {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(weekday(0), 4);
+    }
+
+    #[test]
+    fn next_after_steps() {
+        let cron = CronSchedule::parse("*/15 * * * *").unwrap();
+        let now = at(2024, 3, 10, 0, 7);
+        assert_eq!(cron.next_after(now), Some(at(2024, 3, 10, 0, 15)));
+        assert_eq!(
+            cron.next_after(at(2024, 3, 10, 0, 15)),
+            Some(at(2024, 3, 10, 0, 30))
+        );
+    }
+
+    #[test]
+    fn next_after_weekdays() {
+        let cron = CronSchedule::parse("30 9 * * 1-5").unwrap();
+        // 2024-03-09 is a Saturday
+        let now = at(2024, 3, 9, 12, 0);
+        assert_eq!(cron.next_after(now), Some(at(2024, 3, 11, 9, 30)));
+    }
+
+    #[test]
+    fn next_after_leap_day() {
+        let cron = CronSchedule::parse("0 0 29 2 *").unwrap();
+        let now = at(2024, 3, 1, 0, 0);
+        assert_eq!(cron.next_after(now), Some(at(2028, 2, 29, 0, 0)));
+    }
+
+    #[test]
+    fn day_of_month_or_day_of_week() {
+        // The 13th of every month, and every Friday
+        let cron = CronSchedule::parse("0 0 13 * 5").unwrap();
+        // 2024-03-01 is a Friday
+        assert_eq!(
+            cron.next_after(at(2024, 2, 29, 0, 0)),
+            Some(at(2024, 3, 1, 0, 0))
+        );
+        assert_eq!(
+            cron.next_after(at(2024, 3, 12, 0, 0)),
+            Some(at(2024, 3, 13, 0, 0))
+        );
+    }
+
+    #[test]
+    fn sunday_is_0_and_7() {
+        let a = CronSchedule::parse("0 0 * * 0").unwrap();
+        let b = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert_eq!(a.days_of_week, b.days_of_week);
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        for expr in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(CronSchedule::parse(expr).is_err(), "{}", expr);
+        }
+    }
+}
@@ -0,0 +1,385 @@
+//! Scheduled and periodic message delivery
+//!
+//! Use [`Context::schedule`] to deliver a message to a route once
+//! after a delay, at a fixed rate, or (with the `std` feature)
+//! following a cron expression.  Every job returns a [`JobHandle`]
+//! that cancels it, and all jobs scheduled from a context are
+//! cancelled when that context is dropped, i.e. when its worker or
+//! processor stops.
+//!
+//! Jobs only rely on [`Context::sleep`] outside of `std`, so they also
+//! run on the `no_std` executor.  There, a periodic job sleeps for one
+//! period after each delivery and therefore drifts by the time it
+//! takes to deliver the message.
+
+#[cfg(feature = "std")]
+mod cron;
+
+#[cfg(feature = "std")]
+pub use cron::CronSchedule;
+
+#[cfg(feature = "std")]
+use crate::tokio::time::{sleep_until, Instant};
+use crate::Context;
+use core::fmt;
+use core::time::Duration;
+use futures::future::{AbortHandle, Abortable};
+use ockam_core::compat::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Address, AllowAll, DenyAll, Error, Mailbox, Mailboxes, Message, Result, Route};
+
+/// When a scheduled job delivers its message
+///
+/// Create one with [`Schedule::once`], [`Schedule::every`] or
+/// [`Schedule::cron`].
+#[derive(Debug, Clone)]
+pub struct Schedule(Timing);
+
+#[derive(Debug, Clone)]
+enum Timing {
+    /// Deliver the message once, after the given delay
+    Once(Duration),
+    /// Deliver the message every period, starting one period from now
+    ///
+    /// The job runs at a fixed rate: a slow delivery does not shift
+    /// the following ones.
+    Every(Duration),
+    /// Deliver the message whenever the wall clock matches a cron
+    /// expression
+    #[cfg(feature = "std")]
+    Cron(CronSchedule),
+}
+
+impl Schedule {
+    /// Deliver the message once, after `delay`
+    pub fn once(delay: Duration) -> Self {
+        Self(Timing::Once(delay))
+    }
+
+    /// Deliver the message every `period`
+    ///
+    /// Return an error if `period` is zero.
+    pub fn every(period: Duration) -> Result<Self> {
+        if period.is_zero() {
+            return Err(Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "the period of a schedule must not be zero",
+            ));
+        }
+        Ok(Self(Timing::Every(period)))
+    }
+
+    /// Deliver the message following a cron expression
+    ///
+    /// See [`CronSchedule`] for the supported syntax.
+    #[cfg(feature = "std")]
+    pub fn cron(expr: &str) -> Result<Self> {
+        CronSchedule::parse(expr).map(|cron| Self(Timing::Cron(cron)))
+    }
+}
+
+/// A handle to a scheduled job
+///
+/// Dropping the handle does not cancel the job.
+#[derive(Debug, Clone)]
+pub struct JobHandle {
+    id: u64,
+    abort: AbortHandle,
+    jobs: Jobs,
+}
+
+impl JobHandle {
+    /// Cancel the job
+    ///
+    /// A message that is being delivered while the job is
+    /// cancelled may still arrive.
+    pub fn cancel(&self) {
+        self.abort.abort();
+        self.jobs.remove(self.id);
+    }
+
+    /// Return `true` if the job is still scheduled
+    pub fn is_scheduled(&self) -> bool {
+        self.jobs.contains(self.id)
+    }
+}
+
+/// The jobs scheduled from a context
+#[derive(Clone)]
+pub(crate) struct Jobs {
+    inner: Arc<Mutex<JobsInner>>,
+}
+
+#[derive(Default)]
+struct JobsInner {
+    next_id: u64,
+    handles: BTreeMap<u64, AbortHandle>,
+}
+
+impl Default for Jobs {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(JobsInner::default())),
+        }
+    }
+}
+
+impl fmt::Debug for Jobs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.inner.lock().map(|jobs| jobs.handles.len()).ok();
+        f.debug_struct("Jobs").field("count", &count).finish()
+    }
+}
+
+impl Jobs {
+    /// Register a job and return its id
+    fn insert(&self, abort: AbortHandle) -> u64 {
+        match self.inner.lock() {
+            Ok(mut jobs) => {
+                let id = jobs.next_id;
+                jobs.next_id += 1;
+                jobs.handles.insert(id, abort);
+                id
+            }
+            // The job can still be cancelled through its handle
+            Err(_) => u64::MAX,
+        }
+    }
+
+    fn remove(&self, id: u64) {
+        if let Ok(mut jobs) = self.inner.lock() {
+            jobs.handles.remove(&id);
+        }
+    }
+
+    fn contains(&self, id: u64) -> bool {
+        self.inner
+            .lock()
+            .map(|jobs| jobs.handles.contains_key(&id))
+            .unwrap_or(false)
+    }
+
+    /// Cancel all jobs
+    pub(crate) fn cancel_all(&self) {
+        if let Ok(mut jobs) = self.inner.lock() {
+            for (_, abort) in core::mem::take(&mut jobs.handles) {
+                abort.abort();
+            }
+        }
+    }
+}
+
+/// Start a job that sends `msg` to `route` on the given schedule
+pub(crate) async fn start<M>(
+    ctx: &Context,
+    jobs: &Jobs,
+    route: Route,
+    msg: M,
+    schedule: Schedule,
+) -> Result<JobHandle>
+where
+    M: Message + Clone + Send + 'static,
+{
+    // TODO: @ac 0#Scheduler.job.detached
+    // in:  n/a
+    // out: route
+    let mailboxes = Mailboxes::new(
+        Mailbox::new(
+            Address::random_tagged("Scheduler.job.detached"),
+            Arc::new(DenyAll),
+            Arc::new(AllowAll),
+        ),
+        vec![],
+    );
+    let child_ctx = ctx.new_detached_with_mailboxes(mailboxes).await?;
+
+    let (abort, reg) = AbortHandle::new_pair();
+    let id = jobs.insert(abort.clone());
+
+    let done = jobs.clone();
+    let future = Abortable::new(
+        async move {
+            run(&child_ctx, route, msg, schedule).await;
+            done.remove(id);
+        },
+        reg,
+    );
+    crate::relay::spawn(ctx.runtime(), future);
+
+    Ok(JobHandle {
+        id,
+        abort,
+        jobs: jobs.clone(),
+    })
+}
+
+async fn run<M>(ctx: &Context, route: Route, msg: M, schedule: Schedule)
+where
+    M: Message + Clone + Send + 'static,
+{
+    match schedule.0 {
+        Timing::Once(delay) => {
+            ctx.sleep(delay).await;
+            deliver(ctx, &route, msg).await;
+        }
+        Timing::Every(period) => {
+            #[cfg(feature = "std")]
+            {
+                let start = Instant::now();
+                for n in 1u32.. {
+                    let deadline = match period
+                        .checked_mul(n)
+                        .and_then(|offset| start.checked_add(offset))
+                    {
+                        Some(deadline) => deadline,
+                        None => break,
+                    };
+                    sleep_until(deadline).await;
+                    deliver(ctx, &route, msg.clone()).await;
+                }
+                warn!("Periodic schedule to {} ran out of time", route);
+            }
+            #[cfg(not(feature = "std"))]
+            loop {
+                ctx.sleep(period).await;
+                deliver(ctx, &route, msg.clone()).await;
+            }
+        }
+        #[cfg(feature = "std")]
+        Timing::Cron(cron) => {
+            while let Some(delay) = cron.delay_until_next(std::time::SystemTime::now()) {
+                ctx.sleep(delay).await;
+                deliver(ctx, &route, msg.clone()).await;
+            }
+            warn!("Cron schedule {} has no further occurrence", cron);
+        }
+    }
+}
+
+async fn deliver<M>(ctx: &Context, route: &Route, msg: M)
+where
+    M: Message + Send + 'static,
+{
+    match ctx.send(route.clone(), msg).await {
+        Ok(()) => debug!("Sent scheduled message to {}", route),
+        Err(e) => warn!("Error sending scheduled message to {}: {}", route, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Schedule;
+    use crate::Context;
+    use core::sync::atomic::Ordering;
+    use core::time::Duration;
+    use ockam_core::compat::{boxed::Box, string::ToString, sync::Arc};
+    use ockam_core::{async_trait, Any};
+    use ockam_core::{Result, Routed, Worker};
+    use std::sync::atomic::AtomicI8;
+    use tokio::time::sleep;
+
+    struct CountingWorker {
+        msgs_count: Arc<AtomicI8>,
+    }
+
+    #[async_trait]
+    impl Worker for CountingWorker {
+        type Context = Context;
+        type Message = Any;
+
+        async fn handle_message(
+            &mut self,
+            _context: &mut Self::Context,
+            _msg: Routed<Self::Message>,
+        ) -> Result<()> {
+            let _ = self.msgs_count.fetch_add(1, Ordering::Relaxed);
+
+            Ok(())
+        }
+    }
+
+    /// Schedules a periodic message to `counting_worker` when started
+    struct SchedulingWorker;
+
+    #[async_trait]
+    impl Worker for SchedulingWorker {
+        type Context = Context;
+        type Message = Any;
+
+        async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+            let every = Schedule::every(Duration::from_millis(50))?;
+            ctx.schedule("counting_worker", "Hello".to_string(), every)
+                .await?;
+            Ok(())
+        }
+    }
+
+    async fn start_counting_worker(ctx: &Context) -> Result<Arc<AtomicI8>> {
+        let msgs_count = Arc::new(AtomicI8::new(0));
+        let worker = CountingWorker {
+            msgs_count: msgs_count.clone(),
+        };
+        ctx.start_worker("counting_worker", worker).await?;
+        Ok(msgs_count)
+    }
+
+    #[ockam_macros::test(crate = "crate")]
+    async fn once_is_delivered_once(ctx: &mut Context) -> Result<()> {
+        let msgs_count = start_counting_worker(ctx).await?;
+
+        let once = Schedule::once(Duration::from_millis(50));
+        let job = ctx
+            .schedule("counting_worker", "Hello".to_string(), once)
+            .await?;
+        assert!(job.is_scheduled());
+        sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(1, msgs_count.load(Ordering::Relaxed));
+        assert!(!job.is_scheduled());
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test(crate = "crate")]
+    async fn every_is_delivered_until_cancelled(ctx: &mut Context) -> Result<()> {
+        let msgs_count = start_counting_worker(ctx).await?;
+
+        let every = Schedule::every(Duration::from_millis(100))?;
+        let job = ctx
+            .schedule("counting_worker", "Hello".to_string(), every)
+            .await?;
+        sleep(Duration::from_millis(350)).await;
+        job.cancel();
+        assert!(!job.is_scheduled());
+        let count = msgs_count.load(Ordering::Relaxed);
+        assert!(count >= 2);
+
+        sleep(Duration::from_millis(300)).await;
+        assert_eq!(count, msgs_count.load(Ordering::Relaxed));
+        ctx.stop().await
+    }
+
+    #[test]
+    fn every_rejects_zero_period() {
+        assert!(Schedule::every(Duration::ZERO).is_err());
+    }
+
+    #[ockam_macros::test(crate = "crate")]
+    async fn jobs_stop_with_their_worker(ctx: &mut Context) -> Result<()> {
+        let msgs_count = start_counting_worker(ctx).await?;
+
+        ctx.start_worker("scheduling_worker", SchedulingWorker)
+            .await?;
+        sleep(Duration::from_millis(120)).await;
+        ctx.stop_worker("scheduling_worker").await?;
+        let count = msgs_count.load(Ordering::Relaxed);
+        assert!(count >= 1);
+
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(count, msgs_count.load(Ordering::Relaxed));
+        ctx.stop().await
+    }
+}
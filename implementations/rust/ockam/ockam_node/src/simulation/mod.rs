@@ -0,0 +1,218 @@
+//! Run one or more nodes in a deterministic simulation
+//!
+//! A simulated node runs on a single-threaded runtime whose clock is
+//! paused: time only moves forward when every task is waiting for a
+//! timer, and it then jumps straight to the next deadline.  This
+//! makes [`Context::sleep`], [`DelayedEvent`](crate::DelayedEvent)
+//! and [`Context::receive_timeout`] follow a virtual clock, so a test
+//! that waits for minutes completes in milliseconds.
+//!
+//! Nodes of a simulation exchange messages over an in-memory
+//! transport ([`SIM`]).  The latency, jitter, loss and duplication of
+//! every link, as well as network partitions, can be programmed.
+//!
+//! All random decisions of the network are drawn from a generator
+//! seeded with the simulation seed.  So is the order in which the
+//! workers, processors and routers of the simulated nodes run when
+//! several of them are ready, so a failing run can be replayed by
+//! running it again with the same seed.  Only the branch that
+//! `tokio::select!` picks when several are ready isn't seeded: tokio
+//! doesn't let us seed it without `tokio_unstable`.
+//!
+//! Real sockets should not be used in a simulation: the virtual
+//! clock keeps moving while a task waits for I/O.
+//!
+//! ```ignore
+//! #[ockam_macros::test(seed = 42)]
+//! async fn my_test(ctx: &mut Context) -> Result<()> {
+//!     let sim = Simulation::current().unwrap();
+//!     let alice = sim.start_node("alice").await?;
+//!     let bob = sim.start_node("bob").await?;
+//!     sim.set_link("alice", "bob", Link::with_latency(Duration::from_millis(50)));
+//!     // ... route messages via `route![(SIM, "bob"), "echoer"]` ...
+//!     ctx.stop().await
+//! }
+//! ```
+
+mod network;
+mod shuffle;
+mod transport;
+
+pub use network::Link;
+pub use transport::SIM;
+
+use crate::channel_types::RouterSender;
+use crate::router::Router;
+use crate::tokio::{runtime::Handle, task::JoinHandle};
+use crate::{Context, NodeMessage, ShutdownType};
+use core::cell::RefCell;
+use core::future::Future;
+use network::Network;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::{Address, AllowAll, Mailbox, Mailboxes, Result, ToDoAccessControl};
+use shuffle::Shuffler;
+
+std::thread_local! {
+    static CURRENT: RefCell<Option<Simulation>> = RefCell::new(None);
+}
+
+/// Handle to a running simulation
+///
+/// Cloning this handle is cheap; all clones control the same
+/// simulated network.
+#[derive(Clone)]
+pub struct Simulation {
+    seed: u64,
+    network: Arc<Network>,
+    shuffler: Shuffler,
+    nodes: Arc<Mutex<Vec<SimulatedNode>>>,
+}
+
+/// The router of a node started with [`Simulation::start_node`]
+struct SimulatedNode {
+    sender: RouterSender<NodeMessage>,
+    router: JoinHandle<()>,
+}
+
+impl Simulation {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            network: Arc::new(Network::new(seed)),
+            shuffler: Shuffler::new(seed),
+            nodes: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Return the simulation that the current node runs in
+    ///
+    /// This is `None` when the node was not built with
+    /// [`NodeBuilder::simulation`](crate::NodeBuilder::simulation).
+    pub fn current() -> Option<Simulation> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// Make this simulation the current one on this thread
+    ///
+    /// The simulation runtime is single-threaded, so all tasks of
+    /// the simulated nodes can find it.  The returned guard resets
+    /// the current simulation when it is dropped.
+    pub(crate) fn enter(&self) -> EnterGuard {
+        CURRENT.with(|current| *current.borrow_mut() = Some(self.clone()));
+        EnterGuard
+    }
+
+    /// Run the message delivery loop of the simulated network
+    pub(crate) async fn run(self) {
+        self.network.run().await
+    }
+
+    /// Spawn a task whose polls are shuffled with those of the other
+    /// tasks of the simulation, in an order given by its seed
+    pub(crate) fn spawn<F>(&self, rt: &Handle, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        rt.spawn(self.shuffler.shuffle(future))
+    }
+
+    /// Stop the nodes started with [`start_node`](Self::start_node)
+    /// that are still running
+    pub(crate) async fn stop_nodes(&self) {
+        let nodes = core::mem::take(&mut *self.nodes.lock().expect("simulation lock poisoned"));
+        for node in nodes {
+            let (msg, mut reply) = NodeMessage::stop_node(ShutdownType::Graceful(1));
+            // The node may have been stopped already
+            if node.sender.send(msg).await.is_ok() {
+                let _ = reply.recv().await;
+            }
+            if let Err(e) = node.router.await {
+                error!("Simulated node router failed: {}", e);
+            }
+        }
+    }
+
+    /// The seed of this simulation
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Start a new node in this simulation and connect it to the
+    /// network under the given name
+    ///
+    /// The returned context is the root context of the new node.
+    /// Other nodes reach it with the `(SIM, name)` address.  Nodes
+    /// that are still running when the simulation ends are stopped.
+    pub async fn start_node(&self, name: impl Into<String>) -> Result<Context> {
+        let mut router = Router::new();
+        let addr: Address = "app".into();
+        let (ctx, senders, _) = Context::new(
+            Handle::current(),
+            router.sender(),
+            Mailboxes::new(
+                Mailbox::new(
+                    addr.clone(),
+                    Arc::new(AllowAll),
+                    Arc::new(ToDoAccessControl),
+                ),
+                vec![],
+            ),
+            None,
+        );
+        router.init(addr, senders);
+
+        let sender = router.sender();
+        let router = self.spawn(&Handle::current(), async move {
+            if let Err(e) = router.run().await {
+                error!("Simulated node router failed: {}", e);
+            }
+        });
+        self.nodes
+            .lock()
+            .expect("simulation lock poisoned")
+            .push(SimulatedNode { sender, router });
+
+        self.attach(&ctx, name).await?;
+        Ok(ctx)
+    }
+
+    /// Connect an existing node to the network under the given name
+    pub async fn attach(&self, ctx: &Context, name: impl Into<String>) -> Result<()> {
+        transport::SimRouter::create(ctx, name.into(), self.network.clone()).await
+    }
+
+    /// Set the properties of the link from node `from` to node `to`
+    ///
+    /// Links are directed: the link from `to` back to `from` keeps
+    /// its own properties.
+    pub fn set_link(&self, from: &str, to: &str, link: Link) {
+        self.network.set_link(from, to, link)
+    }
+
+    /// Set the properties of every link that has not been set with
+    /// [`set_link`](Self::set_link)
+    pub fn set_default_link(&self, link: Link) {
+        self.network.set_default_link(link)
+    }
+
+    /// Drop all messages between nodes `a` and `b`, in both directions
+    pub fn partition(&self, a: &str, b: &str) {
+        self.network.partition(a, b)
+    }
+
+    /// Deliver messages between nodes `a` and `b` again
+    pub fn heal(&self, a: &str, b: &str) {
+        self.network.heal(a, b)
+    }
+}
+
+/// Reset the current simulation when dropped
+pub(crate) struct EnterGuard;
+
+impl Drop for EnterGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = None);
+    }
+}
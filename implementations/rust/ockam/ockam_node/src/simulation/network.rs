@@ -0,0 +1,247 @@
+use crate::tokio::{
+    self,
+    sync::Notify,
+    time::{self, Instant},
+};
+use crate::Context;
+use core::cmp::{Ordering, Reverse};
+use core::time::Duration;
+use ockam_core::compat::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use ockam_core::compat::rand::prelude::{Rng, SeedableRng, StdRng};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::{LocalMessage, TransportMessage};
+
+/// Properties of a simulated link between two nodes
+#[derive(Debug, Clone, Default)]
+pub struct Link {
+    /// Delay added to every message
+    pub latency: Duration,
+    /// Upper bound of a random delay added on top of the latency
+    pub jitter: Duration,
+    /// Probability (`0.0` to `1.0`) that a message is lost
+    pub loss: f64,
+    /// Probability (`0.0` to `1.0`) that a message is delivered twice
+    pub duplication: f64,
+}
+
+impl Link {
+    /// Create a link with a fixed latency
+    pub fn with_latency(latency: Duration) -> Self {
+        Self {
+            latency,
+            ..Default::default()
+        }
+    }
+
+    /// Add a random delay of up to `jitter` to every message
+    pub fn jitter(self, jitter: Duration) -> Self {
+        Self { jitter, ..self }
+    }
+
+    /// Lose messages with the given probability
+    pub fn loss(self, loss: f64) -> Self {
+        Self { loss, ..self }
+    }
+
+    /// Deliver messages twice with the given probability
+    pub fn duplication(self, duplication: f64) -> Self {
+        Self {
+            duplication,
+            ..self
+        }
+    }
+}
+
+/// A message waiting for its delivery time
+struct Pending {
+    at: Instant,
+    seq: u64,
+    to: String,
+    msg: TransportMessage,
+}
+
+// Messages with the same delivery time are delivered in the order
+// in which they were sent
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.at, self.seq).cmp(&(other.at, other.seq))
+    }
+}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Pending {}
+
+struct State {
+    rng: StdRng,
+    seq: u64,
+    default_link: Link,
+    links: BTreeMap<(String, String), Link>,
+    partitions: BTreeSet<(String, String)>,
+    nodes: BTreeMap<String, Arc<Context>>,
+    queue: BinaryHeap<Reverse<Pending>>,
+}
+
+impl State {
+    fn partitioned(&self, a: &str, b: &str) -> bool {
+        self.partitions.contains(&ordered(a, b))
+    }
+
+    fn link(&self, from: &str, to: &str) -> Link {
+        self.links
+            .get(&(from.into(), to.into()))
+            .unwrap_or(&self.default_link)
+            .clone()
+    }
+
+    /// Roll a die with the given probability of success
+    fn chance(&mut self, p: f64) -> bool {
+        p > 0.0 && self.rng.gen::<f64>() < p
+    }
+}
+
+/// The in-memory network that connects the nodes of a simulation
+pub(super) struct Network {
+    state: Mutex<State>,
+    notify: Notify,
+}
+
+impl Network {
+    pub(super) fn new(seed: u64) -> Self {
+        Self {
+            state: Mutex::new(State {
+                rng: StdRng::seed_from_u64(seed),
+                seq: 0,
+                default_link: Link::default(),
+                links: BTreeMap::new(),
+                partitions: BTreeSet::new(),
+                nodes: BTreeMap::new(),
+                queue: BinaryHeap::new(),
+            }),
+            notify: Notify::new(),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("simulated network lock poisoned")
+    }
+
+    /// Register the context that delivers messages to a node
+    pub(super) fn add_node(&self, name: String, ctx: Context) {
+        self.state().nodes.insert(name, Arc::new(ctx));
+    }
+
+    pub(super) fn set_link(&self, from: &str, to: &str, link: Link) {
+        self.state().links.insert((from.into(), to.into()), link);
+    }
+
+    pub(super) fn set_default_link(&self, link: Link) {
+        self.state().default_link = link;
+    }
+
+    pub(super) fn partition(&self, a: &str, b: &str) {
+        self.state().partitions.insert(ordered(a, b));
+    }
+
+    pub(super) fn heal(&self, a: &str, b: &str) {
+        self.state().partitions.remove(&ordered(a, b));
+    }
+
+    /// Schedule the delivery of a message from one node to another
+    pub(super) fn send(&self, from: &str, to: &str, msg: TransportMessage) {
+        let mut state = self.state();
+
+        if state.partitioned(from, to) {
+            debug!(
+                "Simulated network partition drops message {} -> {}",
+                from, to
+            );
+            return;
+        }
+
+        let link = state.link(from, to);
+        if state.chance(link.loss) {
+            debug!("Simulated network loses message {} -> {}", from, to);
+            return;
+        }
+
+        let copies = if state.chance(link.duplication) { 2 } else { 1 };
+        for _ in 0..copies {
+            let jitter = if link.jitter.is_zero() {
+                Duration::ZERO
+            } else {
+                state.rng.gen_range(Duration::ZERO..=link.jitter)
+            };
+            state.seq += 1;
+            let pending = Pending {
+                at: Instant::now() + link.latency + jitter,
+                seq: state.seq,
+                to: to.into(),
+                msg: msg.clone(),
+            };
+            state.queue.push(Reverse(pending));
+        }
+
+        drop(state);
+        self.notify.notify_one();
+    }
+
+    /// Deliver scheduled messages when their time has come
+    pub(super) async fn run(&self) {
+        loop {
+            let next = self.state().queue.peek().map(|Reverse(p)| p.at);
+            match next {
+                None => self.notify.notified().await,
+                Some(at) if at > Instant::now() => {
+                    tokio::select! {
+                        _ = time::sleep_until(at) => {}
+                        _ = self.notify.notified() => {}
+                    }
+                }
+                Some(_) => self.deliver_next().await,
+            }
+        }
+    }
+
+    async fn deliver_next(&self) {
+        let (pending, ctx) = {
+            let mut state = self.state();
+            let Reverse(pending) = match state.queue.pop() {
+                Some(p) => p,
+                None => return,
+            };
+            let ctx = state.nodes.get(&pending.to).cloned();
+            (pending, ctx)
+        };
+
+        match ctx {
+            Some(ctx) => {
+                if let Err(e) = ctx.forward(LocalMessage::new(pending.msg, vec![])).await {
+                    warn!(
+                        "Failed to deliver simulated message to {}: {}",
+                        pending.to, e
+                    );
+                }
+            }
+            None => debug!("No simulated node named {}", pending.to),
+        }
+    }
+}
+
+fn ordered(a: &str, b: &str) -> (String, String) {
+    if a <= b {
+        (a.into(), b.into())
+    } else {
+        (b.into(), a.into())
+    }
+}
@@ -0,0 +1,63 @@
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::rand::prelude::{Rng, SeedableRng, StdRng};
+use ockam_core::compat::sync::{Arc, Mutex};
+
+/// Probability that a task lets the other ready tasks run before it
+/// is polled
+const YIELD_PROBABILITY: f64 = 0.5;
+
+/// Seeded task ordering of a simulation
+///
+/// The simulation runtime runs ready tasks in the order in which they
+/// were woken up.  Before every poll of a shuffled task a coin is
+/// flipped, and on heads the task goes to the back of the run queue
+/// instead.  The order in which tasks run therefore depends on the
+/// simulation seed, and is the same every time for the same seed.
+#[derive(Clone)]
+pub(super) struct Shuffler {
+    rng: Arc<Mutex<StdRng>>,
+}
+
+impl Shuffler {
+    pub(super) fn new(seed: u64) -> Self {
+        Self {
+            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(seed))),
+        }
+    }
+
+    pub(super) fn shuffle<F: Future>(&self, future: F) -> Shuffled<F> {
+        Shuffled {
+            future: Box::pin(future),
+            shuffler: self.clone(),
+        }
+    }
+
+    fn should_yield(&self) -> bool {
+        self.rng
+            .lock()
+            .expect("simulation scheduler lock poisoned")
+            .gen_bool(YIELD_PROBABILITY)
+    }
+}
+
+/// A future whose polls are shuffled with those of the other tasks
+/// of a simulation, see [`Shuffler`]
+pub(crate) struct Shuffled<F: Future> {
+    future: Pin<Box<F>>,
+    shuffler: Shuffler,
+}
+
+impl<F: Future> Future for Shuffled<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        if self.shuffler.should_yield() {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.future.as_mut().poll(cx)
+    }
+}
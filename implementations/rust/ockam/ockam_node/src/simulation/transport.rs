@@ -0,0 +1,49 @@
+use super::network::Network;
+use crate::Context;
+use ockam_core::compat::{boxed::Box, string::String, sync::Arc};
+use ockam_core::{async_trait, Address, Any, Result, Routed, TransportType, Worker};
+
+/// Simulated transport type
+pub const SIM: TransportType = TransportType::new(5);
+
+/// Route messages for `SIM` addresses into the simulated network
+pub(super) struct SimRouter {
+    name: String,
+    network: Arc<Network>,
+}
+
+impl SimRouter {
+    /// Register a simulated transport router on the node of `ctx`
+    pub(super) async fn create(ctx: &Context, name: String, network: Arc<Network>) -> Result<()> {
+        // Incoming messages are delivered through a detached context
+        let receiver = ctx
+            .new_detached(Address::random_tagged("SimRouter.receiver.detached"))
+            .await?;
+        network.add_node(name.clone(), receiver);
+
+        let addr = Address::random_tagged("SimRouter");
+        ctx.start_worker(addr.clone(), SimRouter { name, network })
+            .await?;
+        ctx.register(SIM, addr).await
+    }
+}
+
+#[async_trait]
+impl Worker for SimRouter {
+    type Context = Context;
+    type Message = Any;
+
+    async fn handle_message(&mut self, _ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let mut msg = msg.into_local_message().into_transport_message();
+
+        // The next hop names the node to deliver to
+        let next = msg.onward_route.step()?;
+        msg.return_route
+            .modify()
+            .prepend(Address::new(SIM, self.name.clone()));
+
+        trace!("Simulated message {} -> {}", self.name, next.address());
+        self.network.send(&self.name, next.address(), msg);
+        Ok(())
+    }
+}
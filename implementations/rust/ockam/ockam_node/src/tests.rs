@@ -1,3 +1,4 @@
+use crate::channel_types::mailbox_channel;
 use crate::compat::futures::FutureExt;
 use crate::{Context, NodeBuilder};
 use core::sync::atomic::{AtomicBool, Ordering};
@@ -7,10 +8,14 @@ use ockam_core::compat::{
     string::{String, ToString},
     sync::Arc,
 };
-use ockam_core::{async_trait, Address, Any, Decodable, Message, LOCAL};
+use ockam_core::{
+    async_trait, Address, Any, Decodable, LocalMessage, Message, Priority, RelayMessage,
+    TransportMessage, LOCAL,
+};
 use ockam_core::{route, Processor, Result, Routed, Worker};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicI8, AtomicU32};
+use std::sync::Mutex;
 use tokio::time::sleep;
 
 #[allow(non_snake_case)]
@@ -354,6 +359,162 @@ async fn abort_blocked_shutdown(ctx: &mut Context) -> Result<()> {
         .unwrap()
 }
 
+struct DrainingWorker {
+    handled: Arc<AtomicU32>,
+    handled_before_drain: Arc<AtomicU32>,
+    shutdown_was_called: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Worker for DrainingWorker {
+    type Context = Context;
+    type Message = String;
+
+    async fn handle_message(&mut self, _ctx: &mut Context, _msg: Routed<String>) -> Result<()> {
+        self.handled.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn drain(&mut self, _ctx: &mut Context) -> Result<()> {
+        self.handled_before_drain
+            .store(self.handled.load(Ordering::Relaxed), Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, _ctx: &mut Context) -> Result<()> {
+        self.shutdown_was_called.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn drain_handles_queued_messages_before_drain_hook(ctx: &mut Context) -> Result<()> {
+    let handled = Arc::new(AtomicU32::new(0));
+    let handled_before_drain = Arc::new(AtomicU32::new(0));
+    let shutdown_was_called = Arc::new(AtomicBool::new(false));
+
+    let worker = DrainingWorker {
+        handled: handled.clone(),
+        handled_before_drain: handled_before_drain.clone(),
+        shutdown_was_called: shutdown_was_called.clone(),
+    };
+    ctx.start_worker("draining", worker).await?;
+
+    for _ in 0..5 {
+        ctx.send(route!["draining"], "Hello".to_string()).await?;
+    }
+
+    ctx.drain_and_stop(2).await?;
+
+    assert_eq!(handled_before_drain.load(Ordering::Relaxed), 5);
+    assert!(shutdown_was_called.load(Ordering::Relaxed));
+    Ok(())
+}
+
+struct DrainedProcessor {
+    stop_on_drain: bool,
+    stopped: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Processor for DrainedProcessor {
+    type Context = Context;
+
+    fn stop_on_drain(&self) -> bool {
+        self.stop_on_drain
+    }
+
+    async fn process(&mut self, ctx: &mut Context) -> Result<bool> {
+        ctx.sleep(Duration::from_millis(10)).await;
+        Ok(true)
+    }
+
+    async fn shutdown(&mut self, _ctx: &mut Context) -> Result<()> {
+        self.stopped.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Records which processors were stopped while it drained
+struct ProcessorCheckingWorker {
+    listener_stopped: Arc<AtomicBool>,
+    receiver_stopped: Arc<AtomicBool>,
+    seen: Arc<Mutex<Option<(bool, bool)>>>,
+}
+
+#[async_trait]
+impl Worker for ProcessorCheckingWorker {
+    type Context = Context;
+    type Message = ();
+
+    async fn drain(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.sleep(Duration::from_millis(200)).await;
+        *self.seen.lock().unwrap() = Some((
+            self.listener_stopped.load(Ordering::Relaxed),
+            self.receiver_stopped.load(Ordering::Relaxed),
+        ));
+        Ok(())
+    }
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn drain_stops_listening_processors(ctx: &mut Context) -> Result<()> {
+    let listener_stopped = Arc::new(AtomicBool::new(false));
+    let receiver_stopped = Arc::new(AtomicBool::new(false));
+    let seen = Arc::new(Mutex::new(None));
+
+    let listener = DrainedProcessor {
+        stop_on_drain: true,
+        stopped: listener_stopped.clone(),
+    };
+    let receiver = DrainedProcessor {
+        stop_on_drain: false,
+        stopped: receiver_stopped.clone(),
+    };
+    ctx.start_processor("listener", listener).await?;
+    ctx.start_processor("receiver", receiver).await?;
+
+    let worker = ProcessorCheckingWorker {
+        listener_stopped,
+        receiver_stopped: receiver_stopped.clone(),
+        seen: seen.clone(),
+    };
+    ctx.start_worker("checking", worker).await?;
+
+    ctx.drain_and_stop(2).await?;
+
+    // The listener stopped during the drain, the receiver only with
+    // the shutdown that followed it
+    assert_eq!(*seen.lock().unwrap(), Some((true, false)));
+    assert!(receiver_stopped.load(Ordering::Relaxed));
+    Ok(())
+}
+
+struct BadDrainWorker;
+
+#[ockam_core::worker]
+impl Worker for BadDrainWorker {
+    type Context = Context;
+    type Message = ();
+
+    /// This drain function takes _way_ too long to complete
+    async fn drain(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.sleep(Duration::from_secs(10)).await;
+        Ok(())
+    }
+}
+
+/// This test enforces that a drain that is blocked by a worker will
+/// be aborted eventually.
+#[ockam_macros::test(crate = "crate")]
+async fn abort_blocked_drain(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("bad_drain", BadDrainWorker).await?;
+
+    crate::tokio::time::timeout(Duration::from_secs(4), ctx.drain_and_stop(1))
+        .await
+        .unwrap()
+}
+
 struct WaitForWorker;
 
 #[ockam_core::worker]
@@ -560,3 +721,295 @@ async fn starting_worker_with_dup_address_should_fail(ctx: &mut Context) -> Resu
     assert!(ctx.start_worker("dummy_worker", DummyWorker).await.is_err());
     ctx.stop().await
 }
+
+#[ockam_macros::test(crate = "crate")]
+async fn topology_lists_workers_and_processors(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("topology_worker", DummyWorker).await?;
+    ctx.start_processor("topology_processor", DummyProcessor)
+        .await?;
+    let detached = ctx.new_detached("topology_detached").await?;
+    detached.set_cluster("topology_cluster").await?;
+
+    let topology = ctx.topology().await?;
+    let find = |addr: &str| {
+        topology
+            .workers
+            .iter()
+            .find(|w| w.address == addr.into())
+            .cloned()
+            .unwrap()
+    };
+
+    let worker = find("topology_worker");
+    assert!(!worker.processor && !worker.detached);
+
+    let processor = find("topology_processor");
+    assert!(processor.processor);
+
+    let detached = find("topology_detached");
+    assert!(detached.detached);
+    assert_eq!(detached.cluster.as_deref(), Some("topology_cluster"));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn control_messages_overtake_queued_messages(ctx: &mut Context) -> Result<()> {
+    let mut inbox = ctx.new_detached("priority_inbox").await?;
+    for i in 0..3 {
+        ctx.send_with_priority("priority_inbox", format!("bulk {}", i), Priority::Bulk)
+            .await?;
+    }
+    ctx.send("priority_inbox", String::from("normal")).await?;
+    ctx.send_with_priority("priority_inbox", String::from("control"), Priority::Control)
+        .await?;
+
+    let mut received = Vec::new();
+    for _ in 0..5 {
+        let msg = inbox.receive::<String>().await?.take();
+        received.push((msg.local_message().priority(), msg.body()));
+    }
+    assert_eq!(
+        received,
+        vec![
+            (Priority::Control, "control".into()),
+            (Priority::Normal, "normal".into()),
+            (Priority::Bulk, "bulk 0".into()),
+            (Priority::Bulk, "bulk 1".into()),
+            (Priority::Bulk, "bulk 2".into()),
+        ]
+    );
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test(crate = "crate")]
+async fn mailbox_does_not_starve_low_priorities(ctx: &mut Context) -> Result<()> {
+    let (tx, mut rx) = mailbox_channel();
+    let relay_msg = |priority| {
+        let msg = TransportMessage::v1(route![], route![], vec![]);
+        RelayMessage::new(
+            ctx.address(),
+            ctx.address(),
+            LocalMessage::new(msg, vec![]).with_priority(priority),
+            route![],
+            false,
+        )
+    };
+
+    tx.send(relay_msg(Priority::Bulk)).await.unwrap();
+    for _ in 0..12 {
+        tx.send(relay_msg(Priority::Normal)).await.unwrap();
+    }
+
+    let mut received = Vec::new();
+    while let Some(msg) = rx.try_recv() {
+        received.push(msg.local_msg.priority());
+    }
+    let limit = Priority::STARVATION_LIMIT as usize;
+    assert_eq!(received.len(), 13);
+    assert!(received[..limit].iter().all(|p| *p == Priority::Normal));
+    assert_eq!(received[limit], Priority::Bulk);
+
+    ctx.stop().await
+}
+
+#[cfg(feature = "simulation")]
+mod simulation {
+    use super::*;
+    use crate::simulation::{Link, Simulation, SIM};
+    use crate::tokio::time::Instant;
+
+    struct Echoer;
+
+    #[async_trait]
+    impl Worker for Echoer {
+        type Message = String;
+        type Context = Context;
+
+        async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+            ctx.send(msg.return_route(), msg.body()).await
+        }
+    }
+
+    #[ockam_macros::test(crate = "crate", seed = 1, timeout = 7_200_000)]
+    async fn virtual_clock_skips_sleeps(ctx: &mut Context) -> Result<()> {
+        let started = std::time::Instant::now();
+        ctx.sleep(Duration::from_secs(3600)).await;
+        assert!(started.elapsed() < Duration::from_secs(10));
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test(crate = "crate", seed = 2)]
+    async fn messages_cross_simulated_links(ctx: &mut Context) -> Result<()> {
+        let sim = Simulation::current().unwrap();
+        sim.attach(ctx, "alice").await?;
+        let bob = sim.start_node("bob").await?;
+        bob.start_worker("echoer", Echoer).await?;
+        sim.set_link(
+            "alice",
+            "bob",
+            Link::with_latency(Duration::from_millis(300)),
+        );
+        sim.set_link(
+            "bob",
+            "alice",
+            Link::with_latency(Duration::from_millis(200)),
+        );
+
+        let started = Instant::now();
+        let reply: String = ctx
+            .send_and_receive(route![(SIM, "bob"), "echoer"], String::from("Hello"))
+            .await?;
+        assert_eq!(reply, "Hello");
+        assert!(started.elapsed() >= Duration::from_millis(500));
+
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test(crate = "crate", seed = 3)]
+    async fn partitions_drop_messages_until_healed(ctx: &mut Context) -> Result<()> {
+        let sim = Simulation::current().unwrap();
+        sim.attach(ctx, "alice").await?;
+        let bob = sim.start_node("bob").await?;
+        bob.start_worker("echoer", Echoer).await?;
+
+        let mut child = ctx.new_detached("partition_child").await?;
+        let route = route![(SIM, "bob"), "echoer"];
+
+        sim.partition("bob", "alice");
+        child.send(route.clone(), String::from("lost")).await?;
+        assert!(child
+            .receive_duration_timeout::<String>(Duration::from_secs(5))
+            .await
+            .is_err());
+
+        sim.heal("alice", "bob");
+        child.send(route, String::from("found")).await?;
+        let reply = child.receive::<String>().await?.take().body();
+        assert_eq!(reply, "found");
+
+        ctx.stop().await
+    }
+
+    /// Send messages over a lossy, duplicating link and return the
+    /// order in which they arrived
+    fn lossy_run(seed: u64) -> Vec<u32> {
+        let (mut ctx, mut executor) = NodeBuilder::without_access_control()
+            .no_logging()
+            .simulation(seed)
+            .build();
+        executor
+            .execute(async move {
+                let sim = Simulation::current().unwrap();
+                sim.attach(&ctx, "alice").await?;
+                let bob = sim.start_node("bob").await?;
+                let mut inbox = bob.new_detached("inbox").await?;
+                sim.set_default_link(
+                    Link::with_latency(Duration::from_millis(10))
+                        .jitter(Duration::from_millis(100))
+                        .loss(0.3)
+                        .duplication(0.3),
+                );
+
+                for i in 0..50u32 {
+                    ctx.send(route![(SIM, "bob"), "inbox"], i.to_string())
+                        .await?;
+                }
+                let mut received = vec![];
+                while let Ok(msg) = inbox
+                    .receive_duration_timeout::<String>(Duration::from_secs(1))
+                    .await
+                {
+                    received.push(msg.take().body().parse().unwrap());
+                }
+
+                ctx.stop().await?;
+                Result::<_>::Ok(received)
+            })
+            .unwrap()
+            .unwrap()
+    }
+
+    /// Records the ids it receives, in order
+    struct Collector(Arc<Mutex<Vec<u32>>>);
+
+    #[async_trait]
+    impl Worker for Collector {
+        type Context = Context;
+        type Message = String;
+
+        async fn handle_message(&mut self, _ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+            self.0.lock().unwrap().push(msg.body().parse().unwrap());
+            Ok(())
+        }
+    }
+
+    /// Sends its id to the collector a few times for every message
+    struct IdSender(u32);
+
+    #[async_trait]
+    impl Worker for IdSender {
+        type Context = Context;
+        type Message = ();
+
+        async fn handle_message(&mut self, ctx: &mut Context, _msg: Routed<()>) -> Result<()> {
+            for _ in 0..5 {
+                ctx.send("collector", self.0.to_string()).await?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Let a few workers race and return the order in which their
+    /// messages arrived
+    fn racing_run(seed: u64) -> Vec<u32> {
+        let (mut ctx, mut executor) = NodeBuilder::without_access_control()
+            .no_logging()
+            .simulation(seed)
+            .build();
+        executor
+            .execute(async move {
+                let received = Arc::new(Mutex::new(vec![]));
+                ctx.start_worker("collector", Collector(received.clone()))
+                    .await?;
+                for id in 0..4 {
+                    ctx.start_worker(Address::from_string(format!("sender_{}", id)), IdSender(id))
+                        .await?;
+                }
+                for id in 0..4 {
+                    ctx.send(Address::from_string(format!("sender_{}", id)), ())
+                        .await?;
+                }
+                ctx.sleep(Duration::from_secs(1)).await;
+
+                ctx.stop().await?;
+                let received = received.lock().unwrap().clone();
+                Result::<_>::Ok(received)
+            })
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn simulation_replays_task_interleaving_from_seed() {
+        let first = racing_run(7);
+        assert_eq!(first.len(), 4 * 5);
+        assert_eq!(first, racing_run(7));
+        assert!(
+            (0..20).any(|seed| racing_run(seed) != first),
+            "the seed should change the interleaving"
+        );
+    }
+
+    #[test]
+    fn simulation_replays_from_seed() {
+        let first = lossy_run(42);
+        assert_eq!(first, lossy_run(42));
+        assert!(first.len() < 50 * 2);
+
+        let mut sorted = first.clone();
+        sorted.sort_unstable();
+        assert_ne!(first, sorted, "jitter should reorder messages");
+    }
+}
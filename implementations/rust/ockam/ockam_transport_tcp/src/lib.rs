@@ -33,8 +33,11 @@ pub(crate) use portal::*;
 pub(crate) use router::*;
 pub(crate) use workers::*;
 
+mod local_info;
 mod transport;
 
+pub use local_info::*;
+pub use portal::PortalCounters;
 pub use transport::*;
 
 use ockam_core::compat::net::SocketAddr;
@@ -0,0 +1,73 @@
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::{
+    errcode::{Kind, Origin},
+    Decodable, Encodable, Error, LocalInfo, LocalMessage, Result,
+};
+use serde::{Deserialize, Serialize};
+
+/// TCP LocalInfo unique Identifier
+pub const TCP_LOCAL_INFO_IDENTIFIER: &str = "TCP_LOCAL_INFO_IDENTIFIER";
+
+/// Used for LocalMessage received over a TCP connection, to tell which
+/// connection it was received on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TcpLocalInfo {
+    local_addr: SocketAddr,
+    peer_addr: SocketAddr,
+}
+
+impl TcpLocalInfo {
+    /// Convert from LocalInfo
+    pub fn from_local_info(value: &LocalInfo) -> Result<Self> {
+        if value.type_identifier() != TCP_LOCAL_INFO_IDENTIFIER {
+            return Err(Error::new_without_cause(Origin::Transport, Kind::Invalid));
+        }
+
+        if let Ok(info) = TcpLocalInfo::decode(value.data()) {
+            return Ok(info);
+        }
+
+        Err(Error::new_without_cause(Origin::Transport, Kind::Invalid))
+    }
+
+    /// Convert to LocalInfo
+    pub fn to_local_info(&self) -> Result<LocalInfo> {
+        Ok(LocalInfo::new(
+            TCP_LOCAL_INFO_IDENTIFIER.into(),
+            self.encode()?,
+        ))
+    }
+
+    /// Find first such instance in LocalMessage
+    pub fn find_info(local_msg: &LocalMessage) -> Result<Self> {
+        if let Some(local_info) = local_msg
+            .local_info()
+            .iter()
+            .find(|x| x.type_identifier() == TCP_LOCAL_INFO_IDENTIFIER)
+        {
+            Self::from_local_info(local_info)
+        } else {
+            Err(Error::new_without_cause(Origin::Transport, Kind::Invalid))
+        }
+    }
+}
+
+impl TcpLocalInfo {
+    /// Constructor
+    pub fn new(local_addr: SocketAddr, peer_addr: SocketAddr) -> Self {
+        Self {
+            local_addr,
+            peer_addr,
+        }
+    }
+
+    /// Address of the local end of the connection
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Address of the remote end of the connection
+    pub fn peer_addr(&self) -> SocketAddr {
+        self.peer_addr
+    }
+}
@@ -0,0 +1,65 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+use ockam_core::compat::sync::Arc;
+
+/// Traffic of all the connections of an inlet or outlet
+///
+/// Bytes are counted from the point of view of the TCP side of the
+/// portal: `bytes_received` were read from TCP connections and sent
+/// through the portal, `bytes_sent` came through the portal and were
+/// written to TCP connections.
+#[derive(Debug, Default)]
+pub struct PortalCounters {
+    connections: AtomicU64,
+    bytes_received: AtomicU64,
+    bytes_sent: AtomicU64,
+}
+
+impl PortalCounters {
+    /// Create counters starting at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of currently open connections
+    pub fn connections(&self) -> u64 {
+        self.connections.load(Ordering::Relaxed)
+    }
+
+    /// Number of bytes read from TCP connections
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received.load(Ordering::Relaxed)
+    }
+
+    /// Number of bytes written to TCP connections
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn add_received(&self, n: usize) {
+        self.bytes_received.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_sent(&self, n: usize) {
+        self.bytes_sent.fetch_add(n as u64, Ordering::Relaxed);
+    }
+}
+
+/// Counts a connection as open for as long as it lives
+pub(crate) struct ConnectionGuard(Arc<PortalCounters>);
+
+impl ConnectionGuard {
+    pub(crate) fn new(counters: Arc<PortalCounters>) -> Self {
+        counters.connections.fetch_add(1, Ordering::Relaxed);
+        Self(counters)
+    }
+
+    pub(crate) fn counters(&self) -> &Arc<PortalCounters> {
+        &self.0
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
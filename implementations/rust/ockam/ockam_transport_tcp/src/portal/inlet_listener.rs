@@ -1,4 +1,5 @@
-use crate::TcpPortalWorker;
+use crate::{PortalCounters, TcpPortalWorker};
+use core::time::Duration;
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::{
     async_trait,
@@ -19,6 +20,8 @@ pub(crate) struct TcpInletListenProcessor {
     inner: TcpListener,
     outlet_listener_route: Route,
     access_control: Arc<dyn AccessControl>,
+    counters: Arc<PortalCounters>,
+    drain_timeout: Duration,
     // router_address: Address, // TODO @ac for AccessControl // FIXME: Why this is needed?
 }
 
@@ -29,6 +32,8 @@ impl TcpInletListenProcessor {
         outlet_listener_route: Route,
         addr: SocketAddr,
         access_control: Arc<dyn AccessControl>,
+        counters: Arc<PortalCounters>,
+        drain_timeout: Duration,
         // router_address: Address,
     ) -> Result<(Address, SocketAddr)> {
         let waddr = Address::random_tagged("TcpInletListenProcessor");
@@ -46,6 +51,8 @@ impl TcpInletListenProcessor {
             inner,
             outlet_listener_route,
             access_control: access_control.clone(),
+            counters,
+            drain_timeout,
             // router_address,
         };
 
@@ -69,15 +76,24 @@ impl TcpInletListenProcessor {
 impl Processor for TcpInletListenProcessor {
     type Context = Context;
 
+    // Don't accept new connections while the node drains
+    fn stop_on_drain(&self) -> bool {
+        true
+    }
+
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
         let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
+        let (rx, tx) = stream.into_split();
         TcpPortalWorker::start_new_inlet(
             ctx,
-            stream,
-            peer,
+            Box::new(rx),
+            Box::new(tx),
+            peer.to_string(),
             // self.router_address.clone(),
             self.outlet_listener_route.clone(),
             self.access_control.clone(),
+            self.counters.clone(),
+            self.drain_timeout,
         )
         .await?;
 
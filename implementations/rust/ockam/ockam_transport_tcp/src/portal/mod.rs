@@ -1,11 +1,24 @@
+mod counters;
 mod inlet_listener;
 mod outlet_listener;
 mod portal_message;
 mod portal_receiver;
 mod portal_worker;
+mod target;
+mod udp;
+mod udp_inlet_listener;
+#[cfg(unix)]
+mod unix_inlet_listener;
 
+pub(crate) use counters::ConnectionGuard;
+pub use counters::PortalCounters;
 pub(crate) use inlet_listener::*;
 pub(crate) use outlet_listener::*;
 pub(crate) use portal_message::*;
 pub(crate) use portal_receiver::*;
 pub(crate) use portal_worker::*;
+pub(crate) use target::*;
+pub(crate) use udp::*;
+pub(crate) use udp_inlet_listener::*;
+#[cfg(unix)]
+pub(crate) use unix_inlet_listener::*;
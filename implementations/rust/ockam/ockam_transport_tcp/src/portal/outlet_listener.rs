@@ -1,18 +1,28 @@
-use crate::{PortalMessage, TcpPortalWorker, TcpRouterHandle};
+use crate::{
+    PortalCounters, PortalKind, PortalMessage, PortalTarget, TcpPortalWorker, TcpRouterHandle,
+};
+use core::time::Duration;
 use ockam_core::{async_trait, AccessControl, Result, Routed, Worker};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::debug;
 
 /// A TCP Portal Outlet listen worker
 ///
+/// Connects every new portal connection to `peer`, over TCP, UDP or a
+/// Unix socket depending on `kind`.
+///
 /// TCP Portal Outlet listen workers are created by `TcpTransport`
 /// after a call is made to
 /// [`TcpTransport::create_outlet`](crate::TcpTransport::create_outlet).
 pub(crate) struct TcpOutletListenWorker {
     peer: String,
+    kind: PortalKind,
     access_control: Arc<dyn AccessControl>,
+    counters: Arc<PortalCounters>,
+    drain_timeout: Duration,
     // router_address: Address, // TODO @ac for AccessControl // FIXME: Why is this needed
 }
 
@@ -20,12 +30,18 @@ impl TcpOutletListenWorker {
     /// Create a new `TcpOutletListenWorker`
     pub(crate) fn new(
         peer: String,
+        kind: PortalKind,
         access_control: Arc<dyn AccessControl>,
+        counters: Arc<PortalCounters>,
+        drain_timeout: Duration,
         // router_address: Address,
     ) -> Self {
         Self {
             peer,
+            kind,
             access_control,
+            counters,
+            drain_timeout,
             // router_address,
         }
     }
@@ -48,18 +64,24 @@ impl Worker for TcpOutletListenWorker {
             return Err(TransportError::Protocol.into());
         }
 
-        let (peer_addr, _) = TcpRouterHandle::resolve_peer(self.peer.clone())?;
+        let target = match self.kind {
+            PortalKind::Tcp => PortalTarget::Tcp(TcpRouterHandle::resolve_peer(&self.peer)?.0),
+            PortalKind::Udp => PortalTarget::Udp(TcpRouterHandle::resolve_peer(&self.peer)?.0),
+            PortalKind::Unix => PortalTarget::Unix(PathBuf::from(&self.peer)),
+        };
 
         let address = TcpPortalWorker::start_new_outlet(
             ctx,
-            peer_addr,
+            target,
             // self.router_address.clone(),
             return_route.clone(),
             self.access_control.clone(),
+            self.counters.clone(),
+            self.drain_timeout,
         )
         .await?;
 
-        debug!("Created Tcp Outlet at {}", &address);
+        debug!("Created {:?} Outlet at {}", self.kind, &address);
 
         Ok(())
     }
@@ -1,12 +1,15 @@
-use crate::{PortalInternalMessage, PortalMessage};
-use ockam_core::compat::vec::Vec;
-use ockam_core::{async_trait, Encodable, LocalMessage, Route, TransportMessage};
+use crate::{PortalCounters, PortalInternalMessage, PortalMessage, PortalReader};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use ockam_core::compat::{sync::Arc, vec::Vec};
+use ockam_core::{async_trait, Encodable, LocalMessage, Priority, Route, TransportMessage};
 use ockam_core::{route, Address, Processor, Result};
 use ockam_node::Context;
-use tokio::{io::AsyncReadExt, net::tcp::OwnedReadHalf};
+use tokio::io::AsyncReadExt;
 use tracing::{error, warn};
 
-const MAX_PAYLOAD_SIZE: usize = 48 * 1024;
+/// Largest payload read from a connection at once, and so the largest
+/// datagram a UDP portal carries
+pub(crate) const MAX_PAYLOAD_SIZE: usize = 48 * 1024;
 
 /// A TCP Portal receiving message processor
 ///
@@ -15,19 +18,32 @@ const MAX_PAYLOAD_SIZE: usize = 48 * 1024;
 /// [`TcpPortalWorker::start_receiver`](crate::TcpPortalWorker::start_receiver)
 pub(crate) struct TcpPortalRecvProcessor {
     buf: Vec<u8>,
-    rx: OwnedReadHalf,
+    rx: PortalReader,
     sender_address: Address,
     onward_route: Route,
+    reads: Arc<AtomicUsize>,
+    counters: Arc<PortalCounters>,
 }
 
 impl TcpPortalRecvProcessor {
     /// Create a new `TcpPortalRecvProcessor`
-    pub fn new(rx: OwnedReadHalf, sender_address: Address, onward_route: Route) -> Self {
+    ///
+    /// `reads` is incremented after every read from the connection,
+    /// `counters` by the number of bytes read
+    pub fn new(
+        rx: PortalReader,
+        sender_address: Address,
+        onward_route: Route,
+        reads: Arc<AtomicUsize>,
+        counters: Arc<PortalCounters>,
+    ) -> Self {
         Self {
             buf: Vec::with_capacity(MAX_PAYLOAD_SIZE),
             rx,
             sender_address,
             onward_route,
+            reads,
+            counters,
         }
     }
 }
@@ -39,7 +55,7 @@ impl Processor for TcpPortalRecvProcessor {
     async fn process(&mut self, ctx: &mut Context) -> Result<bool> {
         self.buf.clear();
 
-        let _len = match self.rx.read_buf(&mut self.buf).await {
+        let len = match self.rx.read_buf(&mut self.buf).await {
             Ok(len) => len,
             Err(err) => {
                 error!("Tcp Portal connection read failed with error: {}", err);
@@ -47,12 +63,16 @@ impl Processor for TcpPortalRecvProcessor {
             }
         };
 
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.counters.add_received(len);
+
         if self.buf.is_empty() {
             // Notify Sender that connection was closed
             if let Err(err) = ctx
-                .send(
+                .send_with_priority(
                     route![self.sender_address.clone()],
                     PortalInternalMessage::Disconnect,
+                    Priority::Bulk,
                 )
                 .await
             {
@@ -67,7 +87,9 @@ impl Processor for TcpPortalRecvProcessor {
                 self.sender_address.clone(),
                 PortalMessage::Disconnect.encode()?,
             );
-            ctx.forward(LocalMessage::new(msg, vec![])).await?;
+            // Same class as the payloads, so that it can't overtake them
+            ctx.forward(LocalMessage::new(msg, vec![]).with_priority(Priority::Bulk))
+                .await?;
 
             return Ok(false);
         }
@@ -79,7 +101,9 @@ impl Processor for TcpPortalRecvProcessor {
                 self.sender_address.clone(),
                 PortalMessage::Payload(chunk.to_vec()).encode()?,
             );
-            ctx.forward(LocalMessage::new(msg, vec![])).await?;
+            // Payloads must not hold up control traffic on shared connections
+            ctx.forward(LocalMessage::new(msg, vec![]).with_priority(Priority::Bulk))
+                .await?;
         }
 
         Ok(true)
@@ -1,15 +1,24 @@
-use crate::{PortalInternalMessage, PortalMessage, TcpPortalRecvProcessor};
+use crate::{
+    ConnectionGuard, PortalCounters, PortalInternalMessage, PortalMessage, PortalReader,
+    PortalTarget, PortalWriter, TcpPortalRecvProcessor,
+};
+use core::sync::atomic::{AtomicUsize, Ordering};
 use core::time::Duration;
-use ockam_core::compat::{boxed::Box, net::SocketAddr, sync::Arc};
+use ockam_core::compat::{boxed::Box, string::String, sync::Arc};
 use ockam_core::{async_trait, AccessControl, AllowAll, Decodable, Mailbox, Mailboxes};
-use ockam_core::{Address, Any, Result, Route, Routed, Worker};
+use ockam_core::{Address, Any, Priority, Result, Route, Routed, Worker};
 use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
 use ockam_transport_core::TransportError;
 use tokio::io::AsyncWriteExt;
-use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
-use tokio::net::TcpStream;
+use tokio::time::Instant;
 use tracing::{debug, info, trace, warn};
 
+/// How long a draining portal keeps forwarding data at most, unless
+/// its inlet or outlet options say otherwise
+pub(crate) const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long without data before a draining portal is considered idle
+const DRAIN_IDLE: Duration = Duration::from_millis(100);
+
 /// Enumerate all `TcpPortalWorker` states
 ///
 /// Possible state transitions are:
@@ -36,39 +45,51 @@ enum TypeName {
 /// A TCP Portal worker is responsible for managing the life-cycle of
 /// a portal connection and is created by
 /// [`TcpInletListenProcessor::process`](crate::TcpInletListenProcessor)
-/// after a new connection has been accepted.
+/// after a new connection has been accepted. Despite the name it also
+/// carries Unix socket connections and UDP sessions.
 pub(crate) struct TcpPortalWorker {
     state: State,
-    tx: Option<OwnedWriteHalf>,
-    rx: Option<OwnedReadHalf>,
-    peer: SocketAddr,
+    tx: Option<PortalWriter>,
+    rx: Option<PortalReader>,
+    peer: String,
+    target: Option<PortalTarget>,
     // router_address: Address, // TODO @ac for AccessControl
     internal_address: Address,
     remote_address: Address,
     receiver_address: Address,
+    receiver_reads: Arc<AtomicUsize>,
     remote_route: Option<Route>,
     is_disconnecting: bool,
     type_name: TypeName,
+    connection: ConnectionGuard,
+    drain_timeout: Duration,
 }
 
 impl TcpPortalWorker {
     /// Start a new `TcpPortalWorker` of type [`TypeName::Inlet`]
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn start_new_inlet(
         ctx: &Context,
-        stream: TcpStream,
-        peer: SocketAddr,
+        rx: PortalReader,
+        tx: PortalWriter,
+        peer: String,
         // router_address: Address, // for AccessControl
         ping_route: Route,
         access_control: Arc<dyn AccessControl>,
+        counters: Arc<PortalCounters>,
+        drain_timeout: Duration,
     ) -> Result<Address> {
         Self::start(
             ctx,
             peer,
+            None,
             // router_address,
             State::SendPing { ping_route },
-            Some(stream),
+            Some((rx, tx)),
             TypeName::Inlet,
             access_control,
+            counters,
+            drain_timeout,
         )
         .await
     }
@@ -76,32 +97,41 @@ impl TcpPortalWorker {
     /// Start a new `TcpPortalWorker` of type [`TypeName::Outlet`]
     pub(crate) async fn start_new_outlet(
         ctx: &Context,
-        peer: SocketAddr,
+        target: PortalTarget,
         // router_address: Address, // for AccessControl
         pong_route: Route,
         access_control: Arc<dyn AccessControl>,
+        counters: Arc<PortalCounters>,
+        drain_timeout: Duration,
     ) -> Result<Address> {
         Self::start(
             ctx,
-            peer,
+            target.to_string(),
+            Some(target),
             // router_address,
             State::SendPong { pong_route },
             None,
             TypeName::Outlet,
             access_control,
+            counters,
+            drain_timeout,
         )
         .await
     }
 
     /// Start a new `TcpPortalWorker`
+    #[allow(clippy::too_many_arguments)]
     async fn start(
         ctx: &Context,
-        peer: SocketAddr,
+        peer: String,
+        target: Option<PortalTarget>,
         // router_address: Address,
         state: State,
-        stream: Option<TcpStream>,
+        connection: Option<(PortalReader, PortalWriter)>,
         type_name: TypeName,
         access_control: Arc<dyn AccessControl>,
+        counters: Arc<PortalCounters>,
+        drain_timeout: Duration,
     ) -> Result<Address> {
         let internal_address = Address::random_tagged("TcpPortalWorker_internal");
         let remote_address = Address::random_tagged("TcpPortalWorker_remote");
@@ -112,11 +142,8 @@ impl TcpPortalWorker {
             type_name, internal_address, remote_address
         );
 
-        let (rx, tx) = match stream {
-            Some(s) => {
-                let (rx, tx) = s.into_split();
-                (Some(rx), Some(tx))
-            }
+        let (rx, tx) = match connection {
+            Some((rx, tx)) => (Some(rx), Some(tx)),
             None => (None, None),
         };
 
@@ -125,13 +152,17 @@ impl TcpPortalWorker {
             tx,
             rx,
             peer,
+            target,
             // router_address,
             internal_address,
             remote_address: remote_address.clone(),
             remote_route: None,
             receiver_address,
+            receiver_reads: Arc::new(AtomicUsize::new(0)),
             is_disconnecting: false,
             type_name,
+            connection: ConnectionGuard::new(counters),
+            drain_timeout,
         };
 
         // TODO: @ac 0#TcpPortalWorker_internal
@@ -187,6 +218,7 @@ enum DisconnectionReason {
     FailedTx,
     FailedRx,
     Remote,
+    Drain,
 }
 
 impl TcpPortalWorker {
@@ -197,8 +229,13 @@ impl TcpPortalWorker {
     /// Start a `TcpPortalRecvProcessor`
     async fn start_receiver(&mut self, ctx: &Context, onward_route: Route) -> Result<()> {
         if let Some(rx) = self.rx.take() {
-            let receiver =
-                TcpPortalRecvProcessor::new(rx, self.internal_address.clone(), onward_route);
+            let receiver = TcpPortalRecvProcessor::new(
+                rx,
+                self.internal_address.clone(),
+                onward_route,
+                self.receiver_reads.clone(),
+                self.connection.counters().clone(),
+            );
 
             // TODO: @ac 0#TcpPortalRecvProcessor
             // in:  n/a
@@ -277,6 +314,27 @@ impl TcpPortalWorker {
         Ok(())
     }
 
+    /// Give the receiver time to forward the data that is still in
+    /// flight: wait until it didn't read anything for
+    /// [`DRAIN_IDLE`], or until the drain timeout
+    async fn wait_for_receiver_idle(&self, ctx: &Context) {
+        let deadline = Instant::now() + self.drain_timeout;
+        loop {
+            let reads = self.receiver_reads.load(Ordering::Relaxed);
+            ctx.sleep(DRAIN_IDLE).await;
+            if self.receiver_reads.load(Ordering::Relaxed) == reads {
+                break;
+            }
+            if Instant::now() >= deadline {
+                warn!(
+                    "{:?} at: {} still receiving data after {:?}, closing it",
+                    self.type_name, self.internal_address, self.drain_timeout
+                );
+                break;
+            }
+        }
+    }
+
     /// Start the portal disconnection process
     async fn start_disconnection(
         &mut self,
@@ -293,6 +351,13 @@ impl TcpPortalWorker {
                 self.notify_remote_about_disconnection(ctx).await?;
                 self.stop_receiver(ctx).await?;
             }
+            DisconnectionReason::Drain => {
+                // Stop reading first, so that no payload is sent after
+                // the `Disconnect` message
+                self.wait_for_receiver_idle(ctx).await;
+                self.stop_receiver(ctx).await?;
+                self.notify_remote_about_disconnection(ctx).await?;
+            }
             DisconnectionReason::Remote => {
                 self.stop_receiver(ctx).await?;
             }
@@ -328,10 +393,11 @@ impl TcpPortalWorker {
         .await?;
 
         if self.tx.is_none() {
-            let stream = TcpStream::connect(self.peer)
-                .await
-                .map_err(TransportError::from)?;
-            let (rx, tx) = stream.into_split();
+            let target = self
+                .target
+                .as_ref()
+                .ok_or(TransportError::PortalInvalidState)?;
+            let (rx, tx) = target.connect().await.map_err(TransportError::from)?;
             self.tx = Some(tx);
             self.rx = Some(rx);
 
@@ -356,6 +422,10 @@ impl Worker for TcpPortalWorker {
     type Message = Any;
 
     async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        // Payloads are `Bulk`, every other message of the stream must be
+        // too, or a `Disconnect` would overtake the last payloads
+        ctx.set_priority(Priority::Bulk);
+
         let state = self.clone_state();
 
         match state {
@@ -373,6 +443,27 @@ impl Worker for TcpPortalWorker {
         Ok(())
     }
 
+    /// Stop reading from the TCP stream and tell the other side of the
+    /// portal that this connection is going away
+    async fn drain(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        if self.is_disconnecting {
+            return Ok(());
+        }
+
+        match self.state {
+            State::Initialized => {
+                info!(
+                    "Draining {:?} at: {}",
+                    self.type_name, self.internal_address
+                );
+                self.start_disconnection(ctx, DisconnectionReason::Drain)
+                    .await
+            }
+            // Nothing was sent through this portal yet
+            _ => Ok(()),
+        }
+    }
+
     // TcpSendWorker will receive messages from the TcpRouter to send
     // across the TcpStream to our friend
     async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
@@ -447,7 +538,7 @@ impl Worker for TcpPortalWorker {
                         PortalMessage::Payload(payload) => {
                             if let Some(tx) = &mut self.tx {
                                 match tx.write_all(&payload).await {
-                                    Ok(()) => {}
+                                    Ok(()) => self.connection.counters().add_sent(payload.len()),
                                     Err(err) => {
                                         warn!(
                                             "Failed to send message to peer {} with error: {}",
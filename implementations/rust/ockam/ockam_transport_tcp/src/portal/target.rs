@@ -0,0 +1,76 @@
+use crate::{UdpReader, UdpWriter};
+use core::fmt;
+use ockam_core::compat::{boxed::Box, net::SocketAddr, sync::Arc};
+use std::io;
+use std::path::PathBuf;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpStream, UdpSocket};
+
+/// Read side of a portal connection
+pub(crate) type PortalReader = Box<dyn AsyncRead + Send + Sync + Unpin>;
+/// Write side of a portal connection
+pub(crate) type PortalWriter = Box<dyn AsyncWrite + Send + Sync + Unpin>;
+
+/// The kind of connections a portal carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PortalKind {
+    Tcp,
+    Udp,
+    Unix,
+}
+
+/// Where the outlet side of a portal connects to
+#[derive(Debug, Clone)]
+pub(crate) enum PortalTarget {
+    Tcp(SocketAddr),
+    Udp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl PortalTarget {
+    /// Open a new connection to the target
+    ///
+    /// UDP "connections" are a socket bound to a random local port
+    /// which only exchanges datagrams with the target.
+    pub(crate) async fn connect(&self) -> io::Result<(PortalReader, PortalWriter)> {
+        match self {
+            PortalTarget::Tcp(addr) => {
+                let (rx, tx) = TcpStream::connect(addr).await?.into_split();
+                Ok((Box::new(rx), Box::new(tx)))
+            }
+            PortalTarget::Udp(addr) => {
+                let bind_addr: SocketAddr = if addr.is_ipv4() {
+                    ([0, 0, 0, 0], 0).into()
+                } else {
+                    ([0u16; 8], 0).into()
+                };
+                let socket = UdpSocket::bind(bind_addr).await?;
+                socket.connect(addr).await?;
+                let socket = Arc::new(socket);
+                let writer = UdpWriter::new(socket.clone(), None);
+                let reader = UdpReader::new(socket, writer.activity());
+                Ok((Box::new(reader), Box::new(writer)))
+            }
+            #[cfg(unix)]
+            PortalTarget::Unix(path) => {
+                let (rx, tx) = tokio::net::UnixStream::connect(path).await?.into_split();
+                Ok((Box::new(rx), Box::new(tx)))
+            }
+            #[cfg(not(unix))]
+            PortalTarget::Unix(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unix sockets are not supported on this platform",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for PortalTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortalTarget::Tcp(addr) => write!(f, "tcp://{}", addr),
+            PortalTarget::Udp(addr) => write!(f, "udp://{}", addr),
+            PortalTarget::Unix(path) => write!(f, "unix://{}", path.display()),
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+use core::time::Duration;
+use ockam_core::compat::{boxed::Box, net::SocketAddr, sync::Arc, vec::Vec};
+use std::io;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio::time::{sleep, Instant, Sleep};
+use tracing::warn;
+
+/// How long a UDP portal session lives without any datagram in either
+/// direction
+///
+/// UDP has no notion of a closed connection, so this is the only way
+/// for a session to end.
+pub(crate) const UDP_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Ends a UDP session after [`UDP_IDLE_TIMEOUT`] without traffic
+///
+/// Received datagrams reset the timer directly, sent ones through the
+/// `activity` counter of the matching [`UdpWriter`].
+struct UdpIdle {
+    activity: Arc<AtomicUsize>,
+    seen: usize,
+    timer: Pin<Box<Sleep>>,
+}
+
+impl UdpIdle {
+    fn new(activity: Arc<AtomicUsize>) -> Self {
+        Self {
+            seen: activity.load(Ordering::Relaxed),
+            activity,
+            timer: Box::pin(sleep(UDP_IDLE_TIMEOUT)),
+        }
+    }
+
+    fn touch(&mut self) {
+        self.timer.as_mut().reset(Instant::now() + UDP_IDLE_TIMEOUT);
+    }
+
+    /// `true` once the session has been idle for too long
+    fn poll_expired(&mut self, cx: &mut Context<'_>) -> bool {
+        loop {
+            if self.timer.as_mut().poll(cx).is_pending() {
+                return false;
+            }
+            let activity = self.activity.load(Ordering::Relaxed);
+            if activity == self.seen {
+                return true;
+            }
+            self.seen = activity;
+            self.touch();
+        }
+    }
+}
+
+/// Reads the datagrams a connected [`UdpSocket`] receives
+///
+/// Every read returns one datagram, an empty read means that the
+/// session has been idle for too long.
+pub(crate) struct UdpReader {
+    socket: Arc<UdpSocket>,
+    idle: UdpIdle,
+}
+
+impl UdpReader {
+    pub(crate) fn new(socket: Arc<UdpSocket>, activity: Arc<AtomicUsize>) -> Self {
+        Self {
+            socket,
+            idle: UdpIdle::new(activity),
+        }
+    }
+}
+
+impl AsyncRead for UdpReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            let filled = buf.filled().len();
+            match this.socket.poll_recv(cx, buf) {
+                Poll::Ready(Ok(())) if buf.filled().len() > filled => {
+                    this.idle.touch();
+                    return Poll::Ready(Ok(()));
+                }
+                // An empty read would close the portal, skip empty datagrams
+                Poll::Ready(Ok(())) => continue,
+                // The target refused an earlier datagram, which doesn't end
+                // the session: it may just not be listening yet
+                Poll::Ready(Err(err)) if err.kind() == io::ErrorKind::ConnectionRefused => continue,
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => break,
+            }
+        }
+        if this.idle.poll_expired(cx) {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Reads the datagrams a UDP inlet received from one client
+///
+/// The inlet listener owns the socket and hands the datagrams of each
+/// client to its session through a channel.
+pub(crate) struct UdpSessionReader {
+    rx: mpsc::Receiver<Vec<u8>>,
+    idle: UdpIdle,
+}
+
+impl UdpSessionReader {
+    pub(crate) fn new(rx: mpsc::Receiver<Vec<u8>>, activity: Arc<AtomicUsize>) -> Self {
+        Self {
+            rx,
+            idle: UdpIdle::new(activity),
+        }
+    }
+}
+
+impl AsyncRead for UdpSessionReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            match this.rx.poll_recv(cx) {
+                Poll::Ready(Some(datagram)) if datagram.is_empty() => continue,
+                Poll::Ready(Some(datagram)) => {
+                    let len = datagram.len().min(buf.remaining());
+                    if len < datagram.len() {
+                        warn!(
+                            "Truncating UDP datagram of {} bytes to {} bytes",
+                            datagram.len(),
+                            len
+                        );
+                    }
+                    buf.put_slice(&datagram[..len]);
+                    this.idle.touch();
+                    return Poll::Ready(Ok(()));
+                }
+                // The inlet was stopped
+                Poll::Ready(None) => return Poll::Ready(Ok(())),
+                Poll::Pending => break,
+            }
+        }
+        if this.idle.poll_expired(cx) {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Sends every write as one datagram
+///
+/// Sends to `peer`, or to the address the socket is connected to if
+/// there is none.
+pub(crate) struct UdpWriter {
+    socket: Arc<UdpSocket>,
+    peer: Option<SocketAddr>,
+    activity: Arc<AtomicUsize>,
+}
+
+impl UdpWriter {
+    pub(crate) fn new(socket: Arc<UdpSocket>, peer: Option<SocketAddr>) -> Self {
+        Self {
+            socket,
+            peer,
+            activity: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Incremented after every datagram sent
+    pub(crate) fn activity(&self) -> Arc<AtomicUsize> {
+        self.activity.clone()
+    }
+}
+
+impl AsyncWrite for UdpWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let res = match self.peer {
+            Some(peer) => self.socket.poll_send_to(cx, buf, peer),
+            None => self.socket.poll_send(cx, buf),
+        };
+        if let Poll::Ready(Ok(_)) = res {
+            self.activity.fetch_add(1, Ordering::Relaxed);
+        }
+        res
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
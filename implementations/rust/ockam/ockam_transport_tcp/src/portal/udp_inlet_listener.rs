@@ -0,0 +1,150 @@
+use crate::{PortalCounters, TcpPortalWorker, UdpSessionReader, UdpWriter, MAX_PAYLOAD_SIZE};
+use core::time::Duration;
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::{
+    async_trait,
+    compat::{boxed::Box, sync::Arc, vec::Vec},
+};
+use ockam_core::{AccessControl, Address, Mailbox, Mailboxes, Processor, Result, Route};
+use ockam_node::{Context, ProcessorBuilder};
+use ockam_transport_core::TransportError;
+use std::io;
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::{debug, error, warn};
+
+/// How many datagrams of a client may wait for its session
+const SESSION_QUEUE: usize = 64;
+
+/// A UDP Portal Inlet listen processor
+///
+/// Every client address sending to the inlet socket gets its own
+/// portal session, which lasts until it has been idle for
+/// [`UDP_IDLE_TIMEOUT`](crate::UDP_IDLE_TIMEOUT). UDP Portal Inlet
+/// listen processors are created by `TcpTransport` after a call is
+/// made to
+/// [`TcpTransport::create_udp_inlet`](crate::TcpTransport::create_udp_inlet).
+pub(crate) struct UdpInletListenProcessor {
+    socket: Arc<UdpSocket>,
+    buf: Vec<u8>,
+    sessions: HashMap<SocketAddr, mpsc::Sender<Vec<u8>>>,
+    outlet_listener_route: Route,
+    access_control: Arc<dyn AccessControl>,
+    counters: Arc<PortalCounters>,
+    drain_timeout: Duration,
+}
+
+impl UdpInletListenProcessor {
+    /// Start a new `UdpInletListenProcessor`
+    pub(crate) async fn start(
+        ctx: &Context,
+        outlet_listener_route: Route,
+        addr: SocketAddr,
+        access_control: Arc<dyn AccessControl>,
+        counters: Arc<PortalCounters>,
+        drain_timeout: Duration,
+    ) -> Result<(Address, SocketAddr)> {
+        let waddr = Address::random_tagged("UdpInletListenProcessor");
+
+        debug!("Binding UdpInletListenProcessor to {}", addr);
+        let socket = match UdpSocket::bind(addr).await {
+            Ok(socket) => socket,
+            Err(err) => {
+                error!(%addr, %err, "could not bind to address");
+                return Err(TransportError::from(err).into());
+            }
+        };
+        let saddr = socket.local_addr().map_err(TransportError::from)?;
+        let processor = Self {
+            socket: Arc::new(socket),
+            buf: vec![0; MAX_PAYLOAD_SIZE],
+            sessions: HashMap::new(),
+            outlet_listener_route,
+            access_control: access_control.clone(),
+            counters,
+            drain_timeout,
+        };
+
+        let mailbox = Mailbox::new(
+            waddr.clone(),
+            access_control,
+            Arc::new(ockam_core::AllowAll),
+        );
+        ProcessorBuilder::with_mailboxes(Mailboxes::new(mailbox, vec![]), processor)
+            .start(ctx)
+            .await?;
+
+        Ok((waddr, saddr))
+    }
+
+    /// Start the portal session of a new client, with `datagram` as
+    /// its first datagram
+    async fn start_session(
+        &mut self,
+        ctx: &Context,
+        peer: SocketAddr,
+        datagram: Vec<u8>,
+    ) -> Result<()> {
+        // Forget the sessions which ended in the meantime
+        self.sessions.retain(|_, tx| !tx.is_closed());
+
+        let (tx, rx) = mpsc::channel(SESSION_QUEUE);
+        // Can't fail, the channel is empty
+        let _ = tx.try_send(datagram);
+        self.sessions.insert(peer, tx);
+
+        let writer = UdpWriter::new(self.socket.clone(), Some(peer));
+        let reader = UdpSessionReader::new(rx, writer.activity());
+        TcpPortalWorker::start_new_inlet(
+            ctx,
+            Box::new(reader),
+            Box::new(writer),
+            peer.to_string(),
+            self.outlet_listener_route.clone(),
+            self.access_control.clone(),
+            self.counters.clone(),
+            self.drain_timeout,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Processor for UdpInletListenProcessor {
+    type Context = Context;
+
+    // Don't accept new sessions while the node drains
+    fn stop_on_drain(&self) -> bool {
+        true
+    }
+
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let (len, peer) = match self.socket.recv_from(&mut self.buf).await {
+            Ok(res) => res,
+            // A client went away, which doesn't concern the other ones
+            Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => return Ok(true),
+            Err(err) => return Err(TransportError::from(err).into()),
+        };
+        let datagram = self.buf[..len].to_vec();
+
+        let datagram = match self.sessions.get(&peer) {
+            Some(tx) => match tx.try_send(datagram) {
+                Ok(()) => return Ok(true),
+                // Like any other overloaded UDP receiver
+                Err(TrySendError::Full(_)) => {
+                    warn!(%peer, "UDP portal session is overloaded, dropping datagram");
+                    return Ok(true);
+                }
+                Err(TrySendError::Closed(datagram)) => datagram,
+            },
+            None => datagram,
+        };
+
+        self.start_session(ctx, peer, datagram).await?;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{PortalCounters, TcpPortalWorker};
+use core::time::Duration;
+use ockam_core::{
+    async_trait,
+    compat::{boxed::Box, sync::Arc},
+};
+use ockam_core::{AccessControl, Address, Mailbox, Mailboxes, Processor, Result, Route};
+use ockam_node::{Context, ProcessorBuilder};
+use ockam_transport_core::TransportError;
+use std::path::PathBuf;
+use tokio::net::UnixListener;
+use tracing::{debug, error};
+
+/// A Unix socket Portal Inlet listen processor
+///
+/// Unix socket Portal Inlet listen processors are created by
+/// `TcpTransport` after a call is made to
+/// [`TcpTransport::create_unix_inlet`](crate::TcpTransport::create_unix_inlet).
+/// The socket file is removed when the processor stops.
+pub(crate) struct UnixInletListenProcessor {
+    inner: UnixListener,
+    path: PathBuf,
+    outlet_listener_route: Route,
+    access_control: Arc<dyn AccessControl>,
+    counters: Arc<PortalCounters>,
+    drain_timeout: Duration,
+}
+
+impl UnixInletListenProcessor {
+    /// Start a new `UnixInletListenProcessor`
+    pub(crate) async fn start(
+        ctx: &Context,
+        outlet_listener_route: Route,
+        path: PathBuf,
+        access_control: Arc<dyn AccessControl>,
+        counters: Arc<PortalCounters>,
+        drain_timeout: Duration,
+    ) -> Result<Address> {
+        let waddr = Address::random_tagged("UnixInletListenProcessor");
+
+        debug!("Binding UnixInletListenProcessor to {}", path.display());
+        let inner = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(err) => {
+                error!(path = %path.display(), %err, "could not bind to path");
+                return Err(TransportError::from(err).into());
+            }
+        };
+        let processor = Self {
+            inner,
+            path,
+            outlet_listener_route,
+            access_control: access_control.clone(),
+            counters,
+            drain_timeout,
+        };
+
+        let mailbox = Mailbox::new(
+            waddr.clone(),
+            access_control,
+            Arc::new(ockam_core::AllowAll),
+        );
+        ProcessorBuilder::with_mailboxes(Mailboxes::new(mailbox, vec![]), processor)
+            .start(ctx)
+            .await?;
+
+        Ok(waddr)
+    }
+}
+
+#[async_trait]
+impl Processor for UnixInletListenProcessor {
+    type Context = Context;
+
+    // Don't accept new connections while the node drains
+    fn stop_on_drain(&self) -> bool {
+        true
+    }
+
+    async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        if let Err(err) = std::fs::remove_file(&self.path) {
+            debug!(path = %self.path.display(), %err, "could not remove socket file");
+        }
+        Ok(())
+    }
+
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let (stream, _) = self.inner.accept().await.map_err(TransportError::from)?;
+        let (rx, tx) = stream.into_split();
+        TcpPortalWorker::start_new_inlet(
+            ctx,
+            Box::new(rx),
+            Box::new(tx),
+            self.path.display().to_string(),
+            self.outlet_listener_route.clone(),
+            self.access_control.clone(),
+            self.counters.clone(),
+            self.drain_timeout,
+        )
+        .await?;
+
+        Ok(true)
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
-    parse_socket_addr, TcpInletListenProcessor, TcpListenProcessor, TcpRouterRequest,
-    TcpRouterResponse, WorkerPair, TCP,
+    parse_socket_addr, PortalCounters, TcpInletListenProcessor, TcpListenProcessor,
+    TcpRouterRequest, TcpRouterResponse, WorkerPair, TCP,
 };
+use core::time::Duration;
 use ockam_core::compat::net::{SocketAddr, ToSocketAddrs};
 use ockam_core::{
     async_trait,
@@ -208,6 +209,8 @@ impl TcpRouterHandle {
         outlet_listener_route: impl Into<Route>,
         addr: impl Into<SocketAddr>,
         access_control: Arc<dyn AccessControl>,
+        counters: Arc<PortalCounters>,
+        drain_timeout: Duration,
     ) -> Result<(Address, SocketAddr)> {
         let socket_addr = addr.into();
         TcpInletListenProcessor::start(
@@ -215,6 +218,8 @@ impl TcpRouterHandle {
             outlet_listener_route.into(),
             socket_addr,
             access_control,
+            counters,
+            drain_timeout,
             // self.main_addr.clone(),
         )
         .await
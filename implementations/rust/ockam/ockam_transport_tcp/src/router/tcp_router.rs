@@ -234,8 +234,10 @@ impl TcpRouter {
             .modify()
             .prepend(next.clone());
 
-        // Send the transport message to the connection worker
-        ctx.send(next.clone(), msg).await?;
+        // Send the transport message to the connection worker, which
+        // queues it according to its priority
+        let priority = msg.priority();
+        ctx.send_with_priority(next.clone(), msg, priority).await?;
 
         Ok(())
     }
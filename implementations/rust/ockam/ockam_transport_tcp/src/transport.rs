@@ -1,9 +1,13 @@
+use core::time::Duration;
 use ockam_core::access_control::AccessControl;
 use ockam_core::compat::{boxed::Box, net::SocketAddr, sync::Arc};
 use ockam_core::{Address, AllowAll, AsyncTryClone, Result, Route};
 use ockam_node::Context;
 
-use crate::{parse_socket_addr, TcpOutletListenWorker, TcpRouter, TcpRouterHandle};
+use crate::{
+    parse_socket_addr, PortalCounters, PortalKind, TcpOutletListenWorker, TcpRouter,
+    TcpRouterHandle, UdpInletListenProcessor, DEFAULT_DRAIN_TIMEOUT,
+};
 
 /// High level management interface for TCP transports
 ///
@@ -117,6 +121,8 @@ pub struct InletOptions {
     bind_addr: String,
     outlet_route: Route,
     access_control: Arc<dyn AccessControl>,
+    counters: Arc<PortalCounters>,
+    drain_timeout: Duration,
 }
 
 impl InletOptions {
@@ -131,8 +137,23 @@ impl InletOptions {
             bind_addr,
             outlet_route,
             access_control,
+            counters: Arc::new(PortalCounters::new()),
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
         }
     }
+
+    /// Count the traffic of the inlet connections with `counters`
+    pub fn with_counters(mut self, counters: Arc<PortalCounters>) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    /// Close the inlet connections which still receive data `timeout`
+    /// after the node started draining, 5 seconds by default
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
 }
 
 /// Args to start an Outlet
@@ -140,6 +161,8 @@ pub struct OutletOptions {
     address: Address,
     peer: String,
     access_control: Arc<dyn AccessControl>,
+    counters: Arc<PortalCounters>,
+    drain_timeout: Duration,
 }
 
 impl OutletOptions {
@@ -150,8 +173,23 @@ impl OutletOptions {
             address,
             peer,
             access_control,
+            counters: Arc::new(PortalCounters::new()),
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
         }
     }
+
+    /// Count the traffic of the outlet connections with `counters`
+    pub fn with_counters(mut self, counters: Arc<PortalCounters>) -> Self {
+        self.counters = counters;
+        self
+    }
+
+    /// Close the outlet connections which still receive data `timeout`
+    /// after the node started draining, 5 seconds by default
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
 }
 
 impl TcpTransport {
@@ -162,7 +200,13 @@ impl TcpTransport {
     ) -> Result<(Address, SocketAddr)> {
         let bind_addr = parse_socket_addr(options.bind_addr)?;
         self.router_handle
-            .bind_inlet(options.outlet_route, bind_addr, options.access_control)
+            .bind_inlet(
+                options.outlet_route,
+                bind_addr,
+                options.access_control,
+                options.counters,
+                options.drain_timeout,
+            )
             .await
     }
 
@@ -215,9 +259,70 @@ impl TcpTransport {
         Ok(())
     }
 
+    /// Create a UDP Inlet that receives datagrams on bind_addr
+    ///
+    /// Every client sending to the Inlet gets its own connection to
+    /// the Outlet, which ends after it has been idle for a minute.
+    /// Datagrams bigger than 48 KiB are truncated.
+    pub async fn create_udp_inlet(&self, options: InletOptions) -> Result<(Address, SocketAddr)> {
+        let bind_addr = parse_socket_addr(options.bind_addr)?;
+        UdpInletListenProcessor::start(
+            self.router_handle.ctx(),
+            options.outlet_route,
+            bind_addr,
+            options.access_control,
+            options.counters,
+            options.drain_timeout,
+        )
+        .await
+    }
+
+    /// Create a Unix socket Inlet that listens on the socket file at
+    /// bind_addr, which is removed when the Inlet stops
+    pub async fn create_unix_inlet(&self, options: InletOptions) -> Result<Address> {
+        #[cfg(unix)]
+        {
+            crate::UnixInletListenProcessor::start(
+                self.router_handle.ctx(),
+                options.outlet_route,
+                options.bind_addr.into(),
+                options.access_control,
+                options.counters,
+                options.drain_timeout,
+            )
+            .await
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = options;
+            Err(ockam_transport_core::TransportError::InvalidAddress.into())
+        }
+    }
+
     /// Create an Outlet
     pub async fn create_outlet_extended(&self, options: OutletOptions) -> Result<()> {
-        let worker = TcpOutletListenWorker::new(options.peer, options.access_control);
+        self.start_outlet(options, PortalKind::Tcp).await
+    }
+
+    /// Create an Outlet that sends the data of every Inlet connection
+    /// as datagrams to the UDP peer
+    pub async fn create_udp_outlet(&self, options: OutletOptions) -> Result<()> {
+        self.start_outlet(options, PortalKind::Udp).await
+    }
+
+    /// Create an Outlet that connects to the Unix socket file at peer
+    pub async fn create_unix_outlet(&self, options: OutletOptions) -> Result<()> {
+        self.start_outlet(options, PortalKind::Unix).await
+    }
+
+    async fn start_outlet(&self, options: OutletOptions, kind: PortalKind) -> Result<()> {
+        let worker = TcpOutletListenWorker::new(
+            options.peer,
+            kind,
+            options.access_control,
+            options.counters,
+            options.drain_timeout,
+        );
         self.router_handle
             .ctx()
             .start_worker(options.address, worker)
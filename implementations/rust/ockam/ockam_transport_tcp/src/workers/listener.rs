@@ -63,6 +63,11 @@ impl Processor for TcpListenProcessor {
         ctx.set_cluster(crate::CLUSTER_NAME).await
     }
 
+    // Don't accept new connections while the node drains
+    fn stop_on_drain(&self) -> bool {
+        true
+    }
+
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
         debug!("Waiting for incoming TCP connection...");
 
@@ -1,4 +1,4 @@
-use crate::{TcpSendWorkerMsg, TCP};
+use crate::{TcpLocalInfo, TcpSendWorkerMsg, TCP};
 use ockam_core::async_trait;
 use ockam_core::{Address, Decodable, LocalMessage, Processor, Result, TransportMessage};
 use ockam_node::{Context, ExternalLocalInfo};
@@ -103,11 +103,13 @@ impl Processor for TcpRecvProcessor {
         trace!("Message return route: {}", msg.return_route);
 
         // Mark that message originates from some other node
-        let local_info = ExternalLocalInfo::new(TCP).to_local_info()?;
+        let mut local_info = vec![ExternalLocalInfo::new(TCP).to_local_info()?];
+        if let (Ok(local_addr), Ok(peer_addr)) = (self.rx.local_addr(), self.rx.peer_addr()) {
+            local_info.push(TcpLocalInfo::new(local_addr, peer_addr).to_local_info()?);
+        }
 
         // Forward the message to the next hop in the route
-        ctx.forward(LocalMessage::new(msg, vec![local_info]))
-            .await?;
+        ctx.forward(LocalMessage::new(msg, local_info)).await?;
 
         Ok(true)
     }
@@ -1,12 +1,13 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::{TcpListener, TcpStream};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
 
 use ockam_core::compat::rand::random;
-use ockam_core::{route, Result};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{route, AllowAll, Result};
 use ockam_node::Context;
-use ockam_transport_tcp::TcpTransport;
+use ockam_transport_tcp::{InletOptions, OutletOptions, PortalCounters, TcpTransport, TCP};
 
 const LENGTH: usize = 32;
 
@@ -101,3 +102,253 @@ async fn portal__reverse_flow__should_succeed(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 30000)]
+async fn portal__close_after_large_write__should_deliver_every_byte(
+    ctx: &mut Context,
+) -> Result<()> {
+    const TOTAL: usize = 8 * 1024 * 1024;
+    let payload: Vec<u8> = (0..TOTAL).map(|i| (i % 251) as u8).collect();
+    let expected = payload.clone();
+
+    // Go through a TCP connection, so that the stream is queued in the
+    // sender's mailbox
+    let tcp = TcpTransport::create(ctx).await?;
+    let node_addr = tcp.listen("127.0.0.1:0").await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    tcp.create_outlet("outlet", listener.local_addr().unwrap().to_string())
+        .await?;
+    let (_, inlet_addr) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route![(TCP, node_addr.to_string()), "outlet"],
+        )
+        .await?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        let mut received = Vec::new();
+        stream.read_to_end(&mut received).await.unwrap();
+        tx.send(received).unwrap();
+    });
+
+    // Wait till listener is up
+    tokio::time::sleep(Duration::new(0, 250_000)).await;
+
+    let mut stream = TcpStream::connect(inlet_addr).await.unwrap();
+    stream.write_all(&payload).await.unwrap();
+    drop(stream);
+
+    let received = rx.await.unwrap();
+    assert_eq!(received.len(), TOTAL);
+    assert!(received == expected);
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__traffic__should_be_counted(ctx: &mut Context) -> Result<()> {
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let outlet_counters = Arc::new(PortalCounters::new());
+    let options = OutletOptions::new(
+        "outlet".into(),
+        listener.local_addr().unwrap().to_string(),
+        Arc::new(AllowAll),
+    )
+    .with_counters(outlet_counters.clone());
+    tcp.create_outlet_extended(options).await?;
+    let inlet_counters = Arc::new(PortalCounters::new());
+    let options = InletOptions::new("127.0.0.1:0".into(), route!["outlet"], Arc::new(AllowAll))
+        .with_counters(inlet_counters.clone());
+    let (_, inlet_addr) = tcp.create_inlet_extended(options).await?;
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        read_assert_binary(&mut stream, payload1).await;
+        write_binary(&mut stream, payload2).await;
+        write_binary(&mut stream, payload2).await;
+    });
+
+    let mut stream = TcpStream::connect(inlet_addr).await.unwrap();
+    write_binary(&mut stream, payload1).await;
+    read_assert_binary(&mut stream, payload2).await;
+    read_assert_binary(&mut stream, payload2).await;
+
+    tokio::time::sleep(Duration::new(0, 250_000)).await;
+
+    assert_eq!(inlet_counters.connections(), 1);
+    assert_eq!(inlet_counters.bytes_received(), LENGTH as u64);
+    assert_eq!(inlet_counters.bytes_sent(), 2 * LENGTH as u64);
+    assert_eq!(outlet_counters.connections(), 1);
+    assert_eq!(outlet_counters.bytes_received(), 2 * LENGTH as u64);
+    assert_eq!(outlet_counters.bytes_sent(), LENGTH as u64);
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__busy_connection__should_be_closed_after_drain_timeout(
+    ctx: &mut Context,
+) -> Result<()> {
+    let drain_timeout = Duration::from_millis(500);
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let options = OutletOptions::new(
+        "outlet".into(),
+        listener.local_addr().unwrap().to_string(),
+        Arc::new(AllowAll),
+    )
+    .with_drain_timeout(drain_timeout);
+    tcp.create_outlet_extended(options).await?;
+    let options = InletOptions::new("127.0.0.1:0".into(), route!["outlet"], Arc::new(AllowAll))
+        .with_drain_timeout(drain_timeout);
+    let (_, inlet_addr) = tcp.create_inlet_extended(options).await?;
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 1024];
+        while matches!(stream.read(&mut buf).await, Ok(n) if n > 0) {}
+    });
+
+    // The client never stops sending, so its connection never becomes
+    // idle while the node drains
+    let (mut rx, mut tx) = TcpStream::connect(inlet_addr).await.unwrap().into_split();
+    tokio::spawn(async move {
+        while tx.write_all(&generate_binary()).await.is_ok() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    let started = Instant::now();
+    let closed = tokio::spawn(async move {
+        let mut buf = [0u8; LENGTH];
+        while matches!(rx.read(&mut buf).await, Ok(n) if n > 0) {}
+        started.elapsed()
+    });
+
+    ctx.drain_and_stop(10).await?;
+
+    // The default drain timeout alone would take 5 seconds
+    let elapsed = closed.await.unwrap();
+    assert!(elapsed >= drain_timeout, "closed after {elapsed:?}");
+    assert!(elapsed < Duration::from_secs(5), "closed after {elapsed:?}");
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__udp_datagrams__should_be_forwarded(ctx: &mut Context) -> Result<()> {
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let target = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let options = OutletOptions::new(
+        "outlet".into(),
+        target.local_addr().unwrap().to_string(),
+        Arc::new(AllowAll),
+    );
+    tcp.create_udp_outlet(options).await?;
+    let options = InletOptions::new("127.0.0.1:0".into(), route!["outlet"], Arc::new(AllowAll));
+    let (_, inlet_addr) = tcp.create_udp_inlet(options).await?;
+
+    tokio::spawn(async move {
+        let mut datagram = [0u8; 2 * LENGTH];
+        let (length, peer) = target.recv_from(&mut datagram).await.unwrap();
+        assert_eq!(&datagram[..length], &payload1);
+        target.send_to(&payload2, peer).await.unwrap();
+        target.send_to(&payload2, peer).await.unwrap();
+    });
+
+    let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    client.connect(inlet_addr).await.unwrap();
+    client.send(&payload1).await.unwrap();
+    for _ in 0..2 {
+        let mut datagram = [0u8; 2 * LENGTH];
+        let length = client.recv(&mut datagram).await.unwrap();
+        assert_eq!(&datagram[..length], &payload2);
+    }
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__unix_socket__should_be_forwarded(ctx: &mut Context) -> Result<()> {
+    use tokio::net::{UnixListener, UnixStream};
+
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let dir = std::env::temp_dir().join(format!("ockam-portal-{}", random::<u64>()));
+    std::fs::create_dir(&dir).unwrap();
+    let target_path = dir.join("target.sock");
+    let inlet_path = dir.join("inlet.sock");
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = UnixListener::bind(&target_path).unwrap();
+    let options = OutletOptions::new(
+        "outlet".into(),
+        target_path.display().to_string(),
+        Arc::new(AllowAll),
+    );
+    tcp.create_unix_outlet(options).await?;
+    let options = InletOptions::new(
+        inlet_path.display().to_string(),
+        route!["outlet"],
+        Arc::new(AllowAll),
+    );
+    let inlet = tcp.create_unix_inlet(options).await?;
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut payload = [0u8; LENGTH];
+        stream.read_exact(&mut payload).await.unwrap();
+        assert_eq!(payload, payload1);
+        stream.write_all(&payload2).await.unwrap();
+    });
+
+    let mut stream = UnixStream::connect(&inlet_path).await.unwrap();
+    stream.write_all(&payload1).await.unwrap();
+    let mut payload = [0u8; LENGTH];
+    stream.read_exact(&mut payload).await.unwrap();
+    assert_eq!(payload, payload2);
+
+    // The socket file goes away with the inlet
+    tcp.stop_inlet(inlet).await?;
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert!(!inlet_path.exists());
+    let _ = std::fs::remove_dir_all(&dir);
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}